@@ -125,6 +125,14 @@ client_request_definitions! {
         params: v2::ThreadListParams,
         response: v2::ThreadListResponse,
     },
+    ThreadSearch => "thread/search" {
+        params: v2::ThreadSearchParams,
+        response: v2::ThreadSearchResponse,
+    },
+    ThreadRewind => "thread/rewind" {
+        params: v2::ThreadRewindParams,
+        response: v2::ThreadRewindResponse,
+    },
     ThreadCompact => "thread/compact" {
         params: v2::ThreadCompactParams,
         response: v2::ThreadCompactResponse,
@@ -458,6 +466,9 @@ pub struct ExecCommandApprovalParams {
     pub cwd: PathBuf,
     pub reason: Option<String>,
     pub risk: Option<SandboxCommandAssessment>,
+    /// The environment profile the command runs with, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_profile: Option<String>,
     pub parsed_cmd: Vec<ParsedCommand>,
 }
 
@@ -623,6 +634,7 @@ mod tests {
             cwd: PathBuf::from("/tmp"),
             reason: Some("because tests".to_string()),
             risk: None,
+            env_profile: None,
             parsed_cmd: vec![ParsedCommand::Unknown {
                 cmd: "echo hello".to_string(),
             }],
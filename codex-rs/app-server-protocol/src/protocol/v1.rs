@@ -319,6 +319,10 @@ pub struct SandboxSettings {
     #[serde(default)]
     pub writable_roots: Vec<PathBuf>,
     pub network_access: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network_allowlist: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read_only_subpaths: Vec<PathBuf>,
     pub exclude_tmpdir_env_var: Option<bool>,
     pub exclude_slash_tmp: Option<bool>,
 }
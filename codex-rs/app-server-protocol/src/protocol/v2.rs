@@ -52,7 +52,7 @@ v2_enum_from_core!(
 
 v2_enum_from_core!(
     pub enum SandboxMode from codex_protocol::config_types::SandboxMode {
-        ReadOnly, WorkspaceWrite, DangerFullAccess
+        ReadOnly, WorkspaceWrite, DangerFullAccess, Container
     }
 );
 
@@ -69,9 +69,15 @@ pub enum SandboxPolicy {
         #[serde(default)]
         network_access: bool,
         #[serde(default)]
+        network_allowlist: Vec<String>,
+        #[serde(default)]
+        read_only_subpaths: Vec<PathBuf>,
+        #[serde(default)]
         exclude_tmpdir_env_var: bool,
         #[serde(default)]
         exclude_slash_tmp: bool,
+        #[serde(default)]
+        container: bool,
     },
 }
 
@@ -85,13 +91,19 @@ impl SandboxPolicy {
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
+                network_allowlist,
+                read_only_subpaths,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                container,
             } => codex_protocol::protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots: writable_roots.clone(),
                 network_access: *network_access,
+                network_allowlist: network_allowlist.clone(),
+                read_only_subpaths: read_only_subpaths.clone(),
                 exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                 exclude_slash_tmp: *exclude_slash_tmp,
+                container: *container,
             },
         }
     }
@@ -107,13 +119,19 @@ impl From<codex_protocol::protocol::SandboxPolicy> for SandboxPolicy {
             codex_protocol::protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
+                network_allowlist,
+                read_only_subpaths,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                container,
             } => SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
+                network_allowlist,
+                read_only_subpaths,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                container,
             },
         }
     }
@@ -368,6 +386,52 @@ pub struct ThreadListResponse {
     pub next_cursor: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchParams {
+    /// Words to search for; a thread matches when it contains all of them in
+    /// its messages, the commands that ran or the paths of edited files.
+    pub query: String,
+    /// Optional maximum number of results; defaults to a reasonable server-side value.
+    pub limit: Option<u32>,
+    /// Optional provider filter with the same semantics as `thread/list`.
+    pub model_providers: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResponse {
+    /// Matching threads, most relevant first.
+    pub data: Vec<ThreadSearchResult>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResult {
+    pub thread: Thread,
+    /// Excerpt of the recorded text that best matches the query.
+    pub snippet: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRewindParams {
+    pub thread_id: String,
+    /// 0-based index of the user turn to rewind to. The working tree is
+    /// restored to the checkpoint taken when that turn started, and the turn
+    /// and everything after it are dropped from the thread.
+    pub turn: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRewindResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1,5 +1,7 @@
 use crate::codex_message_processor::ApiVersion;
 use crate::codex_message_processor::PendingInterrupts;
+use crate::codex_message_processor::PendingRewinds;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_app_server_protocol::AccountRateLimitsUpdatedNotification;
 use codex_app_server_protocol::AgentMessageDeltaNotification;
@@ -10,6 +12,7 @@ use codex_app_server_protocol::ExecCommandApprovalResponse;
 use codex_app_server_protocol::InterruptConversationResponse;
 use codex_app_server_protocol::ItemCompletedNotification;
 use codex_app_server_protocol::ItemStartedNotification;
+use codex_app_server_protocol::JSONRPCErrorError;
 use codex_app_server_protocol::McpToolCallError;
 use codex_app_server_protocol::McpToolCallResult;
 use codex_app_server_protocol::McpToolCallStatus;
@@ -19,6 +22,7 @@ use codex_app_server_protocol::ReasoningTextDeltaNotification;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequestPayload;
 use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::ThreadRewindResponse;
 use codex_app_server_protocol::TurnInterruptResponse;
 use codex_core::CodexConversation;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
@@ -30,6 +34,7 @@ use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_protocol::ConversationId;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::oneshot;
 use tracing::error;
@@ -42,6 +47,7 @@ pub(crate) async fn apply_bespoke_event_handling(
     conversation: Arc<CodexConversation>,
     outgoing: Arc<OutgoingMessageSender>,
     pending_interrupts: PendingInterrupts,
+    pending_rewinds: PendingRewinds,
 ) {
     let Event { id: event_id, msg } = event;
     match msg {
@@ -127,6 +133,7 @@ pub(crate) async fn apply_bespoke_event_handling(
             cwd,
             reason,
             risk,
+            env_profile,
             parsed_cmd,
         }) => {
             let params = ExecCommandApprovalParams {
@@ -136,6 +143,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                 cwd,
                 reason,
                 risk,
+                env_profile,
                 parsed_cmd,
             };
             let rx = outgoing
@@ -195,6 +203,35 @@ pub(crate) async fn apply_bespoke_event_handling(
                 }
             }
         }
+        // If this UndoCompleted finishes a rewind request, reply to it. A plain
+        // undo completes with another id and is left alone.
+        EventMsg::UndoCompleted(undo_completed_event) => {
+            let pending = {
+                let mut map = pending_rewinds.lock().await;
+                let rid = map
+                    .get_mut(&conversation_id)
+                    .and_then(|rewinds| rewinds.remove(&event_id));
+                if map.get(&conversation_id).is_some_and(HashMap::is_empty) {
+                    map.remove(&conversation_id);
+                }
+                rid
+            };
+            if let Some(rid) = pending {
+                if undo_completed_event.success {
+                    outgoing.send_response(rid, ThreadRewindResponse {}).await;
+                } else {
+                    let error = JSONRPCErrorError {
+                        code: INVALID_REQUEST_ERROR_CODE,
+                        message: undo_completed_event
+                            .message
+                            .clone()
+                            .unwrap_or_else(|| "failed to rewind thread".to_string()),
+                        data: None,
+                    };
+                    outgoing.send_error(rid, error).await;
+                }
+            }
+        }
 
         _ => {}
     }
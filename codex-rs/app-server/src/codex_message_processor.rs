@@ -77,6 +77,10 @@ use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadResumeResponse;
+use codex_app_server_protocol::ThreadRewindParams;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
+use codex_app_server_protocol::ThreadSearchResult;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
@@ -98,6 +102,7 @@ use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::InitialHistory;
 use codex_core::NewConversation;
 use codex_core::RolloutRecorder;
+use codex_core::SearchHit;
 use codex_core::SessionMeta;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::login_with_api_key;
@@ -150,6 +155,9 @@ use uuid::Uuid;
 
 type PendingInterruptQueue = Vec<(RequestId, ApiVersion)>;
 pub(crate) type PendingInterrupts = Arc<Mutex<HashMap<ConversationId, PendingInterruptQueue>>>;
+/// Rewind requests awaiting their `UndoCompleted`, keyed by the id of the
+/// submitted `RestoreCheckpoint` op.
+pub(crate) type PendingRewinds = Arc<Mutex<HashMap<ConversationId, HashMap<String, RequestId>>>>;
 
 // Duration before a ChatGPT login attempt is abandoned.
 const LOGIN_CHATGPT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
@@ -175,6 +183,7 @@ pub(crate) struct CodexMessageProcessor {
     active_login: Arc<Mutex<Option<ActiveLogin>>>,
     // Queue of pending interrupt requests per conversation. We reply when TurnAborted arrives.
     pending_interrupts: PendingInterrupts,
+    pending_rewinds: PendingRewinds,
     pending_fuzzy_searches: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     feedback: CodexFeedback,
 }
@@ -227,6 +236,7 @@ impl CodexMessageProcessor {
             conversation_listeners: HashMap::new(),
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
+            pending_rewinds: Arc::new(Mutex::new(HashMap::new())),
             pending_fuzzy_searches: Arc::new(Mutex::new(HashMap::new())),
             feedback,
         }
@@ -250,6 +260,12 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadList { request_id, params } => {
                 self.thread_list(request_id, params).await;
             }
+            ClientRequest::ThreadSearch { request_id, params } => {
+                self.thread_search(request_id, params).await;
+            }
+            ClientRequest::ThreadRewind { request_id, params } => {
+                self.thread_rewind(request_id, params).await;
+            }
             ClientRequest::ThreadCompact {
                 request_id,
                 params: _,
@@ -1085,12 +1101,14 @@ impl CodexMessageProcessor {
         let outgoing = self.outgoing.clone();
         let req_id = request_id;
         let sandbox_cwd = self.config.cwd.clone();
+        let read_deny = self.config.sandbox_read_deny.clone();
 
         tokio::spawn(async move {
             match codex_core::exec::process_exec_tool_call(
                 exec_params,
                 sandbox_type,
                 &effective_policy,
+                &read_deny,
                 sandbox_cwd.as_path(),
                 &codex_linux_sandbox_exe,
                 None,
@@ -1379,6 +1397,67 @@ impl CodexMessageProcessor {
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn thread_search(&self, request_id: RequestId, params: ThreadSearchParams) {
+        let ThreadSearchParams {
+            query,
+            limit,
+            model_providers,
+        } = params;
+
+        if query.trim().is_empty() {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: "query must not be empty".to_string(),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        }
+
+        let limit = limit.unwrap_or(25).max(1) as usize;
+        let fallback_provider = self.config.model_provider_id.as_str();
+        let model_provider_filter = match model_providers {
+            Some(providers) if providers.is_empty() => None,
+            Some(providers) => Some(providers),
+            None => Some(vec![fallback_provider.to_string()]),
+        };
+
+        // Filter providers after ranking so they match `thread/list`.
+        let hits = match RolloutRecorder::search_conversations(
+            &self.config.codex_home,
+            &query,
+            INTERACTIVE_SESSION_SOURCES,
+            usize::MAX,
+        )
+        .await
+        {
+            Ok(hits) => hits,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to search conversations: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let data = hits
+            .into_iter()
+            .map(|hit| search_hit_to_result(hit, fallback_provider))
+            .filter(|result| {
+                model_provider_filter
+                    .as_ref()
+                    .is_none_or(|providers| providers.contains(&result.thread.model_provider))
+            })
+            .take(limit)
+            .collect();
+
+        let response = ThreadSearchResponse { data };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
     async fn thread_resume(&mut self, request_id: RequestId, params: ThreadResumeParams) {
         let ThreadResumeParams {
             thread_id,
@@ -2366,6 +2445,45 @@ impl CodexMessageProcessor {
         let _ = conversation.submit(Op::Interrupt).await;
     }
 
+    async fn thread_rewind(&self, request_id: RequestId, params: ThreadRewindParams) {
+        let ThreadRewindParams { thread_id, turn } = params;
+
+        let (conversation_id, conversation) =
+            match self.conversation_from_thread_id(&thread_id).await {
+                Ok(v) => v,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
+
+        // Submit the rewind and record it while holding the lock, so that its
+        // UndoCompleted cannot be handled before we know the submission id.
+        // We'll respond upon UndoCompleted.
+        let mut map = self.pending_rewinds.lock().await;
+        match conversation
+            .submit(Op::RestoreCheckpoint {
+                turn: turn as usize,
+            })
+            .await
+        {
+            Ok(submission_id) => {
+                map.entry(conversation_id)
+                    .or_default()
+                    .insert(submission_id, request_id);
+            }
+            Err(err) => {
+                drop(map);
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to rewind thread: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
     async fn add_conversation_listener(
         &mut self,
         request_id: RequestId,
@@ -2440,6 +2558,7 @@ impl CodexMessageProcessor {
 
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
+        let pending_rewinds = self.pending_rewinds.clone();
         tokio::spawn(async move {
             loop {
                 tokio::select! {
@@ -2495,6 +2614,7 @@ impl CodexMessageProcessor {
                             conversation.clone(),
                             outgoing_for_task.clone(),
                             pending_interrupts.clone(),
+                            pending_rewinds.clone(),
                         )
                         .await;
                     }
@@ -2791,6 +2911,31 @@ fn summary_to_thread(summary: ConversationSummary) -> Thread {
     }
 }
 
+fn search_hit_to_result(hit: SearchHit, fallback_provider: &str) -> ThreadSearchResult {
+    let SearchHit {
+        conversation_id,
+        path,
+        created_at,
+        model_provider,
+        preview,
+        snippet,
+        ..
+    } = hit;
+
+    let created_at = parse_datetime(Some(created_at.as_str()));
+
+    ThreadSearchResult {
+        thread: Thread {
+            id: conversation_id.to_string(),
+            preview: preview.unwrap_or_default(),
+            model_provider: model_provider.unwrap_or_else(|| fallback_provider.to_string()),
+            created_at: created_at.map(|dt| dt.timestamp()).unwrap_or(0),
+            path,
+        },
+        snippet,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,6 +275,7 @@ async fn test_send_user_turn_changes_approval_policy_behavior() -> Result<()> {
             cwd: working_directory.clone(),
             reason: None,
             risk: None,
+            env_profile: None,
             parsed_cmd: vec![ParsedCommand::Unknown {
                 cmd: "python3 -c 'print(42)'".to_string()
             }],
@@ -424,8 +425,11 @@ async fn test_send_user_turn_updates_sandbox_and_cwd_between_turns() -> Result<(
             sandbox_policy: SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![first_cwd.clone()],
                 network_access: false,
+                network_allowlist: vec![],
+                read_only_subpaths: vec![],
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                container: false,
             },
             model: model.clone(),
             effort: Some(ReasoningEffort::Medium),
@@ -82,6 +82,8 @@ async fn get_config_toml_parses_all_fields() -> Result<()> {
             sandbox_settings: Some(SandboxSettings {
                 writable_roots: vec!["/tmp".into()],
                 network_access: Some(true),
+                network_allowlist: vec![],
+                read_only_subpaths: vec![],
                 exclude_tmpdir_env_var: Some(true),
                 exclude_slash_tmp: Some(true),
             }),
@@ -4,6 +4,8 @@ mod rate_limits;
 mod thread_archive;
 mod thread_list;
 mod thread_resume;
+mod thread_rewind;
+mod thread_search;
 mod thread_start;
 mod turn_interrupt;
 mod turn_start;
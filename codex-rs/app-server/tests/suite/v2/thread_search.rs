@@ -0,0 +1,122 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_search_matches_all_terms_and_filters_providers() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_minimal_config(codex_home.path())?;
+
+    let flaky = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "The migration test is flaky on CI",
+        Some("mock_provider"),
+    )?;
+    let _other = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-01T12-00-00",
+        "2025-01-01T12:00:00Z",
+        "Write a migration for the users table",
+        Some("mock_provider"),
+    )?;
+    let _other_provider = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-01T13-00-00",
+        "2025-01-01T13:00:00Z",
+        "Another flaky migration",
+        Some("other_provider"),
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let search_id = mcp
+        .send_thread_search_request(ThreadSearchParams {
+            query: "FLAKY migration".to_string(),
+            limit: None,
+            model_providers: Some(vec!["mock_provider".to_string()]),
+        })
+        .await?;
+    let search_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(search_id)),
+    )
+    .await??;
+    let ThreadSearchResponse { data } = to_response::<ThreadSearchResponse>(search_resp)?;
+
+    assert_eq!(data.len(), 1);
+    let result = &data[0];
+    assert_eq!(result.thread.id, flaky);
+    assert_eq!(result.thread.preview, "The migration test is flaky on CI");
+    assert_eq!(result.thread.model_provider, "mock_provider");
+    assert_eq!(result.thread.created_at, 1735819200);
+    assert_eq!(result.snippet, "The migration test is flaky on CI");
+
+    // An empty provider list searches every provider.
+    let search_id = mcp
+        .send_thread_search_request(ThreadSearchParams {
+            query: "flaky".to_string(),
+            limit: Some(10),
+            model_providers: Some(Vec::new()),
+        })
+        .await?;
+    let search_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(search_id)),
+    )
+    .await??;
+    let ThreadSearchResponse { data } = to_response::<ThreadSearchResponse>(search_resp)?;
+    assert_eq!(data.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn thread_search_rejects_empty_query() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_minimal_config(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let search_id = mcp
+        .send_thread_search_request(ThreadSearchParams {
+            query: "   ".to_string(),
+            limit: None,
+            model_providers: None,
+        })
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(search_id)),
+    )
+    .await??;
+    assert_eq!(error.error.message, "query must not be empty");
+
+    Ok(())
+}
+
+// Minimal config.toml for searching.
+fn create_minimal_config(codex_home: &std::path::Path) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        r#"
+model = "mock-model"
+approval_policy = "never"
+"#,
+    )
+}
@@ -384,8 +384,11 @@ async fn turn_start_updates_sandbox_and_cwd_between_turns_v2() -> Result<()> {
             sandbox_policy: Some(codex_app_server_protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![first_cwd.clone()],
                 network_access: false,
+                network_allowlist: vec![],
+                read_only_subpaths: vec![],
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                container: false,
             }),
             model: Some("mock-model".to_string()),
             effort: Some(ReasoningEffort::Medium),
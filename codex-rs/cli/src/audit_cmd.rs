@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_core::audit::AUDIT_LOG_FILENAME;
+use codex_core::audit::AuditVerification;
+use codex_core::audit::verify_audit_log;
+use codex_core::config::find_codex_home;
+
+/// Inspect the audit log of side-effecting actions.
+///
+/// Subcommands:
+/// - `verify` — check that no entry was edited, removed, reordered or truncated
+#[derive(Debug, clap::Parser)]
+pub struct AuditCli {
+    #[command(subcommand)]
+    pub subcommand: AuditSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum AuditSubcommand {
+    /// Verify the hash chain of the audit log.
+    Verify(VerifyArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct VerifyArgs {
+    /// Audit log to verify. Defaults to `$CODEX_HOME/audit.jsonl`.
+    #[arg(value_name = "FILE")]
+    pub path: Option<PathBuf>,
+}
+
+impl AuditCli {
+    pub async fn run(self) -> Result<()> {
+        match self.subcommand {
+            AuditSubcommand::Verify(args) => run_verify(args),
+        }
+    }
+}
+
+fn run_verify(args: VerifyArgs) -> Result<()> {
+    let path = match args.path {
+        Some(path) => path,
+        None => find_codex_home()
+            .context("failed to resolve CODEX_HOME")?
+            .join(AUDIT_LOG_FILENAME),
+    };
+    let verification = verify_audit_log(&path)
+        .with_context(|| format!("failed to read audit log {}", path.display()))?;
+    match verification {
+        AuditVerification::Intact { entries, head_hash } => {
+            println!(
+                "{}: {entries} entries, hash chain intact, head {head_hash}.",
+                path.display()
+            );
+            Ok(())
+        }
+        AuditVerification::Broken { line, reason } => {
+            bail!("{}:{line}: hash chain broken: {reason}", path.display())
+        }
+    }
+}
@@ -206,6 +206,7 @@ async fn run_command_under_sandbox(
                 cwd,
                 &config.sandbox_policy,
                 sandbox_policy_cwd.as_path(),
+                &config.sandbox_read_deny,
                 stdio_policy,
                 env,
             )
@@ -222,6 +223,7 @@ async fn run_command_under_sandbox(
                 cwd,
                 &config.sandbox_policy,
                 sandbox_policy_cwd.as_path(),
+                &config.sandbox_read_deny,
                 stdio_policy,
                 env,
             )
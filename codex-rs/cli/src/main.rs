@@ -26,11 +26,17 @@ use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod audit_cmd;
 mod mcp_cmd;
+mod secrets_cmd;
+mod sessions_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::audit_cmd::AuditCli;
 use crate::mcp_cmd::McpCli;
+use crate::secrets_cmd::SecretsCli;
+use crate::sessions_cmd::SessionsCli;
 
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -100,6 +106,15 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Search recorded sessions.
+    Sessions(SessionsCli),
+
+    /// Verify the audit log of commands, approvals and patches.
+    Audit(AuditCli),
+
+    /// Store or remove secrets injected into shell commands.
+    Secrets(SecretsCli),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -460,6 +475,15 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
             handle_app_exit(exit_info)?;
         }
+        Some(Subcommand::Sessions(sessions_cli)) => {
+            sessions_cli.run().await?;
+        }
+        Some(Subcommand::Audit(audit_cli)) => {
+            audit_cli.run().await?;
+        }
+        Some(Subcommand::Secrets(secrets_cli)) => {
+            secrets_cli.run().await?;
+        }
         Some(Subcommand::Login(mut login_cli)) => {
             prepend_config_flags(
                 &mut login_cli.config_overrides,
@@ -0,0 +1,71 @@
+use std::io::IsTerminal;
+use std::io::Read;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_core::env_secrets::EnvSecretStore;
+
+/// Manage secrets that `shell_environment_policy` injects into commands.
+///
+/// Subcommands:
+/// - `set` — store a secret in the OS keyring
+/// - `delete` — remove a stored secret
+#[derive(Debug, clap::Parser)]
+pub struct SecretsCli {
+    #[command(subcommand)]
+    pub subcommand: SecretsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SecretsSubcommand {
+    /// Store a secret, read from stdin, in the OS keyring.
+    Set(SecretNameArgs),
+
+    /// Remove a secret from the OS keyring.
+    Delete(SecretNameArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SecretNameArgs {
+    /// Name used as `{ secret = "<NAME>" }` in `config.toml`.
+    #[arg(value_name = "NAME")]
+    pub name: String,
+}
+
+impl SecretsCli {
+    pub async fn run(self) -> Result<()> {
+        let store = EnvSecretStore::default();
+        match self.subcommand {
+            SecretsSubcommand::Set(args) => run_set(&store, &args.name),
+            SecretsSubcommand::Delete(args) => run_delete(&store, &args.name),
+        }
+    }
+}
+
+fn run_set(store: &EnvSecretStore, name: &str) -> Result<()> {
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        eprintln!("Enter the value for `{name}`, then press Ctrl-D:");
+    }
+    let mut buffer = String::new();
+    stdin
+        .read_to_string(&mut buffer)
+        .context("failed to read secret from stdin")?;
+    let value = buffer.trim_end_matches(['\r', '\n']);
+    if value.is_empty() {
+        bail!("no value provided for secret `{name}`");
+    }
+    store.save(name, value)?;
+    println!("Saved secret `{name}`.");
+    Ok(())
+}
+
+fn run_delete(store: &EnvSecretStore, name: &str) -> Result<()> {
+    if store.delete(name)? {
+        println!("Deleted secret `{name}`.");
+    } else {
+        println!("No secret named `{name}` was stored.");
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_core::INTERACTIVE_SESSION_SOURCES;
+use codex_core::RolloutRecorder;
+use codex_core::config::find_codex_home;
+use codex_core::find_conversation_path_by_id_str;
+use codex_tui::ExportFormat;
+use codex_tui::export_rollout;
+
+/// Inspect recorded sessions.
+///
+/// Subcommands:
+/// - `search` — full-text search over recorded sessions (with `--json`)
+/// - `export` — render a session as a Markdown, HTML or JSON transcript
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// Search recorded sessions for messages, commands and file paths.
+    Search(SearchArgs),
+
+    /// Export a recorded session as a readable transcript.
+    Export(ExportArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchArgs {
+    /// Words to search for. A session matches when it contains all of them.
+    #[arg(value_name = "QUERY", required = true, num_args = 1..)]
+    pub query: Vec<String>,
+
+    /// Maximum number of sessions to show.
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Output the matching sessions as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ExportArgs {
+    /// Conversation/session id (UUID) or path to a rollout file.
+    #[arg(value_name = "SESSION_ID")]
+    pub session: String,
+
+    /// Transcript format.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Markdown)]
+    pub format: ExportFormat,
+
+    /// Write the transcript to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        match self.subcommand {
+            SessionsSubcommand::Search(args) => run_search(args).await,
+            SessionsSubcommand::Export(args) => run_export(args).await,
+        }
+    }
+}
+
+async fn run_search(args: SearchArgs) -> Result<()> {
+    let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+    let query = args.query.join(" ");
+    let hits = RolloutRecorder::search_conversations(
+        &codex_home,
+        &query,
+        INTERACTIVE_SESSION_SOURCES,
+        args.limit,
+    )
+    .await
+    .context("failed to search sessions")?;
+
+    if args.json {
+        let json_hits: Vec<_> = hits
+            .iter()
+            .map(|hit| {
+                serde_json::json!({
+                    "id": hit.conversation_id.to_string(),
+                    "path": hit.path,
+                    "created_at": hit.created_at,
+                    "cwd": hit.cwd,
+                    "model_provider": hit.model_provider,
+                    "preview": hit.preview,
+                    "snippet": hit.snippet,
+                })
+            })
+            .collect();
+        let output = serde_json::to_string_pretty(&json_hits)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("No sessions match '{query}'.");
+        return Ok(());
+    }
+
+    for hit in &hits {
+        println!(
+            "{}  {}  {}",
+            hit.conversation_id,
+            hit.created_at,
+            hit.cwd.display()
+        );
+        println!("    {}", hit.snippet);
+    }
+    println!();
+    println!("Resume a session with `codex resume <SESSION_ID>`.");
+    Ok(())
+}
+
+async fn run_export(args: ExportArgs) -> Result<()> {
+    let ExportArgs {
+        session,
+        format,
+        output,
+    } = args;
+
+    let rollout_path = if PathBuf::from(&session).is_file() {
+        PathBuf::from(&session)
+    } else {
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        find_conversation_path_by_id_str(&codex_home, &session)
+            .await
+            .context("failed to look up session")?
+            .ok_or_else(|| anyhow!("No session found with id '{session}'."))?
+    };
+
+    let transcript = export_rollout(&rollout_path, format)
+        .await
+        .with_context(|| format!("failed to read session {}", rollout_path.display()))?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, transcript)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!("Exported session to {}.", path.display());
+        }
+        None => print!("{transcript}"),
+    }
+    Ok(())
+}
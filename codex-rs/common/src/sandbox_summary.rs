@@ -7,10 +7,17 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
         SandboxPolicy::WorkspaceWrite {
             writable_roots,
             network_access,
+            network_allowlist,
+            read_only_subpaths: _,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
+            container,
         } => {
-            let mut summary = "workspace-write".to_string();
+            let mut summary = if *container {
+                "container".to_string()
+            } else {
+                "workspace-write".to_string()
+            };
 
             let mut writable_entries = Vec::<String>::new();
             writable_entries.push("workdir".to_string());
@@ -29,6 +36,11 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             summary.push_str(&format!(" [{}]", writable_entries.join(", ")));
             if *network_access {
                 summary.push_str(" (network access enabled)");
+            } else if !network_allowlist.is_empty() {
+                summary.push_str(&format!(
+                    " (network limited to {})",
+                    network_allowlist.join(", ")
+                ));
             }
             summary
         }
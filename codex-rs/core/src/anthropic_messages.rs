@@ -0,0 +1,564 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::ModelProviderInfo;
+use crate::chat_completions::send_streaming_request;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::client_common::split_base64_data_url;
+use crate::client_common::thinking_budget_for_effort;
+use crate::default_client::CodexHttpClient;
+use crate::error::CodexErr;
+use crate::error::ResponseStreamFailed;
+use crate::error::Result;
+use crate::model_family::ModelFamily;
+use crate::protocol::TokenUsage;
+use crate::tools::spec::create_tools_json_for_anthropic_api;
+use bytes::Bytes;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ResponseItem;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+const ANTHROPIC_VERSION_HEADER: &str = "anthropic-version";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// `max_tokens` is mandatory for the Messages API.
+const DEFAULT_MAX_OUTPUT_TOKENS: i64 = 8_192;
+
+/// Implementation for the Anthropic Messages API.
+pub(crate) async fn stream_anthropic_messages(
+    prompt: &Prompt,
+    model_family: &ModelFamily,
+    effort: Option<ReasoningEffortConfig>,
+    max_output_tokens: Option<i64>,
+    client: &CodexHttpClient,
+    provider: &ModelProviderInfo,
+    otel_event_manager: &OtelEventManager,
+) -> Result<ResponseStream> {
+    if prompt.output_schema.is_some() {
+        return Err(CodexErr::UnsupportedOperation(
+            "output_schema is not supported for the Anthropic Messages API".to_string(),
+        ));
+    }
+
+    let payload = build_request_payload(prompt, model_family, effort, max_output_tokens)?;
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None, &model_family.slug),
+        payload
+    );
+
+    let has_version_header = provider.http_headers.as_ref().is_some_and(|headers| {
+        headers
+            .keys()
+            .any(|k| k.eq_ignore_ascii_case(ANTHROPIC_VERSION_HEADER))
+    });
+    let mut extra_headers = Vec::new();
+    if !has_version_header {
+        extra_headers.push((ANTHROPIC_VERSION_HEADER, ANTHROPIC_VERSION.to_string()));
+    }
+
+    let resp = send_streaming_request(
+        client,
+        provider,
+        otel_event_manager,
+        &model_family.slug,
+        &extra_headers,
+        &payload,
+    )
+    .await?;
+
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+    let stream = resp.bytes_stream().map_err(|e| {
+        CodexErr::ResponseStreamFailed(ResponseStreamFailed {
+            source: e,
+            request_id: None,
+        })
+    });
+    tokio::spawn(process_anthropic_sse(
+        stream,
+        tx_event,
+        provider.stream_idle_timeout(),
+        otel_event_manager.clone(),
+    ));
+    Ok(ResponseStream { rx_event })
+}
+
+fn build_request_payload(
+    prompt: &Prompt,
+    model_family: &ModelFamily,
+    effort: Option<ReasoningEffortConfig>,
+    max_output_tokens: Option<i64>,
+) -> Result<Value> {
+    let mut messages = MessagesBuilder::default();
+
+    for item in prompt.get_formatted_input() {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                // The Messages API only knows `user` and `assistant`; system
+                // and developer messages are folded into the user turn.
+                let role = if role == "assistant" {
+                    "assistant"
+                } else {
+                    "user"
+                };
+                for c in content {
+                    match c {
+                        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                            if !text.is_empty() {
+                                messages.push(role, json!({"type": "text", "text": text}));
+                            }
+                        }
+                        ContentItem::InputImage { image_url } => {
+                            messages.push(role, image_block(&image_url));
+                        }
+                    }
+                }
+            }
+            ResponseItem::Reasoning {
+                content,
+                encrypted_content: Some(signature),
+                ..
+            } => {
+                // Thinking blocks must be echoed back verbatim (with their
+                // signature) while the model is in a tool-use loop.
+                let thinking = content
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|entry| match entry {
+                        ReasoningItemContent::ReasoningText { text }
+                        | ReasoningItemContent::Text { text } => text,
+                    })
+                    .collect::<String>();
+                if thinking.is_empty() {
+                    messages.push(
+                        "assistant",
+                        json!({"type": "redacted_thinking", "data": signature}),
+                    );
+                } else {
+                    messages.push(
+                        "assistant",
+                        json!({"type": "thinking", "thinking": thinking, "signature": signature}),
+                    );
+                }
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let input = serde_json::from_str::<Value>(&arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                messages.push(
+                    "assistant",
+                    json!({"type": "tool_use", "id": call_id, "name": name, "input": input}),
+                );
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let content = match &output.content_items {
+                    Some(items) => json!(
+                        items
+                            .iter()
+                            .map(|it| match it {
+                                FunctionCallOutputContentItem::InputText { text } => {
+                                    json!({"type": "text", "text": text})
+                                }
+                                FunctionCallOutputContentItem::InputImage { image_url } => {
+                                    image_block(image_url)
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    ),
+                    None => json!(output.content),
+                };
+                messages.push(
+                    "user",
+                    json!({
+                        "type": "tool_result",
+                        "tool_use_id": call_id,
+                        "content": content,
+                        "is_error": output.success == Some(false),
+                    }),
+                );
+            }
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                messages.push(
+                    "assistant",
+                    json!({"type": "tool_use", "id": call_id, "name": name, "input": {"input": input}}),
+                );
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                messages.push(
+                    "user",
+                    json!({"type": "tool_result", "tool_use_id": call_id, "content": output}),
+                );
+            }
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::LocalShellCall { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::GhostSnapshot { .. }
+            | ResponseItem::Other => {
+                // Not representable in the Messages API.
+                continue;
+            }
+        }
+    }
+
+    let thinking_budget = thinking_budget_for_effort(effort);
+    let mut max_tokens = max_output_tokens.unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS);
+    if let Some(budget) = thinking_budget {
+        // The thinking budget counts against `max_tokens`.
+        max_tokens = max_tokens.max(budget + DEFAULT_MAX_OUTPUT_TOKENS);
+    }
+
+    let mut payload = json!({
+        "model": model_family.slug,
+        "max_tokens": max_tokens,
+        "system": prompt.get_full_instructions(model_family),
+        "messages": messages.messages,
+        "stream": true,
+    });
+    let Some(obj) = payload.as_object_mut() else {
+        return Ok(payload);
+    };
+
+    let tools_json = create_tools_json_for_anthropic_api(&prompt.tools)?;
+    if !tools_json.is_empty() {
+        obj.insert("tools".to_string(), json!(tools_json));
+        obj.insert(
+            "tool_choice".to_string(),
+            json!({"type": "auto", "disable_parallel_tool_use": !prompt.parallel_tool_calls}),
+        );
+    }
+    if let Some(budget) = thinking_budget {
+        obj.insert(
+            "thinking".to_string(),
+            json!({"type": "enabled", "budget_tokens": budget}),
+        );
+    }
+
+    Ok(payload)
+}
+
+fn image_block(image_url: &str) -> Value {
+    match split_base64_data_url(image_url) {
+        Some((media_type, data)) => json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": media_type, "data": data},
+        }),
+        None => json!({
+            "type": "image",
+            "source": {"type": "url", "url": image_url},
+        }),
+    }
+}
+
+/// Builds the `messages` array, merging consecutive blocks for the same role
+/// into one message as the API requires alternating roles.
+#[derive(Default)]
+struct MessagesBuilder {
+    messages: Vec<Value>,
+}
+
+impl MessagesBuilder {
+    fn push(&mut self, role: &str, block: Value) {
+        if let Some(last) = self.messages.last_mut()
+            && last.get("role").and_then(Value::as_str) == Some(role)
+            && let Some(content) = last.get_mut("content").and_then(Value::as_array_mut)
+        {
+            content.push(block);
+            return;
+        }
+        self.messages
+            .push(json!({"role": role, "content": [block]}));
+    }
+}
+
+/// In-flight content block, keyed by its `index` in the SSE stream.
+enum BlockState {
+    Text {
+        text: String,
+    },
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    RedactedThinking {
+        data: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input_json: String,
+    },
+}
+
+/// SSE processor for the Anthropic Messages streaming format. Each content
+/// block is surfaced as its own [`ResponseItem`] so the rest of the pipeline
+/// sees the same item lifecycle as with the Responses API.
+async fn process_anthropic_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+    idle_timeout: Duration,
+    otel_event_manager: OtelEventManager,
+) where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+
+    let mut blocks: HashMap<u64, BlockState> = HashMap::new();
+    let mut response_id = String::new();
+    let mut token_usage = TokenUsage::default();
+
+    loop {
+        let start = std::time::Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        let duration = start.elapsed();
+        otel_event_manager.log_sse_event(&response, duration);
+
+        let sse = match response {
+            Ok(Some(Ok(ev))) => ev,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(e.to_string(), None)))
+                    .await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "stream closed before message_stop".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "idle timeout waiting for SSE".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+        };
+
+        let event: Value = match serde_json::from_str(&sse.data) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        trace!("anthropic received SSE event: {event:?}");
+
+        let index = event.get("index").and_then(Value::as_u64).unwrap_or(0);
+        match event
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+        {
+            "message_start" => {
+                let message = event.get("message");
+                if let Some(id) = message.and_then(|m| m.get("id")).and_then(Value::as_str) {
+                    response_id = id.to_string();
+                }
+                if let Some(usage) = message.and_then(|m| m.get("usage")) {
+                    let cached = usage_field(usage, "cache_read_input_tokens");
+                    token_usage.cached_input_tokens = cached;
+                    token_usage.input_tokens = usage_field(usage, "input_tokens")
+                        + usage_field(usage, "cache_creation_input_tokens")
+                        + cached;
+                }
+                let _ = tx_event.send(Ok(ResponseEvent::Created)).await;
+            }
+            "content_block_start" => {
+                let Some(block) = event.get("content_block") else {
+                    continue;
+                };
+                let str_field = |key: &str| {
+                    block
+                        .get(key)
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                let state = match block.get("type").and_then(Value::as_str) {
+                    Some("text") => {
+                        let item = ResponseItem::Message {
+                            id: None,
+                            role: "assistant".to_string(),
+                            content: vec![],
+                        };
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        let text = str_field("text");
+                        if !text.is_empty() {
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::OutputTextDelta(text.clone())))
+                                .await;
+                        }
+                        BlockState::Text { text }
+                    }
+                    Some("thinking") => {
+                        let item = ResponseItem::Reasoning {
+                            id: String::new(),
+                            summary: Vec::new(),
+                            content: Some(vec![]),
+                            encrypted_content: None,
+                        };
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        BlockState::Thinking {
+                            thinking: str_field("thinking"),
+                            signature: str_field("signature"),
+                        }
+                    }
+                    Some("redacted_thinking") => BlockState::RedactedThinking {
+                        data: str_field("data"),
+                    },
+                    Some("tool_use") => BlockState::ToolUse {
+                        id: str_field("id"),
+                        name: str_field("name"),
+                        input_json: String::new(),
+                    },
+                    _ => continue,
+                };
+                blocks.insert(index, state);
+            }
+            "content_block_delta" => {
+                let Some(delta) = event.get("delta") else {
+                    continue;
+                };
+                let delta_str = |key: &str| delta.get(key).and_then(Value::as_str);
+                match (blocks.get_mut(&index), delta_str("type")) {
+                    (Some(BlockState::Text { text }), Some("text_delta")) => {
+                        if let Some(fragment) = delta_str("text") {
+                            text.push_str(fragment);
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::OutputTextDelta(fragment.to_string())))
+                                .await;
+                        }
+                    }
+                    (Some(BlockState::Thinking { thinking, .. }), Some("thinking_delta")) => {
+                        if let Some(fragment) = delta_str("thinking") {
+                            thinking.push_str(fragment);
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::ReasoningContentDelta {
+                                    delta: fragment.to_string(),
+                                    content_index: 0,
+                                }))
+                                .await;
+                        }
+                    }
+                    (Some(BlockState::Thinking { signature, .. }), Some("signature_delta")) => {
+                        if let Some(fragment) = delta_str("signature") {
+                            signature.push_str(fragment);
+                        }
+                    }
+                    (Some(BlockState::ToolUse { input_json, .. }), Some("input_json_delta")) => {
+                        if let Some(fragment) = delta_str("partial_json") {
+                            input_json.push_str(fragment);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            "content_block_stop" => {
+                let item = match blocks.remove(&index) {
+                    Some(BlockState::Text { text }) => ResponseItem::Message {
+                        id: None,
+                        role: "assistant".to_string(),
+                        content: vec![ContentItem::OutputText { text }],
+                    },
+                    // Store thinking as `Text` so it survives rollout
+                    // serialization: Anthropic needs it echoed back verbatim.
+                    Some(BlockState::Thinking {
+                        thinking,
+                        signature,
+                    }) => ResponseItem::Reasoning {
+                        id: String::new(),
+                        summary: Vec::new(),
+                        content: Some(vec![ReasoningItemContent::Text { text: thinking }]),
+                        encrypted_content: Some(signature),
+                    },
+                    Some(BlockState::RedactedThinking { data }) => ResponseItem::Reasoning {
+                        id: String::new(),
+                        summary: Vec::new(),
+                        content: None,
+                        encrypted_content: Some(data),
+                    },
+                    Some(BlockState::ToolUse {
+                        id,
+                        name,
+                        input_json,
+                    }) => ResponseItem::FunctionCall {
+                        id: None,
+                        name,
+                        arguments: if input_json.trim().is_empty() {
+                            "{}".to_string()
+                        } else {
+                            input_json
+                        },
+                        call_id: id,
+                    },
+                    None => continue,
+                };
+                let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+            }
+            "message_delta" => {
+                if let Some(usage) = event.get("usage") {
+                    token_usage.output_tokens = usage_field(usage, "output_tokens");
+                }
+            }
+            "message_stop" => {
+                token_usage.total_tokens = token_usage.input_tokens + token_usage.output_tokens;
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage: Some(token_usage),
+                    }))
+                    .await;
+                return;
+            }
+            "error" => {
+                let message = event
+                    .get("error")
+                    .and_then(|e| e.get("message"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown error")
+                    .to_string();
+                let _ = tx_event.send(Err(CodexErr::Stream(message, None))).await;
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn usage_field(usage: &Value, key: &str) -> i64 {
+    usage.get(key).and_then(Value::as_i64).unwrap_or(0)
+}
@@ -0,0 +1,583 @@
+//! Tamper-evident audit log of side-effecting actions.
+//!
+//! When enabled under `[audit]`, every approval decision, sandbox attempt,
+//! network proxy denial, executed command, applied patch and MCP tool call is
+//! appended to `~/.codex/audit.jsonl`. Unlike rollouts, the log is shared by
+//! all sessions and is never rewritten. Each line is a JSON object of the form
+//!
+//! ````text
+//! {"seq":7,"timestamp":"…","conversation_id":"…","prev_hash":"…","type":"command",…,"hash":"…"}
+//! ````
+//!
+//! where `hash` is the HMAC-SHA256 of the line with the trailing `hash` member
+//! removed, and `prev_hash` is the `hash` of the previous line. The HMAC key is
+//! generated on first use and kept next to the log in `audit.key`, and the
+//! sequence number and hash of the last entry are kept, also under the key, in
+//! `audit.head`. Editing, removing, reordering or truncating lines, or
+//! recomputing the chain without the key, is detected by [`verify_audit_log`].
+//! Anyone who can read the key can still forge a valid log.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::SecondsFormat;
+use chrono::Utc;
+use codex_otel::otel_event_manager::ToolDecisionSource;
+use codex_protocol::ConversationId;
+use hmac::Hmac;
+use hmac::Mac;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::config::types::AuditConfig;
+use crate::exec::SandboxType;
+use crate::protocol::ReviewDecision;
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+/// Filename of the audit log inside `~/.codex`.
+pub const AUDIT_LOG_FILENAME: &str = "audit.jsonl";
+
+/// `prev_hash` of the first entry.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+const KEY_LEN: usize = 32;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A side-effecting action recorded in the audit log.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum AuditEvent {
+    /// Outcome of the approval step for a tool call.
+    ToolDecision {
+        call_id: String,
+        tool_name: String,
+        decision: ReviewDecision,
+        source: ToolDecisionSource,
+    },
+    /// A tool call is about to run. `escalated` is set when it runs without
+    /// the sandbox, either on request or after the sandbox denied it, and
+    /// `network_access` when it can reach any host.
+    SandboxAttempt {
+        call_id: String,
+        tool_name: String,
+        sandbox: &'static str,
+        escalated: bool,
+        network_access: bool,
+    },
+    /// The network proxy refused a connection outside `network_allowlist`.
+    NetworkDenied {
+        call_id: String,
+        host: String,
+        port: u16,
+    },
+    /// A command run by the shell tools finished or failed to start.
+    Command {
+        call_id: String,
+        command: Vec<String>,
+        cwd: PathBuf,
+        exit_code: i32,
+    },
+    /// A patch was applied, or failed to apply, to the given paths.
+    Patch {
+        call_id: String,
+        paths: Vec<PathBuf>,
+        auto_approved: bool,
+        success: bool,
+    },
+    /// A tool of an MCP server was called.
+    McpToolCall {
+        call_id: String,
+        server: String,
+        tool: String,
+        success: bool,
+    },
+}
+
+/// Name of `sandbox` as written to the audit log.
+pub(crate) fn sandbox_name(sandbox: SandboxType) -> &'static str {
+    match sandbox {
+        SandboxType::None => "none",
+        SandboxType::MacosSeatbelt => "macos_seatbelt",
+        SandboxType::LinuxSeccomp => "linux_seccomp",
+        SandboxType::LinuxNamespaces => "linux_namespaces",
+        SandboxType::WindowsRestrictedToken => "windows_restricted_token",
+    }
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    seq: u64,
+    timestamp: String,
+    conversation_id: String,
+    prev_hash: &'a str,
+    #[serde(flatten)]
+    event: &'a AuditEvent,
+}
+
+/// The members of an entry that [`verify_audit_log`] checks.
+#[derive(Deserialize)]
+struct ChainLink {
+    seq: u64,
+    prev_hash: String,
+    hash: String,
+}
+
+/// Contents of `audit.head`: the last entry of the log, authenticated with the
+/// key so that removing entries from the end of the log is detected.
+#[derive(Serialize, Deserialize)]
+struct ChainHead {
+    seq: u64,
+    hash: String,
+    mac: String,
+}
+
+impl ChainHead {
+    fn new(key: &[u8], seq: u64, hash: String) -> std::io::Result<Self> {
+        let mac = mac_hex(key, &head_message(seq, &hash))?;
+        Ok(Self { seq, hash, mac })
+    }
+
+    fn is_authentic(&self, key: &[u8]) -> std::io::Result<bool> {
+        Ok(mac_hex(key, &head_message(self.seq, &self.hash))? == self.mac)
+    }
+}
+
+fn head_message(seq: u64, hash: &str) -> String {
+    format!("head:{seq}:{hash}")
+}
+
+/// Appends entries to the audit log of a session. Disabled logs drop every
+/// entry.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuditLog {
+    path: Option<PathBuf>,
+    conversation_id: String,
+}
+
+impl AuditLog {
+    pub(crate) fn new(
+        config: &AuditConfig,
+        codex_home: &Path,
+        conversation_id: ConversationId,
+    ) -> Self {
+        let path = config.enabled.then(|| {
+            config
+                .path
+                .clone()
+                .unwrap_or_else(|| codex_home.join(AUDIT_LOG_FILENAME))
+        });
+        Self {
+            path,
+            conversation_id: conversation_id.to_string(),
+        }
+    }
+
+    /// Appends `event` to the log. Failures are logged rather than returned
+    /// so that auditing never interrupts a turn.
+    pub(crate) async fn record(&self, event: AuditEvent) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let conversation_id = self.conversation_id.clone();
+        let result =
+            tokio::task::spawn_blocking(move || append_entry(&path, &conversation_id, &event))
+                .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => warn!("failed to write audit log entry: {err}"),
+            Err(err) => warn!("audit log writer panicked: {err}"),
+        }
+    }
+}
+
+fn append_entry(path: &Path, conversation_id: &str, event: &AuditEvent) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    options.append(true).read(true).create(true);
+    #[cfg(unix)]
+    {
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+
+    // Hold the lock while reading the tail so that concurrent sessions extend
+    // the same chain. This runs on a blocking thread, so wait for the lock
+    // rather than give up on the entry.
+    file.lock()?;
+    append_locked(path, &mut file, conversation_id, event)
+}
+
+fn append_locked(
+    path: &Path,
+    file: &mut File,
+    conversation_id: &str,
+    event: &AuditEvent,
+) -> std::io::Result<()> {
+    let last = last_line(file)?;
+    let key = load_or_create_key(&key_path(path), last.is_none())?;
+    let (seq, prev_hash) = match last {
+        Some(line) => {
+            let link: ChainLink = serde_json::from_str(&line).map_err(|err| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("last audit log entry is malformed: {err}"),
+                )
+            })?;
+            (link.seq + 1, link.hash)
+        }
+        None => (0, GENESIS_HASH.to_string()),
+    };
+    let entry = AuditEntry {
+        seq,
+        timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        conversation_id: conversation_id.to_string(),
+        prev_hash: &prev_hash,
+        event,
+    };
+    let body = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+    let hash = mac_hex(&key, &body)?;
+    let unclosed = body.strip_suffix('}').unwrap_or(&body);
+    file.write_all(format!("{unclosed},\"hash\":\"{hash}\"}}\n").as_bytes())?;
+    file.flush()?;
+
+    let head = ChainHead::new(&key, seq, hash)?;
+    let head = serde_json::to_string(&head).map_err(std::io::Error::other)?;
+    std::fs::write(head_path(path), head)
+}
+
+fn mac_hex(key: &[u8], data: &str) -> std::io::Result<String> {
+    let mut mac = HmacSha256::new_from_slice(key).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid audit log key")
+    })?;
+    mac.update(data.as_bytes());
+    Ok(format!("{:x}", mac.finalize().into_bytes()))
+}
+
+/// Path of the HMAC key of the log at `path`.
+fn key_path(path: &Path) -> PathBuf {
+    path.with_extension("key")
+}
+
+/// Path of the authenticated head of the log at `path`.
+fn head_path(path: &Path) -> PathBuf {
+    path.with_extension("head")
+}
+
+/// Reads the key of the log, generating it when the log is still empty. A log
+/// with entries but no key cannot be extended without breaking the chain.
+fn load_or_create_key(path: &Path, log_is_empty: bool) -> std::io::Result<Vec<u8>> {
+    match std::fs::read(path) {
+        Ok(key) => return Ok(key),
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound || !log_is_empty => {
+            return Err(std::io::Error::new(
+                err.kind(),
+                format!("cannot read audit log key {}: {err}", path.display()),
+            ));
+        }
+        Err(_) => {}
+    }
+    let key: [u8; KEY_LEN] = rand::random();
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(&key)?;
+    Ok(key.to_vec())
+}
+
+/// Reads the last non-empty line of `file` without loading the whole file.
+fn last_line(file: &mut File) -> std::io::Result<Option<String>> {
+    const CHUNK: u64 = 4096;
+    let len = file.seek(SeekFrom::End(0))?;
+    let mut tail = Vec::new();
+    let mut pos = len;
+    while pos > 0 {
+        let start = pos.saturating_sub(CHUNK);
+        let mut chunk = vec![0; (pos - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&tail);
+        tail = chunk;
+        pos = start;
+        let trimmed = tail.trim_ascii_end();
+        if trimmed.contains(&b'\n') {
+            break;
+        }
+    }
+    let trimmed = tail.trim_ascii_end();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let line = match trimmed.iter().rposition(|b| *b == b'\n') {
+        Some(newline) => &trimmed[newline + 1..],
+        None => trimmed,
+    };
+    Ok(Some(String::from_utf8_lossy(line).into_owned()))
+}
+
+/// Result of checking the hash chain of an audit log.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AuditVerification {
+    /// Every entry is intact, linked to its predecessor, and the last one is
+    /// the entry recorded in `audit.head`. `head_hash` is the `hash` of that
+    /// entry, which can be kept elsewhere to compare against later.
+    Intact { entries: u64, head_hash: String },
+    /// The chain is broken at the given 1-based line.
+    Broken { line: usize, reason: String },
+}
+
+/// Checks that every entry of the audit log at `path` carries the HMAC of its
+/// contents under the log's key, links to the previous entry, and that the
+/// log ends at the entry recorded in `audit.head`.
+///
+/// This detects any change made without the key. It cannot detect a log that
+/// was rewritten by someone who could read the key, nor a log and head that
+/// were both rolled back to an earlier copy.
+pub fn verify_audit_log(path: &Path) -> std::io::Result<AuditVerification> {
+    let reader = BufReader::new(File::open(path)?);
+    let key = std::fs::read(key_path(path))?;
+    let mut prev_hash = GENESIS_HASH.to_string();
+    let mut entries = 0;
+    let mut last_line_number = 0;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let broken = |reason: String| AuditVerification::Broken {
+            line: line_number,
+            reason,
+        };
+        let link: ChainLink = match serde_json::from_str(&line) {
+            Ok(link) => link,
+            Err(err) => return Ok(broken(format!("malformed entry: {err}"))),
+        };
+        let suffix = format!(",\"hash\":\"{}\"}}", link.hash);
+        let Some(body) = line.strip_suffix(&suffix) else {
+            return Ok(broken("`hash` is not the last member".to_string()));
+        };
+        if mac_hex(&key, &format!("{body}}}"))? != link.hash {
+            return Ok(broken("entry does not match its hash".to_string()));
+        }
+        if link.prev_hash != prev_hash {
+            return Ok(broken(
+                "entry does not link to the previous one".to_string(),
+            ));
+        }
+        if link.seq != entries {
+            return Ok(broken(format!(
+                "expected sequence number {entries}, found {}",
+                link.seq
+            )));
+        }
+        prev_hash = link.hash;
+        entries += 1;
+        last_line_number = line_number;
+    }
+
+    let broken = |reason: &str| AuditVerification::Broken {
+        line: last_line_number + 1,
+        reason: reason.to_string(),
+    };
+    let head = match std::fs::read_to_string(head_path(path)) {
+        Ok(head) => head,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound && entries == 0 => {
+            return Ok(AuditVerification::Intact {
+                entries,
+                head_hash: prev_hash,
+            });
+        }
+        Err(err) => return Err(err),
+    };
+    let Ok(head) = serde_json::from_str::<ChainHead>(&head) else {
+        return Ok(broken("`audit.head` is malformed"));
+    };
+    if !head.is_authentic(&key)? {
+        return Ok(broken("`audit.head` does not match its MAC"));
+    }
+    if entries != head.seq + 1 {
+        return Ok(broken(&format!(
+            "log has {entries} entries but `audit.head` records {}",
+            head.seq + 1
+        )));
+    }
+    if prev_hash != head.hash {
+        return Ok(broken("last entry is not the one recorded in `audit.head`"));
+    }
+    Ok(AuditVerification::Intact {
+        entries,
+        head_hash: prev_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn command(call_id: &str) -> AuditEvent {
+        AuditEvent::Command {
+            call_id: call_id.to_string(),
+            command: vec!["rm".to_string(), "-rf".to_string(), "build".to_string()],
+            cwd: PathBuf::from("/repo"),
+            exit_code: 0,
+        }
+    }
+
+    fn enabled_log(codex_home: &Path) -> AuditLog {
+        let config = AuditConfig {
+            enabled: true,
+            path: None,
+        };
+        AuditLog::new(&config, codex_home, ConversationId::new())
+    }
+
+    #[tokio::test]
+    async fn entries_form_a_verifiable_chain() {
+        let codex_home = TempDir::new().unwrap();
+        let log = enabled_log(codex_home.path());
+        log.record(command("call-1")).await;
+        log.record(AuditEvent::ToolDecision {
+            call_id: "call-2".to_string(),
+            tool_name: "shell".to_string(),
+            decision: ReviewDecision::Approved,
+            source: ToolDecisionSource::User,
+        })
+        .await;
+        // A second session appends to the same chain.
+        enabled_log(codex_home.path())
+            .record(command("call-3"))
+            .await;
+
+        let path = codex_home.path().join(AUDIT_LOG_FILENAME);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let third: serde_json::Value =
+            serde_json::from_str(contents.lines().nth(2).unwrap()).unwrap();
+        assert_eq!(
+            verify_audit_log(&path).unwrap(),
+            AuditVerification::Intact {
+                entries: 3,
+                head_hash: third["hash"].as_str().unwrap().to_string(),
+            }
+        );
+
+        let second: serde_json::Value =
+            serde_json::from_str(contents.lines().nth(1).unwrap()).unwrap();
+        assert_eq!(second["type"], "tool_decision");
+        assert_eq!(second["source"], "user");
+        assert_eq!(second["decision"], "approved");
+    }
+
+    #[tokio::test]
+    async fn edited_and_removed_entries_break_the_chain() {
+        let codex_home = TempDir::new().unwrap();
+        let log = enabled_log(codex_home.path());
+        for call_id in ["call-1", "call-2", "call-3"] {
+            log.record(command(call_id)).await;
+        }
+        let path = codex_home.path().join(AUDIT_LOG_FILENAME);
+        let original = std::fs::read_to_string(&path).unwrap();
+
+        let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+        lines[1] = lines[1].replace("build", "src");
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        assert_eq!(
+            verify_audit_log(&path).unwrap(),
+            AuditVerification::Broken {
+                line: 2,
+                reason: "entry does not match its hash".to_string(),
+            }
+        );
+
+        let without_second: Vec<&str> = original
+            .lines()
+            .enumerate()
+            .filter(|(index, _)| *index != 1)
+            .map(|(_, line)| line)
+            .collect();
+        std::fs::write(&path, without_second.join("\n")).unwrap();
+        assert_eq!(
+            verify_audit_log(&path).unwrap(),
+            AuditVerification::Broken {
+                line: 2,
+                reason: "entry does not link to the previous one".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn truncated_and_rehashed_logs_are_detected() {
+        let codex_home = TempDir::new().unwrap();
+        let log = enabled_log(codex_home.path());
+        for call_id in ["call-1", "call-2", "call-3"] {
+            log.record(command(call_id)).await;
+        }
+        let path = codex_home.path().join(AUDIT_LOG_FILENAME);
+        let original = std::fs::read_to_string(&path).unwrap();
+
+        let first_two: Vec<&str> = original.lines().take(2).collect();
+        std::fs::write(&path, first_two.join("\n")).unwrap();
+        assert_eq!(
+            verify_audit_log(&path).unwrap(),
+            AuditVerification::Broken {
+                line: 3,
+                reason: "log has 2 entries but `audit.head` records 3".to_string(),
+            }
+        );
+
+        // Rebuilding the chain with a plain SHA-256 instead of the keyed MAC
+        // is rejected at the first entry.
+        let mut prev_hash = GENESIS_HASH.to_string();
+        let mut rehashed = Vec::new();
+        for line in original.lines() {
+            let mut entry: serde_json::Value = serde_json::from_str(line).unwrap();
+            let object = entry.as_object_mut().unwrap();
+            object.remove("hash");
+            object.insert("prev_hash".to_string(), prev_hash.clone().into());
+            let body = serde_json::to_string(&entry).unwrap();
+            let hash = format!("{:x}", <Sha256 as sha2::Digest>::digest(body.as_bytes()));
+            let unclosed = body.strip_suffix('}').unwrap();
+            rehashed.push(format!("{unclosed},\"hash\":\"{hash}\"}}"));
+            prev_hash = hash;
+        }
+        std::fs::write(&path, rehashed.join("\n")).unwrap();
+        assert_eq!(
+            verify_audit_log(&path).unwrap(),
+            AuditVerification::Broken {
+                line: 1,
+                reason: "entry does not match its hash".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn disabled_log_writes_nothing() {
+        let codex_home = TempDir::new().unwrap();
+        let log = AuditLog::new(
+            &AuditConfig::default(),
+            codex_home.path(),
+            ConversationId::new(),
+        );
+        log.record(command("call-1")).await;
+        assert!(!codex_home.path().join(AUDIT_LOG_FILENAME).exists());
+    }
+}
@@ -0,0 +1,249 @@
+//! Token and cost budgets for turns and sessions.
+//!
+//! The tracker accumulates the usage reported after every model response and
+//! compares it against the limits configured under `[budget]`. Crossing the
+//! warning threshold of a limit yields a one-time warning; reaching the limit
+//! means the turn must be aborted.
+
+use std::collections::HashSet;
+
+use crate::config::types::BudgetConfig;
+use crate::model_pricing::get_model_pricing;
+use crate::protocol::TokenUsage;
+
+const DEFAULT_WARN_AT: f64 = 0.8;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Spend {
+    tokens: i64,
+    cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Limit {
+    TurnTokens,
+    SessionTokens,
+    TurnCost,
+    SessionCost,
+}
+
+impl Limit {
+    fn is_turn_scoped(self) -> bool {
+        matches!(self, Limit::TurnTokens | Limit::TurnCost)
+    }
+
+    fn describe(self, used: f64, max: f64) -> String {
+        match self {
+            Limit::TurnTokens => format!("this turn used {used:.0} of {max:.0} tokens"),
+            Limit::SessionTokens => format!("this session used {used:.0} of {max:.0} tokens"),
+            Limit::TurnCost => format!("this turn cost ${used:.2} of ${max:.2}"),
+            Limit::SessionCost => format!("this session cost ${used:.2} of ${max:.2}"),
+        }
+    }
+}
+
+/// Outcome of comparing the current spend against the configured budgets.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct BudgetCheck {
+    /// Warnings that have not been reported yet.
+    pub(crate) warnings: Vec<String>,
+    /// Set when a limit has been reached and the turn must stop.
+    pub(crate) exceeded: Option<String>,
+}
+
+pub(crate) struct BudgetTracker {
+    config: BudgetConfig,
+    session: Spend,
+    turn: Spend,
+    warned: HashSet<Limit>,
+    unpriced_models: HashSet<String>,
+    pending_warnings: Vec<String>,
+}
+
+impl BudgetTracker {
+    pub(crate) fn new(config: BudgetConfig) -> Self {
+        Self {
+            config,
+            session: Spend::default(),
+            turn: Spend::default(),
+            warned: HashSet::new(),
+            unpriced_models: HashSet::new(),
+            pending_warnings: Vec::new(),
+        }
+    }
+
+    /// Reset the per-turn spend at the start of a new turn.
+    pub(crate) fn start_turn(&mut self) {
+        self.turn = Spend::default();
+        self.warned.retain(|limit| !limit.is_turn_scoped());
+    }
+
+    /// Add the usage reported for one model response.
+    pub(crate) fn record(&mut self, model: &str, usage: &TokenUsage) {
+        let cost_usd = match get_model_pricing(model, &self.config.pricing) {
+            Some(pricing) => pricing.cost_usd(usage),
+            None => {
+                if self.has_cost_limit() && self.unpriced_models.insert(model.to_string()) {
+                    self.pending_warnings.push(format!(
+                        "No pricing is known for model `{model}`, so its usage does not count \
+                         toward cost budgets. Add it under [budget.pricing] in config.toml."
+                    ));
+                }
+                0.0
+            }
+        };
+        for spend in [&mut self.session, &mut self.turn] {
+            spend.tokens += usage.total_tokens.max(0);
+            spend.cost_usd += cost_usd;
+        }
+    }
+
+    /// Compare the spend so far against every configured limit.
+    pub(crate) fn check(&mut self) -> BudgetCheck {
+        let mut check = BudgetCheck {
+            warnings: std::mem::take(&mut self.pending_warnings),
+            exceeded: None,
+        };
+        let warn_at = self.config.warn_at.unwrap_or(DEFAULT_WARN_AT);
+
+        for (limit, used, max) in self.limits() {
+            if max <= 0.0 {
+                continue;
+            }
+            if used >= max {
+                check.exceeded.get_or_insert_with(|| {
+                    format!("Budget exceeded: {}.", limit.describe(used, max))
+                });
+            } else if used >= max * warn_at && self.warned.insert(limit) {
+                check.warnings.push(format!(
+                    "Approaching budget: {}.",
+                    limit.describe(used, max)
+                ));
+            }
+        }
+        check
+    }
+
+    fn has_cost_limit(&self) -> bool {
+        self.config.turn_cost_usd.is_some() || self.config.session_cost_usd.is_some()
+    }
+
+    fn limits(&self) -> Vec<(Limit, f64, f64)> {
+        let config = &self.config;
+        [
+            (
+                Limit::TurnTokens,
+                self.turn.tokens as f64,
+                config.turn_tokens.map(|max| max as f64),
+            ),
+            (
+                Limit::SessionTokens,
+                self.session.tokens as f64,
+                config.session_tokens.map(|max| max as f64),
+            ),
+            (Limit::TurnCost, self.turn.cost_usd, config.turn_cost_usd),
+            (
+                Limit::SessionCost,
+                self.session.cost_usd,
+                config.session_cost_usd,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(limit, used, max)| max.map(|max| (limit, used, max)))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::ModelPricing;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn usage(total_tokens: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: total_tokens,
+            cached_input_tokens: 0,
+            output_tokens: 0,
+            reasoning_output_tokens: 0,
+            total_tokens,
+        }
+    }
+
+    #[test]
+    fn warns_once_then_reports_exceeded_turn_tokens() {
+        let mut tracker = BudgetTracker::new(BudgetConfig {
+            turn_tokens: Some(1_000),
+            ..Default::default()
+        });
+        tracker.start_turn();
+
+        tracker.record("gpt-5", &usage(850));
+        assert_eq!(
+            tracker.check(),
+            BudgetCheck {
+                warnings: vec![
+                    "Approaching budget: this turn used 850 of 1000 tokens.".to_string()
+                ],
+                exceeded: None,
+            }
+        );
+
+        tracker.record("gpt-5", &usage(100));
+        assert_eq!(tracker.check(), BudgetCheck::default());
+
+        tracker.record("gpt-5", &usage(50));
+        assert_eq!(
+            tracker.check().exceeded.as_deref(),
+            Some("Budget exceeded: this turn used 1000 of 1000 tokens.")
+        );
+
+        // A new turn starts from zero while the session keeps accumulating.
+        tracker.start_turn();
+        tracker.record("gpt-5", &usage(100));
+        assert_eq!(tracker.check(), BudgetCheck::default());
+    }
+
+    #[test]
+    fn session_cost_uses_configured_pricing() {
+        let mut tracker = BudgetTracker::new(BudgetConfig {
+            session_cost_usd: Some(1.0),
+            pricing: HashMap::from([(
+                "custom".to_string(),
+                ModelPricing {
+                    input: 1.0,
+                    cached_input: None,
+                    output: 1.0,
+                },
+            )]),
+            ..Default::default()
+        });
+
+        tracker.start_turn();
+        tracker.record("custom", &usage(700_000));
+        tracker.start_turn();
+        tracker.record("custom", &usage(400_000));
+
+        assert_eq!(
+            tracker.check().exceeded.as_deref(),
+            Some("Budget exceeded: this session cost $1.10 of $1.00.")
+        );
+    }
+
+    #[test]
+    fn unpriced_model_is_reported_once_when_cost_limits_are_set() {
+        let mut tracker = BudgetTracker::new(BudgetConfig {
+            session_cost_usd: Some(5.0),
+            ..Default::default()
+        });
+
+        tracker.record("my-local-model", &usage(10));
+        tracker.record("my-local-model", &usage(10));
+
+        let check = tracker.check();
+        assert_eq!(check.warnings.len(), 1);
+        assert!(check.warnings[0].contains("`my-local-model`"));
+        assert_eq!(check.exceeded, None);
+    }
+}
@@ -337,56 +337,84 @@ pub(crate) async fn stream_chat_completions(
 
     debug!(
         "POST to {}: {}",
-        provider.get_full_url(&None),
+        provider.get_full_url(&None, &model_family.slug),
         payload.to_string()
     );
 
+    // Include subagent header only for subagent sessions.
+    let mut extra_headers = Vec::new();
+    if let SessionSource::SubAgent(sub) = session_source.clone() {
+        let subagent = if let SubAgentSource::Other(label) = sub {
+            label
+        } else {
+            serde_json::to_value(&sub)
+                .ok()
+                .and_then(|v| v.as_str().map(std::string::ToString::to_string))
+                .unwrap_or_else(|| "other".to_string())
+        };
+        extra_headers.push(("x-openai-subagent", subagent));
+    }
+
+    let resp = send_streaming_request(
+        client,
+        provider,
+        otel_event_manager,
+        &model_family.slug,
+        &extra_headers,
+        &payload,
+    )
+    .await?;
+
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+    let stream = resp.bytes_stream().map_err(|e| {
+        CodexErr::ResponseStreamFailed(ResponseStreamFailed {
+            source: e,
+            request_id: None,
+        })
+    });
+    tokio::spawn(process_chat_sse(
+        stream,
+        tx_event,
+        provider.stream_idle_timeout(),
+        otel_event_manager.clone(),
+    ));
+    Ok(ResponseStream { rx_event })
+}
+
+/// Sends a streaming `POST` with `payload` to `provider`, retrying rate
+/// limits, server errors and connection failures with backoff. Shared by the
+/// wire APIs that do not go through the Responses client.
+pub(crate) async fn send_streaming_request(
+    client: &CodexHttpClient,
+    provider: &ModelProviderInfo,
+    otel_event_manager: &OtelEventManager,
+    model: &str,
+    extra_headers: &[(&'static str, String)],
+    payload: &serde_json::Value,
+) -> Result<reqwest::Response> {
     let mut attempt = 0;
     let max_retries = provider.request_max_retries();
     loop {
         attempt += 1;
 
-        let mut req_builder = provider.create_request_builder(client, &None).await?;
-
-        // Include subagent header only for subagent sessions.
-        if let SessionSource::SubAgent(sub) = session_source.clone() {
-            let subagent = if let SubAgentSource::Other(label) = sub {
-                label
-            } else {
-                serde_json::to_value(&sub)
-                    .ok()
-                    .and_then(|v| v.as_str().map(std::string::ToString::to_string))
-                    .unwrap_or_else(|| "other".to_string())
-            };
-            req_builder = req_builder.header("x-openai-subagent", subagent);
+        let mut req_builder = provider
+            .create_request_builder(client, &None, model)
+            .await?;
+        for (name, value) in extra_headers {
+            req_builder = req_builder.header(*name, value);
         }
 
         let res = otel_event_manager
             .log_request(attempt, || {
                 req_builder
                     .header(reqwest::header::ACCEPT, "text/event-stream")
-                    .json(&payload)
+                    .json(payload)
                     .send()
             })
             .await;
 
         match res {
-            Ok(resp) if resp.status().is_success() => {
-                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
-                let stream = resp.bytes_stream().map_err(|e| {
-                    CodexErr::ResponseStreamFailed(ResponseStreamFailed {
-                        source: e,
-                        request_id: None,
-                    })
-                });
-                tokio::spawn(process_chat_sse(
-                    stream,
-                    tx_event,
-                    provider.stream_idle_timeout(),
-                    otel_event_manager.clone(),
-                ));
-                return Ok(ResponseStream { rx_event });
-            }
+            Ok(resp) if resp.status().is_success() => return Ok(resp),
             Ok(res) => {
                 let status = res.status();
                 if !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
@@ -30,6 +30,7 @@ use tracing::trace;
 use tracing::warn;
 
 use crate::AuthManager;
+use crate::anthropic_messages::stream_anthropic_messages;
 use crate::auth::CodexAuth;
 use crate::auth::RefreshTokenError;
 use crate::chat_completions::AggregateStreamExt;
@@ -51,6 +52,7 @@ use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
+use crate::gemini::stream_gemini_generate_content;
 use crate::model_family::ModelFamily;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
@@ -180,6 +182,30 @@ impl ModelClient {
 
                 Ok(ResponseStream { rx_event: rx })
             }
+            WireApi::Anthropic => {
+                stream_anthropic_messages(
+                    prompt,
+                    &self.config.model_family,
+                    self.effort,
+                    self.config.model_max_output_tokens,
+                    &self.client,
+                    &self.provider,
+                    &self.otel_event_manager,
+                )
+                .await
+            }
+            WireApi::Gemini => {
+                stream_gemini_generate_content(
+                    prompt,
+                    &self.config.model_family,
+                    self.effort,
+                    self.config.model_max_output_tokens,
+                    &self.client,
+                    &self.provider,
+                    &self.otel_event_manager,
+                )
+                .await
+            }
         }
     }
 
@@ -303,13 +329,13 @@ impl ModelClient {
 
         trace!(
             "POST to {}: {}",
-            self.provider.get_full_url(&auth),
+            self.provider.get_full_url(&auth, &self.config.model),
             payload_json.to_string()
         );
 
         let mut req_builder = self
             .provider
-            .create_request_builder(&self.client, &auth)
+            .create_request_builder(&self.client, &auth, &self.config.model)
             .await
             .map_err(StreamAttemptError::Fatal)?;
 
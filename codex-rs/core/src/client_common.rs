@@ -369,6 +369,26 @@ pub(crate) fn create_text_param_for_request(
     })
 }
 
+/// Splits a `data:<mime>;base64,<data>` image URL into its media type and
+/// base64 payload. Returns `None` for any other URL.
+pub(crate) fn split_base64_data_url(url: &str) -> Option<(&str, &str)> {
+    let rest = url.strip_prefix("data:")?;
+    let (media_type, data) = rest.split_once(";base64,")?;
+    Some((media_type, data))
+}
+
+/// Maps the configured reasoning effort onto a thinking-token budget for
+/// wire APIs that take an explicit budget (Anthropic, Gemini). Thinking stays
+/// off unless an effort above `minimal` is set.
+pub(crate) fn thinking_budget_for_effort(effort: Option<ReasoningEffortConfig>) -> Option<i64> {
+    match effort? {
+        ReasoningEffortConfig::None | ReasoningEffortConfig::Minimal => None,
+        ReasoningEffortConfig::Low => Some(2_048),
+        ReasoningEffortConfig::Medium => Some(8_192),
+        ReasoningEffortConfig::High => Some(24_576),
+    }
+}
+
 pub struct ResponseStream {
     pub(crate) rx_event: mpsc::Receiver<Result<ResponseEvent>>,
 }
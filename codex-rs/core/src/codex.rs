@@ -1,14 +1,20 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
 use crate::AuthManager;
+use crate::audit::AuditLog;
+use crate::budget::BudgetTracker;
 use crate::client_common::REVIEW_PROMPT;
+use crate::command_safety::exec_policy::ExecPolicy;
 use crate::compact;
 use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookEvent;
+use crate::hooks::Hooks;
 use crate::mcp::auth::McpAuthStatusEntry;
 use crate::mcp_connection_manager::DEFAULT_STARTUP_TIMEOUT;
 use crate::parse_command::parse_command;
@@ -58,6 +64,7 @@ use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::config::Config;
 use crate::config::types::McpServerTransportConfig;
+use crate::config::types::ResourceLimits;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::context_manager::ContextManager;
 use crate::environment_context::EnvironmentContext;
@@ -67,8 +74,7 @@ use crate::error::Result as CodexResult;
 use crate::exec::StreamOutput;
 // Removed: legacy executor wiring replaced by ToolOrchestrator flows.
 // legacy normalize_exec_result no longer used after orchestrator migration
-use crate::compact::build_compacted_history;
-use crate::compact::collect_user_messages;
+use crate::compact::replay_compaction;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::model_family::find_family_for_model;
@@ -99,8 +105,13 @@ use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::protocol::TurnDiffEvent;
 use crate::protocol::WarningEvent;
+use crate::redaction::SecretRedactor;
+use crate::review_prompts::review_prompt;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
+use crate::sandboxing::assessment::AssessmentCache;
+use crate::sandboxing::assessment::AssessmentKey;
+use crate::sandboxing::assessment::normalize_command;
 use crate::shell;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
@@ -109,6 +120,7 @@ use crate::tasks::GhostSnapshotTask;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::tool_output_store::ToolOutputStore;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::parallel::ToolCallRuntime;
@@ -161,6 +173,25 @@ impl Codex {
         auth_manager: Arc<AuthManager>,
         conversation_history: InitialHistory,
         session_source: SessionSource,
+    ) -> CodexResult<CodexSpawnOk> {
+        Self::spawn_with_budget(
+            config,
+            auth_manager,
+            conversation_history,
+            session_source,
+            None,
+        )
+        .await
+    }
+
+    /// Spawns a session whose spend counts toward `parent_budget`, when set,
+    /// instead of a budget of its own.
+    pub(crate) async fn spawn_with_budget(
+        config: Config,
+        auth_manager: Arc<AuthManager>,
+        conversation_history: InitialHistory,
+        session_source: SessionSource,
+        parent_budget: Option<Arc<Mutex<BudgetTracker>>>,
     ) -> CodexResult<CodexSpawnOk> {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
         let (tx_event, rx_event) = async_channel::unbounded();
@@ -195,6 +226,7 @@ impl Codex {
             tx_event.clone(),
             conversation_history,
             session_source_clone,
+            parent_budget,
         )
         .await
         .map_err(|e| {
@@ -248,6 +280,17 @@ impl Codex {
     }
 }
 
+/// What an exec approval request shows the user.
+pub(crate) struct CommandApprovalRequest {
+    pub(crate) call_id: String,
+    pub(crate) command: Vec<String>,
+    pub(crate) cwd: PathBuf,
+    pub(crate) reason: Option<String>,
+    pub(crate) risk: Option<SandboxCommandAssessment>,
+    /// The environment profile the command runs with, if any.
+    pub(crate) env_profile: Option<String>,
+}
+
 /// Context for an initialized model agent
 ///
 /// A session has at most 1 running task at a time, and can be interrupted by user input.
@@ -275,7 +318,10 @@ pub(crate) struct TurnContext {
     pub(crate) user_instructions: Option<String>,
     pub(crate) approval_policy: AskForApproval,
     pub(crate) sandbox_policy: SandboxPolicy,
+    /// Globs the tools and the sandbox keep unreadable on top of the defaults.
+    pub(crate) sandbox_read_deny: Vec<String>,
     pub(crate) shell_environment_policy: ShellEnvironmentPolicy,
+    pub(crate) resource_limits: ResourceLimits,
     pub(crate) tools_config: ToolsConfig,
     pub(crate) final_output_json_schema: Option<Value>,
     pub(crate) codex_linux_sandbox_exe: Option<PathBuf>,
@@ -419,7 +465,16 @@ impl Session {
         let tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_family: &model_family,
             features: &config.features,
-        });
+        })
+        .with_enabled_tools(config.enabled_tools.clone())
+        .with_env_profiles(
+            config
+                .shell_environment_policy
+                .profiles
+                .iter()
+                .map(|(name, profile)| (name.clone(), profile.description.clone()))
+                .collect(),
+        );
 
         TurnContext {
             sub_id,
@@ -431,7 +486,9 @@ impl Session {
             user_instructions: session_configuration.user_instructions.clone(),
             approval_policy: session_configuration.approval_policy,
             sandbox_policy: session_configuration.sandbox_policy.clone(),
+            sandbox_read_deny: config.sandbox_read_deny.clone(),
             shell_environment_policy: config.shell_environment_policy.clone(),
+            resource_limits: config.resource_limits,
             tools_config,
             final_output_json_schema: None,
             codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
@@ -446,6 +503,7 @@ impl Session {
         tx_event: Sender<Event>,
         initial_history: InitialHistory,
         session_source: SessionSource,
+        parent_budget: Option<Arc<Mutex<BudgetTracker>>>,
     ) -> anyhow::Result<Arc<Self>> {
         debug!(
             "Configuring session: model={}; provider={:?}",
@@ -564,6 +622,24 @@ impl Session {
             });
         }
 
+        let exec_policy = if session_configuration.features.enabled(Feature::ExecPolicy) {
+            let (exec_policy, errors) = ExecPolicy::load(
+                &config.codex_home,
+                &session_configuration.cwd,
+                config.active_project.is_trusted(),
+            )
+            .await;
+            for message in errors {
+                post_session_configured_events.push(Event {
+                    id: INITIAL_SUBMIT_ID.to_owned(),
+                    msg: EventMsg::Warning(WarningEvent { message }),
+                });
+            }
+            exec_policy
+        } else {
+            ExecPolicy::default()
+        };
+
         let otel_event_manager = OtelEventManager::new(
             conversation_id,
             config.model.as_str(),
@@ -604,6 +680,16 @@ impl Session {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            exec_policy,
+            hooks: Hooks::new(config.hooks.clone()),
+            shares_parent_budget: parent_budget.is_some(),
+            budget: parent_budget
+                .unwrap_or_else(|| Arc::new(Mutex::new(BudgetTracker::new(config.budget.clone())))),
+            redactor: SecretRedactor::new(&config.redaction, &config.shell_environment_policy),
+            audit: AuditLog::new(&config.audit, &config.codex_home, conversation_id),
+            approval_rules: Mutex::new(config.approval_rules.clone()),
+            command_assessments: Mutex::new(AssessmentCache::default()),
+            tool_outputs: ToolOutputStore::new(config.features.enabled(Feature::ReadToolOutput)),
         };
 
         let sess = Arc::new(Session {
@@ -639,6 +725,18 @@ impl Session {
         // record_initial_history can emit events. We record only after the SessionConfiguredEvent is emitted.
         sess.record_initial_history(initial_history).await;
 
+        sess.services
+            .hooks
+            .session_start(
+                &HookEvent::SessionStart {
+                    thread_id: conversation_id.to_string(),
+                    cwd: session_configuration.cwd.display().to_string(),
+                    model: session_configuration.model.clone(),
+                },
+                &session_configuration.cwd,
+            )
+            .await;
+
         Ok(sess)
     }
 
@@ -659,6 +757,20 @@ impl Session {
         }
     }
 
+    /// Flush and re-read this session's rollout, with earlier rewinds applied.
+    /// Returns `None` when the session is not being recorded.
+    pub(crate) async fn load_rollout_items(&self) -> Option<std::io::Result<Vec<RolloutItem>>> {
+        let recorder = {
+            let guard = self.services.rollout.lock().await;
+            guard.clone()
+        }?;
+        if let Err(e) = recorder.flush().await {
+            return Some(Err(e));
+        }
+        let history = RolloutRecorder::get_rollout_history(&recorder.rollout_path).await;
+        Some(history.map(|history| history.get_rollout_items()))
+    }
+
     fn next_internal_sub_id(&self) -> String {
         let id = self
             .next_internal_sub_id
@@ -837,13 +949,29 @@ impl Session {
         turn_context: &TurnContext,
         call_id: &str,
         command: &[String],
+        cwd: &Path,
+        escalated_permissions: bool,
         failure_message: Option<&str>,
     ) -> Option<SandboxCommandAssessment> {
+        // Verdicts made before a command runs are reused for the rest of the
+        // session; those explaining a sandbox failure depend on that failure.
+        let cache_key = failure_message.is_none().then(|| AssessmentKey {
+            command: normalize_command(command),
+            cwd: cwd.to_path_buf(),
+            sandbox_policy: turn_context.sandbox_policy.clone(),
+            escalated_permissions,
+        });
+        if let Some(key) = cache_key.as_ref()
+            && let Some(cached) = self.services.command_assessments.lock().await.get(key)
+        {
+            return Some(cached);
+        }
+
         let config = turn_context.client.config();
         let provider = turn_context.client.provider().clone();
         let auth_manager = Arc::clone(&self.services.auth_manager);
         let otel = self.services.otel_event_manager.clone();
-        crate::sandboxing::assessment::assess_command(
+        let assessment = crate::sandboxing::assessment::assess_command(
             config,
             provider,
             auth_manager,
@@ -853,10 +981,20 @@ impl Session {
             call_id,
             command,
             &turn_context.sandbox_policy,
-            &turn_context.cwd,
+            cwd,
+            escalated_permissions,
             failure_message,
+            &self.services.budget,
         )
-        .await
+        .await;
+        if let (Some(key), Some(assessment)) = (cache_key, assessment.as_ref()) {
+            self.services
+                .command_assessments
+                .lock()
+                .await
+                .insert(key, assessment.clone());
+        }
+        assessment
     }
 
     /// Emit an exec approval request event and await the user's decision.
@@ -867,12 +1005,16 @@ impl Session {
     pub async fn request_command_approval(
         &self,
         turn_context: &TurnContext,
-        call_id: String,
-        command: Vec<String>,
-        cwd: PathBuf,
-        reason: Option<String>,
-        risk: Option<SandboxCommandAssessment>,
+        request: CommandApprovalRequest,
     ) -> ReviewDecision {
+        let CommandApprovalRequest {
+            call_id,
+            command,
+            cwd,
+            reason,
+            risk,
+            env_profile,
+        } = request;
         let sub_id = turn_context.sub_id.clone();
         // Add the tx_approve callback to the map before sending the request.
         let (tx_approve, rx_approve) = oneshot::channel();
@@ -898,6 +1040,7 @@ impl Session {
             cwd,
             reason,
             risk,
+            env_profile,
             parsed_cmd,
         });
         self.send_event(turn_context, event).await;
@@ -973,7 +1116,7 @@ impl Session {
         self.send_raw_response_items(turn_context, items).await;
     }
 
-    fn reconstruct_history_from_rollout(
+    pub(crate) fn reconstruct_history_from_rollout(
         &self,
         turn_context: &TurnContext,
         rollout_items: &[RolloutItem],
@@ -982,15 +1125,16 @@ impl Session {
         for item in rollout_items {
             match item {
                 RolloutItem::ResponseItem(response_item) => {
+                    // Outputs elided by compaction stay readable after a resume.
+                    self.services.tool_outputs.insert_item(response_item);
                     history.record_items(std::iter::once(response_item));
                 }
                 RolloutItem::Compacted(compacted) => {
                     let snapshot = history.get_history();
-                    let user_messages = collect_user_messages(&snapshot);
-                    let rebuilt = build_compacted_history(
+                    let rebuilt = replay_compaction(
+                        &snapshot,
                         self.build_initial_context(turn_context),
-                        &user_messages,
-                        &compacted.message,
+                        compacted,
                     );
                     history.replace(rebuilt);
                 }
@@ -1084,9 +1228,39 @@ impl Session {
                 );
             }
         }
+        if let Some(token_usage) = token_usage {
+            self.services
+                .budget
+                .lock()
+                .await
+                .record(&turn_context.client.get_model(), token_usage);
+        }
         self.send_token_count_event(turn_context).await;
     }
 
+    /// Report budget warnings and, once a budget is exhausted, abort the
+    /// running turn. Returns `true` when the turn is being aborted.
+    async fn enforce_budget(self: &Arc<Self>, turn_context: &TurnContext) -> bool {
+        let check = self.services.budget.lock().await.check();
+        for message in check.warnings {
+            self.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+                .await;
+        }
+        let Some(message) = check.exceeded else {
+            return false;
+        };
+        warn!("{message}");
+        self.send_event(turn_context, EventMsg::Error(ErrorEvent { message }))
+            .await;
+        // Abort from outside the running task so it ends through the regular
+        // abort path and reports `TurnAborted` instead of `TaskComplete`.
+        let sess = Arc::clone(self);
+        tokio::spawn(async move {
+            sess.abort_all_tasks(TurnAbortReason::BudgetExceeded).await;
+        });
+        true
+    }
+
     pub(crate) async fn override_last_token_usage_estimate(
         &self,
         turn_context: &TurnContext,
@@ -1311,6 +1485,10 @@ impl Session {
         &self.services.notifier
     }
 
+    pub(crate) fn conversation_id(&self) -> ConversationId {
+        self.conversation_id
+    }
+
     pub(crate) fn user_shell(&self) -> &shell::Shell {
         &self.services.user_shell
     }
@@ -1361,6 +1539,9 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::PatchApproval { id, decision } => {
                 handlers::patch_approval(&sess, id, decision).await;
             }
+            Op::SetApprovalRules { rules } => {
+                handlers::set_approval_rules(&sess, rules).await;
+            }
             Op::AddToHistory { text } => {
                 handlers::add_to_history(&sess, &config, text).await;
             }
@@ -1377,6 +1558,9 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Undo => {
                 handlers::undo(&sess, sub.id.clone()).await;
             }
+            Op::RestoreCheckpoint { turn } => {
+                handlers::restore_checkpoint(&sess, sub.id.clone(), turn).await;
+            }
             Op::Compact => {
                 handlers::compact(&sess, sub.id.clone()).await;
             }
@@ -1414,8 +1598,10 @@ mod handlers {
     use crate::mcp::auth::compute_auth_statuses;
     use crate::tasks::CompactTask;
     use crate::tasks::RegularTask;
+    use crate::tasks::RestoreCheckpointTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
+    use codex_protocol::config_types::ApprovalRule;
     use codex_protocol::custom_prompts::CustomPrompt;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
@@ -1527,6 +1713,10 @@ mod handlers {
         }
     }
 
+    pub async fn set_approval_rules(sess: &Arc<Session>, rules: Vec<ApprovalRule>) {
+        *sess.services.approval_rules.lock().await = rules;
+    }
+
     pub async fn add_to_history(sess: &Arc<Session>, config: &Arc<Config>, text: String) {
         let id = sess.conversation_id;
         let config = Arc::clone(config);
@@ -1628,6 +1818,14 @@ mod handlers {
             .await;
     }
 
+    pub async fn restore_checkpoint(sess: &Arc<Session>, sub_id: String, turn: usize) {
+        let turn_context = sess
+            .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
+            .await;
+        sess.spawn_task(turn_context, Vec::new(), RestoreCheckpointTask::new(turn))
+            .await;
+    }
+
     pub async fn compact(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
@@ -1695,13 +1893,13 @@ mod handlers {
     }
 }
 
-/// Spawn a review thread using the given prompt.
+/// Spawn a review thread for the requested target.
 async fn spawn_review_thread(
     sess: Arc<Session>,
     config: Arc<Config>,
     parent_turn_context: Arc<TurnContext>,
     sub_id: String,
-    review_request: ReviewRequest,
+    mut review_request: ReviewRequest,
 ) {
     let model = config.review_model.clone();
     let review_model_family = find_family_for_model(&model)
@@ -1717,7 +1915,7 @@ async fn spawn_review_thread(
     });
 
     let base_instructions = REVIEW_PROMPT.to_string();
-    let review_prompt = review_request.prompt.clone();
+    let review_prompt = review_prompt(&review_request.target, &parent_turn_context.cwd).await;
     let provider = parent_turn_context.client.get_provider();
     let auth_manager = parent_turn_context.client.get_auth_manager();
     let model_family = review_model_family.clone();
@@ -1762,7 +1960,9 @@ async fn spawn_review_thread(
         compact_prompt: parent_turn_context.compact_prompt.clone(),
         approval_policy: parent_turn_context.approval_policy,
         sandbox_policy: parent_turn_context.sandbox_policy.clone(),
+        sandbox_read_deny: parent_turn_context.sandbox_read_deny.clone(),
         shell_environment_policy: parent_turn_context.shell_environment_policy.clone(),
+        resource_limits: parent_turn_context.resource_limits,
         cwd: parent_turn_context.cwd.clone(),
         final_output_json_schema: None,
         codex_linux_sandbox_exe: parent_turn_context.codex_linux_sandbox_exe.clone(),
@@ -1777,6 +1977,7 @@ async fn spawn_review_thread(
     sess.spawn_task(tc.clone(), input, ReviewTask).await;
 
     // Announce entering review mode so UIs can switch modes.
+    review_request.user_facing_hint = Some(review_request.hint());
     sess.send_event(&tc, EventMsg::EnteredReviewMode(review_request))
         .await;
 }
@@ -1815,6 +2016,10 @@ pub(crate) async fn run_task(
 
     sess.maybe_start_ghost_snapshot(Arc::clone(&turn_context), cancellation_token.child_token())
         .await;
+    // A sub-agent's turn is part of its parent's turn, which keeps counting.
+    if !sess.services.shares_parent_budget {
+        sess.services.budget.lock().await.start_turn();
+    }
     let mut last_agent_message: Option<String> = None;
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
@@ -1822,6 +2027,13 @@ pub(crate) async fn run_task(
     let mut auto_compact_recently_attempted = false;
 
     loop {
+        // Stop before sampling again once a budget is exhausted, including
+        // session budgets that earlier turns already used up.
+        if sess.enforce_budget(&turn_context).await {
+            cancellation_token.cancelled().await;
+            break;
+        }
+
         // Note that pending_input would be something like a message the user
         // submitted through the UI while the model was running. Though the UI
         // may support this, the model might not.
@@ -1899,6 +2111,19 @@ pub(crate) async fn run_task(
                     last_agent_message = get_last_assistant_message_from_turn(
                         &items_to_record_in_conversation_history,
                     );
+                    sess.services
+                        .hooks
+                        .turn_complete(
+                            &HookEvent::TurnComplete {
+                                thread_id: sess.conversation_id.to_string(),
+                                turn_id: turn_context.sub_id.clone(),
+                                cwd: turn_context.cwd.display().to_string(),
+                                input_messages: turn_input_messages.clone(),
+                                last_assistant_message: last_agent_message.clone(),
+                            },
+                            &turn_context.cwd,
+                        )
+                        .await;
                     sess.notifier()
                         .notify(&UserNotification::AgentTurnComplete {
                             thread_id: sess.conversation_id.to_string(),
@@ -2391,6 +2616,8 @@ mod tests {
     use crate::mcp::auth::McpAuthStatusEntry;
     use crate::tools::format_exec_output_str;
 
+    use crate::compact::build_compacted_history;
+    use crate::compact::collect_user_messages;
     use crate::protocol::CompactedItem;
     use crate::protocol::InitialHistory;
     use crate::protocol::ResumedHistory;
@@ -2498,6 +2725,7 @@ mod tests {
             aggregated_output: StreamOutput::new("Command output".to_string()),
             duration: StdDuration::from_secs(1),
             timed_out: true,
+            resource_limit_exceeded: None,
         };
 
         let out = format_exec_output_str(&exec);
@@ -2631,6 +2859,15 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            exec_policy: ExecPolicy::default(),
+            hooks: Hooks::default(),
+            budget: Arc::new(Mutex::new(BudgetTracker::new(config.budget.clone()))),
+            shares_parent_budget: false,
+            redactor: SecretRedactor::new(&config.redaction, &config.shell_environment_policy),
+            audit: AuditLog::default(),
+            approval_rules: Mutex::new(config.approval_rules.clone()),
+            command_assessments: Mutex::new(AssessmentCache::default()),
+            tool_outputs: ToolOutputStore::new(config.features.enabled(Feature::ReadToolOutput)),
         };
 
         let turn_context = Session::make_turn_context(
@@ -2707,6 +2944,15 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            exec_policy: ExecPolicy::default(),
+            hooks: Hooks::default(),
+            budget: Arc::new(Mutex::new(BudgetTracker::new(config.budget.clone()))),
+            shares_parent_budget: false,
+            redactor: SecretRedactor::new(&config.redaction, &config.shell_environment_policy),
+            audit: AuditLog::default(),
+            approval_rules: Mutex::new(config.approval_rules.clone()),
+            command_assessments: Mutex::new(AssessmentCache::default()),
+            tool_outputs: ToolOutputStore::new(config.features.enabled(Feature::ReadToolOutput)),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -2957,6 +3203,7 @@ mod tests {
         live_history.replace(rebuilt1);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
             message: summary1.to_string(),
+            ..Default::default()
         }));
 
         let user2 = ResponseItem::Message {
@@ -2990,6 +3237,7 @@ mod tests {
         live_history.replace(rebuilt2);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
             message: summary2.to_string(),
+            ..Default::default()
         }));
 
         let user3 = ResponseItem::Message {
@@ -18,6 +18,7 @@ use tokio_util::sync::CancellationToken;
 use crate::AuthManager;
 use crate::codex::Codex;
 use crate::codex::CodexSpawnOk;
+use crate::codex::CommandApprovalRequest;
 use crate::codex::SUBMISSION_CHANNEL_CAPACITY;
 use crate::codex::Session;
 use crate::codex::TurnContext;
@@ -37,15 +38,17 @@ pub(crate) async fn run_codex_conversation_interactive(
     parent_ctx: Arc<TurnContext>,
     cancel_token: CancellationToken,
     initial_history: Option<InitialHistory>,
+    source: SubAgentSource,
 ) -> Result<Codex, CodexErr> {
     let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
     let (tx_ops, rx_ops) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
 
-    let CodexSpawnOk { codex, .. } = Codex::spawn(
+    let CodexSpawnOk { codex, .. } = Codex::spawn_with_budget(
         config,
         auth_manager,
         initial_history.unwrap_or(InitialHistory::New),
-        SessionSource::SubAgent(SubAgentSource::Review),
+        SessionSource::SubAgent(source),
+        Some(Arc::clone(&parent_session.services.budget)),
     )
     .await?;
     let codex = Arc::new(codex);
@@ -95,6 +98,7 @@ pub(crate) async fn run_codex_conversation_one_shot(
     parent_ctx: Arc<TurnContext>,
     cancel_token: CancellationToken,
     initial_history: Option<InitialHistory>,
+    source: SubAgentSource,
 ) -> Result<Codex, CodexErr> {
     // Use a child token so we can stop the delegate after completion without
     // requiring the caller to cancel the parent token.
@@ -106,6 +110,7 @@ pub(crate) async fn run_codex_conversation_one_shot(
         parent_ctx,
         child_cancel.clone(),
         initial_history,
+        source,
     )
     .await?;
 
@@ -230,11 +235,14 @@ async fn handle_exec_approval(
     // Race approval with cancellation and timeout to avoid hangs.
     let approval_fut = parent_session.request_command_approval(
         parent_ctx,
-        parent_ctx.sub_id.clone(),
-        event.command,
-        event.cwd,
-        event.reason,
-        event.risk,
+        CommandApprovalRequest {
+            call_id: parent_ctx.sub_id.clone(),
+            command: event.command,
+            cwd: event.cwd,
+            reason: event.reason,
+            risk: event.risk,
+            env_profile: event.env_profile,
+        },
     );
     let decision = await_approval_with_cancel(
         approval_fut,
@@ -0,0 +1,292 @@
+//! Standing approvals configured as `[[approval_rules]]`.
+//!
+//! A rule approves commands that start with a given prefix, commands run
+//! inside a project directory, or both. Scripts passed to `bash -lc` are split
+//! into their plain commands first, and every one of them has to match; a
+//! script using anything beyond plain commands joined by `&&`, `||`, `;` or
+//! `|` never matches a prefix. Neither does a command that passes a flag such
+//! as `find -exec` or `git -c` after the prefix, since those run arbitrary
+//! code.
+
+use std::path::Path;
+
+use codex_protocol::config_types::ApprovalRule;
+
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::command_safety::is_dangerous_command::command_might_be_dangerous;
+
+/// Programs that run arbitrary code given to them, so approving them by
+/// prefix would approve everything.
+const LAUNCHERS: &[&str] = &[
+    "bash", "sh", "zsh", "fish", "dash", "ksh", "csh", "tcsh", "pwsh", "env", "sudo", "doas", "su",
+    "xargs", "parallel", "eval", "exec", "command", "builtin", "nohup", "nice", "ionice", "stdbuf",
+    "time", "timeout", "watch", "script", "strace", "ssh", "busybox", "python", "python3", "node",
+    "perl", "ruby", "php", "lua", "deno", "bun", "bunx", "npx", "pnpx", "uvx", "awk", "gawk",
+    "mawk", "nawk",
+];
+
+/// Subcommands that download or run arbitrary packages, e.g. `npm exec`.
+const LAUNCHER_SUBCOMMANDS: &[(&str, &str)] = &[
+    ("npm", "exec"),
+    ("npm", "x"),
+    ("pnpm", "dlx"),
+    ("pnpm", "exec"),
+    ("yarn", "dlx"),
+    ("yarn", "exec"),
+    ("pipx", "run"),
+];
+
+/// Flags that make an otherwise harmless program run arbitrary commands.
+const CODE_FLAGS: &[(&str, &[&str])] = &[
+    ("find", &["-exec", "-execdir", "-ok", "-okdir"]),
+    ("fd", &["-x", "--exec", "-X", "--exec-batch"]),
+    (
+        "git",
+        &["-c", "--config-env", "--exec-path", "--upload-pack"],
+    ),
+    ("rg", &["--pre"]),
+    ("rsync", &["-e", "--rsh", "--rsync-path"]),
+    (
+        "tar",
+        &[
+            "-I",
+            "--use-compress-program",
+            "--to-command",
+            "--checkpoint-action",
+        ],
+    ),
+];
+
+/// Returns `true` when any rule in `rules` approves running `command` in
+/// `cwd`.
+pub fn is_approved_by_rules(rules: &[ApprovalRule], command: &[String], cwd: &Path) -> bool {
+    rules.iter().any(|rule| rule_matches(rule, command, cwd))
+}
+
+/// Returns `true` when every condition of `rule` holds for `command` run in
+/// `cwd`.
+pub fn rule_matches(rule: &ApprovalRule, command: &[String], cwd: &Path) -> bool {
+    if rule.prefix.is_none() && rule.project.is_none() {
+        return false;
+    }
+    if let Some(project) = &rule.project
+        && !cwd.starts_with(project)
+    {
+        return false;
+    }
+    match &rule.prefix {
+        Some(prefix) if prefix.is_empty() => false,
+        Some(prefix) => plain_commands(command).iter().all(|words| {
+            words.starts_with(prefix) && !has_code_flag(&words[0], &words[prefix.len()..])
+        }),
+        None => true,
+    }
+}
+
+/// The prefix to offer for "always allow commands starting with …": the
+/// program and, when present, its subcommand. `None` when `command` is not a
+/// single plain command, might be dangerous, or runs arbitrary code like
+/// `bash`, `npx` or `find -exec` do.
+pub fn suggested_prefix(command: &[String]) -> Option<Vec<String>> {
+    if command_might_be_dangerous(command) {
+        return None;
+    }
+    let commands = plain_commands(command);
+    let [words] = commands.as_slice() else {
+        return None;
+    };
+    let program = words.first()?;
+    let name = program_name(program);
+    if LAUNCHERS.contains(&name) || has_code_flag(program, &words[1..]) {
+        return None;
+    }
+    let mut prefix = vec![program.clone()];
+    if let Some(subcommand) = words.get(1)
+        && is_subcommand(subcommand)
+    {
+        if LAUNCHER_SUBCOMMANDS.contains(&(name, subcommand.as_str())) {
+            return None;
+        }
+        prefix.push(subcommand.clone());
+    }
+    Some(prefix)
+}
+
+fn program_name(program: &str) -> &str {
+    Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(program)
+}
+
+/// Returns `true` when `args` of `program` include a flag from [`CODE_FLAGS`],
+/// alone or as `--flag=value`.
+fn has_code_flag(program: &str, args: &[String]) -> bool {
+    let name = program_name(program);
+    let Some((_, flags)) = CODE_FLAGS.iter().find(|(candidate, _)| *candidate == name) else {
+        return false;
+    };
+    args.iter().any(|arg| {
+        let flag = arg.split_once('=').map_or(arg.as_str(), |(flag, _)| flag);
+        flags.contains(&flag)
+    })
+}
+
+fn plain_commands(command: &[String]) -> Vec<Vec<String>> {
+    match parse_shell_lc_plain_commands(command) {
+        Some(commands) if !commands.is_empty() => commands,
+        _ => vec![command.to_vec()],
+    }
+}
+
+/// Subcommands are bare words such as `test` or `run`, as opposed to flags,
+/// paths and assignments.
+fn is_subcommand(word: &str) -> bool {
+    !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ':')
+        && !word.starts_with('-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn words(command: &[&str]) -> Vec<String> {
+        command.iter().map(|word| (*word).to_string()).collect()
+    }
+
+    fn prefix_rule(prefix: &[&str]) -> ApprovalRule {
+        ApprovalRule {
+            prefix: Some(words(prefix)),
+            project: None,
+        }
+    }
+
+    #[test]
+    fn prefix_rules_match_every_command_of_a_script() {
+        let rule = prefix_rule(&["cargo", "test"]);
+        let cwd = Path::new("/repo");
+        assert!(rule_matches(
+            &rule,
+            &words(&["cargo", "test", "-p", "core"]),
+            cwd
+        ));
+        assert!(rule_matches(
+            &rule,
+            &words(&["bash", "-lc", "cargo test && cargo test --doc"]),
+            cwd
+        ));
+        assert!(!rule_matches(
+            &rule,
+            &words(&["bash", "-lc", "cargo test && rm -rf target"]),
+            cwd
+        ));
+        assert!(!rule_matches(
+            &rule,
+            &words(&["bash", "-lc", "cargo test > $(whoami)"]),
+            cwd
+        ));
+        assert!(!rule_matches(&rule, &words(&["cargo", "publish"]), cwd));
+    }
+
+    #[test]
+    fn prefix_rules_reject_flags_that_run_code() {
+        let cwd = Path::new("/repo");
+        let find = prefix_rule(&["find"]);
+        assert!(rule_matches(
+            &find,
+            &words(&["find", ".", "-name", "*.rs"]),
+            cwd
+        ));
+        assert!(!rule_matches(
+            &find,
+            &words(&["find", ".", "-exec", "rm", "{}", ";"]),
+            cwd
+        ));
+        let git = prefix_rule(&["git"]);
+        assert!(!rule_matches(
+            &git,
+            &words(&["git", "-c", "alias.x=!rm -rf /", "x"]),
+            cwd
+        ));
+        assert!(!rule_matches(
+            &git,
+            &words(&["git", "--config-env=core.pager=PAGER", "log"]),
+            cwd
+        ));
+    }
+
+    #[test]
+    fn project_rules_match_commands_inside_the_project() {
+        let rule = ApprovalRule {
+            prefix: None,
+            project: Some(PathBuf::from("/repo")),
+        };
+        let command = words(&["make"]);
+        assert!(rule_matches(&rule, &command, Path::new("/repo")));
+        assert!(rule_matches(
+            &rule,
+            &command,
+            Path::new("/repo/crates/core")
+        ));
+        assert!(!rule_matches(&rule, &command, Path::new("/repository")));
+
+        let scoped = ApprovalRule {
+            prefix: Some(words(&["make"])),
+            project: Some(PathBuf::from("/repo")),
+        };
+        assert!(rule_matches(&scoped, &command, Path::new("/repo")));
+        assert!(!rule_matches(&scoped, &words(&["ls"]), Path::new("/repo")));
+    }
+
+    #[test]
+    fn empty_rules_match_nothing() {
+        let command = words(&["ls"]);
+        let cwd = Path::new("/repo");
+        assert!(!rule_matches(&ApprovalRule::default(), &command, cwd));
+        assert!(!rule_matches(&prefix_rule(&[]), &command, cwd));
+    }
+
+    #[test]
+    fn suggests_program_and_subcommand() {
+        assert_eq!(
+            suggested_prefix(&words(&["bash", "-lc", "cargo test -p codex-core"])),
+            Some(words(&["cargo", "test"]))
+        );
+        assert_eq!(
+            suggested_prefix(&words(&["ls", "-la"])),
+            Some(words(&["ls"]))
+        );
+        assert_eq!(
+            suggested_prefix(&words(&["./scripts/check.sh", "src/main.rs"])),
+            Some(words(&["./scripts/check.sh"]))
+        );
+        assert_eq!(
+            suggested_prefix(&words(&["python3", "-c", "print(1)"])),
+            None
+        );
+        assert_eq!(
+            suggested_prefix(&words(&["bash", "-lc", "cargo fmt && cargo test"])),
+            None
+        );
+    }
+
+    #[test]
+    fn suggests_nothing_for_code_runners_and_dangerous_commands() {
+        for command in [
+            &["find", ".", "-execdir", "sh", "-c", "true", ";"][..],
+            &["awk", "{ system(\"id\") }"],
+            &["npx", "cowsay"],
+            &["npm", "exec", "cowsay"],
+            &["git", "-c", "core.pager=sh", "log"],
+            &["rm", "-rf", "build"],
+            &["git", "reset", "--hard"],
+        ] {
+            assert_eq!(suggested_prefix(&words(command)), None, "{command:?}");
+        }
+    }
+}
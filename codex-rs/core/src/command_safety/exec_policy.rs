@@ -0,0 +1,499 @@
+//! User-extensible command policy backed by `codex-execpolicy`.
+//!
+//! The bundled `default.policy` is always loaded first. Additional `*.policy`
+//! files are then read from `$CODEX_HOME/policy/` and, for trusted projects,
+//! from `.codex/policy/` at the root of the current git repository, so teams
+//! can check project rules in alongside the code. Each file is an independent
+//! Starlark program made of `define_program`, `forbid_program_regex` and
+//! `forbid_substrings` calls.
+//!
+//! A command is [`ExecPolicyDecision::Forbidden`] as soon as any loaded policy
+//! forbids it, and [`ExecPolicyDecision::Allowed`] when the default or a user
+//! policy matches it and every file it may write lives inside the sandbox's
+//! writable roots, or inside the workspace when there is no sandbox. Project policies come with the repository, so they can only
+//! forbid commands; their `define_program` rules never skip a prompt.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_execpolicy::ArgType;
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::Policy;
+use codex_execpolicy::PolicyParser;
+use codex_execpolicy::ValidExec;
+use codex_execpolicy::get_default_policy;
+use codex_protocol::protocol::SandboxPolicy;
+use tokio::fs;
+use tracing::warn;
+
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::git_info::get_git_repo_root;
+
+/// Directory (under `$CODEX_HOME` and `<repo>/.codex`) holding policy files.
+const POLICY_DIR_NAME: &str = "policy";
+const POLICY_FILE_EXTENSION: &str = "policy";
+const PROJECT_CONFIG_DIR_NAME: &str = ".codex";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ExecPolicyDecision {
+    /// Every command in the invocation matched a rule and any files it writes
+    /// are inside the writable roots of the active sandbox policy.
+    Allowed,
+    /// At least one command in the invocation is explicitly forbidden.
+    Forbidden { reason: String },
+    /// No policy had an opinion; fall back to the built-in heuristics.
+    Unmatched,
+}
+
+/// Ordered collection of parsed policies. An empty collection never matches.
+#[derive(Default)]
+pub(crate) struct ExecPolicy {
+    /// The default and user policies, which may allow and forbid commands.
+    policies: Vec<Policy>,
+    /// Policies from the project, which may only forbid commands.
+    project_policies: Vec<Policy>,
+}
+
+impl ExecPolicy {
+    /// Loads `default.policy` followed by the user policy files and, when
+    /// `project_trusted` is set, the project policy files. Files that fail to
+    /// parse are skipped; their errors are returned so the caller can surface
+    /// them to the user.
+    pub(crate) async fn load(
+        codex_home: &Path,
+        cwd: &Path,
+        project_trusted: bool,
+    ) -> (Self, Vec<String>) {
+        let mut policies = Vec::new();
+        let mut project_policies = Vec::new();
+        let mut errors = Vec::new();
+
+        match get_default_policy() {
+            Ok(policy) => policies.push(policy),
+            Err(err) => errors.push(format!("failed to parse default exec policy: {err}")),
+        }
+
+        load_policy_dir(
+            &codex_home.join(POLICY_DIR_NAME),
+            &mut policies,
+            &mut errors,
+        )
+        .await;
+        if project_trusted && let Some(dir) = project_policy_dir(cwd) {
+            load_policy_dir(&dir, &mut project_policies, &mut errors).await;
+        }
+
+        for error in &errors {
+            warn!("{error}");
+        }
+
+        (
+            Self {
+                policies,
+                project_policies,
+            },
+            errors,
+        )
+    }
+
+    /// Evaluates `command` (including every plain command inside a
+    /// `bash -lc "..."` script) against the loaded policies.
+    pub(crate) fn evaluate(
+        &self,
+        command: &[String],
+        cwd: &Path,
+        sandbox_policy: &SandboxPolicy,
+    ) -> ExecPolicyDecision {
+        if self.policies.is_empty() && self.project_policies.is_empty() {
+            return ExecPolicyDecision::Unmatched;
+        }
+
+        let commands = match parse_shell_lc_plain_commands(command) {
+            Some(commands) if !commands.is_empty() => commands,
+            _ => vec![command.to_vec()],
+        };
+
+        let mut all_allowed = true;
+        for command in &commands {
+            match self.evaluate_single(command, cwd, sandbox_policy) {
+                forbidden @ ExecPolicyDecision::Forbidden { .. } => return forbidden,
+                ExecPolicyDecision::Allowed => {}
+                ExecPolicyDecision::Unmatched => all_allowed = false,
+            }
+        }
+
+        if all_allowed {
+            ExecPolicyDecision::Allowed
+        } else {
+            ExecPolicyDecision::Unmatched
+        }
+    }
+
+    fn evaluate_single(
+        &self,
+        command: &[String],
+        cwd: &Path,
+        sandbox_policy: &SandboxPolicy,
+    ) -> ExecPolicyDecision {
+        let Some((program, args)) = command.split_first() else {
+            return ExecPolicyDecision::Unmatched;
+        };
+        let exec_call = ExecCall {
+            program: program.clone(),
+            args: args.to_vec(),
+        };
+
+        for policy in &self.project_policies {
+            if let Ok(MatchedExec::Forbidden { reason, .. }) = policy.check(&exec_call) {
+                return ExecPolicyDecision::Forbidden { reason };
+            }
+        }
+
+        let mut allowed = false;
+        for policy in &self.policies {
+            match policy.check(&exec_call) {
+                Ok(MatchedExec::Forbidden { reason, .. }) => {
+                    return ExecPolicyDecision::Forbidden { reason };
+                }
+                Ok(MatchedExec::Match { exec }) => {
+                    if writes_within_writable_roots(&exec, cwd, sandbox_policy) {
+                        allowed = true;
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+
+        if allowed {
+            ExecPolicyDecision::Allowed
+        } else {
+            ExecPolicyDecision::Unmatched
+        }
+    }
+}
+
+fn project_policy_dir(cwd: &Path) -> Option<PathBuf> {
+    get_git_repo_root(cwd).map(|repo_root| {
+        repo_root
+            .join(PROJECT_CONFIG_DIR_NAME)
+            .join(POLICY_DIR_NAME)
+    })
+}
+
+/// Parses every policy file in `dir` into `policies`, recording failures.
+async fn load_policy_dir(dir: &Path, policies: &mut Vec<Policy>, errors: &mut Vec<String>) {
+    for path in discover_policy_files(dir).await {
+        let contents = match fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(err) => {
+                errors.push(format!(
+                    "failed to read exec policy {}: {err}",
+                    path.display()
+                ));
+                continue;
+            }
+        };
+        let source = path.to_string_lossy().to_string();
+        match PolicyParser::new(&source, &contents).parse() {
+            Ok(policy) => policies.push(policy),
+            Err(err) => errors.push(format!(
+                "failed to parse exec policy {}: {err}",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Returns the `*.policy` files directly inside `dir`, sorted by file name.
+/// A missing or unreadable directory yields no files.
+async fn discover_policy_files(dir: &Path) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return out;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let is_policy = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext == POLICY_FILE_EXTENSION)
+            .unwrap_or(false);
+        let is_file = fs::metadata(&path)
+            .await
+            .map(|m| m.is_file())
+            .unwrap_or(false);
+        if is_policy && is_file {
+            out.push(path);
+        }
+    }
+    out.sort();
+    out
+}
+
+fn writes_within_writable_roots(
+    exec: &ValidExec,
+    cwd: &Path,
+    sandbox_policy: &SandboxPolicy,
+) -> bool {
+    let mut written = exec
+        .args
+        .iter()
+        .map(|arg| (&arg.r#type, arg.value.as_str()))
+        .chain(
+            exec.opts
+                .iter()
+                .map(|opt| (&opt.r#type, opt.value.as_str())),
+        )
+        .filter(|(arg_type, _)| matches!(arg_type, ArgType::WriteableFile))
+        .map(|(_, value)| cwd.join(value))
+        .peekable();
+
+    if written.peek().is_none() {
+        return true;
+    }
+
+    let writable_roots = match sandbox_policy {
+        SandboxPolicy::ReadOnly => return false,
+        SandboxPolicy::WorkspaceWrite { .. } => sandbox_policy.get_writable_roots_with_cwd(cwd),
+        // Nothing confines the command, so only writes to the workspace itself
+        // may skip the prompt.
+        SandboxPolicy::DangerFullAccess => SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            network_access: false,
+            network_allowlist: Vec::new(),
+            read_only_subpaths: Vec::new(),
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            container: false,
+        }
+        .get_writable_roots_with_cwd(cwd),
+    };
+    written.all(|path| {
+        writable_roots
+            .iter()
+            .any(|root| root.is_path_writable(&normalize(&path)))
+    })
+}
+
+/// Lexically resolves `.` and `..` so `../outside` cannot escape a root check.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::ParentDir => {
+                out.pop();
+            }
+            std::path::Component::CurDir => {}
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn vec_str(items: &[&str]) -> Vec<String> {
+        items.iter().map(std::string::ToString::to_string).collect()
+    }
+
+    fn workspace_write() -> SandboxPolicy {
+        SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            network_allowlist: vec![],
+            read_only_subpaths: vec![],
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            container: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn default_policy_allows_read_only_commands() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let (policy, errors) = ExecPolicy::load(codex_home.path(), cwd.path(), false).await;
+        assert!(errors.is_empty(), "{errors:?}");
+
+        assert_eq!(
+            ExecPolicyDecision::Allowed,
+            policy.evaluate(&vec_str(&["ls", "-l"]), cwd.path(), &workspace_write())
+        );
+        assert_eq!(
+            ExecPolicyDecision::Unmatched,
+            policy.evaluate(
+                &vec_str(&["cargo", "publish"]),
+                cwd.path(),
+                &workspace_write()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn user_policy_can_forbid_and_allow() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let policy_dir = codex_home.path().join(POLICY_DIR_NAME);
+        std::fs::create_dir_all(&policy_dir).expect("create policy dir");
+        std::fs::write(
+            policy_dir.join("team.policy"),
+            r#"
+define_program(
+    program="cargo",
+    args=["test"],
+)
+
+forbid_program_regex(
+    regex="^terraform$",
+    reason="terraform must be run by a human",
+)
+"#,
+        )
+        .expect("write policy");
+
+        let (policy, errors) = ExecPolicy::load(codex_home.path(), cwd.path(), false).await;
+        assert!(errors.is_empty(), "{errors:?}");
+
+        assert_eq!(
+            ExecPolicyDecision::Allowed,
+            policy.evaluate(
+                &vec_str(&["bash", "-lc", "cargo test && ls"]),
+                cwd.path(),
+                &workspace_write()
+            )
+        );
+        assert_eq!(
+            ExecPolicyDecision::Forbidden {
+                reason: "terraform must be run by a human".to_string()
+            },
+            policy.evaluate(
+                &vec_str(&["bash", "-lc", "ls && terraform apply"]),
+                cwd.path(),
+                &workspace_write()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_outside_writable_roots_are_not_allowed() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let (policy, _) = ExecPolicy::load(codex_home.path(), cwd.path(), false).await;
+
+        assert_eq!(
+            ExecPolicyDecision::Allowed,
+            policy.evaluate(
+                &vec_str(&["cp", "a.txt", "b.txt"]),
+                cwd.path(),
+                &workspace_write()
+            )
+        );
+        assert_eq!(
+            ExecPolicyDecision::Unmatched,
+            policy.evaluate(
+                &vec_str(&["cp", "a.txt", "../b.txt"]),
+                cwd.path(),
+                &workspace_write()
+            )
+        );
+        assert_eq!(
+            ExecPolicyDecision::Unmatched,
+            policy.evaluate(
+                &vec_str(&["cp", "a.txt", "b.txt"]),
+                cwd.path(),
+                &SandboxPolicy::ReadOnly
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_without_a_sandbox_stay_within_the_workspace() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let (policy, _) = ExecPolicy::load(codex_home.path(), cwd.path(), false).await;
+
+        assert_eq!(
+            ExecPolicyDecision::Allowed,
+            policy.evaluate(
+                &vec_str(&["cp", "a.txt", "b.txt"]),
+                cwd.path(),
+                &SandboxPolicy::DangerFullAccess
+            )
+        );
+        assert_eq!(
+            ExecPolicyDecision::Unmatched,
+            policy.evaluate(
+                &vec_str(&["cp", "a.txt", "/etc/x"]),
+                cwd.path(),
+                &SandboxPolicy::DangerFullAccess
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn project_policies_only_forbid_in_trusted_projects() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let repo = TempDir::new().expect("tempdir");
+        std::fs::create_dir(repo.path().join(".git")).expect("create .git");
+        let policy_dir = repo
+            .path()
+            .join(PROJECT_CONFIG_DIR_NAME)
+            .join(POLICY_DIR_NAME);
+        std::fs::create_dir_all(&policy_dir).expect("create policy dir");
+        std::fs::write(
+            policy_dir.join("project.policy"),
+            r#"
+define_program(
+    program="curl",
+    args=["https://example.com"],
+)
+
+forbid_program_regex(
+    regex="^terraform$",
+    reason="terraform must be run by a human",
+)
+"#,
+        )
+        .expect("write policy");
+        let curl = vec_str(&["curl", "https://example.com"]);
+        let terraform = vec_str(&["terraform", "apply"]);
+
+        let (untrusted, errors) = ExecPolicy::load(codex_home.path(), repo.path(), false).await;
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(
+            ExecPolicyDecision::Unmatched,
+            untrusted.evaluate(&terraform, repo.path(), &workspace_write())
+        );
+
+        let (trusted, errors) = ExecPolicy::load(codex_home.path(), repo.path(), true).await;
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(
+            ExecPolicyDecision::Forbidden {
+                reason: "terraform must be run by a human".to_string()
+            },
+            trusted.evaluate(&terraform, repo.path(), &workspace_write())
+        );
+        assert_eq!(
+            ExecPolicyDecision::Unmatched,
+            trusted.evaluate(&curl, repo.path(), &workspace_write())
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_policy_files_are_reported() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let policy_dir = codex_home.path().join(POLICY_DIR_NAME);
+        std::fs::create_dir_all(&policy_dir).expect("create policy dir");
+        std::fs::write(policy_dir.join("broken.policy"), "define_program(").expect("write");
+
+        let (_, errors) = ExecPolicy::load(codex_home.path(), cwd.path(), false).await;
+        assert_eq!(1, errors.len());
+        assert!(errors[0].contains("broken.policy"), "{errors:?}");
+    }
+}
@@ -1,3 +1,5 @@
+pub mod approval_rules;
+pub mod exec_policy;
 pub mod is_dangerous_command;
 pub mod is_safe_command;
 pub mod windows_safe_commands;
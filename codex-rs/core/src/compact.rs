@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::Prompt;
@@ -5,6 +6,7 @@ use crate::client_common::ResponseEvent;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::codex::get_last_assistant_message_from_turn;
+use crate::context_manager::ContextManager;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 use crate::protocol::AgentMessageEvent;
@@ -16,8 +18,10 @@ use crate::protocol::TurnContextItem;
 use crate::protocol::WarningEvent;
 use crate::truncate::truncate_middle;
 use crate::util::backoff;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::items::TurnItem;
 use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::RolloutItem;
@@ -26,8 +30,21 @@ use futures::prelude::*;
 use tracing::error;
 
 pub const SUMMARIZATION_PROMPT: &str = include_str!("../templates/compact/prompt.md");
+const CONDENSE_SUMMARIES_PROMPT: &str = include_str!("../templates/compact/condense_summaries.md");
 const COMPACT_USER_MESSAGE_MAX_TOKENS: usize = 20_000;
 
+/// Added to the summarization prompt by hierarchical compaction, which keeps
+/// the earlier summaries itself.
+const HIERARCHICAL_SUMMARY_INSTRUCTIONS: &str = "Earlier summaries of this conversation are kept separately. Summarize only what happened after the most recent summary.";
+const EARLIER_SUMMARY_PREFIX: &str = "Summary of the earlier conversation:\n\n";
+const LATEST_SUMMARY_PREFIX: &str = "Summary of the most recent conversation:\n\n";
+
+/// Tool outputs smaller than this are not worth eliding.
+const ELIDE_MIN_BYTES: usize = 1024;
+/// Token budget for trimming tool outputs when the model has neither an
+/// auto-compact limit nor a known context window.
+const DEFAULT_TRIM_TARGET_TOKENS: i64 = 100_000;
+
 pub(crate) async fn run_inline_auto_compact_task(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
@@ -55,16 +72,6 @@ async fn run_compact_task_inner(
     turn_context: Arc<TurnContext>,
     input: Vec<UserInput>,
 ) {
-    let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
-
-    let mut history = sess.clone_history().await;
-    history.record_items(&[initial_input_for_turn.into()]);
-
-    let mut truncated_count = 0usize;
-
-    let max_retries = turn_context.client.get_provider().stream_max_retries();
-    let mut retries = 0;
-
     let rollout_item = RolloutItem::TurnContext(TurnContextItem {
         cwd: turn_context.cwd.clone(),
         approval_policy: turn_context.approval_policy,
@@ -75,13 +82,193 @@ async fn run_compact_task_inner(
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
+    let strategy = turn_context.client.config().compaction.strategy;
+    let compacted = match strategy {
+        CompactionStrategy::ModelSummary => {
+            compact_with_model_summary(&sess, &turn_context, input).await
+        }
+        CompactionStrategy::HierarchicalSummary => {
+            compact_with_hierarchical_summary(&sess, &turn_context, input).await
+        }
+        CompactionStrategy::DropToolOutputs | CompactionStrategy::ElideToolOutputs => {
+            // Fall back to a summary when trimming tool outputs is not enough.
+            trim_tool_outputs(&sess, &turn_context, strategy).await
+                || compact_with_model_summary(&sess, &turn_context, input).await
+        }
+    };
+    if !compacted {
+        return;
+    }
+
+    if let Some(estimated_tokens) = sess
+        .clone_history()
+        .await
+        .estimate_token_count(&turn_context)
+    {
+        sess.override_last_token_usage_estimate(&turn_context, estimated_tokens)
+            .await;
+    }
+
+    let event = EventMsg::AgentMessage(AgentMessageEvent {
+        message: "Compact task completed".to_string(),
+    });
+    sess.send_event(&turn_context, event).await;
+
+    let warning = EventMsg::Warning(WarningEvent {
+        message: "Heads up: Long conversations and multiple compactions can cause the model to be less accurate. Start a new conversation when possible to keep conversations small and targeted.".to_string(),
+    });
+    sess.send_event(&turn_context, warning).await;
+}
+
+/// Replaces the history with recent user messages and a summary written by
+/// the model. Returns `false` when the summary could not be produced.
+async fn compact_with_model_summary(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    input: Vec<UserInput>,
+) -> bool {
+    let mut history = sess.clone_history().await;
+    history.record_items(&[ResponseInputItem::from(input).into()]);
+    let Some(summary_text) = summarize(sess, turn_context, history).await else {
+        return false;
+    };
+
+    let history_snapshot = sess.clone_history().await.get_history();
+    let user_messages = collect_user_messages(&history_snapshot);
+    let initial_context = sess.build_initial_context(turn_context.as_ref());
+    let mut new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
+    new_history.extend(ghost_snapshots(&history_snapshot));
+    sess.replace_history(new_history).await;
+
+    let rollout_item = RolloutItem::Compacted(CompactedItem {
+        message: summary_text,
+        strategy: CompactionStrategy::ModelSummary,
+        ..Default::default()
+    });
+    sess.persist_rollout_items(&[rollout_item]).await;
+    true
+}
+
+/// Has the model summarize the conversation since the last compaction, and
+/// condenses the summaries before that into one.
+async fn compact_with_hierarchical_summary(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    mut input: Vec<UserInput>,
+) -> bool {
+    let history_snapshot = sess.clone_history().await.get_history();
+    let (earlier, latest) = previous_summaries(&history_snapshot);
+
+    input.push(UserInput::Text {
+        text: HIERARCHICAL_SUMMARY_INSTRUCTIONS.to_string(),
+    });
+    let mut history = sess.clone_history().await;
+    history.record_items(&[ResponseInputItem::from(input).into()]);
+    let Some(summary_text) = summarize(sess, turn_context, history).await else {
+        return false;
+    };
+
+    let earlier_summary = match (earlier, latest) {
+        (Some(earlier), Some(latest)) => {
+            let mut history = ContextManager::new();
+            let input = vec![UserInput::Text {
+                text: format!(
+                    "{CONDENSE_SUMMARIES_PROMPT}\n<earlier_summary>\n{earlier}\n</earlier_summary>\n\n<later_summary>\n{latest}\n</later_summary>"
+                ),
+            }];
+            history.record_items(&[ResponseInputItem::from(input).into()]);
+            let Some(condensed) = summarize(sess, turn_context, history).await else {
+                return false;
+            };
+            Some(condensed)
+        }
+        (earlier, latest) => earlier.or(latest),
+    };
+
+    let user_messages = collect_user_messages_without_summaries(&history_snapshot);
+    let initial_context = sess.build_initial_context(turn_context.as_ref());
+    let mut new_history = build_hierarchical_history(
+        initial_context,
+        earlier_summary.as_deref(),
+        &user_messages,
+        &summary_text,
+    );
+    new_history.extend(ghost_snapshots(&history_snapshot));
+    sess.replace_history(new_history).await;
+
+    let rollout_item = RolloutItem::Compacted(CompactedItem {
+        message: summary_text,
+        strategy: CompactionStrategy::HierarchicalSummary,
+        earlier_summary,
+        ..Default::default()
+    });
+    sess.persist_rollout_items(&[rollout_item]).await;
+    true
+}
+
+/// Drops or elides the oldest tool outputs until the history is estimated to
+/// fit in half of the auto-compact limit. Returns `false`, so that the caller
+/// falls back to a summary, when there was nothing to trim or the history
+/// still does not fit afterwards.
+async fn trim_tool_outputs(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    strategy: CompactionStrategy,
+) -> bool {
+    let history_snapshot = sess.clone_history().await.get_history();
+    let target_tokens = turn_context
+        .client
+        .get_auto_compact_token_limit()
+        .or_else(|| turn_context.client.get_model_context_window())
+        .unwrap_or(DEFAULT_TRIM_TARGET_TOKENS)
+        / 2;
+    let target_bytes = usize::try_from(target_tokens).unwrap_or_default() * 4;
+    let (call_ids, fits) = select_tool_outputs(&history_snapshot, strategy, target_bytes);
+    if call_ids.is_empty() {
+        return false;
+    }
+
+    let new_history = apply_tool_output_trim(&history_snapshot, strategy, &call_ids);
+    sess.replace_history(new_history).await;
+
+    let verb = match strategy {
+        CompactionStrategy::DropToolOutputs => "Dropped",
+        _ => "Elided",
+    };
+    let message = format!(
+        "{verb} {} older tool output(s) to free up context.",
+        call_ids.len()
+    );
+    sess.notify_background_event(turn_context.as_ref(), message.clone())
+        .await;
+    let rollout_item = RolloutItem::Compacted(CompactedItem {
+        message,
+        strategy,
+        call_ids,
+        ..Default::default()
+    });
+    sess.persist_rollout_items(&[rollout_item]).await;
+    fits
+}
+
+/// Runs `history` through the model and returns the text of its reply.
+async fn summarize(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    mut history: ContextManager,
+) -> Option<String> {
+    let mut truncated_count = 0usize;
+
+    let max_retries = turn_context.client.get_provider().stream_max_retries();
+    let mut retries = 0;
+
     loop {
         let turn_input = history.get_history_for_prompt();
         let prompt = Prompt {
             input: turn_input.clone(),
             ..Default::default()
         };
-        let attempt_result = drain_to_completed(&sess, turn_context.as_ref(), &prompt).await;
+        let attempt_result = drain_to_completed(sess, turn_context.as_ref(), &prompt).await;
 
         match attempt_result {
             Ok(()) => {
@@ -97,7 +284,7 @@ async fn run_compact_task_inner(
                 break;
             }
             Err(CodexErr::Interrupted) => {
-                return;
+                return None;
             }
             Err(e @ CodexErr::ContextWindowExceeded) => {
                 if turn_input.len() > 1 {
@@ -114,8 +301,8 @@ async fn run_compact_task_inner(
                 let event = EventMsg::Error(ErrorEvent {
                     message: e.to_string(),
                 });
-                sess.send_event(&turn_context, event).await;
-                return;
+                sess.send_event(turn_context, event).await;
+                return None;
             }
             Err(e) => {
                 if retries < max_retries {
@@ -132,50 +319,48 @@ async fn run_compact_task_inner(
                     let event = EventMsg::Error(ErrorEvent {
                         message: e.to_string(),
                     });
-                    sess.send_event(&turn_context, event).await;
-                    return;
+                    sess.send_event(turn_context, event).await;
+                    return None;
                 }
             }
         }
     }
 
     let history_snapshot = sess.clone_history().await.get_history();
-    let summary_text = get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default();
-    let user_messages = collect_user_messages(&history_snapshot);
+    Some(get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default())
+}
 
-    let initial_context = sess.build_initial_context(turn_context.as_ref());
-    let mut new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
-    let ghost_snapshots: Vec<ResponseItem> = history_snapshot
+/// Rebuilds the history left behind by `compacted` from the history before
+/// it, so resumed sessions see what the live session saw.
+pub(crate) fn replay_compaction(
+    history: &[ResponseItem],
+    initial_context: Vec<ResponseItem>,
+    compacted: &CompactedItem,
+) -> Vec<ResponseItem> {
+    match compacted.strategy {
+        CompactionStrategy::ModelSummary => build_compacted_history(
+            initial_context,
+            &collect_user_messages(history),
+            &compacted.message,
+        ),
+        CompactionStrategy::HierarchicalSummary => build_hierarchical_history(
+            initial_context,
+            compacted.earlier_summary.as_deref(),
+            &collect_user_messages_without_summaries(history),
+            &compacted.message,
+        ),
+        CompactionStrategy::DropToolOutputs | CompactionStrategy::ElideToolOutputs => {
+            apply_tool_output_trim(history, compacted.strategy, &compacted.call_ids)
+        }
+    }
+}
+
+fn ghost_snapshots(items: &[ResponseItem]) -> Vec<ResponseItem> {
+    items
         .iter()
         .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
         .cloned()
-        .collect();
-    new_history.extend(ghost_snapshots);
-    sess.replace_history(new_history).await;
-
-    if let Some(estimated_tokens) = sess
-        .clone_history()
-        .await
-        .estimate_token_count(&turn_context)
-    {
-        sess.override_last_token_usage_estimate(&turn_context, estimated_tokens)
-            .await;
-    }
-
-    let rollout_item = RolloutItem::Compacted(CompactedItem {
-        message: summary_text.clone(),
-    });
-    sess.persist_rollout_items(&[rollout_item]).await;
-
-    let event = EventMsg::AgentMessage(AgentMessageEvent {
-        message: "Compact task completed".to_string(),
-    });
-    sess.send_event(&turn_context, event).await;
-
-    let warning = EventMsg::Warning(WarningEvent {
-        message: "Heads up: Long conversations and multiple compactions can cause the model to be less accurate. Start a new conversation when possible to keep conversations small and targeted.".to_string(),
-    });
-    sess.send_event(&turn_context, warning).await;
+        .collect()
 }
 
 pub fn content_items_to_text(content: &[ContentItem]) -> Option<String> {
@@ -270,6 +455,177 @@ fn build_compacted_history_with_limit(
     history
 }
 
+/// The summaries left in the history by a previous hierarchical compaction:
+/// the condensed earlier one and the latest one.
+fn previous_summaries(items: &[ResponseItem]) -> (Option<String>, Option<String>) {
+    let mut earlier = None;
+    let mut latest = None;
+    for message in collect_user_messages(items) {
+        if let Some(summary) = message.strip_prefix(EARLIER_SUMMARY_PREFIX) {
+            earlier = Some(summary.to_string());
+        } else if let Some(summary) = message.strip_prefix(LATEST_SUMMARY_PREFIX) {
+            latest = Some(summary.to_string());
+        }
+    }
+    (earlier, latest)
+}
+
+fn collect_user_messages_without_summaries(items: &[ResponseItem]) -> Vec<String> {
+    collect_user_messages(items)
+        .into_iter()
+        .filter(|message| {
+            !message.starts_with(EARLIER_SUMMARY_PREFIX)
+                && !message.starts_with(LATEST_SUMMARY_PREFIX)
+        })
+        .collect()
+}
+
+fn build_hierarchical_history(
+    mut history: Vec<ResponseItem>,
+    earlier_summary: Option<&str>,
+    user_messages: &[String],
+    summary_text: &str,
+) -> Vec<ResponseItem> {
+    if let Some(earlier_summary) = earlier_summary {
+        history.push(ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!("{EARLIER_SUMMARY_PREFIX}{earlier_summary}"),
+            }],
+        });
+    }
+    build_compacted_history(
+        history,
+        user_messages,
+        &format!("{LATEST_SUMMARY_PREFIX}{summary_text}"),
+    )
+}
+
+/// Picks the oldest tool calls whose outputs `strategy` trims until `items`
+/// are estimated to fit in `target_bytes`, and reports whether they then fit.
+fn select_tool_outputs(
+    items: &[ResponseItem],
+    strategy: CompactionStrategy,
+    target_bytes: usize,
+) -> (Vec<String>, bool) {
+    let mut total: usize = items
+        .iter()
+        .filter(|item| !matches!(item, ResponseItem::GhostSnapshot { .. }))
+        .map(item_bytes)
+        .sum();
+    let mut call_ids = Vec::new();
+    for item in items {
+        if total <= target_bytes {
+            break;
+        }
+        let Some(call_id) = output_call_id(item) else {
+            continue;
+        };
+        let saved = match strategy {
+            CompactionStrategy::DropToolOutputs => items
+                .iter()
+                .filter(|other| tool_call_id(other) == Some(call_id))
+                .map(item_bytes)
+                .sum(),
+            CompactionStrategy::ElideToolOutputs => match elide_output(item) {
+                Some(elided) => item_bytes(item).saturating_sub(item_bytes(&elided)),
+                None => continue,
+            },
+            CompactionStrategy::ModelSummary | CompactionStrategy::HierarchicalSummary => break,
+        };
+        total = total.saturating_sub(saved);
+        call_ids.push(call_id.to_string());
+    }
+    (call_ids, total <= target_bytes)
+}
+
+fn apply_tool_output_trim(
+    items: &[ResponseItem],
+    strategy: CompactionStrategy,
+    call_ids: &[String],
+) -> Vec<ResponseItem> {
+    let call_ids: HashSet<&str> = call_ids.iter().map(String::as_str).collect();
+    match strategy {
+        CompactionStrategy::DropToolOutputs => items
+            .iter()
+            .filter(|item| !tool_call_id(item).is_some_and(|id| call_ids.contains(id)))
+            .cloned()
+            .collect(),
+        CompactionStrategy::ElideToolOutputs => items
+            .iter()
+            .map(|item| {
+                output_call_id(item)
+                    .filter(|id| call_ids.contains(id))
+                    .and_then(|_| elide_output(item))
+                    .unwrap_or_else(|| item.clone())
+            })
+            .collect(),
+        CompactionStrategy::ModelSummary | CompactionStrategy::HierarchicalSummary => {
+            items.to_vec()
+        }
+    }
+}
+
+/// Replaces a large tool output with a stub that keeps its call ID.
+fn elide_output(item: &ResponseItem) -> Option<ResponseItem> {
+    match item {
+        ResponseItem::FunctionCallOutput { call_id, output }
+            if output.content.len() >= ELIDE_MIN_BYTES =>
+        {
+            Some(ResponseItem::FunctionCallOutput {
+                call_id: call_id.clone(),
+                output: FunctionCallOutputPayload {
+                    content: elision_stub(call_id, &output.content),
+                    content_items: None,
+                    success: output.success,
+                },
+            })
+        }
+        ResponseItem::CustomToolCallOutput { call_id, output }
+            if output.len() >= ELIDE_MIN_BYTES =>
+        {
+            Some(ResponseItem::CustomToolCallOutput {
+                call_id: call_id.clone(),
+                output: elision_stub(call_id, output),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn elision_stub(call_id: &str, output: &str) -> String {
+    format!(
+        "[Output elided during compaction: {} bytes, {} lines. Read it with read_tool_output, call_id: {call_id}]",
+        output.len(),
+        output.lines().count()
+    )
+}
+
+fn output_call_id(item: &ResponseItem) -> Option<&str> {
+    match item {
+        ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. } => Some(call_id),
+        _ => None,
+    }
+}
+
+/// Call ID of a tool call or of its output.
+fn tool_call_id(item: &ResponseItem) -> Option<&str> {
+    match item {
+        ResponseItem::FunctionCall { call_id, .. }
+        | ResponseItem::CustomToolCall { call_id, .. } => Some(call_id),
+        ResponseItem::LocalShellCall { call_id, .. } => call_id.as_deref(),
+        _ => output_call_id(item),
+    }
+}
+
+fn item_bytes(item: &ResponseItem) -> usize {
+    serde_json::to_string(item)
+        .map(|json| json.len())
+        .unwrap_or_default()
+}
+
 async fn drain_to_completed(
     sess: &Session,
     turn_context: &TurnContext,
@@ -457,4 +813,132 @@ mod tests {
         };
         assert_eq!(summary, summary_text);
     }
+
+    fn call(call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{}".to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn output(call_id: &str, content: String) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn tool_history() -> Vec<ResponseItem> {
+        vec![
+            call("old"),
+            output("old", "a".repeat(4000)),
+            call("small"),
+            output("small", "ok".to_string()),
+            call("new"),
+            output("new", "b".repeat(4000)),
+        ]
+    }
+
+    #[test]
+    fn drop_tool_outputs_removes_oldest_calls_first() {
+        let items = tool_history();
+        let total: usize = items.iter().map(item_bytes).sum();
+
+        let (call_ids, fits) =
+            select_tool_outputs(&items, CompactionStrategy::DropToolOutputs, total - 1000);
+        assert_eq!(call_ids, vec!["old".to_string()]);
+        assert!(fits);
+
+        let trimmed =
+            apply_tool_output_trim(&items, CompactionStrategy::DropToolOutputs, &call_ids);
+        assert_eq!(trimmed, items[2..].to_vec());
+    }
+
+    #[test]
+    fn elide_tool_outputs_skips_small_outputs_and_keeps_call_ids() {
+        let items = tool_history();
+
+        let (call_ids, fits) = select_tool_outputs(&items, CompactionStrategy::ElideToolOutputs, 0);
+        assert_eq!(call_ids, vec!["old".to_string(), "new".to_string()]);
+        assert!(!fits);
+
+        let trimmed =
+            apply_tool_output_trim(&items, CompactionStrategy::ElideToolOutputs, &call_ids);
+        assert_eq!(trimmed.len(), items.len());
+        assert_eq!(
+            trimmed[1],
+            output(
+                "old",
+                "[Output elided during compaction: 4000 bytes, 1 lines. Read it with read_tool_output, call_id: old]"
+                    .to_string()
+            )
+        );
+        assert_eq!(trimmed[3], items[3]);
+    }
+
+    #[test]
+    fn hierarchical_history_keeps_earlier_summary_apart_from_user_messages() {
+        let history = build_hierarchical_history(
+            Vec::new(),
+            Some("earlier"),
+            &["question".to_string()],
+            "latest",
+        );
+        let texts: Vec<String> = history
+            .iter()
+            .map(|item| match item {
+                ResponseItem::Message { content, .. } => {
+                    content_items_to_text(content).unwrap_or_default()
+                }
+                other => panic!("unexpected item in history: {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                format!("{EARLIER_SUMMARY_PREFIX}earlier"),
+                "question".to_string(),
+                format!("{LATEST_SUMMARY_PREFIX}latest"),
+            ]
+        );
+
+        assert_eq!(
+            previous_summaries(&history),
+            (Some("earlier".to_string()), Some("latest".to_string()))
+        );
+        assert_eq!(
+            collect_user_messages_without_summaries(&history),
+            vec!["question".to_string()]
+        );
+    }
+
+    #[test]
+    fn replay_compaction_uses_the_recorded_strategy() {
+        let items = tool_history();
+        let compacted = CompactedItem {
+            message: "Dropped 1 older tool output(s) to free up context.".to_string(),
+            strategy: CompactionStrategy::DropToolOutputs,
+            call_ids: vec!["new".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            replay_compaction(&items, Vec::new(), &compacted),
+            items[..4].to_vec()
+        );
+
+        let compacted = CompactedItem {
+            message: "summary".to_string(),
+            ..Default::default()
+        };
+        let replayed = replay_compaction(&items, Vec::new(), &compacted);
+        assert_eq!(
+            replayed,
+            build_compacted_history(Vec::new(), &[], "summary")
+        );
+    }
 }
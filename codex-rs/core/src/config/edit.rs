@@ -2,6 +2,7 @@ use crate::config::CONFIG_TOML_FILE;
 use crate::config::types::McpServerConfig;
 use crate::config::types::Notice;
 use anyhow::Context;
+use codex_protocol::config_types::ApprovalRule;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_utils_tokenizer::warm_model_cache;
 use std::collections::BTreeMap;
@@ -9,6 +10,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
 use tokio::task;
+use toml_edit::ArrayOfTables;
 use toml_edit::DocumentMut;
 use toml_edit::Item as TomlItem;
 use toml_edit::Table as TomlTable;
@@ -34,6 +36,8 @@ pub enum ConfigEdit {
     SetNoticeHideModelMigrationPrompt(String, bool),
     /// Replace the entire `[mcp_servers]` table.
     ReplaceMcpServers(BTreeMap<String, McpServerConfig>),
+    /// Replace every `[[approval_rules]]` entry.
+    ReplaceApprovalRules(Vec<ApprovalRule>),
     /// Set trust_level = "trusted" under `[projects."<path>"]`,
     /// migrating inline tables to explicit tables.
     SetProjectTrusted(PathBuf),
@@ -50,6 +54,7 @@ pub enum ConfigEdit {
 mod document_helpers {
     use crate::config::types::McpServerConfig;
     use crate::config::types::McpServerTransportConfig;
+    use codex_protocol::config_types::ApprovalRule;
     use toml_edit::Array as TomlArray;
     use toml_edit::InlineTable;
     use toml_edit::Item as TomlItem;
@@ -88,6 +93,17 @@ mod document_helpers {
         }
     }
 
+    pub(super) fn serialize_approval_rule(rule: &ApprovalRule) -> TomlTable {
+        let mut entry = TomlTable::new();
+        if let Some(prefix) = &rule.prefix {
+            entry["prefix"] = array_from_iter(prefix.iter().cloned());
+        }
+        if let Some(project) = &rule.project {
+            entry["project"] = value(project.to_string_lossy().to_string());
+        }
+        entry
+    }
+
     pub(super) fn serialize_mcp_server(config: &McpServerConfig) -> TomlItem {
         let mut entry = TomlTable::new();
         entry.set_implicit(false);
@@ -272,6 +288,7 @@ impl ConfigDocument {
                 value(*acknowledged),
             )),
             ConfigEdit::ReplaceMcpServers(servers) => Ok(self.replace_mcp_servers(servers)),
+            ConfigEdit::ReplaceApprovalRules(rules) => Ok(self.replace_approval_rules(rules)),
             ConfigEdit::SetPath { segments, value } => Ok(self.insert(segments, value.clone())),
             ConfigEdit::ClearPath { segments } => Ok(self.clear_owned(segments)),
             ConfigEdit::SetProjectTrusted(project_path) => {
@@ -320,6 +337,20 @@ impl ConfigDocument {
         self.write_value(Scope::Global, &["mcp_servers"], item)
     }
 
+    fn replace_approval_rules(&mut self, rules: &[ApprovalRule]) -> bool {
+        if rules.is_empty() {
+            return self.clear(Scope::Global, &["approval_rules"]);
+        }
+
+        let mut array = ArrayOfTables::new();
+        for rule in rules {
+            array.push(document_helpers::serialize_approval_rule(rule));
+        }
+
+        let item = TomlItem::ArrayOfTables(array);
+        self.write_value(Scope::Global, &["approval_rules"], item)
+    }
+
     fn scoped_segments(&self, scope: Scope, segments: &[&str]) -> Vec<String> {
         let resolved: Vec<String> = segments
             .iter()
@@ -533,6 +564,12 @@ impl ConfigEditsBuilder {
         self
     }
 
+    pub fn replace_approval_rules(mut self, rules: &[ApprovalRule]) -> Self {
+        self.edits
+            .push(ConfigEdit::ReplaceApprovalRules(rules.to_vec()));
+        self
+    }
+
     pub fn set_project_trusted<P: Into<PathBuf>>(mut self, project_path: P) -> Self {
         self.edits
             .push(ConfigEdit::SetProjectTrusted(project_path.into()));
@@ -1035,6 +1072,50 @@ model_reasoning_effort = "high"
         assert_eq!(notice, Some(true));
     }
 
+    #[test]
+    fn blocking_replace_approval_rules_round_trips() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+        std::fs::write(codex_home.join(CONFIG_TOML_FILE), "model = \"o3\"\n").expect("seed");
+
+        let rules = vec![
+            ApprovalRule {
+                prefix: Some(vec!["cargo".to_string(), "test".to_string()]),
+                project: None,
+            },
+            ApprovalRule {
+                prefix: None,
+                project: Some(PathBuf::from("/repo")),
+            },
+        ];
+        apply_blocking(
+            codex_home,
+            None,
+            &[ConfigEdit::ReplaceApprovalRules(rules.clone())],
+        )
+        .expect("persist");
+
+        let raw = std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        let expected = r#"model = "o3"
+
+[[approval_rules]]
+prefix = ["cargo", "test"]
+
+[[approval_rules]]
+project = "/repo"
+"#;
+        assert_eq!(raw, expected);
+
+        apply_blocking(
+            codex_home,
+            None,
+            &[ConfigEdit::ReplaceApprovalRules(Vec::new())],
+        )
+        .expect("persist");
+        let raw = std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        assert_eq!(raw, "model = \"o3\"\n");
+    }
+
     #[test]
     fn replace_mcp_servers_blocking_clears_table_when_empty() {
         let tmp = tempdir().expect("tmpdir");
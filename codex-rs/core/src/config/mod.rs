@@ -1,6 +1,11 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::config::types::AuditConfig;
+use crate::config::types::BudgetConfig;
+use crate::config::types::CommandAssessmentConfig;
+use crate::config::types::CompactionConfig;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config::types::History;
+use crate::config::types::HooksConfig;
 use crate::config::types::McpServerConfig;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
@@ -8,6 +13,8 @@ use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
 use crate::config::types::ReasoningSummaryFormat;
+use crate::config::types::RedactionConfig;
+use crate::config::types::ResourceLimits;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
@@ -34,6 +41,8 @@ use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
+use codex_protocol::config_types::ApprovalRule;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::config_types::ForcedLoginMethod;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
@@ -111,6 +120,11 @@ pub struct Config {
     /// was coerced to read-only because native auto mode is unsupported.
     pub forced_auto_mode_downgraded_on_windows: bool,
 
+    /// Globs for paths that sandboxed commands and tools may not read, on top
+    /// of [`crate::protocol::DEFAULT_READ_DENY_GLOBS`]. Kept next to
+    /// `sandbox_policy` rather than in it.
+    pub sandbox_read_deny: Vec<String>,
+
     pub shell_environment_policy: ShellEnvironmentPolicy,
 
     /// When `true`, `AgentReasoning` events emitted by the backend will be
@@ -156,6 +170,31 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// Lifecycle hooks that run before and after tool calls, at the end of
+    /// each turn and when the session starts. See [`HooksConfig`].
+    pub hooks: HooksConfig,
+
+    /// Token and cost ceilings for turns and the session. See [`BudgetConfig`].
+    pub budget: BudgetConfig,
+
+    /// Limits for commands run by the shell tools. See [`ResourceLimits`].
+    pub resource_limits: ResourceLimits,
+
+    /// Masking of secrets in tool output sent to the model.
+    pub redaction: RedactionConfig,
+
+    /// How conversation history is compacted.
+    pub compaction: CompactionConfig,
+
+    /// Tamper-evident log of approvals, commands, patches and MCP tool calls.
+    pub audit: AuditConfig,
+
+    /// Standing approvals for commands, from `[[approval_rules]]`.
+    pub approval_rules: Vec<ApprovalRule>,
+
+    /// Model-based risk review of commands before they run.
+    pub command_assessment: CommandAssessmentConfig,
+
     /// TUI notifications preference. When set, the TUI will send OSC 9 notifications on approvals
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -251,6 +290,11 @@ pub struct Config {
     /// Centralized feature flags; source of truth for feature gating.
     pub features: Features,
 
+    /// When set, only tools with these names are exposed to the model. Not
+    /// read from `config.toml`; `spawn_agent` uses it to hand a sub-agent a
+    /// subset of the parent's tools.
+    pub enabled_tools: Option<Vec<String>>,
+
     /// The active profile name used to derive this `Config` (if any).
     pub active_profile: Option<String>,
 
@@ -535,10 +579,46 @@ pub struct ConfigToml {
     /// Sandbox configuration to apply if `sandbox` is `WorkspaceWrite`.
     pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
 
+    /// Extra globs for paths that sandboxed commands and tools may not read,
+    /// on top of the built-in secret locations.
+    pub sandbox_read_deny: Option<Vec<String>>,
+
     /// Optional external command to spawn for end-user notifications.
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Lifecycle hooks that can approve, deny or amend tool calls.
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+
+    /// Token and cost budgets enforced per turn and per session.
+    #[serde(default)]
+    pub budget: Option<BudgetConfig>,
+
+    /// Memory, CPU, process, file size and output limits for commands.
+    #[serde(default)]
+    pub resource_limits: Option<ResourceLimits>,
+
+    /// Secret redaction for tool output sent to the model.
+    #[serde(default)]
+    pub redaction: Option<RedactionConfig>,
+
+    /// Context compaction settings.
+    #[serde(default)]
+    pub compaction: Option<CompactionConfig>,
+
+    /// Audit log of side-effecting actions.
+    #[serde(default)]
+    pub audit: Option<AuditConfig>,
+
+    /// Commands that run without prompting, by prefix and/or project.
+    #[serde(default)]
+    pub approval_rules: Option<Vec<ApprovalRule>>,
+
+    /// Model-based risk review of commands before they run.
+    #[serde(default)]
+    pub command_assessment: Option<CommandAssessmentConfig>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -747,25 +827,63 @@ impl ConfigToml {
             .unwrap_or_default();
         let mut sandbox_policy = match resolved_sandbox_mode {
             SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
-            SandboxMode::WorkspaceWrite => match self.sandbox_workspace_write.as_ref() {
-                Some(SandboxWorkspaceWrite {
-                    writable_roots,
-                    network_access,
-                    exclude_tmpdir_env_var,
-                    exclude_slash_tmp,
-                }) => SandboxPolicy::WorkspaceWrite {
-                    writable_roots: writable_roots.clone(),
-                    network_access: *network_access,
-                    exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
-                    exclude_slash_tmp: *exclude_slash_tmp,
-                },
-                None => SandboxPolicy::new_workspace_write_policy(),
-            },
+            SandboxMode::WorkspaceWrite | SandboxMode::Container => {
+                match self.sandbox_workspace_write.as_ref() {
+                    Some(SandboxWorkspaceWrite {
+                        writable_roots,
+                        network_access,
+                        network_allowlist,
+                        read_only_subpaths,
+                        roots,
+                        exclude_tmpdir_env_var,
+                        exclude_slash_tmp,
+                    }) => {
+                        let mut writable_roots = writable_roots.clone();
+                        let mut read_only_subpaths = read_only_subpaths.clone();
+                        for (root, rules) in roots {
+                            let root = resolved_cwd.join(root);
+                            // Per-root subpaths become absolute so they only
+                            // apply to the root that declared them. A read-only
+                            // root is protected as a whole.
+                            read_only_subpaths.extend(
+                                rules
+                                    .read_only_subpaths
+                                    .iter()
+                                    .map(|subpath| root.join(subpath)),
+                            );
+                            if !rules.writable {
+                                read_only_subpaths.push(root);
+                            } else if root != resolved_cwd && !writable_roots.contains(&root) {
+                                writable_roots.push(root);
+                            }
+                        }
+                        SandboxPolicy::WorkspaceWrite {
+                            writable_roots,
+                            network_access: *network_access,
+                            network_allowlist: network_allowlist.clone(),
+                            read_only_subpaths,
+                            exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
+                            exclude_slash_tmp: *exclude_slash_tmp,
+                            container: false,
+                        }
+                    }
+                    None => SandboxPolicy::new_workspace_write_policy(),
+                }
+            }
             SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
         };
+        // `container` is workspace-write enforced by the namespace container.
+        if resolved_sandbox_mode == SandboxMode::Container
+            && let SandboxPolicy::WorkspaceWrite { container, .. } = &mut sandbox_policy
+        {
+            *container = true;
+        }
         let mut forced_auto_mode_downgraded_on_windows = false;
         if cfg!(target_os = "windows")
-            && matches!(resolved_sandbox_mode, SandboxMode::WorkspaceWrite)
+            && matches!(
+                resolved_sandbox_mode,
+                SandboxMode::WorkspaceWrite | SandboxMode::Container
+            )
             // If the experimental Windows sandbox is enabled, do not force a downgrade.
             && crate::safety::get_platform_sandbox().is_none()
         {
@@ -898,7 +1016,16 @@ impl Config {
             experimental_sandbox_command_assessment: sandbox_command_assessment_override,
         };
 
-        let features = Features::from_config(&cfg, &config_profile, feature_overrides);
+        let mut features = Features::from_config(&cfg, &config_profile, feature_overrides);
+        // The stubs left by `elide_tool_outputs` are read back with
+        // `read_tool_output`, which needs the tool output store.
+        if cfg
+            .compaction
+            .as_ref()
+            .is_some_and(|compaction| compaction.strategy == CompactionStrategy::ElideToolOutputs)
+        {
+            features.enable(Feature::ReadToolOutput);
+        }
         #[cfg(target_os = "windows")]
         {
             crate::safety::set_windows_sandbox_enabled(features.enabled(Feature::WindowsSandbox));
@@ -944,6 +1071,12 @@ impl Config {
             policy: mut sandbox_policy,
             forced_auto_mode_downgraded_on_windows,
         } = cfg.derive_sandbox_policy(sandbox_mode, config_profile.sandbox_mode, &resolved_cwd);
+        if sandbox_policy.uses_container() && !cfg!(target_os = "linux") {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "sandbox_mode = \"container\" is only supported on Linux; use \"workspace-write\" instead",
+            ));
+        }
         if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut sandbox_policy {
             for path in additional_writable_roots {
                 if !writable_roots.iter().any(|existing| existing == &path) {
@@ -1097,8 +1230,17 @@ impl Config {
             sandbox_policy,
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
+            sandbox_read_deny: cfg.sandbox_read_deny.unwrap_or_default(),
             shell_environment_policy,
             notify: cfg.notify,
+            hooks: cfg.hooks.unwrap_or_default(),
+            budget: cfg.budget.unwrap_or_default(),
+            resource_limits: cfg.resource_limits.unwrap_or_default(),
+            redaction: cfg.redaction.unwrap_or_default(),
+            compaction: cfg.compaction.unwrap_or_default(),
+            audit: cfg.audit.unwrap_or_default(),
+            approval_rules: cfg.approval_rules.unwrap_or_default(),
+            command_assessment: cfg.command_assessment.unwrap_or_default(),
             user_instructions,
             base_instructions,
             developer_instructions,
@@ -1155,6 +1297,7 @@ impl Config {
             use_experimental_unified_exec_tool,
             use_experimental_use_rmcp_client,
             features,
+            enabled_tools: None,
             active_profile: active_profile_name,
             active_project,
             windows_wsl_setup_acknowledged: cfg.windows_wsl_setup_acknowledged.unwrap_or(false),
@@ -1280,6 +1423,7 @@ mod tests {
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
     use crate::config::types::HistoryPersistence;
+    use crate::config::types::HookConfig;
     use crate::config::types::McpServerTransportConfig;
     use crate::config::types::Notifications;
     use crate::features::Feature;
@@ -1416,8 +1560,11 @@ exclude_slash_tmp = true
                     policy: SandboxPolicy::WorkspaceWrite {
                         writable_roots: vec![PathBuf::from("/my/workspace")],
                         network_access: false,
+                        network_allowlist: vec![],
+                        read_only_subpaths: vec![],
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        container: false,
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1461,8 +1608,11 @@ trust_level = "trusted"
                     policy: SandboxPolicy::WorkspaceWrite {
                         writable_roots: vec![PathBuf::from("/my/workspace")],
                         network_access: false,
+                        network_allowlist: vec![],
+                        read_only_subpaths: vec![],
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        container: false,
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1470,6 +1620,221 @@ trust_level = "trusted"
         }
     }
 
+    #[test]
+    fn sandbox_read_only_subpaths_resolve_per_root() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+sandbox_mode = "workspace-write"
+
+[sandbox_workspace_write]
+writable_roots = ["/srv/shared"]
+read_only_subpaths = ["migrations", ".github/workflows"]
+
+[sandbox_workspace_write.roots."/srv/shared"]
+read_only_subpaths = ["published"]
+
+[sandbox_workspace_write.roots."."]
+read_only_subpaths = ["vendor"]
+
+[sandbox_workspace_write.roots."/srv/cache"]
+
+[sandbox_workspace_write.roots."/srv/shared/archive"]
+writable = false
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let resolution = cfg.derive_sandbox_policy(None, None, &PathBuf::from("/tmp/test"));
+        if cfg!(target_os = "windows") {
+            return;
+        }
+        let SandboxPolicy::WorkspaceWrite {
+            writable_roots,
+            read_only_subpaths,
+            ..
+        } = resolution.policy
+        else {
+            panic!("expected workspace-write policy");
+        };
+        assert_eq!(
+            writable_roots,
+            vec![PathBuf::from("/srv/shared"), PathBuf::from("/srv/cache")]
+        );
+        assert_eq!(
+            read_only_subpaths,
+            vec![
+                PathBuf::from("migrations"),
+                PathBuf::from(".github/workflows"),
+                PathBuf::from("/srv/shared/published"),
+                PathBuf::from("/srv/shared/archive"),
+                PathBuf::from("/tmp/test/vendor"),
+            ]
+        );
+    }
+
+    #[test]
+    fn container_sandbox_mode_uses_workspace_write_settings() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+sandbox_mode = "container"
+
+[sandbox_workspace_write]
+writable_roots = ["/my/workspace"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let resolution = cfg.derive_sandbox_policy(None, None, &PathBuf::from("/tmp/test"));
+        if cfg!(target_os = "windows") {
+            return;
+        }
+        assert_eq!(
+            resolution,
+            SandboxPolicyResolution {
+                policy: SandboxPolicy::WorkspaceWrite {
+                    writable_roots: vec![PathBuf::from("/my/workspace")],
+                    network_access: false,
+                    network_allowlist: vec![],
+                    read_only_subpaths: vec![],
+                    exclude_tmpdir_env_var: false,
+                    exclude_slash_tmp: false,
+                    container: true,
+                },
+                forced_auto_mode_downgraded_on_windows: false,
+            }
+        );
+    }
+
+    #[test]
+    fn sandbox_read_deny_is_kept_next_to_the_policy() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+sandbox_mode = "read-only"
+sandbox_read_deny = ["~/.config/secrets", "*.pem"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.sandbox_policy, SandboxPolicy::ReadOnly);
+        assert_eq!(
+            config.sandbox_read_deny,
+            vec!["~/.config/secrets".to_string(), "*.pem".to_string()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resource_limits_load_from_toml() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[resource_limits]
+memory_mb = 4096
+cpu_seconds = 600
+output_mb = 16
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.resource_limits,
+            ResourceLimits {
+                memory_mb: Some(4096),
+                cpu_seconds: Some(600),
+                processes: None,
+                file_size_mb: None,
+                output_mb: Some(16),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn redaction_loads_from_toml() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[redaction]
+high_entropy = false
+patterns = ["ACME-[0-9]{6}"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.redaction,
+            RedactionConfig {
+                enabled: true,
+                high_entropy: false,
+                patterns: vec!["ACME-[0-9]{6}".to_string()],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compaction_strategy_loads_from_toml() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[compaction]
+strategy = "elide_tool_outputs"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.compaction,
+            CompactionConfig {
+                strategy: CompactionStrategy::ElideToolOutputs,
+            }
+        );
+        assert!(config.features.enabled(Feature::ReadToolOutput));
+        Ok(())
+    }
+
+    #[test]
+    fn command_assessment_loads_from_toml() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[command_assessment]
+enabled = true
+model = "gpt-5-codex-mini"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.command_assessment,
+            CommandAssessmentConfig {
+                enabled: true,
+                model: Some("gpt-5-codex-mini".to_string()),
+                auto_approve_low_risk: true,
+            }
+        );
+        Ok(())
+    }
+
     #[test]
     fn add_dir_override_extends_workspace_writable_roots() -> std::io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -2867,9 +3232,18 @@ model_verbosity = "high"
                 sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 forced_auto_mode_downgraded_on_windows: false,
+                sandbox_read_deny: Vec::new(),
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                hooks: HooksConfig::default(),
+                budget: BudgetConfig::default(),
+                resource_limits: ResourceLimits::default(),
+                redaction: RedactionConfig::default(),
+                compaction: CompactionConfig::default(),
+                audit: AuditConfig::default(),
+                approval_rules: Vec::new(),
+                command_assessment: CommandAssessmentConfig::default(),
                 cwd: fixture.cwd(),
                 cli_auth_credentials_store_mode: Default::default(),
                 mcp_servers: HashMap::new(),
@@ -2898,6 +3272,7 @@ model_verbosity = "high"
                 use_experimental_unified_exec_tool: false,
                 use_experimental_use_rmcp_client: false,
                 features: Features::with_defaults(),
+                enabled_tools: None,
                 active_profile: Some("o3".to_string()),
                 active_project: ProjectConfig { trust_level: None },
                 windows_wsl_setup_acknowledged: false,
@@ -2938,9 +3313,18 @@ model_verbosity = "high"
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            sandbox_read_deny: Vec::new(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
+            budget: BudgetConfig::default(),
+            resource_limits: ResourceLimits::default(),
+            redaction: RedactionConfig::default(),
+            compaction: CompactionConfig::default(),
+            audit: AuditConfig::default(),
+            approval_rules: Vec::new(),
+            command_assessment: CommandAssessmentConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -2969,6 +3353,7 @@ model_verbosity = "high"
             use_experimental_unified_exec_tool: false,
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
+            enabled_tools: None,
             active_profile: Some("gpt3".to_string()),
             active_project: ProjectConfig { trust_level: None },
             windows_wsl_setup_acknowledged: false,
@@ -3024,9 +3409,18 @@ model_verbosity = "high"
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            sandbox_read_deny: Vec::new(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
+            budget: BudgetConfig::default(),
+            resource_limits: ResourceLimits::default(),
+            redaction: RedactionConfig::default(),
+            compaction: CompactionConfig::default(),
+            audit: AuditConfig::default(),
+            approval_rules: Vec::new(),
+            command_assessment: CommandAssessmentConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -3055,6 +3449,7 @@ model_verbosity = "high"
             use_experimental_unified_exec_tool: false,
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
+            enabled_tools: None,
             active_profile: Some("zdr".to_string()),
             active_project: ProjectConfig { trust_level: None },
             windows_wsl_setup_acknowledged: false,
@@ -3096,9 +3491,18 @@ model_verbosity = "high"
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            sandbox_read_deny: Vec::new(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
+            budget: BudgetConfig::default(),
+            resource_limits: ResourceLimits::default(),
+            redaction: RedactionConfig::default(),
+            compaction: CompactionConfig::default(),
+            audit: AuditConfig::default(),
+            approval_rules: Vec::new(),
+            command_assessment: CommandAssessmentConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -3127,6 +3531,7 @@ model_verbosity = "high"
             use_experimental_unified_exec_tool: false,
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
+            enabled_tools: None,
             active_profile: Some("gpt5".to_string()),
             active_project: ProjectConfig { trust_level: None },
             windows_wsl_setup_acknowledged: false,
@@ -3254,6 +3659,37 @@ trust_level = "trusted"
 
         Ok(())
     }
+
+    #[test]
+    fn test_hooks_deserialize() {
+        let toml = r#"
+            [[hooks.pre_tool_use]]
+            command = ["./no-push.sh"]
+            tools = ["shell"]
+
+            [[hooks.turn_complete]]
+            command = ["./log.sh", "turn"]
+            timeout_ms = 500
+        "#;
+        let cfg: ConfigToml = toml::from_str(toml).expect("deserialize hooks");
+        assert_eq!(
+            cfg.hooks,
+            Some(HooksConfig {
+                pre_tool_use: vec![HookConfig {
+                    command: vec!["./no-push.sh".to_string()],
+                    tools: Some(vec!["shell".to_string()]),
+                    timeout_ms: None,
+                }],
+                post_tool_use: Vec::new(),
+                turn_complete: vec![HookConfig {
+                    command: vec!["./log.sh".to_string(), "turn".to_string()],
+                    tools: None,
+                    timeout_ms: Some(500),
+                }],
+                session_start: Vec::new(),
+            })
+        );
+    }
 }
 
 #[cfg(test)]
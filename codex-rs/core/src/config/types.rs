@@ -3,7 +3,9 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use codex_protocol::config_types::CompactionStrategy;
 use serde::Deserializer;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -271,6 +273,88 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Lifecycle hooks configured under `[hooks]` in `config.toml`. Each list is
+/// run in order when the corresponding event fires.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct HooksConfig {
+    /// Run before a tool call executes. May approve or deny the call.
+    #[serde(default)]
+    pub pre_tool_use: Vec<HookConfig>,
+
+    /// Run after a tool call completes. May deny or replace the output that
+    /// is returned to the model.
+    #[serde(default)]
+    pub post_tool_use: Vec<HookConfig>,
+
+    /// Run when the agent finishes a turn.
+    #[serde(default)]
+    pub turn_complete: Vec<HookConfig>,
+
+    /// Run once after the session has been configured.
+    #[serde(default)]
+    pub session_start: Vec<HookConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct HookConfig {
+    /// Program and arguments to spawn. The event payload is written to the
+    /// process's stdin as a single JSON object.
+    pub command: Vec<String>,
+
+    /// Only run for these tool names. Ignored for non-tool events; when unset
+    /// the hook runs for every tool.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+
+    /// Maximum time to wait for the hook to exit. Defaults to 10 seconds.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Token and cost ceilings configured under `[budget]` in `config.toml`.
+/// Tokens are counted as reported by the model provider, including cached
+/// input; costs are estimated from per-model prices.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct BudgetConfig {
+    /// Maximum tokens a single turn may use.
+    #[serde(default)]
+    pub turn_tokens: Option<i64>,
+
+    /// Maximum tokens the whole session may use.
+    #[serde(default)]
+    pub session_tokens: Option<i64>,
+
+    /// Maximum estimated cost of a single turn, in US dollars.
+    #[serde(default)]
+    pub turn_cost_usd: Option<f64>,
+
+    /// Maximum estimated cost of the whole session, in US dollars.
+    #[serde(default)]
+    pub session_cost_usd: Option<f64>,
+
+    /// Fraction of a budget at which a warning is emitted. Defaults to 0.8.
+    #[serde(default)]
+    pub warn_at: Option<f64>,
+
+    /// Per-model prices that replace the built-in table, keyed by model slug.
+    #[serde(default)]
+    pub pricing: HashMap<String, ModelPricing>,
+}
+
+/// Prices for a model in US dollars per million tokens.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    /// Price of uncached input tokens.
+    pub input: f64,
+
+    /// Price of cached input tokens. Defaults to the uncached input price.
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+
+    /// Price of output tokens, including reasoning tokens.
+    pub output: f64,
+}
+
 // ===== OTEL configuration =====
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -377,17 +461,149 @@ pub struct SandboxWorkspaceWrite {
     pub writable_roots: Vec<PathBuf>,
     #[serde(default)]
     pub network_access: bool,
+    /// Hosts reachable through the sandbox network proxy while
+    /// `network_access` is `false`.
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
+    /// Subpaths, relative to each writable root, that stay read-only.
+    #[serde(default)]
+    pub read_only_subpaths: Vec<PathBuf>,
+    /// Extra rules for individual writable roots, keyed by root path.
+    #[serde(default)]
+    pub roots: BTreeMap<PathBuf, WritableRootRules>,
     #[serde(default)]
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
 }
 
+/// Rules for a single root in `[sandbox_workspace_write.roots]`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct WritableRootRules {
+    /// Whether commands may write to this root. A writable root is added to
+    /// `writable_roots`; a read-only one stays read-only even inside another
+    /// writable root.
+    #[serde(default = "default_enabled")]
+    pub writable: bool,
+
+    /// Subpaths of this root that stay read-only.
+    #[serde(default)]
+    pub read_only_subpaths: Vec<PathBuf>,
+}
+
+/// Limits for commands run by the shell tools, configured under
+/// `[resource_limits]` in `config.toml`. Unset limits are not enforced.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResourceLimits {
+    /// Maximum address space of each process, in MiB.
+    #[serde(default)]
+    pub memory_mb: Option<u64>,
+
+    /// Maximum CPU time of each process, in seconds.
+    #[serde(default)]
+    pub cpu_seconds: Option<u64>,
+
+    /// Maximum number of processes. Enforced with `RLIMIT_NPROC`, which
+    /// counts every process of the user, not only those of the command.
+    #[serde(default)]
+    pub processes: Option<u64>,
+
+    /// Largest file a command may write, in MiB.
+    #[serde(default)]
+    pub file_size_mb: Option<u64>,
+
+    /// Maximum combined stdout and stderr of a command, in MiB.
+    #[serde(default)]
+    pub output_mb: Option<u64>,
+}
+
+/// Append-only audit log of side-effecting actions, configured under
+/// `[audit]` in `config.toml`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AuditConfig {
+    /// When `true`, approvals, commands, patches and MCP tool calls are
+    /// recorded in the audit log.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Location of the log. Defaults to `$CODEX_HOME/audit.jsonl`.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+/// Context compaction, configured under `[compaction]` in `config.toml`.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CompactionConfig {
+    /// How history is compacted, both automatically and by `/compact`.
+    #[serde(default)]
+    pub strategy: CompactionStrategy,
+}
+
+/// Masking of secrets in tool output before it is sent to the model,
+/// configured under `[redaction]` in `config.toml`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct RedactionConfig {
+    /// When `false`, tool output is sent to the model unmodified.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Also mask long random-looking tokens that match no known key format.
+    #[serde(default = "default_enabled")]
+    pub high_entropy: bool,
+
+    /// Extra regular expressions to mask. When a pattern has a capture group,
+    /// only the first group is masked.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            high_entropy: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// Model-based risk review of commands, configured under
+/// `[command_assessment]` in `config.toml`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct CommandAssessmentConfig {
+    /// When `true` and `approval_policy = "on-request"`, every command that is
+    /// not known to be safe is assessed before it runs.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Model used for assessments. Defaults to the session model; a smaller,
+    /// faster model is usually a better fit.
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Run commands assessed as low risk without prompting. When `false`,
+    /// assessments are only shown in approval prompts.
+    #[serde(default = "default_enabled")]
+    pub auto_approve_low_risk: bool,
+}
+
+impl Default for CommandAssessmentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: None,
+            auto_approve_low_risk: true,
+        }
+    }
+}
+
 impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings {
     fn from(sandbox_workspace_write: SandboxWorkspaceWrite) -> Self {
         Self {
             writable_roots: sandbox_workspace_write.writable_roots,
             network_access: Some(sandbox_workspace_write.network_access),
+            network_allowlist: sandbox_workspace_write.network_allowlist,
+            read_only_subpaths: sandbox_workspace_write.read_only_subpaths,
             exclude_tmpdir_env_var: Some(sandbox_workspace_write.exclude_tmpdir_env_var),
             exclude_slash_tmp: Some(sandbox_workspace_write.exclude_slash_tmp),
         }
@@ -420,12 +636,77 @@ pub struct ShellEnvironmentPolicyToml {
     /// List of regular expressions.
     pub exclude: Option<Vec<String>>,
 
-    pub r#set: Option<HashMap<String, String>>,
+    pub r#set: Option<HashMap<String, EnvironmentValueToml>>,
 
     /// List of regular expressions.
     pub include_only: Option<Vec<String>>,
 
     pub experimental_use_profile: Option<bool>,
+
+    /// Named sets of extra variables that a command can ask for.
+    pub profiles: Option<BTreeMap<String, EnvironmentProfileToml>>,
+}
+
+/// Value of an entry in `shell_environment_policy.set`: a literal string, or
+/// `{ secret = "<name>" }` to read the value from the OS keyring (see
+/// `codex secrets set`) instead of keeping it in `config.toml`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum EnvironmentValueToml {
+    Literal(String),
+    Secret { secret: String },
+}
+
+/// An entry of `[shell_environment_policy.profiles]`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct EnvironmentProfileToml {
+    /// Shown to the model so it can pick the right profile.
+    pub description: Option<String>,
+
+    pub r#set: Option<HashMap<String, EnvironmentValueToml>>,
+}
+
+/// Extra variables added on top of the [`ShellEnvironmentPolicy`] for commands
+/// that ask for this profile.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EnvironmentProfile {
+    pub description: Option<String>,
+
+    /// (key, value) pairs to insert in the environment.
+    pub r#set: HashMap<String, String>,
+
+    /// Variables whose value is read from the keyring, by secret name.
+    pub secrets: HashMap<String, String>,
+}
+
+impl From<EnvironmentProfileToml> for EnvironmentProfile {
+    fn from(toml: EnvironmentProfileToml) -> Self {
+        let (r#set, secrets) = split_environment_values(toml.r#set.unwrap_or_default());
+        Self {
+            description: toml.description,
+            r#set,
+            secrets,
+        }
+    }
+}
+
+/// Splits `set` entries into literal values and secret references.
+fn split_environment_values(
+    values: HashMap<String, EnvironmentValueToml>,
+) -> (HashMap<String, String>, HashMap<String, String>) {
+    let mut literals = HashMap::new();
+    let mut secrets = HashMap::new();
+    for (key, value) in values {
+        match value {
+            EnvironmentValueToml::Literal(value) => {
+                literals.insert(key, value);
+            }
+            EnvironmentValueToml::Secret { secret } => {
+                secrets.insert(key, secret);
+            }
+        }
+    }
+    (literals, secrets)
 }
 
 pub type EnvironmentVariablePattern = WildMatchPattern<'*', '?'>;
@@ -435,7 +716,8 @@ pub type EnvironmentVariablePattern = WildMatchPattern<'*', '?'>;
 /// 2. If `ignore_default_excludes` is false, filter the map using the default
 ///    exclude pattern(s), which are: `"*KEY*"` and `"*TOKEN*"`.
 /// 3. If `exclude` is not empty, filter the map using the provided patterns.
-/// 4. Insert any entries from `r#set` into the map.
+/// 4. Insert any entries from `r#set` and `secrets` into the map, followed by
+///    those of the profile the command asked for, if any.
 /// 5. If non-empty, filter the map using the `include_only` patterns.
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct ShellEnvironmentPolicy {
@@ -452,6 +734,12 @@ pub struct ShellEnvironmentPolicy {
     /// (key, value) pairs to insert in the environment.
     pub r#set: HashMap<String, String>,
 
+    /// Variables whose value is read from the keyring, by secret name.
+    pub secrets: HashMap<String, String>,
+
+    /// Named sets of extra variables that a command can ask for.
+    pub profiles: BTreeMap<String, EnvironmentProfile>,
+
     /// Environment variable names to retain in the environment.
     pub include_only: Vec<EnvironmentVariablePattern>,
 
@@ -470,7 +758,7 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
             .into_iter()
             .map(|s| EnvironmentVariablePattern::new_case_insensitive(&s))
             .collect();
-        let r#set = toml.r#set.unwrap_or_default();
+        let (r#set, secrets) = split_environment_values(toml.r#set.unwrap_or_default());
         let include_only = toml
             .include_only
             .unwrap_or_default()
@@ -478,12 +766,20 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
             .map(|s| EnvironmentVariablePattern::new_case_insensitive(&s))
             .collect();
         let use_profile = toml.experimental_use_profile.unwrap_or(false);
+        let profiles = toml
+            .profiles
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, profile)| (name, profile.into()))
+            .collect();
 
         Self {
             inherit,
             ignore_default_excludes,
             exclude,
             r#set,
+            secrets,
+            profiles,
             include_only,
             use_profile,
         }
@@ -770,4 +1066,42 @@ mod tests {
             "unexpected error: {err}"
         );
     }
+
+    #[test]
+    fn shell_environment_policy_splits_secrets_from_literals() {
+        let toml: ShellEnvironmentPolicyToml = toml::from_str(
+            r#"
+            set = { CI = "1", GITHUB_TOKEN = { secret = "github" } }
+
+            [profiles.aws-prod]
+            description = "production AWS account"
+            set = { AWS_PROFILE = "prod", AWS_SECRET_ACCESS_KEY = { secret = "aws-prod-key" } }
+        "#,
+        )
+        .expect("should deserialize shell environment policy");
+
+        let policy = ShellEnvironmentPolicy::from(toml);
+        assert_eq!(
+            policy.r#set,
+            HashMap::from([("CI".to_string(), "1".to_string())])
+        );
+        assert_eq!(
+            policy.secrets,
+            HashMap::from([("GITHUB_TOKEN".to_string(), "github".to_string())])
+        );
+        assert_eq!(
+            policy.profiles,
+            BTreeMap::from([(
+                "aws-prod".to_string(),
+                EnvironmentProfile {
+                    description: Some("production AWS account".to_string()),
+                    r#set: HashMap::from([("AWS_PROFILE".to_string(), "prod".to_string())]),
+                    secrets: HashMap::from([(
+                        "AWS_SECRET_ACCESS_KEY".to_string(),
+                        "aws-prod-key".to_string()
+                    )]),
+                }
+            )])
+        );
+    }
 }
@@ -0,0 +1,184 @@
+//! Secrets referenced from `shell_environment_policy` as
+//! `{ secret = "<name>" }`, kept in the OS keyring instead of `config.toml`.
+//!
+//! Values are stored with `codex secrets set <name>` and read once per process,
+//! the first time a command or the output redaction needs them. Secrets that
+//! could not be read are looked up again after [`MISS_TTL`], so one added while
+//! the process runs is picked up by later commands. The redaction looks
+//! secrets up through the same cache, so it masks every value that a command
+//! may have been given.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::KeyringStore;
+use tracing::warn;
+
+const KEYRING_SERVICE: &str = "Codex Environment Secrets";
+
+/// How long a failed lookup is remembered before the keyring is asked again.
+const MISS_TTL: Duration = Duration::from_secs(60);
+
+/// Results of keyring lookups for this process. Misses are kept for
+/// [`MISS_TTL`] so that a locked or missing keyring is not consulted, and
+/// warned about, on every command.
+static LOADED_SECRETS: LazyLock<Mutex<HashMap<String, Lookup>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+enum Lookup {
+    Found(String),
+    Missing { since: Instant },
+}
+
+/// Keyring-backed storage for environment secrets.
+#[derive(Clone, Debug)]
+pub struct EnvSecretStore {
+    keyring_store: Arc<dyn KeyringStore>,
+}
+
+impl Default for EnvSecretStore {
+    fn default() -> Self {
+        Self::new(Arc::new(DefaultKeyringStore))
+    }
+}
+
+impl EnvSecretStore {
+    pub fn new(keyring_store: Arc<dyn KeyringStore>) -> Self {
+        Self { keyring_store }
+    }
+
+    pub fn load(&self, name: &str) -> std::io::Result<Option<String>> {
+        self.keyring_store
+            .load(KEYRING_SERVICE, name)
+            .map_err(|err| {
+                std::io::Error::other(format!(
+                    "failed to load secret `{name}` from keyring: {}",
+                    err.message()
+                ))
+            })
+    }
+
+    pub fn save(&self, name: &str, value: &str) -> std::io::Result<()> {
+        self.keyring_store
+            .save(KEYRING_SERVICE, name, value)
+            .map_err(|err| {
+                std::io::Error::other(format!(
+                    "failed to write secret `{name}` to keyring: {}",
+                    err.message()
+                ))
+            })
+    }
+
+    /// Returns `true` when a secret was removed.
+    pub fn delete(&self, name: &str) -> std::io::Result<bool> {
+        self.keyring_store
+            .delete(KEYRING_SERVICE, name)
+            .map_err(|err| {
+                std::io::Error::other(format!(
+                    "failed to delete secret `{name}` from keyring: {}",
+                    err.message()
+                ))
+            })
+    }
+}
+
+/// The value of secret `name`, read from the keyring on first use. Missing
+/// secrets and keyring errors are logged and yield `None`.
+pub(crate) fn load_secret(name: &str) -> Option<String> {
+    let mut loaded = LOADED_SECRETS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    load_cached(
+        &EnvSecretStore::default(),
+        &mut loaded,
+        name,
+        Instant::now(),
+    )
+}
+
+fn load_cached(
+    store: &EnvSecretStore,
+    loaded: &mut HashMap<String, Lookup>,
+    name: &str,
+    now: Instant,
+) -> Option<String> {
+    match loaded.get(name) {
+        Some(Lookup::Found(value)) => return Some(value.clone()),
+        Some(Lookup::Missing { since }) if now.duration_since(*since) < MISS_TTL => {
+            return None;
+        }
+        _ => {}
+    }
+    let value = match store.load(name) {
+        Ok(Some(value)) => Some(value),
+        Ok(None) => {
+            warn!("secret `{name}` is not set; run `codex secrets set {name}`");
+            None
+        }
+        Err(err) => {
+            warn!("{err}");
+            None
+        }
+    };
+    let lookup = match &value {
+        Some(value) => Lookup::Found(value.clone()),
+        None => Lookup::Missing { since: now },
+    };
+    loaded.insert(name.to_string(), lookup);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_keyring_store::tests::MockKeyringStore;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn secrets_round_trip_through_the_keyring() -> std::io::Result<()> {
+        let keyring = MockKeyringStore::default();
+        let store = EnvSecretStore::new(Arc::new(keyring.clone()));
+
+        assert_eq!(store.load("github-token")?, None);
+        store.save("github-token", "ghp_example")?;
+        assert_eq!(
+            keyring.saved_value("github-token"),
+            Some("ghp_example".to_string())
+        );
+        assert_eq!(store.load("github-token")?, Some("ghp_example".to_string()));
+
+        assert!(store.delete("github-token")?);
+        assert!(!store.delete("github-token")?);
+        assert_eq!(store.load("github-token")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_secrets_are_looked_up_again_after_the_ttl() -> std::io::Result<()> {
+        let store = EnvSecretStore::new(Arc::new(MockKeyringStore::default()));
+        let mut loaded = HashMap::new();
+        let start = Instant::now();
+
+        assert_eq!(load_cached(&store, &mut loaded, "npm-token", start), None);
+        store.save("npm-token", "npm_example")?;
+        assert_eq!(load_cached(&store, &mut loaded, "npm-token", start), None);
+
+        let later = start + MISS_TTL;
+        assert_eq!(
+            load_cached(&store, &mut loaded, "npm-token", later),
+            Some("npm_example".to_string())
+        );
+        store.delete("npm-token")?;
+        assert_eq!(
+            load_cached(&store, &mut loaded, "npm-token", later),
+            Some("npm_example".to_string())
+        );
+        Ok(())
+    }
+}
@@ -27,6 +27,7 @@ pub(crate) struct EnvironmentContext {
     pub approval_policy: Option<AskForApproval>,
     pub sandbox_mode: Option<SandboxMode>,
     pub network_access: Option<NetworkAccess>,
+    pub network_allowlist: Option<Vec<String>>,
     pub writable_roots: Option<Vec<PathBuf>>,
     pub shell: Option<Shell>,
 }
@@ -38,6 +39,10 @@ impl EnvironmentContext {
         sandbox_policy: Option<SandboxPolicy>,
         shell: Option<Shell>,
     ) -> Self {
+        let network_allowlist = sandbox_policy
+            .as_ref()
+            .map(|policy| policy.network_allowlist().to_vec())
+            .filter(|hosts| !hosts.is_empty());
         Self {
             cwd,
             approval_policy,
@@ -59,6 +64,7 @@ impl EnvironmentContext {
                 }
                 None => None,
             },
+            network_allowlist,
             writable_roots: match sandbox_policy {
                 Some(SandboxPolicy::WorkspaceWrite { writable_roots, .. }) => {
                     if writable_roots.is_empty() {
@@ -82,6 +88,7 @@ impl EnvironmentContext {
             approval_policy,
             sandbox_mode,
             network_access,
+            network_allowlist,
             writable_roots,
             // should compare all fields except shell
             shell: _,
@@ -91,6 +98,7 @@ impl EnvironmentContext {
             && self.approval_policy == *approval_policy
             && self.sandbox_mode == *sandbox_mode
             && self.network_access == *network_access
+            && self.network_allowlist == *network_allowlist
             && self.writable_roots == *writable_roots
     }
 
@@ -138,6 +146,7 @@ impl EnvironmentContext {
     ///   <sandbox_mode>...</sandbox_mode>
     ///   <writable_roots>...</writable_roots>
     ///   <network_access>...</network_access>
+    ///   <network_allowlist>...</network_allowlist>
     ///   <shell>...</shell>
     /// </environment_context>
     /// ```
@@ -159,6 +168,13 @@ impl EnvironmentContext {
                 "  <network_access>{network_access}</network_access>"
             ));
         }
+        if let Some(network_allowlist) = self.network_allowlist {
+            lines.push("  <network_allowlist>".to_string());
+            for host in network_allowlist {
+                lines.push(format!("    <host>{host}</host>"));
+            }
+            lines.push("  </network_allowlist>".to_string());
+        }
         if let Some(writable_roots) = self.writable_roots {
             lines.push("  <writable_roots>".to_string());
             for writable_root in writable_roots {
@@ -203,8 +219,11 @@ mod tests {
         SandboxPolicy::WorkspaceWrite {
             writable_roots: writable_roots.into_iter().map(PathBuf::from).collect(),
             network_access,
+            network_allowlist: vec![],
+            read_only_subpaths: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            container: false,
         }
     }
 
@@ -231,6 +250,36 @@ mod tests {
         assert_eq!(context.serialize_to_xml(), expected);
     }
 
+    #[test]
+    fn serialize_network_allowlist_environment_context() {
+        let context = EnvironmentContext::new(
+            None,
+            Some(AskForApproval::OnRequest),
+            Some(SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![],
+                network_access: false,
+                network_allowlist: vec!["crates.io".to_string(), "*.crates.io:443".to_string()],
+                read_only_subpaths: vec![],
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+                container: false,
+            }),
+            None,
+        );
+
+        let expected = r#"<environment_context>
+  <approval_policy>on-request</approval_policy>
+  <sandbox_mode>workspace-write</sandbox_mode>
+  <network_access>restricted</network_access>
+  <network_allowlist>
+    <host>crates.io</host>
+    <host>*.crates.io:443</host>
+  </network_allowlist>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
     #[test]
     fn serialize_read_only_environment_context() {
         let context = EnvironmentContext::new(
@@ -10,6 +10,7 @@ use chrono::Utc;
 use codex_async_utils::CancelErr;
 use codex_protocol::ConversationId;
 use codex_protocol::protocol::RateLimitSnapshot;
+use codex_protocol::protocol::ResourceLimit;
 use reqwest::StatusCode;
 use serde_json;
 use std::io;
@@ -45,6 +46,10 @@ pub enum SandboxErr {
     #[error("command timed out")]
     Timeout { output: Box<ExecToolCallOutput> },
 
+    /// Command exceeded a configured resource limit
+    #[error("command exceeded its {} limit", describe_resource_limit(.output.resource_limit_exceeded))]
+    ResourceLimit { output: Box<ExecToolCallOutput> },
+
     /// Command was killed by a signal
     #[error("command was killed by a signal")]
     Signal(i32),
@@ -458,12 +463,26 @@ pub fn get_error_message_ui(e: &CodexErr) -> String {
                 output.duration.as_millis()
             )
         }
+        CodexErr::Sandbox(SandboxErr::ResourceLimit { output }) => format!(
+            "error: command exceeded its {} limit",
+            describe_resource_limit(output.resource_limit_exceeded)
+        ),
         _ => e.to_string(),
     };
 
     truncate_middle(&message, ERROR_MESSAGE_UI_MAX_BYTES).0
 }
 
+/// Human-readable name of a resource limit, as used in error messages.
+pub fn describe_resource_limit(limit: Option<ResourceLimit>) -> &'static str {
+    match limit {
+        Some(ResourceLimit::CpuTime) => "CPU time",
+        Some(ResourceLimit::FileSize) => "file size",
+        Some(ResourceLimit::Output) => "output",
+        None => "resource",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -529,6 +548,7 @@ mod tests {
             aggregated_output: StreamOutput::new("aggregate detail".to_string()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -545,6 +565,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -561,6 +582,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(8),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -577,6 +599,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(5),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -6,6 +6,9 @@ use std::io;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::ExitStatus;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -14,7 +17,10 @@ use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
 use tokio::io::BufReader;
 use tokio::process::Child;
+use tokio::sync::Notify;
 
+use crate::audit::AuditLog;
+use crate::config::types::ResourceLimits;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
@@ -22,10 +28,12 @@ use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
 use crate::protocol::ExecOutputStream;
+use crate::protocol::ResourceLimit;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxManager;
+use crate::spawn::SpawnChildRequest;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 
@@ -73,6 +81,10 @@ pub enum SandboxType {
     /// Only available on Linux.
     LinuxSeccomp,
 
+    /// Rootless user, mount, pid and network namespaces set up by
+    /// codex-linux-sandbox. Only available on Linux.
+    LinuxNamespaces,
+
     /// Only available on Windows.
     WindowsRestrictedToken,
 }
@@ -82,12 +94,15 @@ pub struct StdoutStream {
     pub sub_id: String,
     pub call_id: String,
     pub tx_event: Sender<Event>,
+    /// Audit log that network proxy denials for the call are recorded in.
+    pub(crate) audit: AuditLog,
 }
 
 pub async fn process_exec_tool_call(
     params: ExecParams,
     sandbox_type: SandboxType,
     sandbox_policy: &SandboxPolicy,
+    read_deny: &[String],
     sandbox_cwd: &Path,
     codex_linux_sandbox_exe: &Option<PathBuf>,
     stdout_stream: Option<StdoutStream>,
@@ -115,6 +130,7 @@ pub async fn process_exec_tool_call(
         cwd,
         env,
         timeout_ms,
+        resource_limits: ResourceLimits::default(),
         with_escalated_permissions,
         justification,
     };
@@ -124,6 +140,7 @@ pub async fn process_exec_tool_call(
         .transform(
             &spec,
             sandbox_policy,
+            read_deny,
             sandbox_type,
             sandbox_cwd,
             codex_linux_sandbox_exe.as_ref(),
@@ -144,12 +161,20 @@ pub(crate) async fn execute_exec_env(
         cwd,
         env,
         timeout_ms,
+        resource_limits,
         sandbox,
         with_escalated_permissions,
         justification,
         arg0,
+        network_proxy,
     } = env;
 
+    // Serve the sandbox network proxy for as long as the command runs.
+    let _running_proxy = network_proxy
+        .as_ref()
+        .map(|proxy| proxy.start(stdout_stream.clone()))
+        .transpose()?;
+
     let params = ExecParams {
         command,
         cwd,
@@ -161,9 +186,16 @@ pub(crate) async fn execute_exec_env(
     };
 
     let start = Instant::now();
-    let raw_output_result = exec(params, sandbox, sandbox_policy, stdout_stream).await;
+    let raw_output_result = exec(
+        params,
+        sandbox,
+        sandbox_policy,
+        resource_limits,
+        stdout_stream,
+    )
+    .await;
     let duration = start.elapsed();
-    finalize_exec_result(raw_output_result, sandbox, duration)
+    finalize_exec_result(raw_output_result, sandbox, &resource_limits, duration)
 }
 
 #[cfg(target_os = "windows")]
@@ -241,26 +273,30 @@ async fn exec_windows_sandbox(
         stderr,
         aggregated_output,
         timed_out: capture.timed_out,
+        resource_limit_exceeded: None,
     })
 }
 
 fn finalize_exec_result(
     raw_output_result: std::result::Result<RawExecToolCallOutput, CodexErr>,
     sandbox_type: SandboxType,
+    resource_limits: &ResourceLimits,
     duration: Duration,
 ) -> Result<ExecToolCallOutput> {
     match raw_output_result {
         Ok(raw_output) => {
             #[allow(unused_mut)]
             let mut timed_out = raw_output.timed_out;
+            #[allow(unused_mut)]
+            let mut signal = None;
 
             #[cfg(target_family = "unix")]
             {
-                if let Some(signal) = raw_output.exit_status.signal() {
-                    if signal == TIMEOUT_CODE {
+                if let Some(raw_signal) = raw_output.exit_status.signal() {
+                    if raw_signal == TIMEOUT_CODE {
                         timed_out = true;
                     } else {
-                        return Err(CodexErr::Sandbox(SandboxErr::Signal(signal)));
+                        signal = Some(raw_signal);
                     }
                 }
             }
@@ -273,13 +309,14 @@ fn finalize_exec_result(
             let stdout = raw_output.stdout.from_utf8_lossy();
             let stderr = raw_output.stderr.from_utf8_lossy();
             let aggregated_output = raw_output.aggregated_output.from_utf8_lossy();
-            let exec_output = ExecToolCallOutput {
+            let mut exec_output = ExecToolCallOutput {
                 exit_code,
                 stdout,
                 stderr,
                 aggregated_output,
                 duration,
                 timed_out,
+                resource_limit_exceeded: None,
             };
 
             if timed_out {
@@ -288,6 +325,23 @@ fn finalize_exec_result(
                 }));
             }
 
+            let resource_limit_exceeded = raw_output
+                .resource_limit_exceeded
+                .or_else(|| detect_exceeded_resource_limit(resource_limits, signal));
+            if let Some(limit) = resource_limit_exceeded {
+                if let Some(signal) = signal {
+                    exec_output.exit_code = EXIT_CODE_SIGNAL_BASE + signal;
+                }
+                exec_output.resource_limit_exceeded = Some(limit);
+                return Err(CodexErr::Sandbox(SandboxErr::ResourceLimit {
+                    output: Box::new(exec_output),
+                }));
+            }
+
+            if let Some(signal) = signal {
+                return Err(CodexErr::Sandbox(SandboxErr::Signal(signal)));
+            }
+
             if is_likely_sandbox_denied(sandbox_type, &exec_output) {
                 return Err(CodexErr::Sandbox(SandboxErr::Denied {
                     output: Box::new(exec_output),
@@ -317,11 +371,33 @@ pub(crate) mod errors {
                 SandboxTransformError::SeatbeltUnavailable => CodexErr::UnsupportedOperation(
                     "seatbelt sandbox is only available on macOS".to_string(),
                 ),
+                SandboxTransformError::NetworkProxy(err) => CodexErr::Io(err),
             }
         }
     }
 }
 
+/// Works out whether a command was stopped by one of the configured
+/// `resource_limits` from the signal that killed it. Only the CPU time and
+/// file size limits are enforced with signals; exhausting memory or
+/// processes makes a system call fail instead, which the command handles as
+/// it sees fit, so those limits are never reported.
+pub(crate) fn detect_exceeded_resource_limit(
+    #[cfg_attr(not(unix), allow(unused_variables))] resource_limits: &ResourceLimits,
+    #[cfg_attr(not(unix), allow(unused_variables))] signal: Option<i32>,
+) -> Option<ResourceLimit> {
+    #[cfg(unix)]
+    {
+        if resource_limits.cpu_seconds.is_some() && signal == Some(libc::SIGXCPU) {
+            return Some(ResourceLimit::CpuTime);
+        }
+        if resource_limits.file_size_mb.is_some() && signal == Some(libc::SIGXFSZ) {
+            return Some(ResourceLimit::FileSize);
+        }
+    }
+    None
+}
+
 /// We don't have a fully deterministic way to tell if our command failed
 /// because of the sandbox - a command in the user's zshrc file might hit an
 /// error, but the command itself might fail or succeed for other reasons.
@@ -349,6 +425,19 @@ pub(crate) fn is_likely_sandbox_denied(
         "failed to write file",
     ];
 
+    // The container's only network interface is its own loopback, so network
+    // access fails with routing and resolver errors instead.
+    const CONTAINER_DENIED_KEYWORDS: [&str; 3] = [
+        "network is unreachable",
+        "temporary failure in name resolution",
+        "could not resolve host",
+    ];
+    let container_keywords: &[&str] = if sandbox_type == SandboxType::LinuxNamespaces {
+        &CONTAINER_DENIED_KEYWORDS
+    } else {
+        &[]
+    };
+
     let has_sandbox_keyword = [
         &exec_output.stderr.text,
         &exec_output.stdout.text,
@@ -359,6 +448,7 @@ pub(crate) fn is_likely_sandbox_denied(
         let lower = section.to_lowercase();
         SANDBOX_DENIED_KEYWORDS
             .iter()
+            .chain(container_keywords)
             .any(|needle| lower.contains(needle))
     });
 
@@ -397,6 +487,8 @@ struct RawExecToolCallOutput {
     pub stderr: StreamOutput<Vec<u8>>,
     pub aggregated_output: StreamOutput<Vec<u8>>,
     pub timed_out: bool,
+    /// Set when the output limit was hit; other limits are detected later.
+    pub resource_limit_exceeded: Option<ResourceLimit>,
 }
 
 impl StreamOutput<String> {
@@ -430,6 +522,7 @@ pub struct ExecToolCallOutput {
     pub aggregated_output: StreamOutput<String>,
     pub duration: Duration,
     pub timed_out: bool,
+    pub resource_limit_exceeded: Option<ResourceLimit>,
 }
 
 #[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
@@ -437,6 +530,7 @@ async fn exec(
     params: ExecParams,
     sandbox: SandboxType,
     sandbox_policy: &SandboxPolicy,
+    resource_limits: ResourceLimits,
     stdout_stream: Option<StdoutStream>,
 ) -> Result<RawExecToolCallOutput> {
     #[cfg(target_os = "windows")]
@@ -459,17 +553,69 @@ async fn exec(
         ))
     })?;
     let arg0_ref = arg0.as_deref();
-    let child = spawn_child_async(
-        PathBuf::from(program),
-        args.into(),
-        arg0_ref,
+    // codex-linux-sandbox applies the rlimits itself, after setting up the
+    // sandbox.
+    let spawn_limits = if matches!(
+        sandbox,
+        SandboxType::LinuxSeccomp | SandboxType::LinuxNamespaces
+    ) {
+        ResourceLimits::default()
+    } else {
+        resource_limits
+    };
+    let child = spawn_child_async(SpawnChildRequest {
+        program: PathBuf::from(program),
+        args: args.into(),
+        arg0: arg0_ref,
         cwd,
         sandbox_policy,
-        StdioPolicy::RedirectForShellTool,
+        stdio_policy: StdioPolicy::RedirectForShellTool,
         env,
-    )
+        resource_limits: spawn_limits,
+    })
     .await?;
-    consume_truncated_output(child, timeout, stdout_stream).await
+    let output_limit = resource_limits
+        .output_mb
+        .map(|mb| Arc::new(OutputLimit::new(mb.saturating_mul(1024 * 1024))));
+    consume_truncated_output(child, timeout, output_limit, stdout_stream).await
+}
+
+/// Bytes read so far from a command's stdout and stderr, checked against
+/// the configured output limit.
+struct OutputLimit {
+    max_bytes: u64,
+    read_bytes: AtomicU64,
+    exceeded: Notify,
+}
+
+impl OutputLimit {
+    fn new(max_bytes: u64) -> Self {
+        Self {
+            max_bytes,
+            read_bytes: AtomicU64::new(0),
+            exceeded: Notify::new(),
+        }
+    }
+
+    /// Records `n` more bytes of output. Returns `true`, and wakes the task
+    /// waiting on the command, once the limit has been exceeded.
+    fn record(&self, n: usize) -> bool {
+        let n = n as u64;
+        let read_bytes = self.read_bytes.fetch_add(n, Ordering::Relaxed) + n;
+        if read_bytes > self.max_bytes {
+            self.exceeded.notify_one();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+async fn wait_for_output_limit(output_limit: Option<&OutputLimit>) {
+    match output_limit {
+        Some(output_limit) => output_limit.exceeded.notified().await,
+        None => std::future::pending().await,
+    }
 }
 
 /// Consumes the output of a child process, truncating it so it is suitable for
@@ -477,6 +623,7 @@ async fn exec(
 async fn consume_truncated_output(
     mut child: Child,
     timeout: Duration,
+    output_limit: Option<Arc<OutputLimit>>,
     stdout_stream: Option<StdoutStream>,
 ) -> Result<RawExecToolCallOutput> {
     // Both stdout and stderr were configured with `Stdio::piped()`
@@ -501,14 +648,17 @@ async fn consume_truncated_output(
         stdout_stream.clone(),
         false,
         Some(agg_tx.clone()),
+        output_limit.clone(),
     ));
     let stderr_handle = tokio::spawn(read_capped(
         BufReader::new(stderr_reader),
         stdout_stream.clone(),
         true,
         Some(agg_tx.clone()),
+        output_limit.clone(),
     ));
 
+    let mut resource_limit_exceeded = None;
     let (exit_status, timed_out) = tokio::select! {
         result = tokio::time::timeout(timeout, child.wait()) => {
             match result {
@@ -525,6 +675,12 @@ async fn consume_truncated_output(
                 }
             }
         }
+        _ = wait_for_output_limit(output_limit.as_deref()) => {
+            kill_child_process_group(&mut child)?;
+            child.start_kill()?;
+            resource_limit_exceeded = Some(ResourceLimit::Output);
+            (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false)
+        }
         _ = tokio::signal::ctrl_c() => {
             kill_child_process_group(&mut child)?;
             child.start_kill()?;
@@ -596,6 +752,7 @@ async fn consume_truncated_output(
         stderr,
         aggregated_output,
         timed_out,
+        resource_limit_exceeded,
     })
 }
 
@@ -604,6 +761,7 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
     stream: Option<StdoutStream>,
     is_stderr: bool,
     aggregate_tx: Option<Sender<Vec<u8>>>,
+    output_limit: Option<Arc<OutputLimit>>,
 ) -> io::Result<StreamOutput<Vec<u8>>> {
     let mut buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
     let mut tmp = [0u8; READ_CHUNK_SIZE];
@@ -644,6 +802,13 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
         }
 
         append_all(&mut buf, &tmp[..n]);
+
+        // The command is killed once the output limit is exceeded.
+        if let Some(output_limit) = &output_limit
+            && output_limit.record(n)
+        {
+            break;
+        }
         // Continue reading to EOF to avoid back-pressure
     }
 
@@ -702,6 +867,7 @@ fn kill_child_process_group(_: &mut Child) -> io::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pretty_assertions::assert_eq;
     use std::time::Duration;
 
     fn make_exec_output(
@@ -717,6 +883,7 @@ mod tests {
             aggregated_output: StreamOutput::new(aggregated.to_string()),
             duration: Duration::from_millis(1),
             timed_out: false,
+            resource_limit_exceeded: None,
         }
     }
 
@@ -764,6 +931,19 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn sandbox_detection_flags_container_network_errors() {
+        let output = make_exec_output(6, "", "curl: (6) Could not resolve host: example.com", "");
+        assert!(is_likely_sandbox_denied(
+            SandboxType::LinuxNamespaces,
+            &output
+        ));
+        assert!(!is_likely_sandbox_denied(
+            SandboxType::LinuxSeccomp,
+            &output
+        ));
+    }
+
     #[cfg(unix)]
     #[test]
     fn sandbox_detection_flags_sigsys_exit_code() {
@@ -772,6 +952,111 @@ mod tests {
         assert!(is_likely_sandbox_denied(SandboxType::LinuxSeccomp, &output));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn resource_limit_detection_uses_signals() {
+        let limits = ResourceLimits {
+            cpu_seconds: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            detect_exceeded_resource_limit(&limits, Some(libc::SIGXCPU)),
+            Some(ResourceLimit::CpuTime)
+        );
+        assert_eq!(
+            detect_exceeded_resource_limit(&limits, Some(libc::SIGXFSZ)),
+            None
+        );
+        // A shell reporting 128 + SIGXCPU for a child may as well have
+        // exited with that code on its own.
+        assert_eq!(detect_exceeded_resource_limit(&limits, None), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resource_limit_detection_requires_configured_limit() {
+        assert_eq!(
+            detect_exceeded_resource_limit(&ResourceLimits::default(), Some(libc::SIGXFSZ)),
+            None
+        );
+        let limits = ResourceLimits {
+            file_size_mb: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            detect_exceeded_resource_limit(&limits, Some(libc::SIGXFSZ)),
+            Some(ResourceLimit::FileSize)
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn output_limit_kills_noisy_command() -> Result<()> {
+        let params = ExecParams {
+            command: vec!["yes".to_string()],
+            cwd: std::env::current_dir()?,
+            timeout_ms: Some(10_000),
+            env: std::env::vars().collect(),
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        };
+        let limits = ResourceLimits {
+            output_mb: Some(1),
+            ..Default::default()
+        };
+
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            limits,
+            None,
+        )
+        .await?;
+        assert!(!output.timed_out);
+        assert_eq!(output.resource_limit_exceeded, Some(ResourceLimit::Output));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cpu_limit_is_reported() -> Result<()> {
+        let params = ExecParams {
+            command: vec![
+                "/bin/bash".to_string(),
+                "-c".to_string(),
+                "while :; do :; done".to_string(),
+            ],
+            cwd: std::env::current_dir()?,
+            timeout_ms: Some(10_000),
+            env: std::env::vars().collect(),
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        };
+        let limits = ResourceLimits {
+            cpu_seconds: Some(1),
+            ..Default::default()
+        };
+
+        let start = Instant::now();
+        let raw_output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            limits,
+            None,
+        )
+        .await;
+        let result = finalize_exec_result(raw_output, SandboxType::None, &limits, start.elapsed());
+        let Err(CodexErr::Sandbox(SandboxErr::ResourceLimit { output })) = result else {
+            panic!("expected a resource limit error, got {result:?}");
+        };
+        assert_eq!(output.resource_limit_exceeded, Some(ResourceLimit::CpuTime));
+        Ok(())
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn kill_child_process_group_kills_grandchildren_on_timeout() -> Result<()> {
@@ -791,7 +1076,14 @@ mod tests {
             arg0: None,
         };
 
-        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            ResourceLimits::default(),
+            None,
+        )
+        .await?;
         assert!(output.timed_out);
 
         let stdout = output.stdout.from_utf8_lossy().text;
@@ -1,8 +1,23 @@
+use crate::config::types::EnvironmentProfile;
 use crate::config::types::EnvironmentVariablePattern;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyInherit;
+use crate::env_secrets::load_secret;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::net::SocketAddr;
+
+/// Environment variables that HTTP clients consult to find a proxy, in both
+/// the upper- and lowercase spellings that different tools expect.
+const PROXY_ENV_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+const NO_PROXY_ENV_VARS: &[&str] = &["NO_PROXY", "no_proxy"];
 
 /// Construct an environment map based on the rules in the specified policy. The
 /// resulting map can be passed directly to `Command::envs()` after calling
@@ -12,10 +27,43 @@ use std::collections::HashSet;
 /// The derivation follows the algorithm documented in the struct-level comment
 /// for [`ShellEnvironmentPolicy`].
 pub fn create_env(policy: &ShellEnvironmentPolicy) -> HashMap<String, String> {
-    populate_env(std::env::vars(), policy)
+    populate_env(std::env::vars(), policy, None, &load_secret)
 }
 
-fn populate_env<I>(vars: I, policy: &ShellEnvironmentPolicy) -> HashMap<String, String>
+/// Like [`create_env`], with the variables of the profile named `profile`
+/// added. Returns `None` when the policy has no such profile.
+pub fn create_env_for_profile(
+    policy: &ShellEnvironmentPolicy,
+    profile: &str,
+) -> Option<HashMap<String, String>> {
+    let profile = policy.profiles.get(profile)?;
+    Some(populate_env(
+        std::env::vars(),
+        policy,
+        Some(profile),
+        &load_secret,
+    ))
+}
+
+/// Route HTTP(S) traffic of a sandboxed command through the network proxy
+/// listening on `proxy_addr`. `NO_PROXY` is dropped because the sandbox only
+/// allows connections to the proxy itself.
+pub fn set_network_proxy_env(env: &mut HashMap<String, String>, proxy_addr: SocketAddr) {
+    let proxy_url = format!("http://{proxy_addr}");
+    for key in PROXY_ENV_VARS {
+        env.insert((*key).to_string(), proxy_url.clone());
+    }
+    for key in NO_PROXY_ENV_VARS {
+        env.remove(*key);
+    }
+}
+
+fn populate_env<I>(
+    vars: I,
+    policy: &ShellEnvironmentPolicy,
+    profile: Option<&EnvironmentProfile>,
+    load_secret: &dyn Fn(&str) -> Option<String>,
+) -> HashMap<String, String>
 where
     I: IntoIterator<Item = (String, String)>,
 {
@@ -35,29 +83,25 @@ where
         }
     };
 
-    // Internal helper – does `name` match **any** pattern in `patterns`?
-    let matches_any = |name: &str, patterns: &[EnvironmentVariablePattern]| -> bool {
-        patterns.iter().any(|pattern| pattern.matches(name))
-    };
-
-    // Step 2 – Apply the default exclude if not disabled.
-    if !policy.ignore_default_excludes {
-        let default_excludes = vec![
-            EnvironmentVariablePattern::new_case_insensitive("*KEY*"),
-            EnvironmentVariablePattern::new_case_insensitive("*SECRET*"),
-            EnvironmentVariablePattern::new_case_insensitive("*TOKEN*"),
-        ];
-        env_map.retain(|k, _| !matches_any(k, &default_excludes));
-    }
-
-    // Step 3 – Apply custom excludes.
-    if !policy.exclude.is_empty() {
-        env_map.retain(|k, _| !matches_any(k, &policy.exclude));
-    }
-
-    // Step 4 – Apply user-provided overrides.
-    for (key, val) in &policy.r#set {
-        env_map.insert(key.clone(), val.clone());
+    // Steps 2 and 3 – Apply the default and custom excludes.
+    env_map.retain(|k, _| !is_excluded(k, policy));
+
+    // Step 4 – Apply user-provided overrides, then those of the profile.
+    // Secrets that cannot be loaded are left unset.
+    let overrides = std::iter::once((&policy.r#set, &policy.secrets)).chain(
+        profile
+            .into_iter()
+            .map(|profile| (&profile.r#set, &profile.secrets)),
+    );
+    for (literals, secrets) in overrides {
+        for (key, val) in literals {
+            env_map.insert(key.clone(), val.clone());
+        }
+        for (key, secret) in secrets {
+            if let Some(val) = load_secret(secret) {
+                env_map.insert(key.clone(), val);
+            }
+        }
     }
 
     // Step 5 – If include_only is non-empty, keep *only* the matching vars.
@@ -68,12 +112,55 @@ where
     env_map
 }
 
+/// Variables of the current process that the default or custom excludes of
+/// `policy` keep away from spawned commands. Their values are treated as
+/// secrets by the tool output redaction.
+pub fn excluded_env_vars(policy: &ShellEnvironmentPolicy) -> Vec<(String, String)> {
+    std::env::vars()
+        .filter(|(name, _)| is_excluded(name, policy))
+        .collect()
+}
+
+/// Variables that `policy` or any of its profiles fill from the keyring,
+/// with the names of their secrets. The tool output redaction masks the
+/// values of these secrets.
+pub fn injected_secret_vars(policy: &ShellEnvironmentPolicy) -> Vec<(String, String)> {
+    std::iter::once(&policy.secrets)
+        .chain(policy.profiles.values().map(|profile| &profile.secrets))
+        .flatten()
+        .map(|(key, secret)| (key.clone(), secret.clone()))
+        .collect()
+}
+
+/// Does `name` match **any** pattern in `patterns`?
+fn matches_any(name: &str, patterns: &[EnvironmentVariablePattern]) -> bool {
+    patterns.iter().any(|pattern| pattern.matches(name))
+}
+
+fn is_excluded(name: &str, policy: &ShellEnvironmentPolicy) -> bool {
+    if !policy.ignore_default_excludes {
+        let default_excludes = [
+            EnvironmentVariablePattern::new_case_insensitive("*KEY*"),
+            EnvironmentVariablePattern::new_case_insensitive("*SECRET*"),
+            EnvironmentVariablePattern::new_case_insensitive("*TOKEN*"),
+        ];
+        if matches_any(name, &default_excludes) {
+            return true;
+        }
+    }
+    matches_any(name, &policy.exclude)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::types::ShellEnvironmentPolicyInherit;
     use maplit::hashmap;
 
+    fn no_secrets(_: &str) -> Option<String> {
+        None
+    }
+
     fn make_vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
         pairs
             .iter()
@@ -91,7 +178,7 @@ mod tests {
         ]);
 
         let policy = ShellEnvironmentPolicy::default(); // inherit Core, default excludes on
-        let result = populate_env(vars, &policy);
+        let result = populate_env(vars, &policy, None, &no_secrets);
 
         let expected: HashMap<String, String> = hashmap! {
             "PATH".to_string() => "/usr/bin".to_string(),
@@ -112,7 +199,7 @@ mod tests {
             ..Default::default()
         };
 
-        let result = populate_env(vars, &policy);
+        let result = populate_env(vars, &policy, None, &no_secrets);
 
         let expected: HashMap<String, String> = hashmap! {
             "PATH".to_string() => "/usr/bin".to_string(),
@@ -131,7 +218,7 @@ mod tests {
         };
         policy.r#set.insert("NEW_VAR".to_string(), "42".to_string());
 
-        let result = populate_env(vars, &policy);
+        let result = populate_env(vars, &policy, None, &no_secrets);
 
         let expected: HashMap<String, String> = hashmap! {
             "PATH".to_string() => "/usr/bin".to_string(),
@@ -151,7 +238,7 @@ mod tests {
             ..Default::default()
         };
 
-        let result = populate_env(vars.clone(), &policy);
+        let result = populate_env(vars.clone(), &policy, None, &no_secrets);
         let expected: HashMap<String, String> = vars.into_iter().collect();
         assert_eq!(result, expected);
     }
@@ -165,7 +252,7 @@ mod tests {
             ..Default::default()
         };
 
-        let result = populate_env(vars, &policy);
+        let result = populate_env(vars, &policy, None, &no_secrets);
         let expected: HashMap<String, String> = hashmap! {
             "PATH".to_string() => "/usr/bin".to_string(),
         };
@@ -185,10 +272,84 @@ mod tests {
             .r#set
             .insert("ONLY_VAR".to_string(), "yes".to_string());
 
-        let result = populate_env(vars, &policy);
+        let result = populate_env(vars, &policy, None, &no_secrets);
         let expected: HashMap<String, String> = hashmap! {
             "ONLY_VAR".to_string() => "yes".to_string(),
         };
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_secrets_are_resolved_and_missing_ones_skipped() {
+        let vars = make_vars(&[("PATH", "/usr/bin")]);
+
+        let mut policy = ShellEnvironmentPolicy::default();
+        policy
+            .secrets
+            .insert("GITHUB_TOKEN".to_string(), "github".to_string());
+        policy
+            .secrets
+            .insert("NPM_TOKEN".to_string(), "npm".to_string());
+
+        let load_secret = |name: &str| (name == "github").then(|| "ghp_example".to_string());
+        let result = populate_env(vars, &policy, None, &load_secret);
+        let expected: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+            "GITHUB_TOKEN".to_string() => "ghp_example".to_string(),
+        };
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_profile_overrides_base_set() {
+        let vars = make_vars(&[("PATH", "/usr/bin")]);
+
+        let mut policy = ShellEnvironmentPolicy::default();
+        policy
+            .r#set
+            .insert("AWS_PROFILE".to_string(), "dev".to_string());
+        let profile = EnvironmentProfile {
+            description: Some("production AWS account".to_string()),
+            r#set: hashmap! { "AWS_PROFILE".to_string() => "prod".to_string() },
+            secrets: hashmap! { "AWS_SECRET_ACCESS_KEY".to_string() => "aws-prod".to_string() },
+        };
+
+        let load_secret = |name: &str| Some(format!("value-of-{name}"));
+        let result = populate_env(vars, &policy, Some(&profile), &load_secret);
+        let expected: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+            "AWS_PROFILE".to_string() => "prod".to_string(),
+            "AWS_SECRET_ACCESS_KEY".to_string() => "value-of-aws-prod".to_string(),
+        };
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_unknown_profile() {
+        let policy = ShellEnvironmentPolicy::default();
+        assert_eq!(create_env_for_profile(&policy, "prod"), None);
+    }
+
+    #[test]
+    fn test_set_network_proxy_env() {
+        let mut env: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+            "NO_PROXY".to_string() => "crates.io".to_string(),
+            "https_proxy".to_string() => "http://corp-proxy:3128".to_string(),
+        };
+
+        set_network_proxy_env(&mut env, "127.0.0.1:4040".parse().unwrap());
+
+        let proxy = "http://127.0.0.1:4040".to_string();
+        let expected: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+            "HTTP_PROXY".to_string() => proxy.clone(),
+            "HTTPS_PROXY".to_string() => proxy.clone(),
+            "ALL_PROXY".to_string() => proxy.clone(),
+            "http_proxy".to_string() => proxy.clone(),
+            "https_proxy".to_string() => proxy.clone(),
+            "all_proxy".to_string() => proxy,
+        };
+        assert_eq!(env, expected);
+    }
 }
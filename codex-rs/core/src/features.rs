@@ -46,6 +46,14 @@ pub enum Feature {
     GhostCommit,
     /// Enable Windows sandbox (restricted token) on Windows.
     WindowsSandbox,
+    /// Consult Starlark `.policy` files before prompting for command approval.
+    ExecPolicy,
+    /// Include the spawn_agent tool for delegating tasks to sub-agents.
+    SpawnAgent,
+    /// Persist memories across sessions and expose the memory tools.
+    Memory,
+    /// Keep full tool outputs and expose the read_tool_output tool.
+    ReadToolOutput,
 }
 
 impl Feature {
@@ -301,4 +309,28 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::ExecPolicy,
+        key: "exec_policy",
+        stage: Stage::Beta,
+        default_enabled: true,
+    },
+    FeatureSpec {
+        id: Feature::SpawnAgent,
+        key: "spawn_agent",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::Memory,
+        key: "memory",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ReadToolOutput,
+        key: "read_tool_output",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
 ];
@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::ModelProviderInfo;
+use crate::chat_completions::send_streaming_request;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::client_common::split_base64_data_url;
+use crate::client_common::thinking_budget_for_effort;
+use crate::default_client::CodexHttpClient;
+use crate::error::CodexErr;
+use crate::error::ResponseStreamFailed;
+use crate::error::Result;
+use crate::model_family::ModelFamily;
+use crate::protocol::TokenUsage;
+use crate::tools::spec::create_tools_json_for_gemini_api;
+use bytes::Bytes;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ResponseItem;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+use uuid::Uuid;
+
+/// Implementation for the Gemini `streamGenerateContent` API.
+pub(crate) async fn stream_gemini_generate_content(
+    prompt: &Prompt,
+    model_family: &ModelFamily,
+    effort: Option<ReasoningEffortConfig>,
+    max_output_tokens: Option<i64>,
+    client: &CodexHttpClient,
+    provider: &ModelProviderInfo,
+    otel_event_manager: &OtelEventManager,
+) -> Result<ResponseStream> {
+    let payload = build_request_payload(prompt, model_family, effort, max_output_tokens)?;
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None, &model_family.slug),
+        payload
+    );
+
+    let resp = send_streaming_request(
+        client,
+        provider,
+        otel_event_manager,
+        &model_family.slug,
+        &[],
+        &payload,
+    )
+    .await?;
+
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+    let stream = resp.bytes_stream().map_err(|e| {
+        CodexErr::ResponseStreamFailed(ResponseStreamFailed {
+            source: e,
+            request_id: None,
+        })
+    });
+    tokio::spawn(process_gemini_sse(
+        stream,
+        tx_event,
+        provider.stream_idle_timeout(),
+        otel_event_manager.clone(),
+    ));
+    Ok(ResponseStream { rx_event })
+}
+
+fn build_request_payload(
+    prompt: &Prompt,
+    model_family: &ModelFamily,
+    effort: Option<ReasoningEffortConfig>,
+    max_output_tokens: Option<i64>,
+) -> Result<Value> {
+    let input = prompt.get_formatted_input();
+
+    // `functionResponse` parts are matched to calls by name, not by id.
+    let mut call_names: HashMap<&str, &str> = HashMap::new();
+    for item in &input {
+        match item {
+            ResponseItem::FunctionCall { name, call_id, .. }
+            | ResponseItem::CustomToolCall { name, call_id, .. } => {
+                call_names.insert(call_id.as_str(), name.as_str());
+            }
+            _ => {}
+        }
+    }
+
+    let mut contents = ContentsBuilder::default();
+    // Thought signature from the preceding reasoning item; it must be echoed
+    // back on the next model part.
+    let mut pending_signature: Option<String> = None;
+
+    for item in &input {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let role = if role == "assistant" { "model" } else { "user" };
+                for c in content {
+                    match c {
+                        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                            if text.is_empty() {
+                                continue;
+                            }
+                            let mut part = json!({"text": text});
+                            if role == "model" {
+                                attach_signature(&mut part, pending_signature.take());
+                            }
+                            contents.push(role, part);
+                        }
+                        ContentItem::InputImage { image_url } => {
+                            contents.push(role, image_part(image_url));
+                        }
+                    }
+                }
+            }
+            ResponseItem::Reasoning {
+                encrypted_content: Some(signature),
+                ..
+            } => {
+                pending_signature = Some(signature.clone());
+            }
+            ResponseItem::FunctionCall {
+                name, arguments, ..
+            } => {
+                let args = serde_json::from_str::<Value>(arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                let mut part = json!({"functionCall": {"name": name, "args": args}});
+                attach_signature(&mut part, pending_signature.take());
+                contents.push("model", part);
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let name = call_names
+                    .get(call_id.as_str())
+                    .copied()
+                    .unwrap_or_default();
+                contents.push(
+                    "user",
+                    json!({
+                        "functionResponse": {
+                            "name": name,
+                            "response": {"content": output.content},
+                        }
+                    }),
+                );
+                // Images cannot be nested in a function response, so they
+                // follow it as regular user parts.
+                for content_item in output.content_items.iter().flatten() {
+                    if let FunctionCallOutputContentItem::InputImage { image_url } = content_item {
+                        contents.push("user", image_part(image_url));
+                    }
+                }
+            }
+            ResponseItem::CustomToolCall { name, input, .. } => {
+                let mut part = json!({"functionCall": {"name": name, "args": {"input": input}}});
+                attach_signature(&mut part, pending_signature.take());
+                contents.push("model", part);
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                let name = call_names
+                    .get(call_id.as_str())
+                    .copied()
+                    .unwrap_or_default();
+                contents.push(
+                    "user",
+                    json!({
+                        "functionResponse": {
+                            "name": name,
+                            "response": {"content": output},
+                        }
+                    }),
+                );
+            }
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::LocalShellCall { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::GhostSnapshot { .. }
+            | ResponseItem::Other => {
+                // Not representable in the generateContent API.
+                continue;
+            }
+        }
+    }
+
+    let mut generation_config = serde_json::Map::new();
+    if let Some(max_output_tokens) = max_output_tokens {
+        generation_config.insert("maxOutputTokens".to_string(), json!(max_output_tokens));
+    }
+    if let Some(budget) = thinking_budget_for_effort(effort) {
+        generation_config.insert(
+            "thinkingConfig".to_string(),
+            json!({"includeThoughts": true, "thinkingBudget": budget}),
+        );
+    }
+    if let Some(schema) = &prompt.output_schema {
+        generation_config.insert("responseMimeType".to_string(), json!("application/json"));
+        generation_config.insert("responseJsonSchema".to_string(), schema.clone());
+    }
+
+    let mut payload = json!({
+        "systemInstruction": {"parts": [{"text": prompt.get_full_instructions(model_family)}]},
+        "contents": contents.contents,
+    });
+    let Some(obj) = payload.as_object_mut() else {
+        return Ok(payload);
+    };
+
+    let function_declarations = create_tools_json_for_gemini_api(&prompt.tools)?;
+    if !function_declarations.is_empty() {
+        obj.insert(
+            "tools".to_string(),
+            json!([{"functionDeclarations": function_declarations}]),
+        );
+    }
+    if !generation_config.is_empty() {
+        obj.insert(
+            "generationConfig".to_string(),
+            Value::Object(generation_config),
+        );
+    }
+
+    Ok(payload)
+}
+
+fn attach_signature(part: &mut Value, signature: Option<String>) {
+    if let Some(signature) = signature
+        && let Some(obj) = part.as_object_mut()
+    {
+        obj.insert("thoughtSignature".to_string(), json!(signature));
+    }
+}
+
+fn image_part(image_url: &str) -> Value {
+    match split_base64_data_url(image_url) {
+        Some((mime_type, data)) => json!({"inlineData": {"mimeType": mime_type, "data": data}}),
+        None => json!({"fileData": {"fileUri": image_url}}),
+    }
+}
+
+/// Builds the `contents` array, merging consecutive parts for the same role
+/// into one entry.
+#[derive(Default)]
+struct ContentsBuilder {
+    contents: Vec<Value>,
+}
+
+impl ContentsBuilder {
+    fn push(&mut self, role: &str, part: Value) {
+        if let Some(last) = self.contents.last_mut()
+            && last.get("role").and_then(Value::as_str) == Some(role)
+            && let Some(parts) = last.get_mut("parts").and_then(Value::as_array_mut)
+        {
+            parts.push(part);
+            return;
+        }
+        self.contents.push(json!({"role": role, "parts": [part]}));
+    }
+}
+
+/// SSE processor for `streamGenerateContent?alt=sse`. Every event is a full
+/// `GenerateContentResponse` carrying the next slice of parts; text and thought
+/// parts are accumulated into one assistant message and one reasoning item,
+/// while function calls arrive whole and are forwarded immediately.
+async fn process_gemini_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+    idle_timeout: Duration,
+    otel_event_manager: OtelEventManager,
+) where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+
+    let mut assistant_text: Option<String> = None;
+    let mut reasoning_text: Option<String> = None;
+    let mut response_id = String::new();
+    let mut token_usage: Option<TokenUsage> = None;
+
+    loop {
+        let start = std::time::Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        let duration = start.elapsed();
+        otel_event_manager.log_sse_event(&response, duration);
+
+        let sse = match response {
+            Ok(Some(Ok(ev))) => ev,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(e.to_string(), None)))
+                    .await;
+                return;
+            }
+            Ok(None) => {
+                // The stream ends after the chunk carrying `finishReason`.
+                if let Some(item) = reasoning_text.take().map(reasoning_item) {
+                    let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+                }
+                if let Some(text) = assistant_text.take() {
+                    let item = ResponseItem::Message {
+                        id: None,
+                        role: "assistant".to_string(),
+                        content: vec![ContentItem::OutputText { text }],
+                    };
+                    let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+                }
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage,
+                    }))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "idle timeout waiting for SSE".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+        };
+
+        let chunk: Value = match serde_json::from_str(&sse.data) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        trace!("gemini received SSE chunk: {chunk:?}");
+
+        if let Some(error) = chunk.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error")
+                .to_string();
+            let _ = tx_event.send(Err(CodexErr::Stream(message, None))).await;
+            return;
+        }
+
+        if let Some(id) = chunk.get("responseId").and_then(Value::as_str) {
+            response_id = id.to_string();
+        }
+        if let Some(usage) = chunk.get("usageMetadata") {
+            token_usage = Some(parse_usage(usage));
+        }
+
+        let parts = chunk
+            .get("candidates")
+            .and_then(|c| c.get(0))
+            .and_then(|c| c.get("content"))
+            .and_then(|c| c.get("parts"))
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        for part in parts {
+            let signature = part
+                .get("thoughtSignature")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+
+            if let Some(call) = part.get("functionCall") {
+                // Finish any reasoning first so UIs can close it before the
+                // tool call starts. The signature rides along on that item.
+                let reasoning = reasoning_text.take();
+                if reasoning.is_some() || signature.is_some() {
+                    let item = ResponseItem::Reasoning {
+                        id: String::new(),
+                        summary: Vec::new(),
+                        content: reasoning.map(|text| vec![ReasoningItemContent::Text { text }]),
+                        encrypted_content: signature,
+                    };
+                    let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+                }
+
+                let name = call
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                let arguments = call
+                    .get("args")
+                    .map(Value::to_string)
+                    .unwrap_or_else(|| "{}".to_string());
+                let call_id = call
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("call_{}", Uuid::new_v4().simple()));
+                let item = ResponseItem::FunctionCall {
+                    id: None,
+                    name,
+                    arguments,
+                    call_id,
+                };
+                let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+                continue;
+            }
+
+            let Some(text) = part.get("text").and_then(Value::as_str) else {
+                continue;
+            };
+            if text.is_empty() {
+                continue;
+            }
+
+            if part.get("thought").and_then(Value::as_bool) == Some(true) {
+                let buffer = match reasoning_text.as_mut() {
+                    Some(buffer) => buffer,
+                    None => {
+                        let item = ResponseItem::Reasoning {
+                            id: String::new(),
+                            summary: Vec::new(),
+                            content: Some(vec![]),
+                            encrypted_content: None,
+                        };
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        reasoning_text.insert(String::new())
+                    }
+                };
+                buffer.push_str(text);
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::ReasoningContentDelta {
+                        delta: text.to_string(),
+                        content_index: 0,
+                    }))
+                    .await;
+            } else {
+                let buffer = match assistant_text.as_mut() {
+                    Some(buffer) => buffer,
+                    None => {
+                        let item = ResponseItem::Message {
+                            id: None,
+                            role: "assistant".to_string(),
+                            content: vec![],
+                        };
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        assistant_text.insert(String::new())
+                    }
+                };
+                buffer.push_str(text);
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::OutputTextDelta(text.to_string())))
+                    .await;
+            }
+        }
+    }
+}
+
+fn reasoning_item(text: String) -> ResponseItem {
+    ResponseItem::Reasoning {
+        id: String::new(),
+        summary: Vec::new(),
+        content: Some(vec![ReasoningItemContent::Text { text }]),
+        encrypted_content: None,
+    }
+}
+
+fn parse_usage(usage: &Value) -> TokenUsage {
+    let field = |key: &str| usage.get(key).and_then(Value::as_i64).unwrap_or(0);
+    let reasoning_output_tokens = field("thoughtsTokenCount");
+    TokenUsage {
+        input_tokens: field("promptTokenCount"),
+        cached_input_tokens: field("cachedContentTokenCount"),
+        output_tokens: field("candidatesTokenCount") + reasoning_output_tokens,
+        reasoning_output_tokens,
+        total_tokens: field("totalTokenCount"),
+    }
+}
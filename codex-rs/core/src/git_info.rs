@@ -468,7 +468,9 @@ async fn find_closest_sha(cwd: &Path, branches: &[String], remotes: &[String]) -
     closest_sha.map(|(sha, _)| sha)
 }
 
-async fn diff_against_sha(cwd: &Path, sha: &GitSha) -> Option<String> {
+/// Returns the diff of the working tree against `sha`, followed by the
+/// contents of untracked files as additions.
+pub async fn diff_against_sha(cwd: &Path, sha: &GitSha) -> Option<String> {
     let output =
         run_git_command_with_timeout(&["diff", "--no-textconv", "--no-ext-diff", &sha.0], cwd)
             .await?;
@@ -523,6 +525,65 @@ async fn diff_against_sha(cwd: &Path, sha: &GitSha) -> Option<String> {
     Some(diff)
 }
 
+/// Returns the merge base of `HEAD` and the upstream of `branch`, or of `HEAD`
+/// and `branch` itself when it has no upstream.
+pub async fn merge_base_with_head(cwd: &Path, branch: &str) -> Option<GitSha> {
+    if looks_like_option(branch) {
+        return None;
+    }
+    let upstream = format!("{branch}@{{upstream}}");
+    for base in [upstream.as_str(), branch] {
+        if let Some(out) = run_git_command_with_timeout(&["merge-base", "HEAD", base], cwd).await
+            && out.status.success()
+        {
+            let sha = String::from_utf8(out.stdout).ok()?;
+            return Some(GitSha::new(sha.trim()));
+        }
+    }
+    None
+}
+
+/// Returns the message and diff of commit `sha`.
+pub async fn commit_diff(cwd: &Path, sha: &str) -> Option<String> {
+    if looks_like_option(sha) {
+        return None;
+    }
+    git_output(&["show", "--no-textconv", "--no-ext-diff", sha], cwd).await
+}
+
+/// Returns the diff between the endpoints of a range such as `main..HEAD`.
+pub async fn range_diff(cwd: &Path, range: &str) -> Option<String> {
+    if looks_like_option(range) {
+        return None;
+    }
+    git_output(&["diff", "--no-textconv", "--no-ext-diff", range], cwd).await
+}
+
+/// Returns the uncommitted changes to `paths`, relative to `HEAD`.
+pub async fn paths_diff(cwd: &Path, paths: &[PathBuf]) -> Option<String> {
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    let mut args = vec!["diff", "--no-textconv", "--no-ext-diff", "HEAD", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    git_output(&args, cwd).await
+}
+
+/// Revisions come from users and clients; keep them from being read as flags.
+fn looks_like_option(revision: &str) -> bool {
+    revision.starts_with('-')
+}
+
+/// Stdout of a successful git command.
+async fn git_output(args: &[&str], cwd: &Path) -> Option<String> {
+    let output = run_git_command_with_timeout(args, cwd).await?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
 /// Resolve the path that should be used for trust checks. Similar to
 /// `[get_git_repo_root]`, but resolves to the root of the main
 /// repository. Handles worktrees.
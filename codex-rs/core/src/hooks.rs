@@ -0,0 +1,531 @@
+//! Lifecycle hooks configured under `[hooks]` in `config.toml`.
+//!
+//! A hook is an external program that is spawned when an event fires. The
+//! event payload is written to the hook's stdin as a single JSON object and
+//! the hook may print a JSON response on stdout:
+//!
+//! ```json
+//! {"decision": "deny", "reason": "git push is not allowed"}
+//! ```
+//!
+//! `decision` is one of `approve`, `deny` or `continue` (the default).
+//! Post-tool hooks may additionally set `output` to replace the text returned
+//! to the model. Hooks that fail to spawn, time out, exit unsuccessfully or
+//! print something that is not a JSON response are logged and treated as
+//! `continue`, so a broken hook never blocks the agent.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::config::types::HookConfig;
+use crate::config::types::HooksConfig;
+
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Event payload written to the hook's stdin.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub(crate) enum HookEvent {
+    #[serde(rename_all = "kebab-case")]
+    PreToolUse {
+        thread_id: String,
+        turn_id: String,
+        call_id: String,
+        tool_name: String,
+        cwd: String,
+        /// Raw arguments the model passed to the tool.
+        input: String,
+    },
+    #[serde(rename_all = "kebab-case")]
+    PostToolUse {
+        thread_id: String,
+        turn_id: String,
+        call_id: String,
+        tool_name: String,
+        cwd: String,
+        input: String,
+        /// Text that will be returned to the model.
+        output: String,
+        success: bool,
+    },
+    #[serde(rename_all = "kebab-case")]
+    TurnComplete {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        input_messages: Vec<String>,
+        last_assistant_message: Option<String>,
+    },
+    #[serde(rename_all = "kebab-case")]
+    SessionStart {
+        thread_id: String,
+        cwd: String,
+        model: String,
+    },
+}
+
+impl HookEvent {
+    /// Turns a `PreToolUse` event into the matching `PostToolUse` event.
+    pub(crate) fn into_post_tool_use(self, output: String, success: bool) -> Self {
+        match self {
+            HookEvent::PreToolUse {
+                thread_id,
+                turn_id,
+                call_id,
+                tool_name,
+                cwd,
+                input,
+            } => HookEvent::PostToolUse {
+                thread_id,
+                turn_id,
+                call_id,
+                tool_name,
+                cwd,
+                input,
+                output,
+                success,
+            },
+            other => other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum HookDecisionKind {
+    Approve,
+    Deny,
+    #[default]
+    Continue,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct HookResponse {
+    #[serde(default)]
+    decision: HookDecisionKind,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    output: Option<String>,
+}
+
+/// Combined outcome of all pre-tool hooks for a single call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PreToolDecision {
+    /// A hook approved the call; it may skip the user approval prompt.
+    Approve,
+    /// A hook denied the call; `reason` is returned to the model.
+    Deny {
+        reason: String,
+    },
+    Continue,
+}
+
+/// Combined outcome of all post-tool hooks for a single call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PostToolDecision {
+    /// Keep the original output, or use the replacement if one was provided.
+    Continue { output: Option<String> },
+    /// A hook rejected the result; `reason` is returned to the model instead.
+    Deny { reason: String },
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Hooks {
+    config: HooksConfig,
+    /// Call ids approved by a pre-tool hook that have not reached approval yet.
+    approved_calls: Mutex<HashSet<String>>,
+}
+
+impl Hooks {
+    pub(crate) fn new(config: HooksConfig) -> Self {
+        Self {
+            config,
+            approved_calls: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Runs the pre-tool hooks in order. The first `deny` wins; otherwise any
+    /// `approve` is remembered for [`Hooks::take_approval`].
+    pub(crate) async fn pre_tool_use(&self, event: &HookEvent, cwd: &Path) -> PreToolDecision {
+        let HookEvent::PreToolUse {
+            call_id, tool_name, ..
+        } = event
+        else {
+            return PreToolDecision::Continue;
+        };
+
+        let mut approved = false;
+        for hook in matching_hooks(&self.config.pre_tool_use, tool_name) {
+            let response = run_hook(hook, event, cwd).await;
+            match response.decision {
+                HookDecisionKind::Deny => {
+                    return PreToolDecision::Deny {
+                        reason: deny_reason(response.reason, tool_name),
+                    };
+                }
+                HookDecisionKind::Approve => approved = true,
+                HookDecisionKind::Continue => {}
+            }
+        }
+
+        if approved {
+            if let Ok(mut calls) = self.approved_calls.lock() {
+                calls.insert(call_id.clone());
+            }
+            PreToolDecision::Approve
+        } else {
+            PreToolDecision::Continue
+        }
+    }
+
+    /// Runs the post-tool hooks in order. Each hook sees the output as amended
+    /// by the hooks before it.
+    pub(crate) async fn post_tool_use(&self, mut event: HookEvent, cwd: &Path) -> PostToolDecision {
+        let tool_name = match &event {
+            HookEvent::PostToolUse { tool_name, .. } => tool_name.clone(),
+            _ => return PostToolDecision::Continue { output: None },
+        };
+
+        let mut amended = None;
+        for hook in matching_hooks(&self.config.post_tool_use, &tool_name) {
+            let response = run_hook(hook, &event, cwd).await;
+            if response.decision == HookDecisionKind::Deny {
+                return PostToolDecision::Deny {
+                    reason: deny_reason(response.reason, &tool_name),
+                };
+            }
+            if let Some(new_output) = response.output {
+                if let HookEvent::PostToolUse { output, .. } = &mut event {
+                    output.clone_from(&new_output);
+                }
+                amended = Some(new_output);
+            }
+        }
+
+        PostToolDecision::Continue { output: amended }
+    }
+
+    pub(crate) async fn turn_complete(&self, event: &HookEvent, cwd: &Path) {
+        for hook in &self.config.turn_complete {
+            run_hook(hook, event, cwd).await;
+        }
+    }
+
+    pub(crate) async fn session_start(&self, event: &HookEvent, cwd: &Path) {
+        for hook in &self.config.session_start {
+            run_hook(hook, event, cwd).await;
+        }
+    }
+
+    /// Returns true (once) if a pre-tool hook approved `call_id`.
+    pub(crate) fn take_approval(&self, call_id: &str) -> bool {
+        self.approved_calls
+            .lock()
+            .map(|mut calls| calls.remove(call_id))
+            .unwrap_or(false)
+    }
+
+    /// Drops the approval of `call_id`, if it is still pending, once the
+    /// returned guard goes out of scope. Covers calls that end without
+    /// reaching the approval flow: errors, cancellations and tools that never
+    /// ask for approval.
+    pub(crate) fn approval_scope<'a>(&'a self, call_id: &'a str) -> ApprovalScope<'a> {
+        ApprovalScope {
+            hooks: self,
+            call_id,
+        }
+    }
+}
+
+/// See [`Hooks::approval_scope`].
+pub(crate) struct ApprovalScope<'a> {
+    hooks: &'a Hooks,
+    call_id: &'a str,
+}
+
+impl Drop for ApprovalScope<'_> {
+    fn drop(&mut self) {
+        self.hooks.take_approval(self.call_id);
+    }
+}
+
+fn matching_hooks<'a>(
+    hooks: &'a [HookConfig],
+    tool_name: &'a str,
+) -> impl Iterator<Item = &'a HookConfig> + 'a {
+    hooks.iter().filter(move |hook| {
+        hook.tools
+            .as_ref()
+            .is_none_or(|tools| tools.iter().any(|tool| tool == tool_name))
+    })
+}
+
+fn deny_reason(reason: Option<String>, tool_name: &str) -> String {
+    match reason {
+        Some(reason) if !reason.trim().is_empty() => reason,
+        _ => format!("{tool_name} call denied by hook"),
+    }
+}
+
+/// Spawns `hook`, feeds it `event` and parses its response. Any failure is
+/// logged and mapped to an empty (`continue`) response.
+async fn run_hook(hook: &HookConfig, event: &HookEvent, cwd: &Path) -> HookResponse {
+    let Some((program, args)) = hook.command.split_first() else {
+        return HookResponse::default();
+    };
+    let payload = match serde_json::to_vec(event) {
+        Ok(payload) => payload,
+        Err(err) => {
+            warn!("failed to serialise hook payload: {err}");
+            return HookResponse::default();
+        }
+    };
+
+    let mut child = match Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            warn!("failed to spawn hook '{program}': {err}");
+            return HookResponse::default();
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that exits without reading stdin is not an error.
+        let _ = stdin.write_all(&payload).await;
+    }
+
+    let timeout = hook
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_HOOK_TIMEOUT);
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(err)) => {
+            warn!("failed to wait for hook '{program}': {err}");
+            return HookResponse::default();
+        }
+        Err(_) => {
+            warn!("hook '{program}' timed out after {}ms", timeout.as_millis());
+            return HookResponse::default();
+        }
+    };
+
+    if !output.status.success() {
+        warn!("hook '{program}' exited with {}", output.status);
+        return HookResponse::default();
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        return HookResponse::default();
+    }
+    serde_json::from_str(stdout.trim()).unwrap_or_else(|err| {
+        warn!("ignoring invalid response from hook '{program}': {err}");
+        HookResponse::default()
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn sh(script: &str) -> HookConfig {
+        HookConfig {
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            tools: None,
+            timeout_ms: None,
+        }
+    }
+
+    fn pre_tool_event(tool_name: &str, input: &str) -> HookEvent {
+        HookEvent::PreToolUse {
+            thread_id: "thread".to_string(),
+            turn_id: "turn".to_string(),
+            call_id: "call-1".to_string(),
+            tool_name: tool_name.to_string(),
+            cwd: "/tmp".to_string(),
+            input: input.to_string(),
+        }
+    }
+
+    fn post_tool_event(output: &str) -> HookEvent {
+        HookEvent::PostToolUse {
+            thread_id: "thread".to_string(),
+            turn_id: "turn".to_string(),
+            call_id: "call-1".to_string(),
+            tool_name: "shell".to_string(),
+            cwd: "/tmp".to_string(),
+            input: "{}".to_string(),
+            output: output.to_string(),
+            success: true,
+        }
+    }
+
+    #[test]
+    fn serializes_pre_tool_payload() {
+        let json = serde_json::to_string(&pre_tool_event("shell", "{}")).expect("serialize");
+        assert_eq!(
+            json,
+            r#"{"type":"pre-tool-use","thread-id":"thread","turn-id":"turn","call-id":"call-1","tool-name":"shell","cwd":"/tmp","input":"{}"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn pre_tool_hook_can_deny_based_on_stdin() {
+        let cwd = TempDir::new().expect("tempdir");
+        let hooks = Hooks::new(HooksConfig {
+            pre_tool_use: vec![sh(
+                r#"if grep -q "git push"; then echo '{"decision":"deny","reason":"no pushing"}'; fi"#,
+            )],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            PreToolDecision::Deny {
+                reason: "no pushing".to_string()
+            },
+            hooks
+                .pre_tool_use(
+                    &pre_tool_event(
+                        "shell",
+                        r#"{"command":["bash","-lc","git push origin main"]}"#
+                    ),
+                    cwd.path()
+                )
+                .await
+        );
+        assert_eq!(
+            PreToolDecision::Continue,
+            hooks
+                .pre_tool_use(
+                    &pre_tool_event("shell", r#"{"command":["bash","-lc","git status"]}"#),
+                    cwd.path()
+                )
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn pre_tool_approval_is_taken_once() {
+        let cwd = TempDir::new().expect("tempdir");
+        let hooks = Hooks::new(HooksConfig {
+            pre_tool_use: vec![sh(r#"echo '{"decision":"approve"}'"#)],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            PreToolDecision::Approve,
+            hooks
+                .pre_tool_use(&pre_tool_event("shell", "{}"), cwd.path())
+                .await
+        );
+        assert!(hooks.take_approval("call-1"));
+        assert!(!hooks.take_approval("call-1"));
+    }
+
+    #[tokio::test]
+    async fn approval_scope_drops_untaken_approvals() {
+        let cwd = TempDir::new().expect("tempdir");
+        let hooks = Hooks::new(HooksConfig {
+            pre_tool_use: vec![sh(r#"echo '{"decision":"approve"}'"#)],
+            ..Default::default()
+        });
+
+        {
+            let _scope = hooks.approval_scope("call-1");
+            hooks
+                .pre_tool_use(&pre_tool_event("shell", "{}"), cwd.path())
+                .await;
+        }
+        assert!(!hooks.take_approval("call-1"));
+    }
+
+    #[tokio::test]
+    async fn tool_filter_skips_other_tools() {
+        let cwd = TempDir::new().expect("tempdir");
+        let mut hook = sh(r#"echo '{"decision":"deny"}'"#);
+        hook.tools = Some(vec!["apply_patch".to_string()]);
+        let hooks = Hooks::new(HooksConfig {
+            pre_tool_use: vec![hook],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            PreToolDecision::Continue,
+            hooks
+                .pre_tool_use(&pre_tool_event("shell", "{}"), cwd.path())
+                .await
+        );
+        assert_eq!(
+            PreToolDecision::Deny {
+                reason: "apply_patch call denied by hook".to_string()
+            },
+            hooks
+                .pre_tool_use(&pre_tool_event("apply_patch", "{}"), cwd.path())
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn post_tool_hooks_chain_output_amendments() {
+        let cwd = TempDir::new().expect("tempdir");
+        let hooks = Hooks::new(HooksConfig {
+            post_tool_use: vec![
+                sh(r#"echo '{"output":"first"}'"#),
+                sh(r#"grep -q '"output":"first"' && echo '{"output":"second"}'"#),
+            ],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            PostToolDecision::Continue {
+                output: Some("second".to_string())
+            },
+            hooks
+                .post_tool_use(post_tool_event("original"), cwd.path())
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_or_slow_hooks_continue() {
+        let cwd = TempDir::new().expect("tempdir");
+        let mut slow = sh("sleep 5");
+        slow.timeout_ms = Some(50);
+        let hooks = Hooks::new(HooksConfig {
+            pre_tool_use: vec![
+                sh(r#"echo '{"decision":"deny"}'; exit 1"#),
+                sh("echo not-json"),
+                slow,
+            ],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            PreToolDecision::Continue,
+            hooks
+                .pre_tool_use(&pre_tool_event("shell", "{}"), cwd.path())
+                .await
+        );
+    }
+}
@@ -1,4 +1,6 @@
+use crate::config::types::ResourceLimits;
 use crate::protocol::SandboxPolicy;
+use crate::spawn::SpawnChildRequest;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 use std::collections::HashMap;
@@ -19,31 +21,48 @@ pub async fn spawn_command_under_linux_sandbox<P>(
     command_cwd: PathBuf,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    read_deny: &[String],
     stdio_policy: StdioPolicy,
     env: HashMap<String, String>,
 ) -> std::io::Result<Child>
 where
     P: AsRef<Path>,
 {
-    let args = create_linux_sandbox_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    let args = create_linux_sandbox_command_args(
+        command,
+        sandbox_policy,
+        sandbox_policy_cwd,
+        read_deny,
+        None,
+        &ResourceLimits::default(),
+    );
     let arg0 = Some("codex-linux-sandbox");
-    spawn_child_async(
-        codex_linux_sandbox_exe.as_ref().to_path_buf(),
+    spawn_child_async(SpawnChildRequest {
+        program: codex_linux_sandbox_exe.as_ref().to_path_buf(),
         args,
         arg0,
-        command_cwd,
+        cwd: command_cwd,
         sandbox_policy,
         stdio_policy,
         env,
-    )
+        resource_limits: ResourceLimits::default(),
+    })
     .await
 }
 
 /// Converts the sandbox policy into the CLI invocation for `codex-linux-sandbox`.
+/// `read_deny` holds the configured read-deny globs; the helper adds the
+/// defaults itself. When `network_proxy_port` is set, the helper runs the
+/// command in its own network namespace where that loopback port reaches the
+/// proxy. The helper applies `resource_limits` to itself right before it
+/// executes the command.
 pub(crate) fn create_linux_sandbox_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    read_deny: &[String],
+    network_proxy_port: Option<u16>,
+    resource_limits: &ResourceLimits,
 ) -> Vec<String> {
     #[expect(clippy::expect_used)]
     let sandbox_policy_cwd = sandbox_policy_cwd
@@ -60,10 +79,37 @@ pub(crate) fn create_linux_sandbox_command_args(
         sandbox_policy_cwd,
         "--sandbox-policy".to_string(),
         sandbox_policy_json,
-        // Separator so that command arguments starting with `-` are not parsed as
-        // options of the helper itself.
-        "--".to_string(),
     ];
+    for glob in read_deny {
+        linux_cmd.push("--read-deny".to_string());
+        linux_cmd.push(glob.clone());
+    }
+    if let Some(port) = network_proxy_port {
+        linux_cmd.push("--network-proxy-port".to_string());
+        linux_cmd.push(port.to_string());
+    }
+    let ResourceLimits {
+        memory_mb,
+        cpu_seconds,
+        processes,
+        file_size_mb,
+        // The output limit is enforced by the parent while reading output.
+        output_mb: _,
+    } = *resource_limits;
+    for (flag, limit) in [
+        ("--memory-limit-mb", memory_mb),
+        ("--cpu-limit-seconds", cpu_seconds),
+        ("--process-limit", processes),
+        ("--file-size-limit-mb", file_size_mb),
+    ] {
+        if let Some(limit) = limit {
+            linux_cmd.push(flag.to_string());
+            linux_cmd.push(limit.to_string());
+        }
+    }
+    // Separator so that command arguments starting with `-` are not parsed as
+    // options of the helper itself.
+    linux_cmd.push("--".to_string());
 
     // Append the original tool command.
     linux_cmd.extend(command);
@@ -5,9 +5,12 @@
 // the TUI or the tracing stack).
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
+mod anthropic_messages;
 mod apply_patch;
+pub mod audit;
 pub mod auth;
 pub mod bash;
+mod budget;
 mod chat_completions;
 mod client;
 mod client_common;
@@ -20,20 +23,27 @@ pub mod config;
 pub mod config_loader;
 mod context_manager;
 pub mod custom_prompts;
+pub mod env_secrets;
 mod environment_context;
 pub mod error;
 pub mod exec;
 pub mod exec_env;
 pub mod features;
 mod flags;
+mod gemini;
 pub mod git_info;
+mod hooks;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
 mod mcp_tool_call;
+pub mod memory;
 mod message_history;
+mod model_pricing;
 mod model_provider_info;
+mod network_proxy;
 pub mod parse_command;
+mod redaction;
 mod response_processing;
 pub mod sandboxing;
 pub mod token_data;
@@ -48,6 +58,7 @@ pub use model_provider_info::create_oss_provider_with_base_url;
 mod conversation_manager;
 mod event_mapping;
 pub mod review_format;
+mod review_prompts;
 pub use codex_protocol::protocol::InitialHistory;
 pub use conversation_manager::ConversationManager;
 pub use conversation_manager::NewConversation;
@@ -64,6 +75,7 @@ pub mod seatbelt;
 pub mod shell;
 pub mod spawn;
 pub mod terminal;
+mod tool_output_store;
 mod tools;
 pub mod turn_diff_tracker;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
@@ -77,6 +89,8 @@ pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
 pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
+pub use rollout::search::SearchHit;
+pub use rollout::search::command_from_tool_args;
 mod function_tool;
 mod state;
 mod tasks;
@@ -85,6 +99,7 @@ mod user_shell_command;
 pub mod util;
 
 pub use apply_patch::CODEX_APPLY_PATCH_ARG1;
+pub use command_safety::approval_rules;
 pub use command_safety::is_safe_command;
 pub use safety::get_platform_sandbox;
 pub use safety::set_windows_sandbox_enabled;
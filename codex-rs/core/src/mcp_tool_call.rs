@@ -2,6 +2,7 @@ use std::time::Instant;
 
 use tracing::error;
 
+use crate::audit::AuditEvent;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::protocol::EventMsg;
@@ -71,6 +72,17 @@ pub(crate) async fn handle_mcp_tool_call(
     });
 
     notify_mcp_tool_call_event(sess, turn_context, tool_call_end_event.clone()).await;
+    sess.services
+        .audit
+        .record(AuditEvent::McpToolCall {
+            call_id: call_id.clone(),
+            server,
+            tool: tool_name,
+            success: result
+                .as_ref()
+                .is_ok_and(|result| result.is_error != Some(true)),
+        })
+        .await;
 
     ResponseInputItem::McpToolCallOutput { call_id, result }
 }
@@ -0,0 +1,390 @@
+//! Memories that persist across sessions.
+//!
+//! Memories are stored as plain Markdown under `$CODEX_HOME/memories` so they
+//! can be read and edited by hand:
+//!
+//! - `global.md` holds memories that apply to every project.
+//! - `projects/<name>-<hash>.md` holds memories for a single project. The
+//!   project is the Git root containing the working directory, or the working
+//!   directory itself outside of a repository.
+//!
+//! Each memory is one `- ` bullet line. Any other line (headings, notes,
+//! blank lines) is preserved when the file is rewritten but otherwise ignored.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+
+use crate::git_info::get_git_repo_root;
+
+pub const MEMORIES_SUBDIR: &str = "memories";
+const GLOBAL_MEMORIES_FILENAME: &str = "global.md";
+const PROJECT_MEMORIES_SUBDIR: &str = "projects";
+const MEMORY_PREFIX: &str = "- ";
+
+/// Upper bound on how much memory text is injected into the initial context.
+const MAX_PROMPT_MEMORY_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryScope {
+    Project,
+    Global,
+}
+
+impl fmt::Display for MemoryScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryScope::Project => f.write_str("project"),
+            MemoryScope::Global => f.write_str("global"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Memory {
+    pub scope: MemoryScope,
+    pub text: String,
+}
+
+/// Reads and writes the memory files for one project.
+#[derive(Debug, Clone)]
+pub struct MemoryStore {
+    global_path: PathBuf,
+    project_path: PathBuf,
+}
+
+impl MemoryStore {
+    pub fn new(codex_home: &Path, cwd: &Path) -> Self {
+        let root = codex_home.join(MEMORIES_SUBDIR);
+        let project_root = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        let project_root = dunce::canonicalize(&project_root).unwrap_or(project_root);
+        Self {
+            global_path: root.join(GLOBAL_MEMORIES_FILENAME),
+            project_path: root
+                .join(PROJECT_MEMORIES_SUBDIR)
+                .join(project_file_name(&project_root)),
+        }
+    }
+
+    pub fn path(&self, scope: MemoryScope) -> &Path {
+        match scope {
+            MemoryScope::Project => &self.project_path,
+            MemoryScope::Global => &self.global_path,
+        }
+    }
+
+    /// Memories in `scope`, in file order. A missing file has no memories.
+    pub fn list(&self, scope: MemoryScope) -> io::Result<Vec<Memory>> {
+        let contents = match std::fs::read_to_string(self.path(scope)) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(contents
+            .lines()
+            .filter_map(parse_memory_line)
+            .map(|text| Memory {
+                scope,
+                text: text.to_string(),
+            })
+            .collect())
+    }
+
+    /// Project memories followed by global ones.
+    pub fn list_all(&self) -> io::Result<Vec<Memory>> {
+        let mut memories = self.list(MemoryScope::Project)?;
+        memories.extend(self.list(MemoryScope::Global)?);
+        Ok(memories)
+    }
+
+    /// Appends a memory to `scope`. Returns `false` without writing when an
+    /// identical memory is already stored.
+    pub fn remember(&self, scope: MemoryScope, text: &str) -> io::Result<bool> {
+        let text = normalize_memory_text(text);
+        if text.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "memory must not be empty",
+            ));
+        }
+        if self.list(scope)?.iter().any(|memory| memory.text == text) {
+            return Ok(false);
+        }
+
+        let path = self.path(scope);
+        let mut contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        contents.push_str(MEMORY_PREFIX);
+        contents.push_str(&text);
+        contents.push('\n');
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)?;
+        Ok(true)
+    }
+
+    /// Memories that share at least one word with `query`, best matches
+    /// first. An empty query returns every memory.
+    pub fn recall(&self, query: &str, scope: Option<MemoryScope>) -> io::Result<Vec<Memory>> {
+        let memories = match scope {
+            Some(scope) => self.list(scope)?,
+            None => self.list_all()?,
+        };
+        let words: Vec<String> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        if words.is_empty() {
+            return Ok(memories);
+        }
+
+        let mut scored: Vec<(usize, Memory)> = memories
+            .into_iter()
+            .filter_map(|memory| {
+                let text = memory.text.to_lowercase();
+                let score = words.iter().filter(|word| text.contains(*word)).count();
+                (score > 0).then_some((score, memory))
+            })
+            .collect();
+        // Stable sort keeps project memories ahead of global ones on ties.
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        Ok(scored.into_iter().map(|(_, memory)| memory).collect())
+    }
+
+    /// Memories whose text contains `query`, ignoring case.
+    pub fn find(&self, query: &str, scope: Option<MemoryScope>) -> io::Result<Vec<Memory>> {
+        let query = normalize_memory_text(query).to_lowercase();
+        let memories = match scope {
+            Some(scope) => self.list(scope)?,
+            None => self.list_all()?,
+        };
+        Ok(memories
+            .into_iter()
+            .filter(|memory| memory.text.to_lowercase().contains(&query))
+            .collect())
+    }
+
+    /// Removes `memory` from its file, leaving every other line untouched.
+    /// Returns `false` when it was not found.
+    pub fn forget(&self, memory: &Memory) -> io::Result<bool> {
+        let path = self.path(memory.scope);
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+
+        let mut removed = false;
+        let mut kept = String::with_capacity(contents.len());
+        for line in contents.lines() {
+            if !removed && parse_memory_line(line) == Some(memory.text.as_str()) {
+                removed = true;
+                continue;
+            }
+            kept.push_str(line);
+            kept.push('\n');
+        }
+        if removed {
+            std::fs::write(path, kept)?;
+        }
+        Ok(removed)
+    }
+}
+
+/// Formats the memories for the initial context, project memories first,
+/// stopping once [`MAX_PROMPT_MEMORY_BYTES`] is reached.
+pub(crate) fn format_memories_for_prompt(memories: &[Memory]) -> Option<String> {
+    if memories.is_empty() {
+        return None;
+    }
+
+    let mut out = String::from(
+        "Memories saved in earlier sessions. Use the `recall`, `remember` and `forget` tools to manage them.\n",
+    );
+    let mut budget = MAX_PROMPT_MEMORY_BYTES;
+    for memory in memories {
+        let line = format!("- ({}) {}\n", memory.scope, memory.text);
+        if line.len() > budget {
+            tracing::warn!("memories exceed {MAX_PROMPT_MEMORY_BYTES} bytes - truncating.");
+            break;
+        }
+        budget -= line.len();
+        out.push_str(&line);
+    }
+    Some(out.trim_end().to_string())
+}
+
+fn parse_memory_line(line: &str) -> Option<&str> {
+    line.strip_prefix(MEMORY_PREFIX)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+/// Memories are single lines so the file stays a flat bullet list.
+fn normalize_memory_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn project_file_name(project_root: &Path) -> String {
+    let name: String = project_root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let mut hasher = Sha1::new();
+    hasher.update(project_root.to_string_lossy().as_bytes());
+    let hash = format!("{:x}", hasher.finalize());
+    let hash = &hash[..12];
+    if name.is_empty() {
+        format!("{hash}.md")
+    } else {
+        format!("{name}-{hash}.md")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn store() -> (TempDir, MemoryStore) {
+        let tmp = TempDir::new().expect("tempdir");
+        let cwd = tmp.path().join("my project");
+        std::fs::create_dir_all(&cwd).expect("create cwd");
+        let store = MemoryStore::new(&tmp.path().join("home"), &cwd);
+        (tmp, store)
+    }
+
+    fn memory(scope: MemoryScope, text: &str) -> Memory {
+        Memory {
+            scope,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn project_file_is_named_after_the_project() {
+        let (_tmp, store) = store();
+        let file_name = store
+            .path(MemoryScope::Project)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .expect("file name");
+        assert!(file_name.starts_with("my_project-"), "{file_name}");
+        assert!(file_name.ends_with(".md"), "{file_name}");
+    }
+
+    #[test]
+    fn remember_appends_bullets_and_skips_duplicates() {
+        let (_tmp, store) = store();
+        assert!(
+            store
+                .remember(MemoryScope::Project, "Run `just fmt`\nbefore committing")
+                .expect("remember")
+        );
+        assert!(
+            !store
+                .remember(MemoryScope::Project, "Run `just fmt` before committing")
+                .expect("remember")
+        );
+        store
+            .remember(MemoryScope::Global, "Prefers tabs")
+            .expect("remember");
+
+        assert_eq!(
+            std::fs::read_to_string(store.path(MemoryScope::Project)).expect("read"),
+            "- Run `just fmt` before committing\n"
+        );
+        assert_eq!(
+            store.list_all().expect("list"),
+            vec![
+                memory(MemoryScope::Project, "Run `just fmt` before committing"),
+                memory(MemoryScope::Global, "Prefers tabs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn hand_edited_lines_survive_forget() {
+        let (_tmp, store) = store();
+        let path = store.path(MemoryScope::Global).to_path_buf();
+        std::fs::create_dir_all(path.parent().expect("parent")).expect("mkdir");
+        std::fs::write(&path, "# Notes\n\n- keep me\n- drop me\n").expect("write");
+
+        assert!(
+            store
+                .forget(&memory(MemoryScope::Global, "drop me"))
+                .expect("forget")
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read"),
+            "# Notes\n\n- keep me\n"
+        );
+    }
+
+    #[test]
+    fn recall_ranks_by_matching_words() {
+        let (_tmp, store) = store();
+        store
+            .remember(MemoryScope::Global, "Tests use cargo nextest")
+            .expect("remember");
+        store
+            .remember(MemoryScope::Project, "The API crate talks to the backend")
+            .expect("remember");
+        store
+            .remember(MemoryScope::Project, "Run api tests with cargo test -p api")
+            .expect("remember");
+
+        let recalled = store.recall("api tests", None).expect("recall");
+        assert_eq!(
+            recalled,
+            vec![
+                memory(MemoryScope::Project, "Run api tests with cargo test -p api"),
+                memory(MemoryScope::Project, "The API crate talks to the backend"),
+                memory(MemoryScope::Global, "Tests use cargo nextest"),
+            ]
+        );
+        assert_eq!(store.recall("", None).expect("recall").len(), 3);
+    }
+
+    #[test]
+    fn prompt_lists_memories_with_scope() {
+        let memories = vec![
+            memory(MemoryScope::Project, "Uses pnpm"),
+            memory(MemoryScope::Global, "Prefers small commits"),
+        ];
+        assert_eq!(
+            format_memories_for_prompt(&memories).as_deref(),
+            Some(
+                "Memories saved in earlier sessions. Use the `recall`, `remember` and `forget` tools to manage them.\n- (project) Uses pnpm\n- (global) Prefers small commits"
+            )
+        );
+        assert_eq!(format_memories_for_prompt(&[]), None);
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::config::types::ModelPricing;
+use crate::protocol::TokenUsage;
+
+impl ModelPricing {
+    const fn new(input: f64, cached_input: f64, output: f64) -> Self {
+        Self {
+            input,
+            cached_input: Some(cached_input),
+            output,
+        }
+    }
+
+    /// Estimated cost of `usage` in US dollars.
+    pub(crate) fn cost_usd(&self, usage: &TokenUsage) -> f64 {
+        let cached = usage.cached_input_tokens.max(0);
+        let uncached = (usage.input_tokens - cached).max(0);
+        let output = usage.output_tokens.max(0);
+        let cached_price = self.cached_input.unwrap_or(self.input);
+        (uncached as f64 * self.input + cached as f64 * cached_price + output as f64 * self.output)
+            / 1_000_000.0
+    }
+}
+
+/// Look up the pricing for `slug`, preferring user overrides.
+pub(crate) fn get_model_pricing(
+    slug: &str,
+    overrides: &HashMap<String, ModelPricing>,
+) -> Option<ModelPricing> {
+    if let Some(pricing) = overrides.get(slug) {
+        return Some(*pricing);
+    }
+
+    match slug {
+        // https://platform.openai.com/docs/pricing
+        "o3" => Some(ModelPricing::new(2.0, 0.5, 8.0)),
+        "o4-mini" => Some(ModelPricing::new(1.1, 0.275, 4.4)),
+        "codex-mini-latest" => Some(ModelPricing::new(1.5, 0.375, 6.0)),
+        "gpt-4.1" | "gpt-4.1-2025-04-14" => Some(ModelPricing::new(2.0, 0.5, 8.0)),
+        "gpt-4.1-mini" => Some(ModelPricing::new(0.4, 0.1, 1.6)),
+        "gpt-4o" | "gpt-4o-2024-08-06" | "gpt-4o-2024-11-20" => {
+            Some(ModelPricing::new(2.5, 1.25, 10.0))
+        }
+        "gpt-4o-2024-05-13" => Some(ModelPricing::new(5.0, 5.0, 15.0)),
+        "gpt-3.5-turbo" => Some(ModelPricing::new(0.5, 0.5, 1.5)),
+
+        _ if slug.starts_with("gpt-5-mini") => Some(ModelPricing::new(0.25, 0.025, 2.0)),
+        _ if slug.starts_with("gpt-5-nano") => Some(ModelPricing::new(0.05, 0.005, 0.4)),
+        _ if slug.starts_with("gpt-5") => Some(ModelPricing::new(1.25, 0.125, 10.0)),
+
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn cost_splits_cached_and_uncached_input() {
+        let pricing = ModelPricing::new(1.0, 0.1, 10.0);
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            cached_input_tokens: 500_000,
+            output_tokens: 100_000,
+            reasoning_output_tokens: 50_000,
+            total_tokens: 1_100_000,
+        };
+
+        let cost = pricing.cost_usd(&usage);
+
+        assert!((cost - 1.55).abs() < 1e-9, "unexpected cost {cost}");
+    }
+
+    #[test]
+    fn overrides_take_precedence_over_built_in_prices() {
+        let custom = ModelPricing {
+            input: 3.0,
+            cached_input: None,
+            output: 6.0,
+        };
+        let overrides = HashMap::from([("gpt-5".to_string(), custom)]);
+
+        assert_eq!(get_model_pricing("gpt-5", &overrides), Some(custom));
+        assert_eq!(
+            get_model_pricing("gpt-5-codex", &overrides),
+            Some(ModelPricing::new(1.25, 0.125, 10.0))
+        );
+        assert_eq!(get_model_pricing("my-local-model", &overrides), None);
+    }
+}
@@ -27,9 +27,10 @@ const MAX_REQUEST_MAX_RETRIES: u64 = 100;
 /// Wire protocol that the provider speaks. Most third-party services only
 /// implement the classic OpenAI Chat Completions JSON schema, whereas OpenAI
 /// itself (and a handful of others) additionally expose the more modern
-/// *Responses* API. The two protocols use different request/response shapes
-/// and *cannot* be auto-detected at runtime, therefore each provider entry
-/// must declare which one it expects.
+/// *Responses* API. Anthropic and Google expose their own native protocols.
+/// The protocols use different request/response shapes and *cannot* be
+/// auto-detected at runtime, therefore each provider entry must declare which
+/// one it expects.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum WireApi {
@@ -39,8 +40,20 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// Anthropic Messages API at `/v1/messages`.
+    Anthropic,
+
+    /// Gemini `generateContent` API at
+    /// `/v1beta/models/{model}:streamGenerateContent`.
+    Gemini,
 }
 
+const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_CHATGPT_BASE_URL: &str = "https://chatgpt.com/backend-api/codex";
+const DEFAULT_ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com/v1";
+const DEFAULT_GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
 /// Serializable representation of a provider definition.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct ModelProviderInfo {
@@ -104,10 +117,13 @@ impl ModelProviderInfo {
     ///
     /// If the provider declares an `env_key` but the variable is missing/empty, returns an [`Err`] identical to the
     /// one produced by [`ModelProviderInfo::api_key`].
+    ///
+    /// `model` is only used by wire APIs that address the model in the URL.
     pub async fn create_request_builder<'a>(
         &'a self,
         client: &'a CodexHttpClient,
         auth: &Option<CodexAuth>,
+        model: &str,
     ) -> crate::error::Result<CodexRequestBuilder> {
         let effective_auth = if let Some(secret_key) = &self.experimental_bearer_token {
             Some(CodexAuth::from_api_key(secret_key))
@@ -125,12 +141,17 @@ impl ModelProviderInfo {
             }
         };
 
-        let url = self.get_full_url(&effective_auth);
+        let url = self.get_full_url(&effective_auth, model);
 
         let mut builder = client.post(url);
 
         if let Some(auth) = effective_auth.as_ref() {
-            builder = builder.bearer_auth(auth.get_token().await?);
+            let token = auth.get_token().await?;
+            builder = match self.wire_api {
+                WireApi::Anthropic => builder.header("x-api-key", token),
+                WireApi::Gemini => builder.header("x-goog-api-key", token),
+                WireApi::Responses | WireApi::Chat => builder.bearer_auth(token),
+            };
         }
 
         Ok(self.apply_http_headers(builder))
@@ -149,17 +170,22 @@ impl ModelProviderInfo {
             })
     }
 
-    pub(crate) fn get_full_url(&self, auth: &Option<CodexAuth>) -> String {
-        let default_base_url = if matches!(
-            auth,
-            Some(CodexAuth {
-                mode: AuthMode::ChatGPT,
-                ..
-            })
-        ) {
-            "https://chatgpt.com/backend-api/codex"
-        } else {
-            "https://api.openai.com/v1"
+    pub(crate) fn get_full_url(&self, auth: &Option<CodexAuth>, model: &str) -> String {
+        let default_base_url = match self.wire_api {
+            WireApi::Anthropic => DEFAULT_ANTHROPIC_BASE_URL,
+            WireApi::Gemini => DEFAULT_GEMINI_BASE_URL,
+            WireApi::Responses | WireApi::Chat
+                if matches!(
+                    auth,
+                    Some(CodexAuth {
+                        mode: AuthMode::ChatGPT,
+                        ..
+                    })
+                ) =>
+            {
+                DEFAULT_CHATGPT_BASE_URL
+            }
+            WireApi::Responses | WireApi::Chat => DEFAULT_OPENAI_BASE_URL,
         };
         let query_string = self.get_query_string();
         let base_url = self
@@ -170,6 +196,13 @@ impl ModelProviderInfo {
         match self.wire_api {
             WireApi::Responses => format!("{base_url}/responses{query_string}"),
             WireApi::Chat => format!("{base_url}/chat/completions{query_string}"),
+            WireApi::Anthropic => format!("{base_url}/messages{query_string}"),
+            WireApi::Gemini => {
+                // SSE framing is selected with `alt=sse`; any user-provided
+                // query parameters are appended after it.
+                let extra_params = query_string.replacen('?', "&", 1);
+                format!("{base_url}/models/{model}:streamGenerateContent?alt=sse{extra_params}")
+            }
         }
     }
 
@@ -463,6 +496,54 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
         assert_eq!(expected_provider, provider);
     }
 
+    #[test]
+    fn test_native_wire_api_urls() {
+        let provider_for = |wire_api: WireApi| ModelProviderInfo {
+            name: "test".into(),
+            base_url: None,
+            env_key: None,
+            env_key_instructions: None,
+            experimental_bearer_token: None,
+            wire_api,
+            query_params: None,
+            http_headers: None,
+            env_http_headers: None,
+            request_max_retries: None,
+            stream_max_retries: None,
+            stream_idle_timeout_ms: None,
+            requires_openai_auth: false,
+        };
+
+        assert_eq!(
+            "https://api.anthropic.com/v1/messages",
+            provider_for(WireApi::Anthropic).get_full_url(&None, "claude-sonnet-4-5")
+        );
+
+        let mut gemini = provider_for(WireApi::Gemini);
+        assert_eq!(
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-pro:streamGenerateContent?alt=sse",
+            gemini.get_full_url(&None, "gemini-2.5-pro")
+        );
+        gemini.base_url = Some("http://localhost:8080/v1beta".into());
+        gemini.query_params = Some(maplit::hashmap! {
+            "key".to_string() => "abc".to_string(),
+        });
+        assert_eq!(
+            "http://localhost:8080/v1beta/models/gemini-2.5-pro:streamGenerateContent?alt=sse&key=abc",
+            gemini.get_full_url(&None, "gemini-2.5-pro")
+        );
+
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Anthropic"
+env_key = "ANTHROPIC_API_KEY"
+wire_api = "anthropic"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(WireApi::Anthropic, provider.wire_api);
+    }
+
     #[test]
     fn detects_azure_responses_base_urls() {
         fn provider_for(base_url: &str) -> ModelProviderInfo {
@@ -0,0 +1,460 @@
+//! Loopback HTTP(S) proxy that limits sandboxed commands to allowlisted hosts.
+//!
+//! When a `workspace-write` policy turns off general network access but lists
+//! `network_allowlist` entries, every sandboxed command gets its own proxy
+//! bound to `127.0.0.1`. The platform sandbox only lets the command reach that
+//! port, and `HTTP_PROXY`/`HTTPS_PROXY` point well-behaved clients at it. The
+//! proxy serves `CONNECT host:port` tunnels and absolute-form `http://`
+//! requests, and refuses every host that is not on the allowlist.
+
+use std::collections::HashSet;
+use std::io;
+use std::net::SocketAddr;
+use std::net::TcpListener as StdTcpListener;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+use tokio::task::JoinSet;
+use tracing::debug;
+use tracing::info;
+use tracing::warn;
+
+use crate::audit::AuditEvent;
+use crate::exec::StdoutStream;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::NetworkRequestDeniedEvent;
+
+/// Upper bound on the size of a request head sent to the proxy.
+const MAX_REQUEST_HEAD_BYTES: usize = 64 * 1024;
+
+/// One `network_allowlist` entry: `host`, `host:port`, `*.domain` or
+/// `*.domain:port`. Entries without a port allow every port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HostPattern {
+    host: String,
+    include_subdomains: bool,
+    port: Option<u16>,
+}
+
+impl HostPattern {
+    fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim();
+        let (host, port) = split_host_port(entry)?;
+        let host = normalize_host(host);
+        let (host, include_subdomains) = match host.strip_prefix("*.") {
+            Some(domain) => (domain.to_string(), true),
+            None => (host, false),
+        };
+        if host.is_empty() || host.contains('*') {
+            return None;
+        }
+        Some(Self {
+            host,
+            include_subdomains,
+            port,
+        })
+    }
+
+    fn matches(&self, host: &str, port: u16) -> bool {
+        if self.port.is_some_and(|allowed| allowed != port) {
+            return false;
+        }
+        host == self.host
+            || (self.include_subdomains
+                && host
+                    .strip_suffix(self.host.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.')))
+    }
+}
+
+/// Hosts a sandboxed command may reach through the proxy.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NetworkAllowlist {
+    patterns: Vec<HostPattern>,
+}
+
+impl NetworkAllowlist {
+    pub(crate) fn new(entries: &[String]) -> Self {
+        let patterns = entries
+            .iter()
+            .filter_map(|entry| {
+                let pattern = HostPattern::parse(entry);
+                if pattern.is_none() {
+                    warn!("ignoring invalid network_allowlist entry `{entry}`");
+                }
+                pattern
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    pub(crate) fn allows(&self, host: &str, port: u16) -> bool {
+        let host = normalize_host(host);
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches(&host, port))
+    }
+}
+
+/// A proxy listener reserved for a single sandboxed command. Binding happens
+/// up front so the sandbox can be told which port to allow; connections are
+/// only served once [`NetworkProxy::start`] is called.
+#[derive(Debug, Clone)]
+pub struct NetworkProxy {
+    listener: Arc<StdTcpListener>,
+    addr: SocketAddr,
+    allowlist: NetworkAllowlist,
+}
+
+impl NetworkProxy {
+    pub(crate) fn bind(allowlist: &[String]) -> io::Result<Self> {
+        let listener = StdTcpListener::bind(("127.0.0.1", 0))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        Ok(Self {
+            listener: Arc::new(listener),
+            addr,
+            allowlist: NetworkAllowlist::new(allowlist),
+        })
+    }
+
+    pub(crate) fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Start serving connections. The proxy stops when the returned handle is
+    /// dropped. Denied requests are reported on `stdout_stream` when present.
+    pub(crate) fn start(
+        &self,
+        stdout_stream: Option<StdoutStream>,
+    ) -> io::Result<RunningNetworkProxy> {
+        let listener = TcpListener::from_std(self.listener.try_clone()?)?;
+        let state = Arc::new(ProxyState {
+            allowlist: self.allowlist.clone(),
+            stdout_stream,
+            reported: Mutex::new(HashSet::new()),
+        });
+        let task = tokio::spawn(async move {
+            let mut connections = JoinSet::new();
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            let state = Arc::clone(&state);
+                            connections.spawn(async move {
+                                if let Err(err) = handle_connection(stream, &state).await {
+                                    debug!("network proxy connection failed: {err}");
+                                }
+                            });
+                        }
+                        Err(err) => {
+                            warn!("network proxy stopped accepting connections: {err}");
+                            break;
+                        }
+                    },
+                    Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                }
+            }
+        });
+        Ok(RunningNetworkProxy { task })
+    }
+}
+
+/// Keeps the proxy serving until dropped.
+pub(crate) struct RunningNetworkProxy {
+    task: JoinHandle<()>,
+}
+
+impl Drop for RunningNetworkProxy {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct ProxyState {
+    allowlist: NetworkAllowlist,
+    stdout_stream: Option<StdoutStream>,
+    reported: Mutex<HashSet<(String, u16)>>,
+}
+
+impl ProxyState {
+    async fn report_denied(&self, host: &str, port: u16) {
+        let first_time = self
+            .reported
+            .lock()
+            .map(|mut reported| reported.insert((host.to_string(), port)))
+            .unwrap_or(true);
+        if !first_time {
+            return;
+        }
+        info!("network proxy denied connection to {host}:{port}");
+        if let Some(stream) = &self.stdout_stream {
+            let event = Event {
+                id: stream.sub_id.clone(),
+                msg: EventMsg::NetworkRequestDenied(NetworkRequestDeniedEvent {
+                    call_id: stream.call_id.clone(),
+                    host: host.to_string(),
+                    port,
+                }),
+            };
+            let _ = stream.tx_event.send(event).await;
+            stream
+                .audit
+                .record(AuditEvent::NetworkDenied {
+                    call_id: stream.call_id.clone(),
+                    host: host.to_string(),
+                    port,
+                })
+                .await;
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    /// Request head to send upstream, or `None` for a `CONNECT` tunnel.
+    forward_head: Option<Vec<u8>>,
+}
+
+async fn handle_connection(mut client: TcpStream, state: &ProxyState) -> io::Result<()> {
+    let (head, body) = read_request_head(&mut client).await?;
+    let Some(request) = parse_request(&head) else {
+        client
+            .write_all(b"HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n")
+            .await?;
+        return Ok(());
+    };
+
+    if !state.allowlist.allows(&request.host, request.port) {
+        state.report_denied(&request.host, request.port).await;
+        client
+            .write_all(
+                b"HTTP/1.1 403 Forbidden\r\nConnection: close\r\nContent-Length: 0\r\n\
+                  X-Codex-Denied: host is not in network_allowlist\r\n\r\n",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let mut upstream = match TcpStream::connect((request.host.as_str(), request.port)).await {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            client
+                .write_all(b"HTTP/1.1 502 Bad Gateway\r\nConnection: close\r\n\r\n")
+                .await?;
+            return Err(err);
+        }
+    };
+
+    match request.forward_head {
+        None => {
+            client
+                .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+                .await?;
+        }
+        Some(forward_head) => upstream.write_all(&forward_head).await?,
+    }
+    upstream.write_all(&body).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+/// Read up to the end of the request head. Returns the head (including the
+/// terminating blank line) and any bytes that followed it.
+async fn read_request_head(stream: &mut TcpStream) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before request head",
+            ));
+        }
+        buf.extend_from_slice(&chunk[..read]);
+        if let Some(end) = find_head_end(&buf) {
+            let body = buf.split_off(end);
+            return Ok((buf, body));
+        }
+        if buf.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+    }
+}
+
+fn find_head_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|pos| pos + 4)
+}
+
+fn parse_request(head: &[u8]) -> Option<ProxyRequest> {
+    let head = std::str::from_utf8(head).ok()?;
+    let (request_line, rest) = head.split_once("\r\n")?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    let version = parts.next()?;
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target)?;
+        return Some(ProxyRequest {
+            host: normalize_host(host),
+            port: port?,
+            forward_head: None,
+        });
+    }
+
+    // Plain HTTP requests arrive in absolute form: `GET http://host/path`.
+    let without_scheme = target
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("http://"))
+        .map(|_| &target[7..])?;
+    let (authority, path) = match without_scheme.find('/') {
+        Some(idx) => without_scheme.split_at(idx),
+        None => (without_scheme, "/"),
+    };
+    let (host, port) = split_host_port(authority)?;
+    let forward_head = format!("{method} {path} {version}\r\n{rest}").into_bytes();
+    Some(ProxyRequest {
+        host: normalize_host(host),
+        port: port.unwrap_or(80),
+        forward_head: Some(forward_head),
+    })
+}
+
+/// Split `host[:port]`, accepting bracketed IPv6 literals.
+fn split_host_port(authority: &str) -> Option<(&str, Option<u16>)> {
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        return match after.strip_prefix(':') {
+            Some(port) => Some((host, Some(port.parse().ok()?))),
+            None if after.is_empty() => Some((host, None)),
+            None => None,
+        };
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => Some((host, Some(port.parse().ok()?))),
+        Some(_) => Some((authority, None)),
+        None => Some((authority, None)),
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditLog;
+    use pretty_assertions::assert_eq;
+
+    fn allowlist(entries: &[&str]) -> NetworkAllowlist {
+        let entries: Vec<String> = entries.iter().map(ToString::to_string).collect();
+        NetworkAllowlist::new(&entries)
+    }
+
+    #[test]
+    fn allowlist_matches_hosts_ports_and_subdomains() {
+        let allowlist = allowlist(&["crates.io", "*.crates.io:443", "registry.internal:8443"]);
+
+        assert!(allowlist.allows("crates.io", 80));
+        assert!(allowlist.allows("Crates.IO.", 443));
+        assert!(allowlist.allows("static.crates.io", 443));
+        assert!(!allowlist.allows("static.crates.io", 80));
+        assert!(!allowlist.allows("evilcrates.io", 443));
+        assert!(allowlist.allows("registry.internal", 8443));
+        assert!(!allowlist.allows("registry.internal", 443));
+        assert!(!allowlist.allows("example.com", 443));
+    }
+
+    #[test]
+    fn parses_connect_and_absolute_form_requests() {
+        assert_eq!(
+            parse_request(b"CONNECT static.crates.io:443 HTTP/1.1\r\nHost: x\r\n\r\n"),
+            Some(ProxyRequest {
+                host: "static.crates.io".to_string(),
+                port: 443,
+                forward_head: None,
+            })
+        );
+        assert_eq!(
+            parse_request(b"GET http://Example.com/a/b?c HTTP/1.1\r\nHost: example.com\r\n\r\n"),
+            Some(ProxyRequest {
+                host: "example.com".to_string(),
+                port: 80,
+                forward_head: Some(b"GET /a/b?c HTTP/1.1\r\nHost: example.com\r\n\r\n".to_vec()),
+            })
+        );
+        assert_eq!(parse_request(b"CONNECT crates.io HTTP/1.1\r\n\r\n"), None);
+        assert_eq!(parse_request(b"GET /relative HTTP/1.1\r\n\r\n"), None);
+    }
+
+    #[tokio::test]
+    async fn proxy_tunnels_allowed_hosts_and_reports_denied_ones() {
+        let upstream = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let upstream_port = upstream.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = upstream.accept().await.unwrap();
+            let mut buf = [0u8; 4];
+            stream.read_exact(&mut buf).await.unwrap();
+            stream.write_all(&buf).await.unwrap();
+        });
+
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let proxy = NetworkProxy::bind(&[format!("127.0.0.1:{upstream_port}")]).unwrap();
+        let _running = proxy
+            .start(Some(StdoutStream {
+                sub_id: "sub".to_string(),
+                call_id: "call".to_string(),
+                tx_event,
+                audit: AuditLog::default(),
+            }))
+            .unwrap();
+
+        let mut client = TcpStream::connect(proxy.addr()).await.unwrap();
+        client
+            .write_all(format!("CONNECT 127.0.0.1:{upstream_port} HTTP/1.1\r\n\r\nping").as_bytes())
+            .await
+            .unwrap();
+        let mut response = vec![0u8; "HTTP/1.1 200 Connection Established\r\n\r\nping".len()];
+        client.read_exact(&mut response).await.unwrap();
+        assert_eq!(
+            String::from_utf8(response).unwrap(),
+            "HTTP/1.1 200 Connection Established\r\n\r\nping"
+        );
+
+        let mut denied = TcpStream::connect(proxy.addr()).await.unwrap();
+        denied
+            .write_all(b"CONNECT example.com:443 HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        denied.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"), "{response}");
+
+        let event = rx_event.recv().await.unwrap();
+        assert_eq!(event.id, "sub");
+        let denied = match event.msg {
+            EventMsg::NetworkRequestDenied(denied) => denied,
+            other => panic!("unexpected event: {other:?}"),
+        };
+        assert_eq!(
+            (denied.call_id.as_str(), denied.host.as_str(), denied.port),
+            ("call", "example.com", 443)
+        );
+    }
+}
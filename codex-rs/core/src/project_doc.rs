@@ -14,6 +14,9 @@
 //! 3.  We do **not** walk past the Git root.
 
 use crate::config::Config;
+use crate::features::Feature;
+use crate::memory::MemoryStore;
+use crate::memory::format_memories_for_prompt;
 use dunce::canonicalize as normalize_path;
 use std::path::PathBuf;
 use tokio::io::AsyncReadExt;
@@ -28,10 +31,14 @@ pub const LOCAL_PROJECT_DOC_FILENAME: &str = "AGENTS.override.md";
 /// be concatenated with the following separator.
 const PROJECT_DOC_SEPARATOR: &str = "\n\n--- project-doc ---\n\n";
 
-/// Combines `Config::instructions` and `AGENTS.md` (if present) into a single
-/// string of instructions.
+/// Separates saved memories from the rest of the user instructions.
+const MEMORY_SEPARATOR: &str = "\n\n--- memories ---\n\n";
+
+/// Combines `Config::instructions`, `AGENTS.md` (if present) and, when the
+/// memory feature is enabled, saved memories into a single string of
+/// instructions.
 pub(crate) async fn get_user_instructions(config: &Config) -> Option<String> {
-    match read_project_docs(config).await {
+    let instructions = match read_project_docs(config).await {
         Ok(Some(project_doc)) => match &config.user_instructions {
             Some(original_instructions) => Some(format!(
                 "{original_instructions}{PROJECT_DOC_SEPARATOR}{project_doc}"
@@ -43,6 +50,27 @@ pub(crate) async fn get_user_instructions(config: &Config) -> Option<String> {
             error!("error trying to find project doc: {e:#}");
             config.user_instructions.clone()
         }
+    };
+
+    match read_memories(config) {
+        Some(memories) => match instructions {
+            Some(instructions) => Some(format!("{instructions}{MEMORY_SEPARATOR}{memories}")),
+            None => Some(memories),
+        },
+        None => instructions,
+    }
+}
+
+fn read_memories(config: &Config) -> Option<String> {
+    if !config.features.enabled(Feature::Memory) {
+        return None;
+    }
+    match MemoryStore::new(&config.codex_home, &config.cwd).list_all() {
+        Ok(memories) => format_memories_for_prompt(&memories),
+        Err(e) => {
+            error!("error trying to read memories: {e:#}");
+            None
+        }
     }
 }
 
@@ -447,4 +475,29 @@ mod tests {
                 .eq(DEFAULT_PROJECT_DOC_FILENAME)
         );
     }
+
+    /// With the memory feature enabled, saved memories follow the project doc.
+    #[tokio::test]
+    async fn appends_memories_when_enabled() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::write(tmp.path().join("AGENTS.md"), "proj doc").unwrap();
+        let codex_home = tempfile::tempdir().expect("tempdir");
+
+        let mut cfg = make_config(&tmp, 4096, None);
+        cfg.codex_home = codex_home.path().to_path_buf();
+        MemoryStore::new(&cfg.codex_home, &cfg.cwd)
+            .remember(crate::memory::MemoryScope::Project, "uses pnpm")
+            .expect("remember");
+
+        let res = get_user_instructions(&cfg).await.expect("doc expected");
+        assert_eq!(res, "proj doc");
+
+        cfg.features.enable(Feature::Memory);
+        let res = get_user_instructions(&cfg).await.expect("doc expected");
+        assert!(
+            res.starts_with(&format!("proj doc{MEMORY_SEPARATOR}")),
+            "{res}"
+        );
+        assert!(res.ends_with("- (project) uses pnpm"), "{res}");
+    }
 }
@@ -3,6 +3,8 @@ use crate::codex::TurnContext;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::SecretsRedactedEvent;
 use tracing::warn;
 
 /// Process streamed `ResponseItem`s from the model into the pair of:
@@ -95,6 +97,14 @@ pub(crate) async fn process_items(
         }
     }
 
+    redact_tool_outputs(
+        &mut items_to_record_in_conversation_history,
+        sess,
+        turn_context,
+    )
+    .await;
+    store_tool_outputs(&items_to_record_in_conversation_history, sess);
+
     // Only attempt to take the lock if there is something to record.
     if !items_to_record_in_conversation_history.is_empty() {
         sess.record_conversation_items(turn_context, &items_to_record_in_conversation_history)
@@ -102,3 +112,40 @@ pub(crate) async fn process_items(
     }
     (responses, items_to_record_in_conversation_history)
 }
+
+/// Keeps the full output of tool calls that did not already store one, so
+/// `read_tool_output` can page through it once the recorded copy is truncated.
+/// Runs after redaction so the store never holds an unmasked secret.
+fn store_tool_outputs(items: &[ResponseItem], sess: &Session) {
+    for item in items {
+        sess.services.tool_outputs.insert_item(item);
+    }
+}
+
+/// Masks secrets in tool outputs before they are recorded, and therefore
+/// before they are sent to the model, and reports the redactions.
+async fn redact_tool_outputs(
+    items: &mut [ResponseItem],
+    sess: &Session,
+    turn_context: &TurnContext,
+) {
+    let redactor = &sess.services.redactor;
+    for item in items {
+        let count = redactor.redact_item(item);
+        if count == 0 {
+            continue;
+        }
+        let (ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. }) = item
+        else {
+            continue;
+        };
+        let session_total = redactor.record(count);
+        let event = EventMsg::SecretsRedacted(SecretsRedactedEvent {
+            call_id: call_id.clone(),
+            count: u32::try_from(count).unwrap_or(u32::MAX),
+            session_total,
+        });
+        sess.send_event(turn_context, event).await;
+    }
+}
@@ -0,0 +1,213 @@
+//! Turns a [`ReviewTarget`] into the prompt for the review session.
+//!
+//! When git can compute the diff of the target, it is attached to the prompt
+//! so the reviewer always starts from the same changes. Otherwise the prompt
+//! tells the reviewer how to find them.
+
+use std::path::Path;
+
+use codex_app_server_protocol::GitSha;
+use codex_protocol::protocol::ReviewTarget;
+
+use crate::git_info::commit_diff;
+use crate::git_info::diff_against_sha;
+use crate::git_info::merge_base_with_head;
+use crate::git_info::paths_diff;
+use crate::git_info::range_diff;
+
+/// Larger diffs are truncated; the reviewer can still read the rest with git.
+const MAX_DIFF_BYTES: usize = 256 * 1024;
+
+pub(crate) async fn review_prompt(target: &ReviewTarget, cwd: &Path) -> String {
+    let (instructions, diff, fallback) = match target {
+        ReviewTarget::UncommittedChanges => (
+            "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
+            diff_against_sha(cwd, &GitSha::new("HEAD")).await,
+            "Use `git status` and `git diff HEAD` to see them.".to_string(),
+        ),
+        ReviewTarget::BaseBranch { branch } => match merge_base_with_head(cwd, branch).await {
+            Some(sha) => (
+                format!(
+                    "Review the code changes against the base branch '{branch}'. The diff below is against {sha}, the merge base of HEAD and {branch}, so it shows what would be merged into {branch}. Provide prioritized, actionable findings.",
+                    sha = sha.0
+                ),
+                diff_against_sha(cwd, &sha).await,
+                format!("Run `git diff {}` to see them.", sha.0),
+            ),
+            None => (
+                format!(
+                    "Review the code changes against the base branch '{branch}'. Provide prioritized, actionable findings."
+                ),
+                None,
+                format!(
+                    "Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch."
+                ),
+            ),
+        },
+        ReviewTarget::Commit { sha, title } => {
+            let title = title
+                .as_ref()
+                .map(|title| format!(" (\"{title}\")"))
+                .unwrap_or_default();
+            (
+                format!(
+                    "Review the code changes introduced by commit {sha}{title}. Provide prioritized, actionable findings."
+                ),
+                commit_diff(cwd, sha).await,
+                format!("Run `git show {sha}` to see them."),
+            )
+        }
+        ReviewTarget::CommitRange { range } => (
+            format!(
+                "Review the code changes introduced by the commits in {range}. Provide prioritized, actionable findings."
+            ),
+            range_diff(cwd, range).await,
+            format!("Run `git log {range}` and `git diff {range}` to see them."),
+        ),
+        ReviewTarget::Files { paths } => {
+            let list = paths
+                .iter()
+                .map(|path| format!("- {}", path.display()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            (
+                format!(
+                    "Review the following files and provide prioritized, actionable findings:\n{list}"
+                ),
+                paths_diff(cwd, paths).await.filter(|diff| !diff.is_empty()),
+                "Read the files in full; they have no uncommitted changes that could be shown."
+                    .to_string(),
+            )
+        }
+        ReviewTarget::Custom { instructions } => return instructions.clone(),
+    };
+
+    match diff {
+        Some(diff) if diff.trim().is_empty() => {
+            format!("{instructions}\n\nThere are no changes to review.")
+        }
+        Some(diff) => format!("{instructions}\n\n{}", diff_block(&diff)),
+        None => format!("{instructions}\n\n{fallback}"),
+    }
+}
+
+fn diff_block(diff: &str) -> String {
+    if diff.len() <= MAX_DIFF_BYTES {
+        return format!("<diff>\n{diff}\n</diff>");
+    }
+    let mut end = MAX_DIFF_BYTES;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "<diff>\n{}\n</diff>\n\nThe diff was truncated after {MAX_DIFF_BYTES} bytes; use git to see the rest.",
+        &diff[..end]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_test_support::skip_if_sandbox;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(repo: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo)
+            .output()
+            .expect("git should run");
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn repo_with_commit() -> TempDir {
+        let dir = TempDir::new().expect("tempdir");
+        git(dir.path(), &["init", "-q"]);
+        std::fs::write(dir.path().join("lib.rs"), "fn one() {}\n").expect("write");
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "Add one"]);
+        dir
+    }
+
+    #[tokio::test]
+    async fn custom_instructions_are_used_verbatim() {
+        let dir = TempDir::new().expect("tempdir");
+        let target = ReviewTarget::Custom {
+            instructions: "Look for races".to_string(),
+        };
+        assert_eq!(review_prompt(&target, dir.path()).await, "Look for races");
+    }
+
+    #[tokio::test]
+    async fn uncommitted_changes_attach_the_diff() {
+        skip_if_sandbox!();
+        let dir = repo_with_commit();
+        std::fs::write(dir.path().join("lib.rs"), "fn two() {}\n").expect("write");
+        std::fs::write(dir.path().join("new.rs"), "fn three() {}\n").expect("write");
+
+        let prompt = review_prompt(&ReviewTarget::UncommittedChanges, dir.path()).await;
+        assert!(prompt.contains("<diff>"), "{prompt}");
+        assert!(prompt.contains("-fn one() {}"), "{prompt}");
+        assert!(prompt.contains("+fn two() {}"), "{prompt}");
+        assert!(prompt.contains("+fn three() {}"), "{prompt}");
+    }
+
+    #[tokio::test]
+    async fn commit_attaches_its_diff_and_title() {
+        skip_if_sandbox!();
+        let dir = repo_with_commit();
+        let sha = git(dir.path(), &["rev-parse", "HEAD"]);
+        let target = ReviewTarget::Commit {
+            sha: sha.clone(),
+            title: Some("Add one".to_string()),
+        };
+
+        let prompt = review_prompt(&target, dir.path()).await;
+        assert!(
+            prompt.starts_with(&format!(
+                "Review the code changes introduced by commit {sha} (\"Add one\")."
+            )),
+            "{prompt}"
+        );
+        assert!(prompt.contains("+fn one() {}"), "{prompt}");
+    }
+
+    #[tokio::test]
+    async fn unresolvable_targets_fall_back_to_git_instructions() {
+        skip_if_sandbox!();
+        let dir = repo_with_commit();
+        let target = ReviewTarget::Commit {
+            sha: "0000000".to_string(),
+            title: None,
+        };
+        let prompt = review_prompt(&target, dir.path()).await;
+        assert_eq!(
+            prompt,
+            "Review the code changes introduced by commit 0000000. Provide prioritized, actionable findings.\n\nRun `git show 0000000` to see them."
+        );
+
+        let files = ReviewTarget::Files {
+            paths: vec![PathBuf::from("lib.rs")],
+        };
+        let prompt = review_prompt(&files, dir.path()).await;
+        assert_eq!(
+            prompt,
+            "Review the following files and provide prioritized, actionable findings:\n- lib.rs\n\nRead the files in full; they have no uncommitted changes that could be shown."
+        );
+    }
+
+    #[test]
+    fn long_diffs_are_truncated_on_a_char_boundary() {
+        let diff = "é".repeat(MAX_DIFF_BYTES);
+        let block = diff_block(&diff);
+        assert!(block.ends_with("use git to see the rest."));
+        assert!(block.len() < diff.len());
+    }
+}
@@ -23,6 +23,10 @@ use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
+use super::rewind::apply_checkpoint_rewinds;
+use super::search::SearchHit;
+use super::search::SearchIndexWriter;
+use super::search::search_conversations;
 use crate::config::Config;
 use crate::default_client::originator;
 use crate::git_info::collect_git_info;
@@ -111,6 +115,17 @@ impl RolloutRecorder {
         .await
     }
 
+    /// Full-text search over the recorded conversations under the provided
+    /// Codex home directory.
+    pub async fn search_conversations(
+        codex_home: &Path,
+        query: &str,
+        allowed_sources: &[SessionSource],
+        limit: usize,
+    ) -> std::io::Result<Vec<SearchHit>> {
+        search_conversations(codex_home, query, allowed_sources, limit).await
+    }
+
     /// Attempt to create a new [`RolloutRecorder`]. If the sessions directory
     /// cannot be created or the rollout file cannot be opened we return the
     /// error so the caller can decide whether to disable persistence.
@@ -161,6 +176,22 @@ impl RolloutRecorder {
             ),
         };
 
+        // The search index is a convenience; failing to open it must not stop
+        // the session from being recorded.
+        let index = match &meta {
+            Some(meta) => {
+                SearchIndexWriter::create(&config.codex_home, meta.id, &rollout_path).await
+            }
+            None => SearchIndexWriter::resume(&config.codex_home, &rollout_path).await,
+        }
+        .inspect_err(|e| {
+            warn!(
+                "failed to open search index for {}: {e}",
+                rollout_path.display()
+            )
+        })
+        .ok();
+
         // Clone the cwd for the spawned task to collect git info asynchronously
         let cwd = config.cwd.clone();
 
@@ -172,7 +203,7 @@ impl RolloutRecorder {
         // Spawn a Tokio task that owns the file handle and performs async
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd));
+        tokio::task::spawn(rollout_writer(file, index, rx, meta, cwd));
 
         Ok(Self { tx, rollout_path })
     }
@@ -266,6 +297,7 @@ impl RolloutRecorder {
         let conversation_id = conversation_id
             .ok_or_else(|| IoError::other("failed to parse conversation ID from rollout file"))?;
 
+        let items = apply_checkpoint_rewinds(items);
         if items.is_empty() {
             return Ok(InitialHistory::New);
         }
@@ -348,11 +380,12 @@ fn create_log_file(
 
 async fn rollout_writer(
     file: tokio::fs::File,
+    index: Option<SearchIndexWriter>,
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
     cwd: std::path::PathBuf,
 ) -> std::io::Result<()> {
-    let mut writer = JsonlWriter { file };
+    let mut writer = JsonlWriter { file, index };
 
     // If we have a meta, collect git info asynchronously and write meta first
     if let Some(session_meta) = meta.take() {
@@ -397,6 +430,7 @@ async fn rollout_writer(
 
 struct JsonlWriter {
     file: tokio::fs::File,
+    index: Option<SearchIndexWriter>,
 }
 
 impl JsonlWriter {
@@ -412,7 +446,15 @@ impl JsonlWriter {
             timestamp,
             item: rollout_item,
         };
-        self.write_line(&line).await
+        self.write_line(&line).await?;
+
+        if let Some(index) = self.index.as_mut()
+            && let Err(e) = index.record(&line.item).await
+        {
+            warn!("failed to update search index, disabling it for this session: {e}");
+            self.index = None;
+        }
+        Ok(())
     }
     async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
         let mut json = serde_json::to_string(item)?;
@@ -0,0 +1,167 @@
+//! Turn-level checkpoints recorded in rollouts.
+//!
+//! Every user turn starts with a ghost snapshot of the working tree, recorded
+//! right after the turn's user message. Turns are numbered by the 0-based
+//! position of their user message, which matches how forks count turns.
+//! Rewinding to a turn appends a `CheckpointRestored` marker to the rollout;
+//! replaying the rollout drops that turn and everything after it.
+
+use codex_git::GhostCommit;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+
+/// Indices of the user turn messages in `items`, in rollout order.
+pub(crate) fn user_turn_positions(items: &[RolloutItem]) -> Vec<usize> {
+    items
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| match item {
+            RolloutItem::ResponseItem(item @ ResponseItem::Message { .. })
+                if matches!(
+                    crate::event_mapping::parse_turn_item(item),
+                    Some(TurnItem::UserMessage(_))
+                ) =>
+            {
+                Some(idx)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Ghost snapshot captured at the start of user turn `turn`, if one was recorded.
+pub(crate) fn checkpoint_for_turn(items: &[RolloutItem], turn: usize) -> Option<GhostCommit> {
+    let positions = user_turn_positions(items);
+    let start = *positions.get(turn)?;
+    let end = positions.get(turn + 1).copied().unwrap_or(items.len());
+    items[start..end].iter().find_map(|item| match item {
+        RolloutItem::ResponseItem(ResponseItem::GhostSnapshot { ghost_commit }) => {
+            Some(ghost_commit.clone())
+        }
+        _ => None,
+    })
+}
+
+/// Replay the `CheckpointRestored` markers in `items`, dropping each rewound
+/// turn and everything recorded after it up to the marker.
+pub(crate) fn apply_checkpoint_rewinds(items: Vec<RolloutItem>) -> Vec<RolloutItem> {
+    let mut rewound = Vec::with_capacity(items.len());
+    for item in items {
+        if let RolloutItem::EventMsg(EventMsg::CheckpointRestored(event)) = &item {
+            if let Some(cut) = user_turn_positions(&rewound).get(event.turn).copied() {
+                rewound.truncate(cut);
+            }
+            continue;
+        }
+        rewound.push(item);
+    }
+    rewound
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::protocol::CheckpointRestoredEvent;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        })
+    }
+
+    fn snapshot(id: &str) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::GhostSnapshot {
+            ghost_commit: GhostCommit::new(id.to_string(), None, Vec::new(), Vec::new()),
+        })
+    }
+
+    fn rewind(turn: usize) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::CheckpointRestored(CheckpointRestoredEvent {
+            turn,
+            commit_id: format!("ghost-{turn}"),
+        }))
+    }
+
+    fn texts(items: &[RolloutItem]) -> Vec<String> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                RolloutItem::ResponseItem(ResponseItem::Message { content, .. }) => {
+                    match content.first() {
+                        Some(ContentItem::InputText { text }) => Some(text.clone()),
+                        _ => None,
+                    }
+                }
+                RolloutItem::ResponseItem(ResponseItem::GhostSnapshot { ghost_commit }) => {
+                    Some(ghost_commit.id().to_string())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn checkpoint_for_turn_finds_the_snapshot_after_each_user_message() {
+        let items = vec![
+            message("user", "first"),
+            snapshot("ghost-0"),
+            message("assistant", "done"),
+            message("user", "second"),
+            message("assistant", "no snapshot this time"),
+        ];
+
+        assert_eq!(
+            checkpoint_for_turn(&items, 0).map(|commit| commit.id().to_string()),
+            Some("ghost-0".to_string())
+        );
+        assert!(checkpoint_for_turn(&items, 1).is_none());
+        assert!(checkpoint_for_turn(&items, 2).is_none());
+    }
+
+    #[test]
+    fn apply_checkpoint_rewinds_drops_rewound_turns() {
+        let items = vec![
+            message("user", "first"),
+            snapshot("ghost-0"),
+            message("user", "second"),
+            snapshot("ghost-1"),
+            message("user", "third"),
+            snapshot("ghost-2"),
+            rewind(1),
+            message("user", "second again"),
+            snapshot("ghost-1b"),
+        ];
+
+        let rewound = apply_checkpoint_rewinds(items);
+
+        assert_eq!(
+            texts(&rewound),
+            vec!["first", "ghost-0", "second again", "ghost-1b"]
+        );
+        assert_eq!(user_turn_positions(&rewound), vec![0, 2]);
+    }
+
+    #[test]
+    fn apply_checkpoint_rewinds_counts_turns_after_earlier_rewinds() {
+        let items = vec![
+            message("user", "first"),
+            message("user", "second"),
+            rewind(1),
+            message("user", "replacement"),
+            message("user", "later"),
+            rewind(1),
+        ];
+
+        let rewound = apply_checkpoint_rewinds(items);
+
+        assert_eq!(texts(&rewound), vec!["first"]);
+    }
+}
@@ -0,0 +1,702 @@
+//! Full-text search over recorded rollouts.
+//!
+//! Every rollout has a sidecar index under `~/.codex/search_index/<id>.jsonl`
+//! holding only the searchable text of the session: user and agent messages,
+//! commands the agent ran and the paths of files it patched. The
+//! [`RolloutRecorder`](super::RolloutRecorder) appends to the index as it
+//! writes the rollout. Rollouts recorded before the index existed, or whose
+//! index is older than the rollout itself, are (re)indexed the next time a
+//! search runs.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ConversationId;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionSource;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use super::SESSIONS_SUBDIR;
+use super::list::collect_dirs_desc;
+use super::list::collect_files;
+use super::list::parse_timestamp_uuid_from_filename;
+use crate::bash::extract_bash_command;
+use crate::protocol::EventMsg;
+
+pub const SEARCH_INDEX_SUBDIR: &str = "search_index";
+
+/// Longest snippet returned with a hit, in characters.
+const SNIPPET_MAX_CHARS: usize = 160;
+
+/// One line of a session's index file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum IndexRecord {
+    Session {
+        id: ConversationId,
+        rollout_path: PathBuf,
+        created_at: String,
+        cwd: PathBuf,
+        source: SessionSource,
+        #[serde(default)]
+        model_provider: Option<String>,
+    },
+    UserMessage {
+        text: String,
+    },
+    AgentMessage {
+        text: String,
+    },
+    Command {
+        text: String,
+    },
+    File {
+        path: String,
+    },
+}
+
+impl IndexRecord {
+    fn text(&self) -> Option<&str> {
+        match self {
+            IndexRecord::Session { .. } => None,
+            IndexRecord::UserMessage { text }
+            | IndexRecord::AgentMessage { text }
+            | IndexRecord::Command { text } => Some(text),
+            IndexRecord::File { path } => Some(path),
+        }
+    }
+
+    /// Matches in what the user said count for more than matches in what
+    /// the agent said or ran.
+    fn weight(&self) -> usize {
+        match self {
+            IndexRecord::Session { .. } => 0,
+            IndexRecord::UserMessage { .. } => 3,
+            IndexRecord::Command { .. } | IndexRecord::File { .. } => 2,
+            IndexRecord::AgentMessage { .. } => 1,
+        }
+    }
+}
+
+/// A session that matched a search query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub conversation_id: ConversationId,
+    /// Absolute path to the rollout file.
+    pub path: PathBuf,
+    /// RFC3339 timestamp string for when the session was created.
+    pub created_at: String,
+    pub cwd: PathBuf,
+    pub source: SessionSource,
+    pub model_provider: Option<String>,
+    /// The first user message of the session, if any.
+    pub preview: Option<String>,
+    /// The indexed text that best matches the query, shortened to fit on a line.
+    pub snippet: String,
+    pub score: usize,
+}
+
+/// Appends index records for a single rollout as it is being recorded.
+pub(crate) struct SearchIndexWriter {
+    file: tokio::fs::File,
+    rollout_path: PathBuf,
+}
+
+impl SearchIndexWriter {
+    /// Starts a fresh index for a new rollout.
+    pub(crate) async fn create(
+        codex_home: &Path,
+        conversation_id: ConversationId,
+        rollout_path: &Path,
+    ) -> io::Result<Self> {
+        let index_path = index_path(codex_home, &conversation_id.to_string());
+        if let Some(parent) = index_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&index_path)
+            .await?;
+        Ok(Self {
+            file,
+            rollout_path: rollout_path.to_path_buf(),
+        })
+    }
+
+    /// Re-indexes an existing rollout that is about to be resumed and keeps
+    /// the index open for the items that follow.
+    pub(crate) async fn resume(codex_home: &Path, rollout_path: &Path) -> io::Result<Self> {
+        let id = rollout_id(rollout_path)
+            .ok_or_else(|| io::Error::other("rollout file name has no conversation id"))?;
+        let index_path = index_path(codex_home, &id);
+        rebuild_index(rollout_path, &index_path).await?;
+        let file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&index_path)
+            .await?;
+        Ok(Self {
+            file,
+            rollout_path: rollout_path.to_path_buf(),
+        })
+    }
+
+    /// Records the searchable parts of `item`. Call this after `item` has
+    /// been written to the rollout so the index is never older than it.
+    pub(crate) async fn record(&mut self, item: &RolloutItem) -> io::Result<()> {
+        let records = index_records(item, &self.rollout_path);
+        if records.is_empty() {
+            return Ok(());
+        }
+        let mut buf = String::new();
+        for record in records {
+            buf.push_str(&serde_json::to_string(&record)?);
+            buf.push('\n');
+        }
+        self.file.write_all(buf.as_bytes()).await?;
+        self.file.flush().await
+    }
+}
+
+/// Searches every recorded session for `query`. A session matches when each
+/// whitespace-separated term of the query appears somewhere in it, ignoring
+/// case. Only sessions recorded from one of `allowed_sources` are returned.
+/// Hits are ordered by relevance, then newest first.
+pub async fn search_conversations(
+    codex_home: &Path,
+    query: &str,
+    allowed_sources: &[SessionSource],
+    limit: usize,
+) -> io::Result<Vec<SearchHit>> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    refresh_index(codex_home).await?;
+
+    let index_dir = codex_home.join(SEARCH_INDEX_SUBDIR);
+    let index_files = match collect_files(&index_dir, |name, path| {
+        name.ends_with(".jsonl").then(|| path.to_path_buf())
+    })
+    .await
+    {
+        Ok(files) => files,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut hits = Vec::new();
+    for index_file in index_files {
+        let records = match read_index(&index_file).await {
+            Ok(records) => records,
+            Err(e) => {
+                warn!("failed to read search index {}: {e}", index_file.display());
+                continue;
+            }
+        };
+        if let Some(hit) = score_session(&records, &terms)
+            && allowed_sources.contains(&hit.source)
+            && tokio::fs::try_exists(&hit.path).await.unwrap_or(false)
+        {
+            hits.push(hit);
+        }
+    }
+
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.created_at.cmp(&a.created_at))
+    });
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+/// Indexes every rollout under `~/.codex/sessions` whose index is missing or
+/// older than the rollout.
+async fn refresh_index(codex_home: &Path) -> io::Result<()> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    if !tokio::fs::try_exists(&root).await.unwrap_or(false) {
+        return Ok(());
+    }
+
+    for (_year, year_path) in collect_dirs_desc(&root, |s| s.parse::<u16>().ok()).await? {
+        for (_month, month_path) in collect_dirs_desc(&year_path, |s| s.parse::<u8>().ok()).await? {
+            for (_day, day_path) in collect_dirs_desc(&month_path, |s| s.parse::<u8>().ok()).await?
+            {
+                let rollouts = collect_files(&day_path, |name, path| {
+                    let id = rollout_id(Path::new(name))?;
+                    Some((id, path.to_path_buf()))
+                })
+                .await?;
+                for (id, rollout_path) in rollouts {
+                    let index_path = index_path(codex_home, &id);
+                    if is_index_stale(&rollout_path, &index_path).await
+                        && let Err(e) = rebuild_index(&rollout_path, &index_path).await
+                    {
+                        warn!("failed to index rollout {}: {e}", rollout_path.display());
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn is_index_stale(rollout_path: &Path, index_path: &Path) -> bool {
+    let modified = |path: &Path| {
+        let path = path.to_path_buf();
+        async move { tokio::fs::metadata(path).await.and_then(|m| m.modified()) }
+    };
+    match (modified(rollout_path).await, modified(index_path).await) {
+        (Ok(rollout), Ok(index)) => index < rollout,
+        _ => true,
+    }
+}
+
+/// Rewrites the index for `rollout_path` from scratch. The file is rewritten
+/// in place so a recorder that still has it open keeps appending to it.
+async fn rebuild_index(rollout_path: &Path, index_path: &Path) -> io::Result<()> {
+    let text = tokio::fs::read_to_string(rollout_path).await?;
+    let mut buf = String::new();
+    for line in text.lines() {
+        let Ok(rollout_line) = serde_json::from_str::<RolloutLine>(line) else {
+            continue;
+        };
+        for record in index_records(&rollout_line.item, rollout_path) {
+            buf.push_str(&serde_json::to_string(&record)?);
+            buf.push('\n');
+        }
+    }
+    if let Some(parent) = index_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(index_path, buf).await
+}
+
+async fn read_index(index_path: &Path) -> io::Result<Vec<IndexRecord>> {
+    let text = tokio::fs::read_to_string(index_path).await?;
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str::<IndexRecord>(line).ok())
+        .collect())
+}
+
+fn score_session(records: &[IndexRecord], terms: &[String]) -> Option<SearchHit> {
+    let mut session = None;
+    let mut preview = None;
+    let mut matched = vec![false; terms.len()];
+    let mut score = 0;
+    // (distinct terms matched, weight, text) of the best record so far.
+    let mut best: Option<(usize, usize, &str)> = None;
+
+    for record in records {
+        if let IndexRecord::Session { .. } = record {
+            session.get_or_insert(record);
+            continue;
+        }
+        if let IndexRecord::UserMessage { text } = record {
+            preview.get_or_insert(text.as_str());
+        }
+        let Some(text) = record.text() else {
+            continue;
+        };
+        let lowered = text.to_lowercase();
+        let mut distinct = 0;
+        for (term, seen) in terms.iter().zip(matched.iter_mut()) {
+            if lowered.contains(term.as_str()) {
+                *seen = true;
+                distinct += 1;
+            }
+        }
+        if distinct == 0 {
+            continue;
+        }
+        score += distinct * record.weight();
+        let candidate = (distinct, record.weight(), text);
+        if best.is_none_or(|(d, w, _)| (distinct, record.weight()) > (d, w)) {
+            best = Some(candidate);
+        }
+    }
+
+    if !matched.iter().all(|seen| *seen) {
+        return None;
+    }
+    let Some(IndexRecord::Session {
+        id,
+        rollout_path,
+        created_at,
+        cwd,
+        source,
+        model_provider,
+    }) = session
+    else {
+        return None;
+    };
+    let (_, _, best_text) = best?;
+
+    Some(SearchHit {
+        conversation_id: *id,
+        path: rollout_path.clone(),
+        created_at: created_at.clone(),
+        cwd: cwd.clone(),
+        source: source.clone(),
+        model_provider: model_provider.clone(),
+        preview: preview.map(str::to_string),
+        snippet: snippet(best_text, &terms[0]),
+        score,
+    })
+}
+
+/// Single-line excerpt of `text` around the first occurrence of `term`.
+fn snippet(text: &str, term: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let chars: Vec<char> = flat.chars().collect();
+    if chars.len() <= SNIPPET_MAX_CHARS {
+        return flat;
+    }
+
+    let lowered: Vec<char> = flat.to_lowercase().chars().collect();
+    let term: Vec<char> = term.chars().collect();
+    let hit = lowered
+        .windows(term.len().max(1))
+        .position(|window| window == term.as_slice())
+        .unwrap_or(0);
+    let start = hit.saturating_sub(SNIPPET_MAX_CHARS / 4);
+    let end = (start + SNIPPET_MAX_CHARS).min(chars.len());
+    let start = end.saturating_sub(SNIPPET_MAX_CHARS);
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.extend(&chars[start..end]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+fn index_records(item: &RolloutItem, rollout_path: &Path) -> Vec<IndexRecord> {
+    match item {
+        RolloutItem::SessionMeta(meta_line) => vec![IndexRecord::Session {
+            id: meta_line.meta.id,
+            rollout_path: rollout_path.to_path_buf(),
+            created_at: meta_line.meta.timestamp.clone(),
+            cwd: meta_line.meta.cwd.clone(),
+            source: meta_line.meta.source.clone(),
+            model_provider: meta_line.meta.model_provider.clone(),
+        }],
+        RolloutItem::EventMsg(EventMsg::UserMessage(ev)) => vec![IndexRecord::UserMessage {
+            text: ev.message.clone(),
+        }],
+        RolloutItem::EventMsg(EventMsg::AgentMessage(ev)) => vec![IndexRecord::AgentMessage {
+            text: ev.message.clone(),
+        }],
+        RolloutItem::ResponseItem(item) => response_item_records(item),
+        RolloutItem::EventMsg(_) | RolloutItem::Compacted(_) | RolloutItem::TurnContext(_) => {
+            Vec::new()
+        }
+    }
+}
+
+fn response_item_records(item: &ResponseItem) -> Vec<IndexRecord> {
+    match item {
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => vec![IndexRecord::Command {
+            text: join_command(&exec.command),
+        }],
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => {
+            let Ok(args) = serde_json::from_str::<serde_json::Value>(arguments) else {
+                return Vec::new();
+            };
+            match name.as_str() {
+                "apply_patch" => args
+                    .get("input")
+                    .and_then(serde_json::Value::as_str)
+                    .map(patch_file_records)
+                    .unwrap_or_default(),
+                _ => command_from_tool_args(&args)
+                    .map(|text| vec![IndexRecord::Command { text }])
+                    .unwrap_or_default(),
+            }
+        }
+        ResponseItem::CustomToolCall { name, input, .. } if name == "apply_patch" => {
+            patch_file_records(input)
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The command of a shell-like tool call: `command` as an argv array or a
+/// string (`shell`, `shell_command`), or `cmd` (`exec_command`).
+pub fn command_from_tool_args(args: &serde_json::Value) -> Option<String> {
+    match args.get("command").or_else(|| args.get("cmd"))? {
+        serde_json::Value::String(command) => Some(command.clone()),
+        serde_json::Value::Array(argv) => {
+            let argv: Vec<String> = argv
+                .iter()
+                .filter_map(|arg| arg.as_str().map(str::to_string))
+                .collect();
+            Some(join_command(&argv))
+        }
+        _ => None,
+    }
+}
+
+/// Joins an argv for display, showing only the script of `bash -lc <script>`.
+fn join_command(argv: &[String]) -> String {
+    if let Some((_, script)) = extract_bash_command(argv) {
+        return script.to_string();
+    }
+    shlex::try_join(argv.iter().map(String::as_str)).unwrap_or_else(|_| argv.join(" "))
+}
+
+fn patch_file_records(patch: &str) -> Vec<IndexRecord> {
+    const HEADERS: [&str; 4] = [
+        "*** Add File: ",
+        "*** Update File: ",
+        "*** Delete File: ",
+        "*** Move to: ",
+    ];
+    patch
+        .lines()
+        .filter_map(|line| HEADERS.iter().find_map(|header| line.strip_prefix(header)))
+        .map(|path| IndexRecord::File {
+            path: path.trim().to_string(),
+        })
+        .collect()
+}
+
+fn rollout_id(rollout_path: &Path) -> Option<String> {
+    let name = rollout_path.file_name()?.to_str()?;
+    parse_timestamp_uuid_from_filename(name).map(|(_, id)| id.to_string())
+}
+
+fn index_path(codex_home: &Path, conversation_id: &str) -> PathBuf {
+    codex_home
+        .join(SEARCH_INDEX_SUBDIR)
+        .join(format!("{conversation_id}.jsonl"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rollout::INTERACTIVE_SESSION_SOURCES;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const UUID_A: &str = "00000000-0000-0000-0000-00000000000a";
+    const UUID_B: &str = "00000000-0000-0000-0000-00000000000b";
+
+    /// Writes a rollout recorded on 2025-01-02 at `time` (`hh-mm-ss`).
+    fn write_rollout(
+        codex_home: &Path,
+        time: &str,
+        uuid: &str,
+        items: Vec<RolloutItem>,
+    ) -> PathBuf {
+        let dir = codex_home.join("sessions/2025/01/02");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("rollout-2025-01-02T{time}-{uuid}.jsonl"));
+        let meta = RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                id: ConversationId::from_string(uuid).unwrap(),
+                timestamp: format!("2025-01-02T{}Z", time.replace('-', ":")),
+                cwd: PathBuf::from("/repo"),
+                source: SessionSource::Cli,
+                ..Default::default()
+            },
+            git: None,
+        });
+        let lines: Vec<String> = std::iter::once(meta)
+            .chain(items)
+            .map(|item| {
+                serde_json::to_string(&RolloutLine {
+                    timestamp: "2025-01-02T00:00:00.000Z".to_string(),
+                    item,
+                })
+                .unwrap()
+            })
+            .collect();
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+        path
+    }
+
+    fn user(text: &str) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+            message: text.to_string(),
+            images: None,
+        }))
+    }
+
+    fn agent(text: &str) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+            message: text.to_string(),
+        }))
+    }
+
+    fn function_call(name: &str, arguments: serde_json::Value) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            call_id: "call".to_string(),
+        })
+    }
+
+    #[test]
+    fn extracts_commands_and_patched_files() {
+        let path = Path::new("/rollout.jsonl");
+        assert_eq!(
+            index_records(
+                &function_call(
+                    "shell",
+                    serde_json::json!({"command": ["cargo", "test", "-p", "my crate"]})
+                ),
+                path
+            ),
+            vec![IndexRecord::Command {
+                text: "cargo test -p 'my crate'".to_string()
+            }]
+        );
+        assert_eq!(
+            index_records(
+                &RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+                    id: None,
+                    status: None,
+                    call_id: "call".to_string(),
+                    name: "apply_patch".to_string(),
+                    input: "*** Begin Patch\n*** Update File: src/db/migrate.rs\n@@\n-a\n+b\n*** Add File: tests/flaky.rs\n+x\n*** End Patch".to_string(),
+                }),
+                path
+            ),
+            vec![
+                IndexRecord::File {
+                    path: "src/db/migrate.rs".to_string()
+                },
+                IndexRecord::File {
+                    path: "tests/flaky.rs".to_string()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_indexes_existing_rollouts_and_requires_all_terms() {
+        let home = TempDir::new().unwrap();
+        let flaky = write_rollout(
+            home.path(),
+            "10-00-00",
+            UUID_A,
+            vec![
+                user("the migration test keeps failing on CI"),
+                function_call(
+                    "shell",
+                    serde_json::json!({"command": ["cargo", "test", "migration"]}),
+                ),
+                agent("Fixed the flaky migration test by awaiting the pool."),
+            ],
+        );
+        write_rollout(
+            home.path(),
+            "11-00-00",
+            UUID_B,
+            vec![user("add a migration for users")],
+        );
+
+        let hits = search_conversations(
+            home.path(),
+            "flaky MIGRATION",
+            INTERACTIVE_SESSION_SOURCES,
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!(hit.path, flaky);
+        assert_eq!(
+            hit.preview.as_deref(),
+            Some("the migration test keeps failing on CI")
+        );
+        assert_eq!(
+            hit.snippet,
+            "Fixed the flaky migration test by awaiting the pool."
+        );
+        assert!(
+            home.path()
+                .join(SEARCH_INDEX_SUBDIR)
+                .join(format!("{UUID_A}.jsonl"))
+                .exists()
+        );
+
+        let hits = search_conversations(home.path(), "migration", INTERACTIVE_SESSION_SOURCES, 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].path, flaky, "more matches rank first");
+
+        assert!(
+            search_conversations(home.path(), "nonexistent", INTERACTIVE_SESSION_SOURCES, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            search_conversations(home.path(), "migration", &[SessionSource::Exec], 10)
+                .await
+                .unwrap()
+                .is_empty(),
+            "sessions from other sources are filtered out"
+        );
+    }
+
+    #[tokio::test]
+    async fn writer_appends_to_index() {
+        let home = TempDir::new().unwrap();
+        let rollout = write_rollout(home.path(), "10-00-00", UUID_A, Vec::new());
+
+        let mut writer = SearchIndexWriter::resume(home.path(), &rollout)
+            .await
+            .unwrap();
+        writer
+            .record(&user("rename the config loader"))
+            .await
+            .unwrap();
+
+        let records = read_index(&index_path(home.path(), UUID_A)).await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[1],
+            IndexRecord::UserMessage {
+                text: "rename the config loader".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn snippet_centers_long_text_on_the_match() {
+        let text = format!("{} needle {}", "a ".repeat(200), "b ".repeat(200));
+        let out = snippet(&text, "needle");
+        assert!(out.starts_with('…') && out.ends_with('…'), "{out}");
+        assert!(out.contains("needle"));
+        assert!(out.chars().count() <= SNIPPET_MAX_CHARS + 2);
+    }
+}
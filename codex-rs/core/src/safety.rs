@@ -62,6 +62,21 @@ pub fn assess_patch_safety(
         }
     }
 
+    // Protected subpaths always go through the user: with `OnFailure` the
+    // patch would otherwise only be escalated after the sandbox rejects it.
+    if let Some(path) = find_protected_path(action, sandbox_policy, cwd) {
+        return if policy == AskForApproval::Never {
+            SafetyCheck::Reject {
+                reason: format!(
+                    "`{}` is a protected path; rejected by user approval settings",
+                    path.display()
+                ),
+            }
+        } else {
+            SafetyCheck::AskUser
+        };
+    }
+
     // Even though the patch appears to be constrained to writable paths, it is
     // possible that paths in the patch are hard links to files outside the
     // writable roots, so we should still run `apply_patch` in a sandbox in that case.
@@ -130,22 +145,6 @@ fn is_write_patch_constrained_to_writable_paths(
         SandboxPolicy::WorkspaceWrite { .. } => sandbox_policy.get_writable_roots_with_cwd(cwd),
     };
 
-    // Normalize a path by removing `.` and resolving `..` without touching the
-    // filesystem (works even if the file does not exist).
-    fn normalize(path: &Path) -> Option<PathBuf> {
-        let mut out = PathBuf::new();
-        for comp in path.components() {
-            match comp {
-                Component::ParentDir => {
-                    out.pop();
-                }
-                Component::CurDir => { /* skip */ }
-                other => out.push(other.as_os_str()),
-            }
-        }
-        Some(out)
-    }
-
     // Determine whether `path` is inside **any** writable root. Both `path`
     // and roots are converted to absolute, normalized forms before the
     // prefix check.
@@ -165,27 +164,55 @@ fn is_write_patch_constrained_to_writable_paths(
             .any(|writable_root| writable_root.is_path_writable(&abs))
     };
 
-    for (path, change) in action.changes() {
-        match change {
-            ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => {
-                if !is_path_writable(path) {
-                    return false;
-                }
-            }
-            ApplyPatchFileChange::Update { move_path, .. } => {
-                if !is_path_writable(path) {
-                    return false;
-                }
-                if let Some(dest) = move_path
-                    && !is_path_writable(dest)
-                {
-                    return false;
-                }
+    patch_paths(action).all(is_path_writable)
+}
+
+/// Returns the first path touched by `action` that lies in a read-only
+/// subpath of a writable root, such as `.git` or a configured
+/// `read_only_subpaths` entry.
+fn find_protected_path(
+    action: &ApplyPatchAction,
+    sandbox_policy: &SandboxPolicy,
+    cwd: &Path,
+) -> Option<PathBuf> {
+    let writable_roots = sandbox_policy.get_writable_roots_with_cwd(cwd);
+    patch_paths(action)
+        .filter_map(|path| normalize(&cwd.join(path)))
+        .find(|path| {
+            writable_roots.iter().any(|writable_root| {
+                writable_root
+                    .read_only_subpaths
+                    .iter()
+                    .any(|subpath| path.starts_with(subpath))
+            })
+        })
+}
+
+/// Every path a patch writes to, including the destination of moves.
+fn patch_paths(action: &ApplyPatchAction) -> impl Iterator<Item = &PathBuf> {
+    action.changes().iter().flat_map(|(path, change)| {
+        let move_path = match change {
+            ApplyPatchFileChange::Update { move_path, .. } => move_path.as_ref(),
+            ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => None,
+        };
+        std::iter::once(path).chain(move_path)
+    })
+}
+
+// Normalize a path by removing `.` and resolving `..` without touching the
+// filesystem (works even if the file does not exist).
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::ParentDir => {
+                out.pop();
             }
+            Component::CurDir => { /* skip */ }
+            other => out.push(other.as_os_str()),
         }
     }
-
-    true
+    Some(out)
 }
 
 #[cfg(test)]
@@ -212,8 +239,11 @@ mod tests {
         let policy_workspace_only = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            network_allowlist: vec![],
+            read_only_subpaths: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            container: false,
         };
 
         assert!(is_write_patch_constrained_to_writable_paths(
@@ -233,8 +263,11 @@ mod tests {
         let policy_with_parent = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![parent],
             network_access: false,
+            network_allowlist: vec![],
+            read_only_subpaths: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            container: false,
         };
         assert!(is_write_patch_constrained_to_writable_paths(
             &add_outside,
@@ -242,4 +275,36 @@ mod tests {
             &cwd,
         ));
     }
+
+    #[test]
+    fn patches_into_protected_subpaths_need_approval() {
+        let tmp = TempDir::new().unwrap();
+        let cwd = tmp.path().to_path_buf();
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            network_allowlist: vec![],
+            read_only_subpaths: vec![PathBuf::from("migrations")],
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            container: false,
+        };
+
+        let protected = ApplyPatchAction::new_add_for_test(
+            &cwd.join("migrations").join("0001_init.sql"),
+            "".to_string(),
+        );
+        assert_eq!(
+            assess_patch_safety(&protected, AskForApproval::OnFailure, &policy, &cwd),
+            SafetyCheck::AskUser
+        );
+        assert!(matches!(
+            assess_patch_safety(&protected, AskForApproval::Never, &policy, &cwd),
+            SafetyCheck::Reject { .. }
+        ));
+
+        let unprotected =
+            ApplyPatchAction::new_add_for_test(&cwd.join("src").join("lib.rs"), "".to_string());
+        assert_eq!(find_protected_path(&unprotected, &policy, &cwd), None);
+    }
 }
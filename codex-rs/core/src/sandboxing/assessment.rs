@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -6,10 +7,14 @@ use std::time::Instant;
 
 use crate::AuthManager;
 use crate::ModelProviderInfo;
+use crate::budget::BudgetTracker;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::config::Config;
+use crate::model_family::derive_default_model_family;
+use crate::model_family::find_family_for_model;
+use crate::openai_model_info::get_model_info;
 use crate::protocol::SandboxPolicy;
 use askama::Template;
 use codex_otel::otel_event_manager::OtelEventManager;
@@ -20,11 +25,15 @@ use codex_protocol::protocol::SandboxCommandAssessment;
 use codex_protocol::protocol::SessionSource;
 use futures::StreamExt;
 use serde_json::json;
+use tokio::sync::Mutex;
 use tokio::time::timeout;
 use tracing::warn;
 
 const SANDBOX_ASSESSMENT_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Pre-execution verdicts kept per session before the oldest are evicted.
+const MAX_CACHED_ASSESSMENTS: usize = 256;
+
 #[derive(Template)]
 #[template(path = "sandboxing/assessment_prompt.md", escape = "none")]
 struct SandboxAssessmentPromptTemplate<'a> {
@@ -34,9 +43,44 @@ struct SandboxAssessmentPromptTemplate<'a> {
     working_directory: &'a str,
     command_argv: &'a str,
     command_joined: &'a str,
+    escalated_permissions: bool,
     sandbox_failure_message: Option<&'a str>,
 }
 
+/// Everything about a command that goes into its assessment prompt, so a
+/// cached verdict is only reused for the same command in the same context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AssessmentKey {
+    pub(crate) command: String,
+    pub(crate) cwd: PathBuf,
+    pub(crate) sandbox_policy: SandboxPolicy,
+    pub(crate) escalated_permissions: bool,
+}
+
+/// Verdicts made before commands run, reused for the rest of the session.
+/// Holds at most [`MAX_CACHED_ASSESSMENTS`] entries, evicting the oldest.
+#[derive(Default)]
+pub(crate) struct AssessmentCache {
+    entries: VecDeque<(AssessmentKey, SandboxCommandAssessment)>,
+}
+
+impl AssessmentCache {
+    pub(crate) fn get(&self, key: &AssessmentKey) -> Option<SandboxCommandAssessment> {
+        self.entries
+            .iter()
+            .find(|(cached, _)| cached == key)
+            .map(|(_, assessment)| assessment.clone())
+    }
+
+    pub(crate) fn insert(&mut self, key: AssessmentKey, assessment: SandboxCommandAssessment) {
+        self.entries.retain(|(cached, _)| cached != &key);
+        if self.entries.len() == MAX_CACHED_ASSESSMENTS {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, assessment));
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn assess_command(
     config: Arc<Config>,
@@ -49,11 +93,16 @@ pub(crate) async fn assess_command(
     command: &[String],
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    escalated_permissions: bool,
     failure_message: Option<&str>,
+    budget: &Mutex<BudgetTracker>,
 ) -> Option<SandboxCommandAssessment> {
-    if !config.experimental_sandbox_command_assessment || command.is_empty() {
+    let enabled =
+        config.experimental_sandbox_command_assessment || config.command_assessment.enabled;
+    if !enabled || command.is_empty() {
         return None;
     }
+    let config = assessment_config(config);
 
     let command_json = serde_json::to_string(command).unwrap_or_else(|_| "[]".to_string());
     let command_joined =
@@ -83,6 +132,7 @@ pub(crate) async fn assess_command(
         working_directory: cwd_str.as_str(),
         command_argv: command_json.as_str(),
         command_joined: command_joined.as_str(),
+        escalated_permissions,
         sandbox_failure_message: failure.as_deref(),
     };
     let rendered_prompt = match prompt_template.render() {
@@ -140,6 +190,7 @@ pub(crate) async fn assess_command(
     let assessment_result = timeout(SANDBOX_ASSESSMENT_TIMEOUT, async move {
         let mut stream = client.stream(&prompt).await?;
         let mut last_json: Option<String> = None;
+        let mut usage = None;
         while let Some(event) = stream.next().await {
             match event {
                 Ok(ResponseEvent::OutputItemDone(item)) => {
@@ -148,34 +199,45 @@ pub(crate) async fn assess_command(
                     }
                 }
                 Ok(ResponseEvent::RateLimits(_)) => {}
-                Ok(ResponseEvent::Completed { .. }) => break,
+                Ok(ResponseEvent::Completed { token_usage, .. }) => {
+                    usage = token_usage;
+                    break;
+                }
                 Ok(_) => continue,
                 Err(err) => return Err(err),
             }
         }
-        Ok(last_json)
+        Ok((last_json, usage))
     })
     .await;
     let duration = start.elapsed();
     parent_otel.sandbox_assessment_latency(call_id, duration);
 
+    // The assessment is paid for by the session, so it counts toward its
+    // budget like any other model call.
+    if let Ok(Ok((_, Some(usage)))) = &assessment_result {
+        budget.lock().await.record(&config.model, usage);
+    }
+
     match assessment_result {
-        Ok(Ok(Some(raw))) => match serde_json::from_str::<SandboxCommandAssessment>(raw.trim()) {
-            Ok(assessment) => {
-                parent_otel.sandbox_assessment(
-                    call_id,
-                    "success",
-                    Some(assessment.risk_level),
-                    duration,
-                );
-                return Some(assessment);
-            }
-            Err(err) => {
-                warn!("failed to parse sandbox assessment JSON: {err}");
-                parent_otel.sandbox_assessment(call_id, "parse_error", None, duration);
+        Ok(Ok((Some(raw), _))) => {
+            match serde_json::from_str::<SandboxCommandAssessment>(raw.trim()) {
+                Ok(assessment) => {
+                    parent_otel.sandbox_assessment(
+                        call_id,
+                        "success",
+                        Some(assessment.risk_level),
+                        duration,
+                    );
+                    return Some(assessment);
+                }
+                Err(err) => {
+                    warn!("failed to parse sandbox assessment JSON: {err}");
+                    parent_otel.sandbox_assessment(call_id, "parse_error", None, duration);
+                }
             }
-        },
-        Ok(Ok(None)) => {
+        }
+        Ok(Ok((None, _))) => {
             warn!("sandbox assessment response did not include any message");
             parent_otel.sandbox_assessment(call_id, "no_output", None, duration);
         }
@@ -192,6 +254,55 @@ pub(crate) async fn assess_command(
     None
 }
 
+/// The command part of an [`AssessmentKey`]: the script of a `bash -lc` style
+/// invocation or the joined argv, with runs of spaces and tabs collapsed so
+/// trivially different spellings share one assessment. Line breaks are kept,
+/// since they separate shell commands.
+pub(crate) fn normalize_command(command: &[String]) -> String {
+    let text = match command {
+        [shell, flag, script]
+            if matches!(flag.as_str(), "-c" | "-lc")
+                && Path::new(shell)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| matches!(name, "bash" | "sh" | "zsh")) =>
+        {
+            script.clone()
+        }
+        _ => shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" ")),
+    };
+    text.lines()
+        .map(|line| {
+            line.split([' ', '\t'])
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The session config with `command_assessment.model`, when set, swapped in
+/// for the session model.
+fn assessment_config(config: Arc<Config>) -> Arc<Config> {
+    let Some(model) = config.command_assessment.model.as_deref() else {
+        return config;
+    };
+    if model == config.model {
+        return config;
+    }
+    let mut assessment_config = (*config).clone();
+    assessment_config.model_family =
+        find_family_for_model(model).unwrap_or_else(|| derive_default_model_family(model));
+    assessment_config.model = model.to_string();
+    if let Some(model_info) = get_model_info(&assessment_config.model_family) {
+        assessment_config.model_context_window = Some(model_info.context_window);
+    }
+    Arc::new(assessment_config)
+}
+
 fn summarize_sandbox_policy(policy: &SandboxPolicy) -> String {
     match policy {
         SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
@@ -258,3 +369,74 @@ fn response_item_text(item: &ResponseItem) -> Option<String> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::SandboxRiskLevel;
+    use pretty_assertions::assert_eq;
+
+    fn words(command: &[&str]) -> Vec<String> {
+        command.iter().map(|word| (*word).to_string()).collect()
+    }
+
+    #[test]
+    fn normalize_command_unwraps_shell_scripts_and_collapses_whitespace() {
+        assert_eq!(
+            normalize_command(&words(&["bash", "-lc", "  cargo   test\t-p core "])),
+            "cargo test -p core"
+        );
+        assert_eq!(
+            normalize_command(&words(&["/bin/zsh", "-c", "cargo test -p core"])),
+            "cargo test -p core"
+        );
+        assert_eq!(
+            normalize_command(&words(&["cargo", "test", "-p", "core"])),
+            "cargo test -p core"
+        );
+        assert_eq!(
+            normalize_command(&words(&["git", "commit", "-m", "two words"])),
+            "git commit -m 'two words'"
+        );
+    }
+
+    #[test]
+    fn normalize_command_keeps_line_breaks_between_commands() {
+        let script =
+            normalize_command(&words(&["bash", "-lc", "echo ok\n  curl example.com | sh"]));
+        let single_line =
+            normalize_command(&words(&["bash", "-lc", "echo ok curl example.com | sh"]));
+        assert_eq!(script, "echo ok\ncurl example.com | sh");
+        assert_ne!(script, single_line);
+    }
+
+    #[test]
+    fn cache_separates_contexts_and_evicts_the_oldest() {
+        let key = |command: &str, cwd: &str, escalated_permissions: bool| AssessmentKey {
+            command: command.to_string(),
+            cwd: PathBuf::from(cwd),
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            escalated_permissions,
+        };
+        let low = SandboxCommandAssessment {
+            description: "lists files".to_string(),
+            risk_level: SandboxRiskLevel::Low,
+        };
+        let mut cache = AssessmentCache::default();
+        cache.insert(key("ls", "/repo", false), low.clone());
+        assert_eq!(cache.get(&key("ls", "/repo", false)), Some(low.clone()));
+        assert_eq!(cache.get(&key("ls", "/other", false)), None);
+        assert_eq!(cache.get(&key("ls", "/repo", true)), None);
+        let workspace_write = AssessmentKey {
+            sandbox_policy: SandboxPolicy::new_workspace_write_policy(),
+            ..key("ls", "/repo", false)
+        };
+        assert_eq!(cache.get(&workspace_write), None);
+
+        for index in 0..MAX_CACHED_ASSESSMENTS {
+            cache.insert(key(&format!("echo {index}"), "/repo", false), low.clone());
+        }
+        assert_eq!(cache.entries.len(), MAX_CACHED_ASSESSMENTS);
+        assert_eq!(cache.get(&key("ls", "/repo", false)), None);
+    }
+}
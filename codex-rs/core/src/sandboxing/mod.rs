@@ -7,12 +7,16 @@ ready‑to‑spawn environment.
 */
 
 pub mod assessment;
+pub mod read_deny;
 
+use crate::config::types::ResourceLimits;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
 use crate::exec::execute_exec_env;
+use crate::exec_env::set_network_proxy_env;
 use crate::landlock::create_linux_sandbox_command_args;
+use crate::network_proxy::NetworkProxy;
 use crate::protocol::SandboxPolicy;
 #[cfg(target_os = "macos")]
 use crate::seatbelt::MACOS_PATH_TO_SEATBELT_EXECUTABLE;
@@ -33,6 +37,7 @@ pub struct CommandSpec {
     pub cwd: PathBuf,
     pub env: HashMap<String, String>,
     pub timeout_ms: Option<u64>,
+    pub resource_limits: ResourceLimits,
     pub with_escalated_permissions: Option<bool>,
     pub justification: Option<String>,
 }
@@ -43,10 +48,13 @@ pub struct ExecEnv {
     pub cwd: PathBuf,
     pub env: HashMap<String, String>,
     pub timeout_ms: Option<u64>,
+    pub resource_limits: ResourceLimits,
     pub sandbox: SandboxType,
     pub with_escalated_permissions: Option<bool>,
     pub justification: Option<String>,
     pub arg0: Option<String>,
+    /// Proxy reserved for this command when the policy allowlists hosts.
+    pub network_proxy: Option<NetworkProxy>,
 }
 
 pub enum SandboxPreference {
@@ -62,6 +70,8 @@ pub(crate) enum SandboxTransformError {
     #[cfg(not(target_os = "macos"))]
     #[error("seatbelt sandbox is only available on macOS")]
     SeatbeltUnavailable,
+    #[error("failed to start the sandbox network proxy: {0}")]
+    NetworkProxy(#[source] std::io::Error),
 }
 
 #[derive(Default)]
@@ -72,21 +82,29 @@ impl SandboxManager {
         Self
     }
 
+    /// Picks the sandbox for the first attempt. A policy that asks for the
+    /// namespace container gets it in place of the platform sandbox; config
+    /// loading only allows such policies on Linux.
     pub(crate) fn select_initial(
         &self,
         policy: &SandboxPolicy,
         pref: SandboxablePreference,
     ) -> SandboxType {
-        match pref {
-            SandboxablePreference::Forbid => SandboxType::None,
-            SandboxablePreference::Require => {
-                // Require a platform sandbox when available; on Windows this
-                // respects the enable_experimental_windows_sandbox feature.
+        let platform_sandbox = || {
+            if policy.uses_container() && cfg!(target_os = "linux") {
+                SandboxType::LinuxNamespaces
+            } else {
                 crate::safety::get_platform_sandbox().unwrap_or(SandboxType::None)
             }
+        };
+        match pref {
+            SandboxablePreference::Forbid => SandboxType::None,
+            // Require a platform sandbox when available; on Windows this
+            // respects the enable_experimental_windows_sandbox feature.
+            SandboxablePreference::Require => platform_sandbox(),
             SandboxablePreference::Auto => match policy {
                 SandboxPolicy::DangerFullAccess => SandboxType::None,
-                _ => crate::safety::get_platform_sandbox().unwrap_or(SandboxType::None),
+                _ => platform_sandbox(),
             },
         }
     }
@@ -95,6 +113,7 @@ impl SandboxManager {
         &self,
         spec: &CommandSpec,
         policy: &SandboxPolicy,
+        read_deny: &[String],
         sandbox: SandboxType,
         sandbox_policy_cwd: &Path,
         codex_linux_sandbox_exe: Option<&PathBuf>,
@@ -107,6 +126,19 @@ impl SandboxManager {
             );
         }
 
+        // Only sandboxed commands are confined to the proxy; unsandboxed ones
+        // already have unrestricted network access.
+        let network_proxy = match (sandbox, policy.network_allowlist()) {
+            (SandboxType::None, _) | (_, []) => None,
+            (_, allowlist) => {
+                let proxy =
+                    NetworkProxy::bind(allowlist).map_err(SandboxTransformError::NetworkProxy)?;
+                set_network_proxy_env(&mut env, proxy.addr());
+                Some(proxy)
+            }
+        };
+        let network_proxy_port = network_proxy.as_ref().map(|proxy| proxy.addr().port());
+
         let mut command = Vec::with_capacity(1 + spec.args.len());
         command.push(spec.program.clone());
         command.extend(spec.args.iter().cloned());
@@ -117,8 +149,13 @@ impl SandboxManager {
             SandboxType::MacosSeatbelt => {
                 let mut seatbelt_env = HashMap::new();
                 seatbelt_env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
-                let mut args =
-                    create_seatbelt_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let mut args = create_seatbelt_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    read_deny,
+                    network_proxy_port,
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(MACOS_PATH_TO_SEATBELT_EXECUTABLE.to_string());
                 full_command.append(&mut args);
@@ -126,11 +163,20 @@ impl SandboxManager {
             }
             #[cfg(not(target_os = "macos"))]
             SandboxType::MacosSeatbelt => return Err(SandboxTransformError::SeatbeltUnavailable),
-            SandboxType::LinuxSeccomp => {
+            SandboxType::LinuxSeccomp | SandboxType::LinuxNamespaces => {
                 let exe = codex_linux_sandbox_exe
                     .ok_or(SandboxTransformError::MissingLinuxSandboxExecutable)?;
-                let mut args =
-                    create_linux_sandbox_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let mut args = create_linux_sandbox_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    read_deny,
+                    network_proxy_port,
+                    &spec.resource_limits,
+                );
+                if sandbox == SandboxType::LinuxNamespaces {
+                    args.insert(0, "--container".to_string());
+                }
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(exe.to_string_lossy().to_string());
                 full_command.append(&mut args);
@@ -157,10 +203,12 @@ impl SandboxManager {
             cwd: spec.cwd.clone(),
             env,
             timeout_ms: spec.timeout_ms,
+            resource_limits: spec.resource_limits,
             sandbox,
             with_escalated_permissions: spec.with_escalated_permissions,
             justification: spec.justification.clone(),
             arg0: arg0_override,
+            network_proxy,
         })
     }
 
@@ -0,0 +1,289 @@
+//! Matching of paths against the read-deny globs that apply next to a
+//! [`SandboxPolicy`].
+//!
+//! Tool handlers consult [`ReadDenyList::is_denied`] before touching the
+//! file-system. The platform sandboxes enforce the same globs on commands;
+//! path-based ones resolve them to concrete paths with
+//! [`ReadDenyList::denied_paths`] and [`ReadDenyList::find_denied_paths`].
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::path::PathBuf;
+
+use globset::Glob;
+use globset::GlobBuilder;
+use globset::GlobSet;
+use globset::GlobSetBuilder;
+use tracing::warn;
+
+use crate::protocol::DEFAULT_READ_DENY_GLOBS;
+use crate::protocol::SandboxPolicy;
+
+/// Upper bound on the directory entries [`ReadDenyList::find_denied_paths`]
+/// looks at, so large checkouts do not delay every command.
+pub const MAX_SCANNED_ENTRIES: usize = 50_000;
+
+const GLOB_META_CHARS: &[char] = &['*', '?', '[', ']', '{', '}', '\\'];
+
+/// Compiled set of read-deny globs.
+#[derive(Debug, Clone, Default)]
+pub struct ReadDenyList {
+    /// Globs after `~` expansion, anchored to absolute paths or prefixed with
+    /// `**/`.
+    patterns: Vec<String>,
+    matcher: GlobSet,
+}
+
+impl ReadDenyList {
+    /// Builds the deny list that applies under `sandbox_policy`: the built-in
+    /// [`DEFAULT_READ_DENY_GLOBS`] followed by the configured `read_deny`
+    /// globs. Nothing is denied under [`SandboxPolicy::DangerFullAccess`].
+    pub fn new(sandbox_policy: &SandboxPolicy, read_deny: &[String]) -> Self {
+        if matches!(sandbox_policy, SandboxPolicy::DangerFullAccess) {
+            return Self::default();
+        }
+        let globs: Vec<String> = DEFAULT_READ_DENY_GLOBS
+            .iter()
+            .map(|glob| (*glob).to_string())
+            .chain(read_deny.iter().cloned())
+            .collect();
+        Self::from_globs(&globs)
+    }
+
+    /// Builds a deny list from raw globs. Invalid globs are logged and
+    /// skipped.
+    pub fn from_globs(globs: &[String]) -> Self {
+        let home = dirs::home_dir();
+        let mut patterns = Vec::new();
+        let mut builder = GlobSetBuilder::new();
+        for glob in globs {
+            let Some(pattern) = expand_glob(glob, home.as_deref()) else {
+                continue;
+            };
+            // A denied directory also denies everything beneath it.
+            match (build_glob(&pattern), build_glob(&format!("{pattern}/**"))) {
+                (Ok(path_glob), Ok(subtree_glob)) => {
+                    builder.add(path_glob);
+                    builder.add(subtree_glob);
+                    patterns.push(pattern);
+                }
+                (Err(err), _) | (_, Err(err)) => {
+                    warn!("ignoring invalid read-deny glob `{glob}`: {err}");
+                }
+            }
+        }
+        let matcher = builder.build().unwrap_or_else(|err| {
+            warn!("failed to compile read-deny globs: {err}");
+            GlobSet::empty()
+        });
+        Self { patterns, matcher }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Globs after `~` expansion. Relative globs are prefixed with `**/` so
+    /// they match at any depth.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Returns `true` when `path`, or the file it resolves to through
+    /// symlinks, must not be read.
+    pub fn is_denied(&self, path: &Path) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        if self.matcher.is_match(path) {
+            return true;
+        }
+        path.canonicalize()
+            .is_ok_and(|canonical| canonical != path && self.matcher.is_match(&canonical))
+    }
+
+    /// Existing absolute paths named by globs without wildcards, resolved
+    /// through symlinks. These are the entries a path-based sandbox such as
+    /// Landlock can enforce.
+    pub fn denied_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self
+            .patterns
+            .iter()
+            .map(|pattern| pattern.strip_suffix("/**").unwrap_or(pattern))
+            .filter(|pattern| !pattern.contains(GLOB_META_CHARS))
+            .map(Path::new)
+            .filter(|path| path.is_absolute())
+            .filter_map(|path| path.canonicalize().ok())
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    /// Denied entries beneath `roots`, which must be canonical, found by
+    /// walking them breadth-first. This resolves globs such as `.env` that
+    /// [`ReadDenyList::denied_paths`] cannot. Denied directories are not
+    /// descended into and symlinks are not followed. `complete` is `false`
+    /// when the walk stopped after [`MAX_SCANNED_ENTRIES`] entries.
+    pub fn find_denied_paths(&self, roots: &[PathBuf]) -> FoundDeniedPaths {
+        let mut found = FoundDeniedPaths {
+            paths: Vec::new(),
+            complete: true,
+        };
+        if self.is_empty() {
+            return found;
+        }
+        let mut scanned = 0;
+        let mut pending: VecDeque<PathBuf> = roots.iter().cloned().collect();
+        while let Some(dir) = pending.pop_front() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                scanned += 1;
+                if scanned > MAX_SCANNED_ENTRIES {
+                    found.complete = false;
+                    return found.sorted();
+                }
+                let path = entry.path();
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                if self.matcher.is_match(&path) {
+                    found.paths.push(path);
+                } else if file_type.is_dir() {
+                    pending.push_back(path);
+                }
+            }
+        }
+        found.sorted()
+    }
+}
+
+/// Result of [`ReadDenyList::find_denied_paths`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundDeniedPaths {
+    pub paths: Vec<PathBuf>,
+    pub complete: bool,
+}
+
+impl FoundDeniedPaths {
+    fn sorted(mut self) -> Self {
+        self.paths.sort();
+        self.paths.dedup();
+        self
+    }
+}
+
+fn build_glob(pattern: &str) -> Result<Glob, globset::Error> {
+    GlobBuilder::new(pattern).literal_separator(true).build()
+}
+
+fn expand_glob(glob: &str, home: Option<&Path>) -> Option<String> {
+    let glob = glob.trim().trim_end_matches('/');
+    if glob.is_empty() {
+        return None;
+    }
+    if glob == "~" || glob.starts_with("~/") {
+        let home = globset::escape(&home?.to_string_lossy());
+        return Some(format!("{}{}", home.trim_end_matches('/'), &glob[1..]));
+    }
+    if Path::new(glob).is_absolute() || glob.starts_with("**/") {
+        return Some(glob.to_string());
+    }
+    Some(format!("**/{glob}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn deny_list(globs: &[&str]) -> ReadDenyList {
+        let globs: Vec<String> = globs.iter().map(|glob| (*glob).to_string()).collect();
+        ReadDenyList::from_globs(&globs)
+    }
+
+    #[test]
+    fn relative_globs_match_at_any_depth() {
+        let list = deny_list(&[".env", ".env.*", "*.pem"]);
+        assert!(list.is_denied(Path::new("/repo/.env")));
+        assert!(list.is_denied(Path::new("/repo/app/.env.production")));
+        assert!(list.is_denied(Path::new("certs/server.pem")));
+        assert!(!list.is_denied(Path::new("/repo/.envrc")));
+        assert!(!list.is_denied(Path::new("/repo/src/main.rs")));
+    }
+
+    #[test]
+    fn directories_deny_their_contents() {
+        let list = deny_list(&["/srv/secrets", "/opt/*/keys"]);
+        assert!(list.is_denied(Path::new("/srv/secrets")));
+        assert!(list.is_denied(Path::new("/srv/secrets/db/password")));
+        assert!(list.is_denied(Path::new("/opt/app/keys/id_rsa")));
+        assert!(!list.is_denied(Path::new("/srv/secrets-public/readme")));
+        assert!(!list.is_denied(Path::new("/opt/app/nested/keys")));
+    }
+
+    #[test]
+    fn tilde_expands_to_home() {
+        let home = Path::new("/home/user");
+        assert_eq!(
+            expand_glob("~/.ssh/", Some(home)),
+            Some("/home/user/.ssh".to_string())
+        );
+        assert_eq!(expand_glob("~/.ssh", None), None);
+        assert_eq!(expand_glob(".env", Some(home)), Some("**/.env".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_into_denied_paths_are_denied() -> std::io::Result<()> {
+        let temp = TempDir::new()?;
+        let secrets = temp.path().join("secrets");
+        std::fs::create_dir(&secrets)?;
+        std::fs::write(secrets.join("token"), "hunter2")?;
+        let link = temp.path().join("innocent");
+        std::os::unix::fs::symlink(secrets.join("token"), &link)?;
+
+        let secrets = secrets.canonicalize()?;
+        let list = ReadDenyList::from_globs(&[secrets.to_string_lossy().into_owned()]);
+        assert!(list.is_denied(&link));
+        assert_eq!(list.denied_paths(), vec![secrets]);
+        Ok(())
+    }
+
+    #[test]
+    fn danger_full_access_denies_nothing() {
+        let configured = vec!["*.pem".to_string()];
+        assert!(ReadDenyList::new(&SandboxPolicy::DangerFullAccess, &configured).is_empty());
+        let read_only = ReadDenyList::new(&SandboxPolicy::new_read_only_policy(), &configured);
+        assert!(read_only.is_denied(Path::new("/repo/.env")));
+        assert!(read_only.is_denied(Path::new("/repo/server.pem")));
+    }
+
+    #[test]
+    fn find_denied_paths_resolves_name_globs_under_roots() -> std::io::Result<()> {
+        let temp = TempDir::new()?;
+        let root = temp.path().canonicalize()?;
+        std::fs::create_dir_all(root.join("app/secrets"))?;
+        std::fs::write(root.join(".env"), "TOKEN=1")?;
+        std::fs::write(root.join("app/.env.local"), "TOKEN=2")?;
+        std::fs::write(root.join("app/secrets/key"), "hunter2")?;
+        std::fs::write(root.join("app/main.rs"), "fn main() {}")?;
+
+        let list = deny_list(&[".env", ".env.*", "secrets"]);
+        assert_eq!(
+            list.find_denied_paths(std::slice::from_ref(&root)),
+            FoundDeniedPaths {
+                paths: vec![
+                    root.join(".env"),
+                    root.join("app/.env.local"),
+                    root.join("app/secrets"),
+                ],
+                complete: true,
+            }
+        );
+        Ok(())
+    }
+}
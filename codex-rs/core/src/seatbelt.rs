@@ -6,13 +6,18 @@ use std::path::Path;
 use std::path::PathBuf;
 use tokio::process::Child;
 
+use crate::config::types::ResourceLimits;
 use crate::protocol::SandboxPolicy;
+use crate::sandboxing::read_deny::ReadDenyList;
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
+use crate::spawn::SpawnChildRequest;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 
 const MACOS_SEATBELT_BASE_POLICY: &str = include_str!("seatbelt_base_policy.sbpl");
 const MACOS_SEATBELT_NETWORK_POLICY: &str = include_str!("seatbelt_network_policy.sbpl");
+const MACOS_SEATBELT_NETWORK_PROXY_POLICY: &str =
+    include_str!("seatbelt_network_proxy_policy.sbpl");
 
 /// When working with `sandbox-exec`, only consider `sandbox-exec` in `/usr/bin`
 /// to defend against an attacker trying to inject a malicious version on the
@@ -25,21 +30,24 @@ pub async fn spawn_command_under_seatbelt(
     command_cwd: PathBuf,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    read_deny: &[String],
     stdio_policy: StdioPolicy,
     mut env: HashMap<String, String>,
 ) -> std::io::Result<Child> {
-    let args = create_seatbelt_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    let args =
+        create_seatbelt_command_args(command, sandbox_policy, sandbox_policy_cwd, read_deny, None);
     let arg0 = None;
     env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
-    spawn_child_async(
-        PathBuf::from(MACOS_PATH_TO_SEATBELT_EXECUTABLE),
+    spawn_child_async(SpawnChildRequest {
+        program: PathBuf::from(MACOS_PATH_TO_SEATBELT_EXECUTABLE),
         args,
         arg0,
-        command_cwd,
+        cwd: command_cwd,
         sandbox_policy,
         stdio_policy,
         env,
-    )
+        resource_limits: ResourceLimits::default(),
+    })
     .await
 }
 
@@ -47,6 +55,8 @@ pub(crate) fn create_seatbelt_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    read_deny: &[String],
+    network_proxy_port: Option<u16>,
 ) -> Vec<String> {
     let (file_write_policy, file_write_dir_params) = {
         if sandbox_policy.has_full_disk_write_access() {
@@ -99,16 +109,24 @@ pub(crate) fn create_seatbelt_command_args(
     };
 
     let file_read_policy = if sandbox_policy.has_full_disk_read_access() {
-        "; allow read-only file operations\n(allow file-read*)"
+        format!(
+            "; allow read-only file operations\n(allow file-read*){}",
+            create_read_deny_policy(sandbox_policy, read_deny)
+        )
     } else {
-        ""
+        String::new()
     };
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
     let network_policy = if sandbox_policy.has_full_network_access() {
-        MACOS_SEATBELT_NETWORK_POLICY
+        MACOS_SEATBELT_NETWORK_POLICY.to_string()
+    } else if let Some(port) = network_proxy_port {
+        format!(
+            "{MACOS_SEATBELT_NETWORK_PROXY_POLICY}\n\
+             (allow network-outbound (remote ip \"localhost:{port}\"))"
+        )
     } else {
-        ""
+        String::new()
     };
 
     let full_policy = format!(
@@ -127,6 +145,52 @@ pub(crate) fn create_seatbelt_command_args(
     seatbelt_args
 }
 
+/// Builds `(deny file-read-data ...)` rules for the read-deny globs.
+/// They must follow `(allow file-read*)` because later rules take precedence.
+/// Metadata stays readable so denied entries still show up in listings.
+fn create_read_deny_policy(sandbox_policy: &SandboxPolicy, read_deny: &[String]) -> String {
+    let read_deny = ReadDenyList::new(sandbox_policy, read_deny);
+    read_deny
+        .patterns()
+        .iter()
+        .filter_map(|pattern| glob_to_seatbelt_regex(pattern))
+        .map(|regex| format!("\n(deny file-read-data (regex #\"{regex}\"))"))
+        .collect()
+}
+
+/// Translates an absolute (or `**/`-prefixed) glob into a Seatbelt regex that
+/// matches the path and everything beneath it. Returns `None` for character
+/// classes and alternations, which only the tool handlers enforce.
+fn glob_to_seatbelt_regex(glob: &str) -> Option<String> {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.next_if_eq(&'/').is_some() {
+                    regex.push_str("(.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' | ']' | '{' | '}' | '\\' | '"' => return None,
+            c if c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '~' | ' ') => {
+                regex.push(c)
+            }
+            c if c.is_ascii() => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+    }
+    regex.push_str("(/.*)?$");
+    Some(regex)
+}
+
 /// Wraps libc::confstr to return a String.
 fn confstr(name: libc::c_int) -> Option<String> {
     let mut buf = vec![0_i8; (libc::PATH_MAX as usize) + 1];
@@ -156,7 +220,9 @@ fn macos_dir_params() -> Vec<(String, PathBuf)> {
 #[cfg(test)]
 mod tests {
     use super::MACOS_SEATBELT_BASE_POLICY;
+    use super::create_read_deny_policy;
     use super::create_seatbelt_command_args;
+    use super::glob_to_seatbelt_regex;
     use super::macos_dir_params;
     use crate::protocol::SandboxPolicy;
     use pretty_assertions::assert_eq;
@@ -184,14 +250,19 @@ mod tests {
         let policy = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![root_with_git, root_without_git],
             network_access: false,
+            network_allowlist: vec![],
+            read_only_subpaths: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            container: false,
         };
 
         let args = create_seatbelt_command_args(
             vec!["/bin/echo".to_string(), "hello".to_string()],
             &policy,
             &cwd,
+            &[],
+            None,
         );
 
         // Build the expected policy text using a raw string for readability.
@@ -199,10 +270,11 @@ mod tests {
         // - the base policy,
         // - read-only access to the filesystem,
         // - write access to WRITABLE_ROOT_0 (but not its .git) and WRITABLE_ROOT_1.
+        let read_deny_policy = create_read_deny_policy(&policy, &[]);
         let expected_policy = format!(
             r#"{MACOS_SEATBELT_BASE_POLICY}
 ; allow read-only file operations
-(allow file-read*)
+(allow file-read*){read_deny_policy}
 (allow file-write*
 (require-all (subpath (param "WRITABLE_ROOT_0")) (require-not (subpath (param "WRITABLE_ROOT_0_RO_0"))) ) (subpath (param "WRITABLE_ROOT_1")) (subpath (param "WRITABLE_ROOT_2"))
 )
@@ -242,6 +314,54 @@ mod tests {
         assert_eq!(expected_args, args);
     }
 
+    #[test]
+    fn read_deny_globs_become_seatbelt_regexes() {
+        assert_eq!(
+            glob_to_seatbelt_regex("/Users/me/.ssh"),
+            Some(r"^/Users/me/\.ssh(/.*)?$".to_string())
+        );
+        assert_eq!(
+            glob_to_seatbelt_regex("**/.env.*"),
+            Some(r"^(.*/)?\.env\.[^/]*(/.*)?$".to_string())
+        );
+        assert_eq!(glob_to_seatbelt_regex("**/*.{pem,key}"), None);
+
+        let read_deny_policy =
+            create_read_deny_policy(&SandboxPolicy::ReadOnly, &["/srv/secrets".to_string()]);
+        assert!(
+            read_deny_policy.contains(r#"(deny file-read-data (regex #"^/srv/secrets(/.*)?$"))"#),
+            "{read_deny_policy}"
+        );
+    }
+
+    #[test]
+    fn network_allowlist_only_allows_the_proxy_port() {
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            network_allowlist: vec!["crates.io".to_string()],
+            read_only_subpaths: vec![],
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            container: false,
+        };
+
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            Path::new("/tmp"),
+            &[],
+            Some(4040),
+        );
+
+        let full_policy = &args[1];
+        assert!(
+            full_policy.contains(r#"(allow network-outbound (remote ip "localhost:4040"))"#),
+            "{full_policy}"
+        );
+        assert!(!full_policy.contains("(allow network-outbound)"));
+    }
+
     #[test]
     fn create_seatbelt_args_for_cwd_as_git_repo() {
         // Create a temporary workspace with two writable roots: one containing
@@ -260,14 +380,19 @@ mod tests {
         let policy = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            network_allowlist: vec![],
+            read_only_subpaths: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            container: false,
         };
 
         let args = create_seatbelt_command_args(
             vec!["/bin/echo".to_string(), "hello".to_string()],
             &policy,
             root_with_git.as_path(),
+            &[],
+            None,
         );
 
         let tmpdir_env_var = std::env::var("TMPDIR")
@@ -287,10 +412,11 @@ mod tests {
         // - the base policy,
         // - read-only access to the filesystem,
         // - write access to WRITABLE_ROOT_0 (but not its .git) and WRITABLE_ROOT_1.
+        let read_deny_policy = create_read_deny_policy(&policy, &[]);
         let expected_policy = format!(
             r#"{MACOS_SEATBELT_BASE_POLICY}
 ; allow read-only file operations
-(allow file-read*)
+(allow file-read*){read_deny_policy}
 (allow file-write*
 (require-all (subpath (param "WRITABLE_ROOT_0")) (require-not (subpath (param "WRITABLE_ROOT_0_RO_0"))) ) (subpath (param "WRITABLE_ROOT_1")){tempdir_policy_entry}
 )
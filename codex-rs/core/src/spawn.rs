@@ -5,6 +5,7 @@ use tokio::process::Child;
 use tokio::process::Command;
 use tracing::trace;
 
+use crate::config::types::ResourceLimits;
 use crate::protocol::SandboxPolicy;
 
 /// Experimental environment variable that will be set to some non-empty value
@@ -28,22 +29,39 @@ pub enum StdioPolicy {
     Inherit,
 }
 
+/// Everything [`spawn_child_async`] needs to start a child process.
+pub(crate) struct SpawnChildRequest<'a> {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub arg0: Option<&'a str>,
+    pub cwd: PathBuf,
+    /// We take `SandboxPolicy` here because we need to determine whether to
+    /// set the `CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR` environment variable.
+    pub sandbox_policy: &'a SandboxPolicy,
+    pub stdio_policy: StdioPolicy,
+    pub env: HashMap<String, String>,
+    /// Applied to the child before it executes `program`. Only the
+    /// rlimit-based limits are handled here; the output limit is up to
+    /// whoever reads the child's output.
+    pub resource_limits: ResourceLimits,
+}
+
 /// Spawns the appropriate child process for the ExecParams and SandboxPolicy,
 /// ensuring the args and environment variables used to create the `Command`
 /// (and `Child`) honor the configuration.
-///
-/// For now, we take `SandboxPolicy` as a parameter to spawn_child() because
-/// we need to determine whether to set the
-/// `CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR` environment variable.
-pub(crate) async fn spawn_child_async(
-    program: PathBuf,
-    args: Vec<String>,
-    #[cfg_attr(not(unix), allow(unused_variables))] arg0: Option<&str>,
-    cwd: PathBuf,
-    sandbox_policy: &SandboxPolicy,
-    stdio_policy: StdioPolicy,
-    env: HashMap<String, String>,
-) -> std::io::Result<Child> {
+pub(crate) async fn spawn_child_async(request: SpawnChildRequest<'_>) -> std::io::Result<Child> {
+    let SpawnChildRequest {
+        program,
+        args,
+        #[cfg_attr(not(unix), allow(unused_variables))]
+        arg0,
+        cwd,
+        sandbox_policy,
+        stdio_policy,
+        env,
+        #[cfg_attr(not(unix), allow(unused_variables))]
+        resource_limits,
+    } = request;
     trace!(
         "spawn_child_async: {program:?} {args:?} {arg0:?} {cwd:?} {sandbox_policy:?} {stdio_policy:?} {env:?}"
     );
@@ -73,6 +91,8 @@ pub(crate) async fn spawn_child_async(
                 return Err(std::io::Error::last_os_error());
             }
 
+            apply_resource_limits(&resource_limits)?;
+
             // This relies on prctl(2), so it only works on Linux.
             #[cfg(target_os = "linux")]
             {
@@ -115,3 +135,54 @@ pub(crate) async fn spawn_child_async(
 
     cmd.kill_on_drop(true).spawn()
 }
+
+/// Lowers the rlimits of the current process to `limits`; they are inherited
+/// by every process it starts. Limits above the current hard limit are
+/// clamped to it.
+///
+/// Only calls `getrlimit(2)` and `setrlimit(2)`, so it is safe to use between
+/// `fork(2)` and `exec(2)`.
+#[cfg(unix)]
+pub fn apply_resource_limits(limits: &ResourceLimits) -> std::io::Result<()> {
+    const MIB: u64 = 1024 * 1024;
+
+    let rlimits = [
+        (
+            libc::RLIMIT_AS,
+            limits.memory_mb.map(|mb| mb.saturating_mul(MIB)),
+        ),
+        (libc::RLIMIT_CPU, limits.cpu_seconds),
+        (libc::RLIMIT_NPROC, limits.processes),
+        (
+            libc::RLIMIT_FSIZE,
+            limits.file_size_mb.map(|mb| mb.saturating_mul(MIB)),
+        ),
+    ];
+    for (resource, limit) in rlimits {
+        let Some(limit) = limit else {
+            continue;
+        };
+        let mut current = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        if unsafe { libc::getrlimit(resource, &mut current) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // Leave a second between the soft and hard CPU limits so the command
+        // receives SIGXCPU, which identifies the limit, before SIGKILL.
+        let hard_limit = if resource == libc::RLIMIT_CPU {
+            limit.saturating_add(1)
+        } else {
+            limit
+        };
+        let new_limit = libc::rlimit {
+            rlim_cur: limit.min(current.rlim_max),
+            rlim_max: hard_limit.min(current.rlim_max),
+        };
+        if unsafe { libc::setrlimit(resource, &new_limit) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
@@ -2,11 +2,19 @@ use std::sync::Arc;
 
 use crate::AuthManager;
 use crate::RolloutRecorder;
+use crate::audit::AuditLog;
+use crate::budget::BudgetTracker;
+use crate::command_safety::exec_policy::ExecPolicy;
+use crate::hooks::Hooks;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::redaction::SecretRedactor;
+use crate::sandboxing::assessment::AssessmentCache;
+use crate::tool_output_store::ToolOutputStore;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
 use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::ApprovalRule;
 use tokio::sync::Mutex;
 
 pub(crate) struct SessionServices {
@@ -19,4 +27,17 @@ pub(crate) struct SessionServices {
     pub(crate) auth_manager: Arc<AuthManager>,
     pub(crate) otel_event_manager: OtelEventManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    pub(crate) exec_policy: ExecPolicy,
+    pub(crate) hooks: Hooks,
+    /// Sub-agents share their parent's tracker, so the parent's limits cap
+    /// the spend of every session it starts.
+    pub(crate) budget: Arc<Mutex<BudgetTracker>>,
+    /// Set when `budget` is the parent's, whose running turn a sub-agent turn
+    /// belongs to and must not reset.
+    pub(crate) shares_parent_budget: bool,
+    pub(crate) redactor: SecretRedactor,
+    pub(crate) audit: AuditLog,
+    pub(crate) approval_rules: Mutex<Vec<ApprovalRule>>,
+    pub(crate) command_assessments: Mutex<AssessmentCache>,
+    pub(crate) tool_outputs: ToolOutputStore,
 }
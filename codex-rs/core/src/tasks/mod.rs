@@ -1,6 +1,7 @@
 mod compact;
 mod ghost_snapshot;
 mod regular;
+mod restore_checkpoint;
 mod review;
 mod undo;
 mod user_shell;
@@ -31,6 +32,7 @@ use codex_protocol::user_input::UserInput;
 pub(crate) use compact::CompactTask;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
 pub(crate) use regular::RegularTask;
+pub(crate) use restore_checkpoint::RestoreCheckpointTask;
 pub(crate) use review::ReviewTask;
 pub(crate) use undo::UndoTask;
 pub(crate) use user_shell::UserShellCommandTask;
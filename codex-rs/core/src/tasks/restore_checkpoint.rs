@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use crate::codex::TurnContext;
+use crate::protocol::CheckpointRestoredEvent;
+use crate::protocol::EventMsg;
+use crate::protocol::UndoCompletedEvent;
+use crate::protocol::UndoStartedEvent;
+use crate::rollout::rewind::checkpoint_for_turn;
+use crate::rollout::rewind::user_turn_positions;
+use crate::state::TaskKind;
+use crate::tasks::SessionTask;
+use crate::tasks::SessionTaskContext;
+use async_trait::async_trait;
+use codex_git::restore_ghost_commit;
+use codex_protocol::user_input::UserInput;
+use tokio_util::sync::CancellationToken;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+/// Rewinds both the working tree and the conversation history to the
+/// checkpoint taken at the start of a user turn.
+pub(crate) struct RestoreCheckpointTask {
+    turn: usize,
+}
+
+impl RestoreCheckpointTask {
+    pub(crate) fn new(turn: usize) -> Self {
+        Self { turn }
+    }
+}
+
+#[async_trait]
+impl SessionTask for RestoreCheckpointTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let turn = self.turn;
+        sess.send_event(
+            ctx.as_ref(),
+            EventMsg::UndoStarted(UndoStartedEvent {
+                message: Some("Rewind in progress...".to_string()),
+            }),
+        )
+        .await;
+
+        let mut completed = UndoCompletedEvent {
+            success: false,
+            message: None,
+        };
+
+        if cancellation_token.is_cancelled() {
+            completed.message = Some("Rewind cancelled.".to_string());
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        }
+
+        let items = match sess.load_rollout_items().await {
+            Some(Ok(items)) => items,
+            Some(Err(err)) => {
+                let message = format!("Failed to read session history: {err}");
+                warn!("{message}");
+                completed.message = Some(message);
+                sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                    .await;
+                return None;
+            }
+            None => {
+                completed.message =
+                    Some("Rewind is unavailable because this session is not recorded.".to_string());
+                sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                    .await;
+                return None;
+            }
+        };
+
+        let Some(cut_idx) = user_turn_positions(&items).get(turn).copied() else {
+            completed.message = Some(format!("No turn {turn} to rewind to."));
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        };
+        let Some(ghost_commit) = checkpoint_for_turn(&items, turn) else {
+            completed.message = Some(format!("No checkpoint available for turn {turn}."));
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        };
+
+        let commit_id = ghost_commit.id().to_string();
+        let repo_path = ctx.cwd.clone();
+        let restore_result =
+            tokio::task::spawn_blocking(move || restore_ghost_commit(&repo_path, &ghost_commit))
+                .await;
+
+        match restore_result {
+            Ok(Ok(())) => {
+                let history = sess.reconstruct_history_from_rollout(&ctx, &items[..cut_idx]);
+                sess.replace_history(history).await;
+                sess.send_event(
+                    ctx.as_ref(),
+                    EventMsg::CheckpointRestored(CheckpointRestoredEvent {
+                        turn,
+                        commit_id: commit_id.clone(),
+                    }),
+                )
+                .await;
+                let short_id: String = commit_id.chars().take(7).collect();
+                info!(commit_id = commit_id, turn, "Rewound to checkpoint");
+                completed.success = true;
+                completed.message = Some(format!(
+                    "Rewound code and conversation to snapshot {short_id}."
+                ));
+            }
+            Ok(Err(err)) => {
+                let message = format!("Failed to restore snapshot {commit_id}: {err}");
+                warn!("{message}");
+                completed.message = Some(message);
+            }
+            Err(err) => {
+                let message = format!("Failed to restore snapshot {commit_id}: {err}");
+                error!("{message}");
+                completed.message = Some(message);
+            }
+        }
+
+        sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+            .await;
+        None
+    }
+}
@@ -9,7 +9,9 @@ use tokio_util::sync::CancellationToken;
 use tracing::error;
 use uuid::Uuid;
 
+use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::types::ResourceLimits;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
@@ -22,6 +24,7 @@ use crate::protocol::ExecCommandBeginEvent;
 use crate::protocol::ExecCommandEndEvent;
 use crate::protocol::ExecCommandSource;
 use crate::protocol::SandboxPolicy;
+use crate::protocol::SecretsRedactedEvent;
 use crate::protocol::TaskStartedEvent;
 use crate::sandboxing::ExecEnv;
 use crate::state::TaskKind;
@@ -92,16 +95,20 @@ impl SessionTask for UserShellCommandTask {
             cwd: turn_context.cwd.clone(),
             env: create_env(&turn_context.shell_environment_policy),
             timeout_ms: None,
+            // Resource limits only apply to commands run by the model.
+            resource_limits: ResourceLimits::default(),
             sandbox: SandboxType::None,
             with_escalated_permissions: None,
             justification: None,
             arg0: None,
+            network_proxy: None,
         };
 
         let stdout_stream = Some(StdoutStream {
             sub_id: turn_context.sub_id.clone(),
             call_id: call_id.clone(),
             tx_event: session.get_tx_event(),
+            audit: session.services.audit.clone(),
         });
 
         let sandbox_policy = SandboxPolicy::DangerFullAccess;
@@ -119,11 +126,16 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(aborted_message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    resource_limit_exceeded: None,
                 };
-                let output_items = [user_shell_command_record_item(&raw_command, &exec_output)];
-                session
-                    .record_conversation_items(turn_context.as_ref(), &output_items)
-                    .await;
+                record_user_shell_command(
+                    &session,
+                    &turn_context,
+                    &call_id,
+                    &raw_command,
+                    &exec_output,
+                )
+                .await;
                 session
                     .send_event(
                         turn_context.as_ref(),
@@ -135,6 +147,7 @@ impl SessionTask for UserShellCommandTask {
                             exit_code: -1,
                             duration: Duration::ZERO,
                             formatted_output: aborted_message,
+                            resource_limit_exceeded: None,
                         }),
                     )
                     .await;
@@ -151,13 +164,12 @@ impl SessionTask for UserShellCommandTask {
                             exit_code: output.exit_code,
                             duration: output.duration,
                             formatted_output: format_exec_output_str(&output),
+                            resource_limit_exceeded: output.resource_limit_exceeded,
                         }),
                     )
                     .await;
 
-                let output_items = [user_shell_command_record_item(&raw_command, &output)];
-                session
-                    .record_conversation_items(turn_context.as_ref(), &output_items)
+                record_user_shell_command(&session, &turn_context, &call_id, &raw_command, &output)
                     .await;
             }
             Ok(Err(err)) => {
@@ -170,27 +182,60 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    resource_limit_exceeded: None,
                 };
                 session
                     .send_event(
                         turn_context.as_ref(),
                         EventMsg::ExecCommandEnd(ExecCommandEndEvent {
-                            call_id,
+                            call_id: call_id.clone(),
                             stdout: exec_output.stdout.text.clone(),
                             stderr: exec_output.stderr.text.clone(),
                             aggregated_output: exec_output.aggregated_output.text.clone(),
                             exit_code: exec_output.exit_code,
                             duration: exec_output.duration,
                             formatted_output: format_exec_output_str(&exec_output),
+                            resource_limit_exceeded: None,
                         }),
                     )
                     .await;
-                let output_items = [user_shell_command_record_item(&raw_command, &exec_output)];
-                session
-                    .record_conversation_items(turn_context.as_ref(), &output_items)
-                    .await;
+                record_user_shell_command(
+                    &session,
+                    &turn_context,
+                    &call_id,
+                    &raw_command,
+                    &exec_output,
+                )
+                .await;
             }
         }
         None
     }
 }
+
+/// Records the command and its output in the history. The command runs with
+/// the keyring secrets of the shell environment policy, so the record is
+/// redacted like a tool output before it reaches the model or the rollout.
+async fn record_user_shell_command(
+    session: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    command: &str,
+    exec_output: &ExecToolCallOutput,
+) {
+    let mut item = user_shell_command_record_item(command, exec_output);
+    let redactor = &session.services.redactor;
+    let count = redactor.redact_item(&mut item);
+    if count > 0 {
+        let session_total = redactor.record(count);
+        let event = EventMsg::SecretsRedacted(SecretsRedactedEvent {
+            call_id: call_id.to_string(),
+            count: u32::try_from(count).unwrap_or(u32::MAX),
+            session_total,
+        });
+        session.send_event(turn_context, event).await;
+    }
+    session
+        .record_conversation_items(turn_context, &[item])
+        .await;
+}
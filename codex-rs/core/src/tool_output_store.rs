@@ -0,0 +1,155 @@
+//! Full outputs of the tool calls made in a session.
+//!
+//! Outputs sent to the model are truncated to fit its context window. The
+//! store keeps the untruncated text so that the `read_tool_output` tool can
+//! page through it later, until the session ends or the store is full and
+//! evicts the oldest outputs. A resumed session starts with the outputs
+//! recorded in its rollout, which are the copies the model saw.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use codex_protocol::models::ResponseItem;
+
+/// Outputs are evicted, oldest first, once together they exceed this size.
+const MAX_TOTAL_BYTES: usize = 64 * 1024 * 1024;
+
+pub(crate) struct ToolOutputStore {
+    enabled: bool,
+    max_total_bytes: usize,
+    inner: Mutex<Outputs>,
+}
+
+#[derive(Default)]
+struct Outputs {
+    by_call_id: HashMap<String, String>,
+    order: VecDeque<String>,
+    total_bytes: usize,
+}
+
+impl ToolOutputStore {
+    /// A disabled store keeps nothing.
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self::with_capacity(enabled, MAX_TOTAL_BYTES)
+    }
+
+    fn with_capacity(enabled: bool, max_total_bytes: usize) -> Self {
+        Self {
+            enabled,
+            max_total_bytes,
+            inner: Mutex::new(Outputs::default()),
+        }
+    }
+
+    /// Keeps the output of `call_id` unless one is already stored, so the
+    /// first, most complete, copy of an output wins.
+    pub(crate) fn insert(&self, call_id: &str, output: &str) {
+        if !self.enabled || output.len() > self.max_total_bytes {
+            return;
+        }
+        let Ok(mut outputs) = self.inner.lock() else {
+            return;
+        };
+        if outputs.by_call_id.contains_key(call_id) {
+            return;
+        }
+        outputs.total_bytes += output.len();
+        outputs
+            .by_call_id
+            .insert(call_id.to_string(), output.to_string());
+        outputs.order.push_back(call_id.to_string());
+        while outputs.total_bytes > self.max_total_bytes {
+            let Some(oldest) = outputs.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = outputs.by_call_id.remove(&oldest) {
+                outputs.total_bytes -= evicted.len();
+            }
+        }
+    }
+
+    /// Keeps the output carried by a tool output item; other items are
+    /// ignored.
+    pub(crate) fn insert_item(&self, item: &ResponseItem) {
+        match item {
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                self.insert(call_id, &output.content);
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => self.insert(call_id, output),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn get(&self, call_id: &str) -> Option<String> {
+        let outputs = self.inner.lock().ok()?;
+        outputs.by_call_id.get(call_id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn keeps_the_first_output_of_a_call() {
+        let store = ToolOutputStore::new(true);
+        store.insert("call-1", "full output");
+        store.insert("call-1", "truncated");
+        assert_eq!(store.get("call-1").as_deref(), Some("full output"));
+        assert_eq!(store.get("call-2"), None);
+    }
+
+    #[test]
+    fn evicts_the_oldest_outputs_when_full() {
+        let store = ToolOutputStore::with_capacity(true, 10);
+        store.insert("a", "12345");
+        store.insert("b", "12345");
+        store.insert("c", "123");
+        store.insert("too-big", "12345678901");
+
+        assert_eq!(store.get("a"), None);
+        assert_eq!(store.get("b").as_deref(), Some("12345"));
+        assert_eq!(store.get("c").as_deref(), Some("123"));
+        assert_eq!(store.get("too-big"), None);
+    }
+
+    #[test]
+    fn keeps_outputs_of_tool_output_items_only() {
+        let store = ToolOutputStore::new(true);
+        store.insert_item(&ResponseItem::FunctionCallOutput {
+            call_id: "call-1".to_string(),
+            output: FunctionCallOutputPayload {
+                content: "function output".to_string(),
+                ..Default::default()
+            },
+        });
+        store.insert_item(&ResponseItem::CustomToolCallOutput {
+            call_id: "call-2".to_string(),
+            output: "custom output".to_string(),
+        });
+        store.insert_item(&ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{}".to_string(),
+            call_id: "call-3".to_string(),
+        });
+
+        assert_eq!(store.get("call-1").as_deref(), Some("function output"));
+        assert_eq!(store.get("call-2").as_deref(), Some("custom output"));
+        assert_eq!(store.get("call-3"), None);
+    }
+
+    #[test]
+    fn disabled_store_keeps_nothing() {
+        let store = ToolOutputStore::new(false);
+        store.insert("call-1", "output");
+        assert_eq!(store.get("call-1"), None);
+    }
+}
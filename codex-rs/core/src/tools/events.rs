@@ -1,3 +1,4 @@
+use crate::audit::AuditEvent;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
@@ -12,6 +13,7 @@ use crate::protocol::ExecCommandSource;
 use crate::protocol::FileChange;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
+use crate::protocol::ResourceLimit;
 use crate::protocol::TurnDiffEvent;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::sandboxing::ToolError;
@@ -128,6 +130,9 @@ impl ToolEmitter {
     }
 
     pub async fn emit(&self, ctx: ToolEventCtx<'_>, stage: ToolEventStage) {
+        if let Some(event) = self.audit_event(ctx.call_id, &stage) {
+            ctx.session.services.audit.record(event).await;
+        }
         match (self, stage) {
             (
                 Self::Shell {
@@ -140,40 +145,13 @@ impl ToolEmitter {
                 emit_exec_command_begin(ctx, command, cwd.as_path(), *source, None).await;
             }
             (Self::Shell { .. }, ToolEventStage::Success(output)) => {
-                emit_exec_end(
-                    ctx,
-                    output.stdout.text.clone(),
-                    output.stderr.text.clone(),
-                    output.aggregated_output.text.clone(),
-                    output.exit_code,
-                    output.duration,
-                    format_exec_output_str(&output),
-                )
-                .await;
+                emit_exec_end(ctx, ExecEnd::from_output(&output)).await;
             }
             (Self::Shell { .. }, ToolEventStage::Failure(ToolEventFailure::Output(output))) => {
-                emit_exec_end(
-                    ctx,
-                    output.stdout.text.clone(),
-                    output.stderr.text.clone(),
-                    output.aggregated_output.text.clone(),
-                    output.exit_code,
-                    output.duration,
-                    format_exec_output_str(&output),
-                )
-                .await;
+                emit_exec_end(ctx, ExecEnd::from_output(&output)).await;
             }
             (Self::Shell { .. }, ToolEventStage::Failure(ToolEventFailure::Message(message))) => {
-                emit_exec_end(
-                    ctx,
-                    String::new(),
-                    (*message).to_string(),
-                    (*message).to_string(),
-                    -1,
-                    Duration::ZERO,
-                    message.clone(),
-                )
-                .await;
+                emit_exec_end(ctx, ExecEnd::from_message(&message)).await;
             }
 
             (
@@ -244,50 +222,57 @@ impl ToolEmitter {
                 .await;
             }
             (Self::UnifiedExec { .. }, ToolEventStage::Success(output)) => {
-                emit_exec_end(
-                    ctx,
-                    output.stdout.text.clone(),
-                    output.stderr.text.clone(),
-                    output.aggregated_output.text.clone(),
-                    output.exit_code,
-                    output.duration,
-                    format_exec_output_str(&output),
-                )
-                .await;
+                emit_exec_end(ctx, ExecEnd::from_output(&output)).await;
             }
             (
                 Self::UnifiedExec { .. },
                 ToolEventStage::Failure(ToolEventFailure::Output(output)),
             ) => {
-                emit_exec_end(
-                    ctx,
-                    output.stdout.text.clone(),
-                    output.stderr.text.clone(),
-                    output.aggregated_output.text.clone(),
-                    output.exit_code,
-                    output.duration,
-                    format_exec_output_str(&output),
-                )
-                .await;
+                emit_exec_end(ctx, ExecEnd::from_output(&output)).await;
             }
             (
                 Self::UnifiedExec { .. },
                 ToolEventStage::Failure(ToolEventFailure::Message(message)),
             ) => {
-                emit_exec_end(
-                    ctx,
-                    String::new(),
-                    (*message).to_string(),
-                    (*message).to_string(),
-                    -1,
-                    Duration::ZERO,
-                    message.clone(),
-                )
-                .await;
+                emit_exec_end(ctx, ExecEnd::from_message(&message)).await;
             }
         }
     }
 
+    /// The audit log entry for the end of the call, or `None` at its start.
+    fn audit_event(&self, call_id: &str, stage: &ToolEventStage) -> Option<AuditEvent> {
+        let exit_code = match stage {
+            ToolEventStage::Begin => return None,
+            ToolEventStage::Success(output)
+            | ToolEventStage::Failure(ToolEventFailure::Output(output)) => output.exit_code,
+            ToolEventStage::Failure(ToolEventFailure::Message(_)) => -1,
+        };
+        let event = match self {
+            Self::Shell { command, cwd, .. } | Self::UnifiedExec { command, cwd, .. } => {
+                AuditEvent::Command {
+                    call_id: call_id.to_string(),
+                    command: command.clone(),
+                    cwd: cwd.clone(),
+                    exit_code,
+                }
+            }
+            Self::ApplyPatch {
+                changes,
+                auto_approved,
+            } => {
+                let mut paths: Vec<PathBuf> = changes.keys().cloned().collect();
+                paths.sort();
+                AuditEvent::Patch {
+                    call_id: call_id.to_string(),
+                    paths,
+                    auto_approved: *auto_approved,
+                    success: exit_code == 0,
+                }
+            }
+        };
+        Some(event)
+    }
+
     pub async fn begin(&self, ctx: ToolEventCtx<'_>) {
         self.emit(ctx, ToolEventStage::Begin).await;
     }
@@ -300,6 +285,10 @@ impl ToolEmitter {
         let (event, result) = match out {
             Ok(output) => {
                 let content = super::format_exec_output_for_model(&output);
+                ctx.session
+                    .services
+                    .tool_outputs
+                    .insert(ctx.call_id, &output.aggregated_output.text);
                 let exit_code = output.exit_code;
                 let event = ToolEventStage::Success(output);
                 let result = if exit_code == 0 {
@@ -310,8 +299,13 @@ impl ToolEmitter {
                 (event, result)
             }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output })))
+            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::ResourceLimit { output })))
             | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
                 let response = super::format_exec_output_for_model(&output);
+                ctx.session
+                    .services
+                    .tool_outputs
+                    .insert(ctx.call_id, &output.aggregated_output.text);
                 let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
                 let result = Err(FunctionCallError::RespondToModel(response));
                 (event, result)
@@ -340,15 +334,53 @@ impl ToolEmitter {
     }
 }
 
-async fn emit_exec_end(
-    ctx: ToolEventCtx<'_>,
+/// Contents of an [`ExecCommandEndEvent`] besides the call id.
+struct ExecEnd {
     stdout: String,
     stderr: String,
     aggregated_output: String,
     exit_code: i32,
     duration: Duration,
     formatted_output: String,
-) {
+    resource_limit_exceeded: Option<ResourceLimit>,
+}
+
+impl ExecEnd {
+    fn from_output(output: &ExecToolCallOutput) -> Self {
+        Self {
+            stdout: output.stdout.text.clone(),
+            stderr: output.stderr.text.clone(),
+            aggregated_output: output.aggregated_output.text.clone(),
+            exit_code: output.exit_code,
+            duration: output.duration,
+            formatted_output: format_exec_output_str(output),
+            resource_limit_exceeded: output.resource_limit_exceeded,
+        }
+    }
+
+    fn from_message(message: &str) -> Self {
+        Self {
+            stdout: String::new(),
+            stderr: message.to_string(),
+            aggregated_output: message.to_string(),
+            exit_code: -1,
+            duration: Duration::ZERO,
+            formatted_output: message.to_string(),
+            resource_limit_exceeded: None,
+        }
+    }
+}
+
+async fn emit_exec_end(ctx: ToolEventCtx<'_>, end: ExecEnd) {
+    let ExecEnd {
+        stdout,
+        stderr,
+        aggregated_output,
+        exit_code,
+        duration,
+        formatted_output,
+        resource_limit_exceeded,
+    } = end;
     ctx.session
         .send_event(
             ctx.turn,
@@ -360,6 +392,7 @@ async fn emit_exec_end(
                 exit_code,
                 duration,
                 formatted_output,
+                resource_limit_exceeded,
             }),
         )
         .await;
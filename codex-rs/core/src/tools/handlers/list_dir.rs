@@ -10,6 +10,7 @@ use serde::Deserialize;
 use tokio::fs;
 
 use crate::function_tool::FunctionCallError;
+use crate::sandboxing::read_deny::ReadDenyList;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -51,7 +52,7 @@ impl ToolHandler for ListDirHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -100,7 +101,15 @@ impl ToolHandler for ListDirHandler {
             ));
         }
 
-        let entries = list_dir_slice(&path, offset, limit, depth).await?;
+        let read_deny = ReadDenyList::new(&turn.sandbox_policy, &turn.sandbox_read_deny);
+        if read_deny.is_denied(&path) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "listing `{}` is denied by the sandbox policy",
+                path.display()
+            )));
+        }
+
+        let entries = list_dir_slice(&path, offset, limit, depth, &read_deny).await?;
         let mut output = Vec::with_capacity(entries.len() + 1);
         output.push(format!("Absolute path: {}", path.display()));
         output.extend(entries);
@@ -117,9 +126,10 @@ async fn list_dir_slice(
     offset: usize,
     limit: usize,
     depth: usize,
+    read_deny: &ReadDenyList,
 ) -> Result<Vec<String>, FunctionCallError> {
     let mut entries = Vec::new();
-    collect_entries(path, Path::new(""), depth, &mut entries).await?;
+    collect_entries(path, Path::new(""), depth, read_deny, &mut entries).await?;
 
     if entries.is_empty() {
         return Ok(Vec::new());
@@ -154,6 +164,7 @@ async fn collect_entries(
     dir_path: &Path,
     relative_prefix: &Path,
     depth: usize,
+    read_deny: &ReadDenyList,
     entries: &mut Vec<DirEntry>,
 ) -> Result<(), FunctionCallError> {
     let mut queue = VecDeque::new();
@@ -169,6 +180,12 @@ async fn collect_entries(
         while let Some(entry) = read_dir.next_entry().await.map_err(|err| {
             FunctionCallError::RespondToModel(format!("failed to read directory: {err}"))
         })? {
+            // Denied entries are hidden entirely rather than listed without
+            // their contents.
+            if read_deny.is_denied(&entry.path()) {
+                continue;
+            }
+
             let file_type = entry.file_type().await.map_err(|err| {
                 FunctionCallError::RespondToModel(format!("failed to inspect entry: {err}"))
             })?;
@@ -307,7 +324,7 @@ mod tests {
             symlink(dir_path.join("entry.txt"), &link_path).expect("create symlink");
         }
 
-        let entries = list_dir_slice(dir_path, 1, 20, 3)
+        let entries = list_dir_slice(dir_path, 1, 20, 3, &ReadDenyList::default())
             .await
             .expect("list directory");
 
@@ -341,7 +358,7 @@ mod tests {
             .await
             .expect("create sub dir");
 
-        let err = list_dir_slice(dir_path, 10, 1, 2)
+        let err = list_dir_slice(dir_path, 10, 1, 2, &ReadDenyList::default())
             .await
             .expect_err("offset exceeds entries");
         assert_eq!(
@@ -368,7 +385,7 @@ mod tests {
             .await
             .expect("write deeper");
 
-        let entries_depth_one = list_dir_slice(dir_path, 1, 10, 1)
+        let entries_depth_one = list_dir_slice(dir_path, 1, 10, 1, &ReadDenyList::default())
             .await
             .expect("list depth 1");
         assert_eq!(
@@ -376,7 +393,7 @@ mod tests {
             vec!["nested/".to_string(), "root.txt".to_string(),]
         );
 
-        let entries_depth_two = list_dir_slice(dir_path, 1, 20, 2)
+        let entries_depth_two = list_dir_slice(dir_path, 1, 20, 2, &ReadDenyList::default())
             .await
             .expect("list depth 2");
         assert_eq!(
@@ -389,7 +406,7 @@ mod tests {
             ]
         );
 
-        let entries_depth_three = list_dir_slice(dir_path, 1, 30, 3)
+        let entries_depth_three = list_dir_slice(dir_path, 1, 30, 3, &ReadDenyList::default())
             .await
             .expect("list depth 3");
         assert_eq!(
@@ -418,7 +435,7 @@ mod tests {
             .await
             .expect("write gamma");
 
-        let entries = list_dir_slice(dir_path, 2, usize::MAX, 1)
+        let entries = list_dir_slice(dir_path, 2, usize::MAX, 1, &ReadDenyList::default())
             .await
             .expect("list without overflow");
         assert_eq!(
@@ -439,7 +456,7 @@ mod tests {
                 .expect("write file");
         }
 
-        let entries = list_dir_slice(dir_path, 1, 25, 1)
+        let entries = list_dir_slice(dir_path, 1, 25, 1, &ReadDenyList::default())
             .await
             .expect("list directory");
         assert_eq!(entries.len(), 26);
@@ -461,7 +478,8 @@ mod tests {
         tokio::fs::write(nested.join("child.txt"), b"child").await?;
         tokio::fs::write(deeper.join("grandchild.txt"), b"deep").await?;
 
-        let entries_depth_three = list_dir_slice(dir_path, 1, 3, 3).await?;
+        let entries_depth_three =
+            list_dir_slice(dir_path, 1, 3, 3, &ReadDenyList::default()).await?;
         assert_eq!(
             entries_depth_three,
             vec![
@@ -474,4 +492,21 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn hides_read_denied_entries() -> anyhow::Result<()> {
+        let temp = tempdir()?;
+        let dir_path = temp.path();
+        let ssh = dir_path.join(".ssh");
+        tokio::fs::create_dir(&ssh).await?;
+        tokio::fs::write(ssh.join("id_ed25519"), b"key").await?;
+        tokio::fs::write(dir_path.join(".env"), b"TOKEN=1").await?;
+        tokio::fs::write(dir_path.join("main.rs"), b"fn main() {}").await?;
+
+        let read_deny = ReadDenyList::from_globs(&[".env".to_string(), ".ssh".to_string()]);
+        let entries = list_dir_slice(dir_path, 1, 10, 2, &read_deny).await?;
+        assert_eq!(entries, vec!["main.rs".to_string()]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::function_tool::FunctionCallError;
+use crate::memory::Memory;
+use crate::memory::MemoryScope;
+use crate::memory::MemoryStore;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct MemoryHandler;
+
+#[derive(Deserialize)]
+struct RememberArgs {
+    memory: String,
+    #[serde(default)]
+    scope: Option<MemoryScope>,
+}
+
+#[derive(Deserialize)]
+struct RecallArgs {
+    #[serde(default)]
+    query: Option<String>,
+    #[serde(default)]
+    scope: Option<MemoryScope>,
+}
+
+#[derive(Deserialize)]
+struct ForgetArgs {
+    memory: String,
+    #[serde(default)]
+    scope: Option<MemoryScope>,
+}
+
+#[async_trait]
+impl ToolHandler for MemoryHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            turn,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "{tool_name} handler received unsupported payload"
+                )));
+            }
+        };
+
+        let store = MemoryStore::new(&turn.client.config().codex_home, &turn.cwd);
+        let content = match tool_name.as_str() {
+            "remember" => remember(&store, parse_args(&arguments)?),
+            "recall" => recall(&store, parse_args(&arguments)?),
+            "forget" => forget(&store, parse_args(&arguments)?),
+            other => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "unsupported memory tool: {other}"
+                )));
+            }
+        }?;
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn parse_args<T: DeserializeOwned>(arguments: &str) -> Result<T, FunctionCallError> {
+    serde_json::from_str(arguments).map_err(|e| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+    })
+}
+
+fn io_error(e: std::io::Error) -> FunctionCallError {
+    FunctionCallError::RespondToModel(format!("failed to access memories: {e}"))
+}
+
+fn remember(store: &MemoryStore, args: RememberArgs) -> Result<String, FunctionCallError> {
+    let scope = args.scope.unwrap_or(MemoryScope::Project);
+    if store.remember(scope, &args.memory).map_err(io_error)? {
+        Ok(format!("Saved to {scope} memories."))
+    } else {
+        Ok(format!("Already in {scope} memories."))
+    }
+}
+
+fn recall(store: &MemoryStore, args: RecallArgs) -> Result<String, FunctionCallError> {
+    let query = args.query.unwrap_or_default();
+    let memories = store.recall(&query, args.scope).map_err(io_error)?;
+    if memories.is_empty() {
+        return Ok("No memories found.".to_string());
+    }
+    Ok(format_memories(&memories))
+}
+
+fn forget(store: &MemoryStore, args: ForgetArgs) -> Result<String, FunctionCallError> {
+    let matches = store.find(&args.memory, args.scope).map_err(io_error)?;
+    // An exact match wins even when the text also appears in longer memories.
+    let target = match matches.as_slice() {
+        [] => {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "no memory matches `{}`",
+                args.memory
+            )));
+        }
+        [only] => only,
+        _ => match matches
+            .iter()
+            .find(|memory| memory.text.eq_ignore_ascii_case(args.memory.trim()))
+        {
+            Some(exact) => exact,
+            None => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "`{}` matches more than one memory; be more specific:\n{}",
+                    args.memory,
+                    format_memories(&matches)
+                )));
+            }
+        },
+    };
+
+    if store.forget(target).map_err(io_error)? {
+        Ok(format!("Forgot {} memory: {}", target.scope, target.text))
+    } else {
+        Err(FunctionCallError::RespondToModel(format!(
+            "memory `{}` was already removed",
+            target.text
+        )))
+    }
+}
+
+fn format_memories(memories: &[Memory]) -> String {
+    memories
+        .iter()
+        .map(|memory| format!("- ({}) {}", memory.scope, memory.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn store(tmp: &TempDir) -> MemoryStore {
+        MemoryStore::new(&tmp.path().join("home"), tmp.path())
+    }
+
+    fn forget_args(memory: &str) -> ForgetArgs {
+        ForgetArgs {
+            memory: memory.to_string(),
+            scope: None,
+        }
+    }
+
+    #[test]
+    fn forget_requires_an_unambiguous_match() {
+        let tmp = TempDir::new().expect("tempdir");
+        let store = store(&tmp);
+        store
+            .remember(MemoryScope::Project, "uses pnpm")
+            .expect("remember");
+        store
+            .remember(MemoryScope::Global, "uses pnpm for scripts")
+            .expect("remember");
+
+        assert!(forget(&store, forget_args("pnpm")).is_err());
+        assert!(forget(&store, forget_args("yarn")).is_err());
+
+        assert_eq!(
+            forget(&store, forget_args("uses pnpm")).ok(),
+            Some("Forgot project memory: uses pnpm".to_string())
+        );
+        assert_eq!(
+            forget(&store, forget_args("pnpm")).ok(),
+            Some("Forgot global memory: uses pnpm for scripts".to_string())
+        );
+    }
+
+    #[test]
+    fn recall_reports_empty_store() {
+        let tmp = TempDir::new().expect("tempdir");
+        let store = store(&tmp);
+        assert_eq!(
+            recall(
+                &store,
+                RecallArgs {
+                    query: None,
+                    scope: None,
+                }
+            )
+            .ok(),
+            Some("No memories found.".to_string())
+        );
+    }
+}
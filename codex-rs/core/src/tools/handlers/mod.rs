@@ -3,9 +3,12 @@ mod grep_files;
 mod list_dir;
 mod mcp;
 mod mcp_resource;
+mod memory;
 mod plan;
 mod read_file;
+mod read_tool_output;
 mod shell;
+mod spawn_agent;
 mod test_sync;
 mod unified_exec;
 mod view_image;
@@ -17,10 +20,13 @@ pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
+pub use memory::MemoryHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
+pub use read_tool_output::ReadToolOutputHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
+pub use spawn_agent::SpawnAgentHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
@@ -6,6 +6,7 @@ use codex_utils_string::take_bytes_at_char_boundary;
 use serde::Deserialize;
 
 use crate::function_tool::FunctionCallError;
+use crate::sandboxing::read_deny::ReadDenyList;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -96,7 +97,7 @@ impl ToolHandler for ReadFileHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -140,6 +141,13 @@ impl ToolHandler for ReadFileHandler {
             ));
         }
 
+        if ReadDenyList::new(&turn.sandbox_policy, &turn.sandbox_read_deny).is_denied(&path) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "reading `{}` is denied by the sandbox policy",
+                path.display()
+            )));
+        }
+
         let collected = match mode {
             ReadMode::Slice => slice::read(&path, offset, limit).await?,
             ReadMode::Indentation => {
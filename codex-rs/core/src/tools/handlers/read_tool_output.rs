@@ -0,0 +1,346 @@
+use async_trait::async_trait;
+use codex_utils_string::take_bytes_at_char_boundary;
+use regex_lite::Regex;
+use serde::Deserialize;
+
+use crate::context_manager::MODEL_FORMAT_MAX_BYTES;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct ReadToolOutputHandler;
+
+const MAX_LINE_LENGTH: usize = 500;
+
+/// JSON arguments accepted by the `read_tool_output` tool handler.
+#[derive(Deserialize)]
+struct ReadToolOutputArgs {
+    /// Call id of the earlier tool call whose output is read.
+    call_id: String,
+    /// 1-indexed line number to start reading from; defaults to 1.
+    #[serde(default = "default_offset")]
+    offset: usize,
+    /// 1-indexed byte column to start reading the `offset` line from, for
+    /// resuming a long line; defaults to 1.
+    #[serde(default = "default_column")]
+    column: usize,
+    /// Maximum number of lines to return; defaults to 200.
+    #[serde(default = "default_limit")]
+    limit: usize,
+    /// Optional regex; only matching lines are returned.
+    #[serde(default)]
+    grep: Option<String>,
+}
+
+fn default_offset() -> usize {
+    1
+}
+
+fn default_column() -> usize {
+    1
+}
+
+fn default_limit() -> usize {
+    200
+}
+
+#[async_trait]
+impl ToolHandler for ReadToolOutputHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "read_tool_output handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ReadToolOutputArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let Some(output) = session.services.tool_outputs.get(&args.call_id) else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "no stored output for call_id `{}`",
+                args.call_id
+            )));
+        };
+
+        // Mask the whole output before it is cut into lines and pieces, so a
+        // secret cannot slip past the patterns by being split across them.
+        let (output, _) = session.services.redactor.redact(&output);
+        let content = read_page(&output, &args)?;
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Returns up to `limit` lines of `output` starting at `offset`, keeping only
+/// lines matching `grep` when set. Lines longer than `MAX_LINE_LENGTH` bytes
+/// are split into pieces labelled with their byte column. The page ends early
+/// rather than exceed what the model is shown of a tool output, and says where
+/// the next page starts.
+fn read_page(output: &str, args: &ReadToolOutputArgs) -> Result<String, FunctionCallError> {
+    if args.offset == 0 {
+        return Err(FunctionCallError::RespondToModel(
+            "offset must be a 1-indexed line number".to_string(),
+        ));
+    }
+    if args.column == 0 {
+        return Err(FunctionCallError::RespondToModel(
+            "column must be a 1-indexed byte column".to_string(),
+        ));
+    }
+    if args.limit == 0 {
+        return Err(FunctionCallError::RespondToModel(
+            "limit must be greater than zero".to_string(),
+        ));
+    }
+    let grep = args
+        .grep
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .map_err(|err| FunctionCallError::RespondToModel(format!("invalid grep pattern: {err}")))?;
+
+    let total_lines = output.lines().count();
+    if total_lines == 0 && args.offset == 1 {
+        return Ok("[end of output, 0 lines in total]".to_string());
+    }
+    if args.offset > total_lines {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "offset exceeds output length of {total_lines} lines"
+        )));
+    }
+
+    let mut page = Vec::new();
+    let mut page_lines = 0;
+    let mut bytes = 0;
+    let mut next = None;
+    let lines = output.lines().enumerate().skip(args.offset - 1);
+    'lines: for (index, line) in lines {
+        if grep.as_ref().is_some_and(|grep| !grep.is_match(line)) {
+            continue;
+        }
+        if page_lines == args.limit {
+            next = Some((index + 1, 1));
+            break;
+        }
+        let start = if index + 1 == args.offset {
+            column_start(line, index + 1, args.column)?
+        } else {
+            0
+        };
+        let split = start > 0 || line.len() > MAX_LINE_LENGTH;
+        for (column, piece) in line_pieces(line, start) {
+            let formatted = if split {
+                format!("L{}:{column}: {piece}", index + 1)
+            } else {
+                format!("L{}: {piece}", index + 1)
+            };
+            if bytes + formatted.len() + 1 > MODEL_FORMAT_MAX_BYTES {
+                next = Some((index + 1, column));
+                break 'lines;
+            }
+            bytes += formatted.len() + 1;
+            page.push(formatted);
+        }
+        page_lines += 1;
+    }
+
+    if page.is_empty() && next.is_none() {
+        return Ok(format!(
+            "No lines matched from line {} to {total_lines}.",
+            args.offset
+        ));
+    }
+    let mut content = page.join("\n");
+    match next {
+        Some((offset, 1)) => content.push_str(&format!(
+            "\n[{total_lines} lines in total; continue with offset {offset}]"
+        )),
+        Some((offset, column)) => content.push_str(&format!(
+            "\n[{total_lines} lines in total; continue with offset {offset} and column {column}]"
+        )),
+        None => content.push_str(&format!("\n[end of output, {total_lines} lines in total]")),
+    }
+    Ok(content)
+}
+
+/// Converts the 1-indexed byte `column` of line `number` into a byte index,
+/// moved back to the start of the character it falls in.
+fn column_start(line: &str, number: usize, column: usize) -> Result<usize, FunctionCallError> {
+    let mut start = column - 1;
+    if start > 0 && start >= line.len() {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "column exceeds length of line {number} ({} bytes)",
+            line.len()
+        )));
+    }
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    Ok(start)
+}
+
+/// Splits `line` from byte `start` into pieces of at most `MAX_LINE_LENGTH`
+/// bytes, cut at character boundaries, each with its 1-indexed byte column.
+fn line_pieces(line: &str, mut start: usize) -> Vec<(usize, &str)> {
+    let mut pieces = Vec::new();
+    loop {
+        let piece = take_bytes_at_char_boundary(&line[start..], MAX_LINE_LENGTH);
+        pieces.push((start + 1, piece));
+        start += piece.len();
+        if start >= line.len() || piece.is_empty() {
+            return pieces;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn args(offset: usize, limit: usize, grep: Option<&str>) -> ReadToolOutputArgs {
+        ReadToolOutputArgs {
+            call_id: "call-1".to_string(),
+            offset,
+            column: 1,
+            limit,
+            grep: grep.map(str::to_string),
+        }
+    }
+
+    fn page(output: &str, args: &ReadToolOutputArgs) -> String {
+        match read_page(output, args) {
+            Ok(content) => content,
+            Err(err) => panic!("read_page failed: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn pages_through_lines() {
+        let output = "one\ntwo\nthree\nfour";
+        assert_eq!(
+            page(output, &args(2, 2, None)),
+            "L2: two\nL3: three\n[4 lines in total; continue with offset 4]"
+        );
+        assert_eq!(
+            page(output, &args(4, 2, None)),
+            "L4: four\n[end of output, 4 lines in total]"
+        );
+    }
+
+    #[test]
+    fn grep_keeps_matching_lines_and_their_numbers() {
+        let output = "test a ... ok\ntest b ... FAILED\ntest c ... ok\ntest d ... FAILED";
+        assert_eq!(
+            page(output, &args(1, 1, Some("FAILED"))),
+            "L2: test b ... FAILED\n[4 lines in total; continue with offset 4]"
+        );
+        assert_eq!(
+            page(output, &args(1, 10, Some("panicked"))),
+            "No lines matched from line 1 to 4."
+        );
+    }
+
+    #[test]
+    fn pages_stop_before_the_model_output_limit() {
+        let output = format!("{}\n", "x".repeat(400)).repeat(100);
+        let content = page(&output, &args(1, 100, None));
+        assert!(
+            content.len() <= MODEL_FORMAT_MAX_BYTES + 64,
+            "{}",
+            content.len()
+        );
+        assert!(content.ends_with("lines in total; continue with offset 26]"));
+    }
+
+    #[test]
+    fn long_lines_are_split_and_can_be_resumed_by_column() {
+        let output = format!(
+            "{}{}{}\nshort",
+            "a".repeat(500),
+            "b".repeat(500),
+            "c".repeat(20)
+        );
+        assert_eq!(
+            page(&output, &args(1, 10, None)),
+            format!(
+                "L1:1: {}\nL1:501: {}\nL1:1001: {}\nL2: short\n[end of output, 2 lines in total]",
+                "a".repeat(500),
+                "b".repeat(500),
+                "c".repeat(20)
+            )
+        );
+
+        let mut resumed = args(1, 10, None);
+        resumed.column = 1001;
+        assert_eq!(
+            page(&output, &resumed),
+            format!(
+                "L1:1001: {}\nL2: short\n[end of output, 2 lines in total]",
+                "c".repeat(20)
+            )
+        );
+    }
+
+    #[test]
+    fn pages_stop_inside_a_line_longer_than_the_model_output_limit() {
+        let output = "x".repeat(MODEL_FORMAT_MAX_BYTES * 2);
+        let content = page(&output, &args(1, 1, None));
+        assert!(content.len() <= MODEL_FORMAT_MAX_BYTES + 64);
+        let Some((_, hint)) = content.rsplit_once("continue with offset 1 and column ") else {
+            panic!("expected a column to continue from: {content}");
+        };
+        let column: usize = match hint.trim_end_matches(']').parse() {
+            Ok(column) => column,
+            Err(err) => panic!("invalid column in {hint}: {err}"),
+        };
+
+        let mut resumed = args(1, 1, None);
+        resumed.column = column;
+        assert!(page(&output, &resumed).starts_with(&format!("L1:{column}: x")));
+    }
+
+    #[test]
+    fn empty_outputs_read_as_an_empty_page() {
+        assert_eq!(
+            page("", &args(1, 10, None)),
+            "[end of output, 0 lines in total]"
+        );
+        assert!(read_page("", &args(2, 10, None)).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(read_page("one", &args(0, 1, None)).is_err());
+        assert!(read_page("one", &args(1, 0, None)).is_err());
+        assert!(read_page("one", &args(2, 1, None)).is_err());
+        assert!(read_page("one", &args(1, 1, Some("("))).is_err());
+
+        let mut past_the_line = args(1, 1, None);
+        past_the_line.column = 4;
+        assert!(read_page("one", &past_the_line).is_err());
+        past_the_line.column = 0;
+        assert!(read_page("one", &past_the_line).is_err());
+    }
+}
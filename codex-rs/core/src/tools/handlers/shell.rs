@@ -1,14 +1,16 @@
 use async_trait::async_trait;
 use codex_protocol::models::ShellCommandToolCallParams;
 use codex_protocol::models::ShellToolCallParams;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::apply_patch;
 use crate::apply_patch::InternalApplyPatchInvocation;
 use crate::apply_patch::convert_apply_patch_to_protocol;
 use crate::codex::TurnContext;
-use crate::exec::ExecParams;
+use crate::config::types::ShellEnvironmentPolicy;
 use crate::exec_env::create_env;
+use crate::exec_env::create_env_for_profile;
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
 use crate::protocol::ExecCommandSource;
@@ -31,41 +33,72 @@ pub struct ShellHandler;
 pub struct ShellCommandHandler;
 
 impl ShellHandler {
-    fn to_exec_params(params: ShellToolCallParams, turn_context: &TurnContext) -> ExecParams {
-        ExecParams {
+    fn to_request(
+        params: ShellToolCallParams,
+        turn_context: &TurnContext,
+    ) -> Result<ShellRequest, FunctionCallError> {
+        Ok(ShellRequest {
             command: params.command,
             cwd: turn_context.resolve_path(params.workdir.clone()),
             timeout_ms: params.timeout_ms,
-            env: create_env(&turn_context.shell_environment_policy),
+            env: command_env(
+                &turn_context.shell_environment_policy,
+                params.env_profile.as_deref(),
+            )?,
             with_escalated_permissions: params.with_escalated_permissions,
             justification: params.justification,
-            arg0: None,
-        }
+            env_profile: params.env_profile,
+        })
     }
 }
 
 impl ShellCommandHandler {
-    fn to_exec_params(
+    fn to_request(
         params: ShellCommandToolCallParams,
         session: &crate::codex::Session,
         turn_context: &TurnContext,
-    ) -> ExecParams {
+    ) -> Result<ShellRequest, FunctionCallError> {
         let shell = session.user_shell();
         let use_login_shell = true;
         let command = shell.derive_exec_args(&params.command, use_login_shell);
 
-        ExecParams {
+        Ok(ShellRequest {
             command,
             cwd: turn_context.resolve_path(params.workdir.clone()),
             timeout_ms: params.timeout_ms,
-            env: create_env(&turn_context.shell_environment_policy),
+            env: command_env(
+                &turn_context.shell_environment_policy,
+                params.env_profile.as_deref(),
+            )?,
             with_escalated_permissions: params.with_escalated_permissions,
             justification: params.justification,
-            arg0: None,
-        }
+            env_profile: params.env_profile,
+        })
     }
 }
 
+/// The environment for a command, with the variables of `env_profile` when the
+/// model asked for one.
+fn command_env(
+    policy: &ShellEnvironmentPolicy,
+    env_profile: Option<&str>,
+) -> Result<HashMap<String, String>, FunctionCallError> {
+    let Some(name) = env_profile else {
+        return Ok(create_env(policy));
+    };
+    create_env_for_profile(policy, name).ok_or_else(|| {
+        let available = policy
+            .profiles
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        FunctionCallError::RespondToModel(format!(
+            "unknown environment profile `{name}`; available profiles: [{available}]"
+        ))
+    })
+}
+
 #[async_trait]
 impl ToolHandler for ShellHandler {
     fn kind(&self) -> ToolKind {
@@ -109,10 +142,10 @@ impl ToolHandler for ShellHandler {
                             "failed to parse function arguments: {e:?}"
                         ))
                     })?;
-                let exec_params = Self::to_exec_params(params, turn.as_ref());
+                let req = Self::to_request(params, turn.as_ref())?;
                 Self::run_exec_like(
                     tool_name.as_str(),
-                    exec_params,
+                    req,
                     session,
                     turn,
                     tracker,
@@ -122,10 +155,10 @@ impl ToolHandler for ShellHandler {
                 .await
             }
             ToolPayload::LocalShell { params } => {
-                let exec_params = Self::to_exec_params(params, turn.as_ref());
+                let req = Self::to_request(params, turn.as_ref())?;
                 Self::run_exec_like(
                     tool_name.as_str(),
-                    exec_params,
+                    req,
                     session,
                     turn,
                     tracker,
@@ -170,10 +203,10 @@ impl ToolHandler for ShellCommandHandler {
         let params: ShellCommandToolCallParams = serde_json::from_str(&arguments).map_err(|e| {
             FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
         })?;
-        let exec_params = Self::to_exec_params(params, session.as_ref(), turn.as_ref());
+        let req = Self::to_request(params, session.as_ref(), turn.as_ref())?;
         ShellHandler::run_exec_like(
             tool_name.as_str(),
-            exec_params,
+            req,
             session,
             turn,
             tracker,
@@ -187,7 +220,7 @@ impl ToolHandler for ShellCommandHandler {
 impl ShellHandler {
     async fn run_exec_like(
         tool_name: &str,
-        exec_params: ExecParams,
+        req: ShellRequest,
         session: Arc<crate::codex::Session>,
         turn: Arc<TurnContext>,
         tracker: crate::tools::context::SharedTurnDiffTracker,
@@ -195,7 +228,7 @@ impl ShellHandler {
         is_user_shell_command: bool,
     ) -> Result<ToolOutput, FunctionCallError> {
         // Approval policy guard for explicit escalation in non-OnRequest modes.
-        if exec_params.with_escalated_permissions.unwrap_or(false)
+        if req.with_escalated_permissions.unwrap_or(false)
             && !matches!(
                 turn.approval_policy,
                 codex_protocol::protocol::AskForApproval::OnRequest
@@ -208,10 +241,7 @@ impl ShellHandler {
         }
 
         // Intercept apply_patch if present.
-        match codex_apply_patch::maybe_parse_apply_patch_verified(
-            &exec_params.command,
-            &exec_params.cwd,
-        ) {
+        match codex_apply_patch::maybe_parse_apply_patch_verified(&req.command, &req.cwd) {
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
                 match apply_patch::apply_patch(session.as_ref(), turn.as_ref(), &call_id, changes)
                     .await
@@ -241,7 +271,7 @@ impl ShellHandler {
                         let req = ApplyPatchRequest {
                             patch: apply.action.patch.clone(),
                             cwd: apply.action.cwd.clone(),
-                            timeout_ms: exec_params.timeout_ms,
+                            timeout_ms: req.timeout_ms,
                             user_explicitly_approved: apply.user_explicitly_approved_this_action,
                             codex_exe: turn.codex_linux_sandbox_exe.clone(),
                         };
@@ -291,19 +321,10 @@ impl ShellHandler {
         } else {
             ExecCommandSource::Agent
         };
-        let emitter =
-            ToolEmitter::shell(exec_params.command.clone(), exec_params.cwd.clone(), source);
+        let emitter = ToolEmitter::shell(req.command.clone(), req.cwd.clone(), source);
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         emitter.begin(event_ctx).await;
 
-        let req = ShellRequest {
-            command: exec_params.command.clone(),
-            cwd: exec_params.cwd.clone(),
-            timeout_ms: exec_params.timeout_ms,
-            env: exec_params.env.clone(),
-            with_escalated_permissions: exec_params.with_escalated_permissions,
-            justification: exec_params.justification.clone(),
-        };
         let mut orchestrator = ToolOrchestrator::new();
         let mut runtime = ShellRuntime::new();
         let tool_ctx = ToolCtx {
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::SubAgentSource;
+use codex_protocol::user_input::UserInput;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::codex_delegate::run_codex_conversation_one_shot;
+use crate::features::Feature;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::EventMsg;
+use crate::protocol::SandboxPolicy;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct SpawnAgentHandler;
+
+#[derive(Deserialize)]
+struct SpawnAgentArgs {
+    prompt: String,
+    #[serde(default)]
+    instructions: Option<String>,
+    #[serde(default)]
+    tools: Option<Vec<String>>,
+    #[serde(default)]
+    sandbox: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for SpawnAgentHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "spawn_agent handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SpawnAgentArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+        })?;
+        if args.prompt.trim().is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "prompt must not be empty".to_string(),
+            ));
+        }
+
+        let mut config = turn.client.config().as_ref().clone();
+        config.model = turn.client.get_model();
+        config.model_family = turn.client.get_model_family();
+        config.model_reasoning_effort = turn.client.get_reasoning_effort();
+        config.cwd = turn.cwd.clone();
+        config.approval_policy = turn.approval_policy;
+        config.sandbox_policy =
+            resolve_sandbox_policy(&turn.sandbox_policy, args.sandbox.as_deref())?;
+        // Sub-agents do not spawn sub-agents of their own.
+        config.features.disable(Feature::SpawnAgent);
+        if let Some(tools) = args.tools {
+            config.enabled_tools = Some(tools);
+        }
+        if let Some(instructions) = args.instructions {
+            config.developer_instructions = Some(match config.developer_instructions.take() {
+                Some(existing) => format!("{existing}\n\n{instructions}"),
+                None => instructions,
+            });
+        }
+
+        // The tool future is dropped when the parent turn is interrupted; the
+        // guard makes sure the sub-agent goes down with it.
+        let cancellation_token = CancellationToken::new();
+        let _cancel_on_drop = cancellation_token.clone().drop_guard();
+
+        let io = run_codex_conversation_one_shot(
+            config,
+            Arc::clone(&session.services.auth_manager),
+            vec![UserInput::Text { text: args.prompt }],
+            Arc::clone(&session),
+            Arc::clone(&turn),
+            cancellation_token,
+            None,
+            SubAgentSource::Agent,
+        )
+        .await
+        .map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to start sub-agent: {e}"))
+        })?;
+
+        let mut activity = ActivityLog::default();
+        let mut last_error: Option<String> = None;
+        while let Ok(event) = io.next_event().await {
+            match event.msg {
+                EventMsg::TaskComplete(done) => {
+                    let message = done
+                        .last_agent_message
+                        .or(last_error)
+                        .unwrap_or_else(|| "sub-agent finished without a message".to_string());
+                    return Ok(ToolOutput::Function {
+                        content: activity.format_output(&message),
+                        content_items: None,
+                        success: Some(true),
+                    });
+                }
+                EventMsg::TurnAborted(_) => {
+                    return Err(FunctionCallError::RespondToModel(
+                        "sub-agent was interrupted before finishing".to_string(),
+                    ));
+                }
+                EventMsg::Error(err) => {
+                    last_error = Some(err.message);
+                }
+                msg @ (EventMsg::ExecCommandBegin(_)
+                | EventMsg::ExecCommandEnd(_)
+                | EventMsg::PatchApplyBegin(_)
+                | EventMsg::PatchApplyEnd(_)
+                | EventMsg::McpToolCallBegin(_)
+                | EventMsg::McpToolCallEnd(_)) => {
+                    activity.record(&msg);
+                    session.send_event(turn.as_ref(), msg).await;
+                }
+                _ => {}
+            }
+        }
+
+        Err(FunctionCallError::RespondToModel(match last_error {
+            Some(message) => format!("sub-agent stopped: {message}"),
+            None => "sub-agent stopped before finishing".to_string(),
+        }))
+    }
+}
+
+/// Narrows the parent's sandbox to the one requested by the model. A request
+/// for more access than the parent has is rejected rather than downgraded so
+/// the model learns about the restriction.
+fn resolve_sandbox_policy(
+    parent: &SandboxPolicy,
+    requested: Option<&str>,
+) -> Result<SandboxPolicy, FunctionCallError> {
+    match requested {
+        None => Ok(parent.clone()),
+        Some("read-only") => Ok(SandboxPolicy::ReadOnly),
+        Some("workspace-write") => match parent {
+            SandboxPolicy::ReadOnly => Err(FunctionCallError::RespondToModel(
+                "sandbox `workspace-write` is more permissive than the current `read-only` sandbox"
+                    .to_string(),
+            )),
+            SandboxPolicy::WorkspaceWrite { .. } => Ok(parent.clone()),
+            SandboxPolicy::DangerFullAccess => Ok(SandboxPolicy::new_workspace_write_policy()),
+        },
+        Some(other) => Err(FunctionCallError::RespondToModel(format!(
+            "unknown sandbox `{other}`; expected `read-only` or `workspace-write`"
+        ))),
+    }
+}
+
+/// One-line summaries of what the sub-agent did, returned to the parent model
+/// together with the sub-agent's final message.
+#[derive(Default)]
+struct ActivityLog {
+    pending_commands: HashMap<String, String>,
+    pending_patches: HashMap<String, Vec<PathBuf>>,
+    lines: Vec<String>,
+}
+
+impl ActivityLog {
+    fn record(&mut self, msg: &EventMsg) {
+        match msg {
+            EventMsg::ExecCommandBegin(ev) => {
+                let command = shlex::try_join(ev.command.iter().map(String::as_str))
+                    .unwrap_or_else(|_| ev.command.join(" "));
+                self.pending_commands.insert(ev.call_id.clone(), command);
+            }
+            EventMsg::ExecCommandEnd(ev) => {
+                if let Some(command) = self.pending_commands.remove(&ev.call_id) {
+                    self.lines
+                        .push(format!("ran `{command}` (exit {})", ev.exit_code));
+                }
+            }
+            EventMsg::PatchApplyBegin(ev) => {
+                let mut paths: Vec<PathBuf> = ev.changes.keys().cloned().collect();
+                paths.sort();
+                self.pending_patches.insert(ev.call_id.clone(), paths);
+            }
+            EventMsg::PatchApplyEnd(ev) => {
+                if let Some(paths) = self.pending_patches.remove(&ev.call_id) {
+                    let paths = paths
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let verb = if ev.success {
+                        "patched"
+                    } else {
+                        "failed to patch"
+                    };
+                    self.lines.push(format!("{verb} {paths}"));
+                }
+            }
+            EventMsg::McpToolCallEnd(ev) => {
+                let status = if ev.is_success() { "ok" } else { "failed" };
+                self.lines.push(format!(
+                    "called {}.{} ({status})",
+                    ev.invocation.server, ev.invocation.tool
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    fn format_output(&self, message: &str) -> String {
+        if self.lines.is_empty() {
+            return message.to_string();
+        }
+        let mut output = format!("{message}\n\nSub-agent activity:");
+        for line in &self.lines {
+            output.push_str("\n- ");
+            output.push_str(line);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ExecCommandBeginEvent;
+    use crate::protocol::ExecCommandEndEvent;
+    use crate::protocol::ExecCommandSource;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn sandbox_defaults_to_parent() {
+        let parent = SandboxPolicy::new_workspace_write_policy();
+        assert_eq!(resolve_sandbox_policy(&parent, None).ok(), Some(parent));
+    }
+
+    #[test]
+    fn sandbox_can_be_narrowed() {
+        let parent = SandboxPolicy::DangerFullAccess;
+        assert_eq!(
+            resolve_sandbox_policy(&parent, Some("read-only")).ok(),
+            Some(SandboxPolicy::new_read_only_policy())
+        );
+        assert_eq!(
+            resolve_sandbox_policy(&parent, Some("workspace-write")).ok(),
+            Some(SandboxPolicy::new_workspace_write_policy())
+        );
+    }
+
+    #[test]
+    fn sandbox_cannot_be_widened() {
+        let parent = SandboxPolicy::new_read_only_policy();
+        assert!(resolve_sandbox_policy(&parent, Some("workspace-write")).is_err());
+        assert!(resolve_sandbox_policy(&parent, Some("danger-full-access")).is_err());
+    }
+
+    #[test]
+    fn activity_log_summarizes_commands() {
+        let mut log = ActivityLog::default();
+        log.record(&EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+            call_id: "call-1".to_string(),
+            command: vec!["rg".to_string(), "foo bar".to_string()],
+            cwd: PathBuf::from("/tmp"),
+            parsed_cmd: Vec::new(),
+            source: ExecCommandSource::Agent,
+            interaction_input: None,
+        }));
+        log.record(&EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+            call_id: "call-1".to_string(),
+            stdout: String::new(),
+            stderr: String::new(),
+            aggregated_output: String::new(),
+            exit_code: 1,
+            duration: Duration::from_millis(5),
+            formatted_output: String::new(),
+            resource_limit_exceeded: None,
+        }));
+
+        assert_eq!(
+            log.format_output("done"),
+            "done\n\nSub-agent activity:\n- ran `rg 'foo bar'` (exit 1)"
+        );
+    }
+
+    #[test]
+    fn activity_log_is_omitted_when_empty() {
+        assert_eq!(ActivityLog::default().format_output("done"), "done");
+    }
+}
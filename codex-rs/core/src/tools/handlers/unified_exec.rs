@@ -226,7 +226,22 @@ impl ToolHandler for UnifiedExecHandler {
                 .await;
         }
 
-        let content = format_response(&response);
+        // The model is shown the truncated output; the store keeps the full
+        // text, masked like every stored output, for `read_tool_output`.
+        if let Some(full_output) = &response.full_output
+            && session.services.tool_outputs.is_enabled()
+        {
+            let (full_content, _) = session
+                .services
+                .redactor
+                .redact(&format_response(&response, full_output));
+            session
+                .services
+                .tool_outputs
+                .insert(&call_id, &full_content);
+        }
+
+        let content = format_response(&response, &response.output);
 
         Ok(ToolOutput::Function {
             content,
@@ -241,7 +256,7 @@ fn get_command(args: &ExecCommandArgs) -> Vec<String> {
     shell.derive_exec_args(&args.cmd, args.login)
 }
 
-fn format_response(response: &UnifiedExecResponse) -> String {
+fn format_response(response: &UnifiedExecResponse, output: &str) -> String {
     let mut sections = Vec::new();
 
     if !response.chunk_id.is_empty() {
@@ -264,7 +279,7 @@ fn format_response(response: &UnifiedExecResponse) -> String {
     }
 
     sections.push("Output:".to_string());
-    sections.push(response.output.clone());
+    sections.push(output.to_string());
 
     sections.join("\n")
 }
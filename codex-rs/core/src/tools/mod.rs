@@ -12,6 +12,7 @@ pub mod spec;
 use crate::context_manager::MODEL_FORMAT_MAX_BYTES;
 use crate::context_manager::MODEL_FORMAT_MAX_LINES;
 use crate::context_manager::format_output_for_model_body;
+use crate::error::describe_resource_limit;
 use crate::exec::ExecToolCallOutput;
 pub use router::ToolRouter;
 use serde::Serialize;
@@ -72,6 +73,11 @@ pub fn format_exec_output_str(exec_output: &ExecToolCallOutput) -> String {
             "command timed out after {} milliseconds\n{content}",
             exec_output.duration.as_millis()
         )
+    } else if exec_output.resource_limit_exceeded.is_some() {
+        format!(
+            "command exceeded its {} limit\n{content}",
+            describe_resource_limit(exec_output.resource_limit_exceeded)
+        )
     } else {
         content.to_string()
     };
@@ -2,13 +2,21 @@
 Module: orchestrator
 
 Central place for approvals + sandbox selection + retry semantics. Drives a
-simple sequence for any ToolRuntime: approval → select sandbox → attempt →
-retry without sandbox on denial (no re‑approval thanks to caching).
+simple sequence for any ToolRuntime: exec policy / hook pre-approval →
+optional model risk review → approval → select sandbox → attempt → retry
+without sandbox on denial (no re‑approval thanks to caching).
 */
+use crate::audit::AuditEvent;
+use crate::audit::sandbox_name;
+use crate::command_safety::approval_rules::is_approved_by_rules;
+use crate::command_safety::exec_policy::ExecPolicyDecision;
+use crate::command_safety::is_dangerous_command::command_might_be_dangerous;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::error::get_error_message_ui;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::SandboxType;
+use crate::is_safe_command::is_known_safe_command;
 use crate::sandboxing::SandboxManager;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ProvidesSandboxRetryData;
@@ -16,8 +24,10 @@ use crate::tools::sandboxing::SandboxAttempt;
 use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
+use codex_otel::otel_event_manager::ToolDecisionSource;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::SandboxRiskLevel;
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
@@ -45,21 +55,112 @@ impl ToolOrchestrator {
         let otel = turn_ctx.client.get_otel_event_manager();
         let otel_tn = &tool_ctx.tool_name;
         let otel_ci = &tool_ctx.call_id;
-        let otel_user = codex_otel::otel_event_manager::ToolDecisionSource::User;
-        let otel_cfg = codex_otel::otel_event_manager::ToolDecisionSource::Config;
+        let otel_user = ToolDecisionSource::User;
+        let otel_cfg = ToolDecisionSource::Config;
+
+        // 0) Exec policy: forbidden commands never run, and commands matched by
+        // a policy rule skip the initial prompt unless they ask for escalation.
+        let mut allowed_by_policy = false;
+        if let Some(metadata) = req.sandbox_retry_data() {
+            match tool_ctx.session.services.exec_policy.evaluate(
+                &metadata.command,
+                &metadata.cwd,
+                &turn_ctx.sandbox_policy,
+            ) {
+                ExecPolicyDecision::Forbidden { reason } => {
+                    otel.tool_decision(otel_tn, otel_ci, ReviewDecision::Denied, otel_cfg.clone());
+                    audit_decision(tool_ctx, ReviewDecision::Denied, otel_cfg.clone()).await;
+                    return Err(ToolError::Rejected(format!(
+                        "command forbidden by exec policy: {reason}"
+                    )));
+                }
+                ExecPolicyDecision::Allowed => {
+                    allowed_by_policy = !tool.wants_escalated_first_attempt(req);
+                }
+                ExecPolicyDecision::Unmatched => {}
+            }
+        }
+
+        // A standing approval rule from `[[approval_rules]]` also skips the prompt.
+        if !allowed_by_policy
+            && !tool.wants_escalated_first_attempt(req)
+            && let Some(metadata) = req.sandbox_retry_data()
+        {
+            let rules = tool_ctx.session.services.approval_rules.lock().await;
+            allowed_by_policy = is_approved_by_rules(&rules, &metadata.command, &metadata.cwd);
+        }
+
+        // A pre-tool hook may also pre-approve the call.
+        if tool_ctx
+            .session
+            .services
+            .hooks
+            .take_approval(&tool_ctx.call_id)
+            && !tool.wants_escalated_first_attempt(req)
+        {
+            allowed_by_policy = true;
+        }
+
+        // Pre-execution review: with `[command_assessment]` enabled under
+        // `on-request`, commands not known to be safe are assessed before they
+        // run. Low-risk ones skip the prompt unless they look dangerous; the
+        // rest ask, showing the assessment. Requests for escalated permissions
+        // always ask, so an assessment never lets a command leave the sandbox.
+        let mut risk = None;
+        let mut reviewed = false;
+        let mut flagged_by_review = false;
+        let escalated_first_attempt = tool.wants_escalated_first_attempt(req);
+        let config = turn_ctx.client.config();
+        let assessment = &config.command_assessment;
+        if !allowed_by_policy
+            && !escalated_first_attempt
+            && assessment.enabled
+            && approval_policy == AskForApproval::OnRequest
+            && let Some(metadata) = req.sandbox_retry_data()
+            && !is_known_safe_command(&metadata.command)
+        {
+            risk = tool_ctx
+                .session
+                .assess_sandbox_command(
+                    turn_ctx,
+                    &tool_ctx.call_id,
+                    &metadata.command,
+                    &metadata.cwd,
+                    false,
+                    None,
+                )
+                .await;
+            reviewed = true;
+            match risk.as_ref().map(|risk| risk.risk_level) {
+                Some(SandboxRiskLevel::Low)
+                    if assessment.auto_approve_low_risk
+                        && !command_might_be_dangerous(&metadata.command) =>
+                {
+                    allowed_by_policy = true;
+                }
+                Some(_) => flagged_by_review = true,
+                None => {}
+            }
+        }
 
         // 1) Approval
-        let needs_initial_approval =
-            tool.wants_initial_approval(req, approval_policy, &turn_ctx.sandbox_policy);
+        let needs_initial_approval = !allowed_by_policy
+            && (flagged_by_review
+                || tool.wants_initial_approval(req, approval_policy, &turn_ctx.sandbox_policy));
         let mut already_approved = false;
 
         if needs_initial_approval {
-            let mut risk = None;
-
-            if let Some(metadata) = req.sandbox_retry_data() {
+            if !reviewed && let Some(metadata) = req.sandbox_retry_data() {
                 risk = tool_ctx
                     .session
-                    .assess_sandbox_command(turn_ctx, &tool_ctx.call_id, &metadata.command, None)
+                    .assess_sandbox_command(
+                        turn_ctx,
+                        &tool_ctx.call_id,
+                        &metadata.command,
+                        &metadata.cwd,
+                        escalated_first_attempt,
+                        None,
+                    )
                     .await;
             }
 
@@ -73,6 +174,7 @@ impl ToolOrchestrator {
             let decision = tool.start_approval_async(req, approval_ctx).await;
 
             otel.tool_decision(otel_tn, otel_ci, decision, otel_user.clone());
+            audit_decision(tool_ctx, decision, otel_user.clone()).await;
 
             match decision {
                 ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -82,26 +184,29 @@ impl ToolOrchestrator {
             }
             already_approved = true;
         } else {
-            otel.tool_decision(otel_tn, otel_ci, ReviewDecision::Approved, otel_cfg);
+            otel.tool_decision(otel_tn, otel_ci, ReviewDecision::Approved, otel_cfg.clone());
+            audit_decision(tool_ctx, ReviewDecision::Approved, otel_cfg).await;
         }
 
         // 2) First attempt under the selected sandbox.
         let mut initial_sandbox = self
             .sandbox
             .select_initial(&turn_ctx.sandbox_policy, tool.sandbox_preference());
-        if tool.wants_escalated_first_attempt(req) {
-            initial_sandbox = crate::exec::SandboxType::None;
+        if escalated_first_attempt {
+            initial_sandbox = SandboxType::None;
         }
         // Platform-specific flag gating is handled by SandboxManager::select_initial
         // via crate::safety::get_platform_sandbox().
         let initial_attempt = SandboxAttempt {
             sandbox: initial_sandbox,
             policy: &turn_ctx.sandbox_policy,
+            read_deny: &turn_ctx.sandbox_read_deny,
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
         };
 
+        audit_attempt(tool_ctx, turn_ctx, initial_sandbox, escalated_first_attempt).await;
         match tool.run(req, &initial_attempt, tool_ctx).await {
             Ok(out) => {
                 // We have a successful initial result
@@ -138,6 +243,8 @@ impl ToolOrchestrator {
                                 turn_ctx,
                                 &tool_ctx.call_id,
                                 &metadata.command,
+                                &metadata.cwd,
+                                escalated_first_attempt,
                                 Some(failure_summary.as_str()),
                             )
                             .await;
@@ -153,7 +260,8 @@ impl ToolOrchestrator {
                     };
 
                     let decision = tool.start_approval_async(req, approval_ctx).await;
-                    otel.tool_decision(otel_tn, otel_ci, decision, otel_user);
+                    otel.tool_decision(otel_tn, otel_ci, decision, otel_user.clone());
+                    audit_decision(tool_ctx, decision, otel_user).await;
 
                     match decision {
                         ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -164,14 +272,16 @@ impl ToolOrchestrator {
                 }
 
                 let escalated_attempt = SandboxAttempt {
-                    sandbox: crate::exec::SandboxType::None,
+                    sandbox: SandboxType::None,
                     policy: &turn_ctx.sandbox_policy,
+                    read_deny: &turn_ctx.sandbox_read_deny,
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
                     codex_linux_sandbox_exe: None,
                 };
 
                 // Second attempt.
+                audit_attempt(tool_ctx, turn_ctx, escalated_attempt.sandbox, true).await;
                 (*tool).run(req, &escalated_attempt, tool_ctx).await
             }
             other => other,
@@ -179,6 +289,37 @@ impl ToolOrchestrator {
     }
 }
 
+async fn audit_decision(
+    tool_ctx: &ToolCtx<'_>,
+    decision: ReviewDecision,
+    source: ToolDecisionSource,
+) {
+    let event = AuditEvent::ToolDecision {
+        call_id: tool_ctx.call_id.clone(),
+        tool_name: tool_ctx.tool_name.clone(),
+        decision,
+        source,
+    };
+    tool_ctx.session.services.audit.record(event).await;
+}
+
+async fn audit_attempt(
+    tool_ctx: &ToolCtx<'_>,
+    turn_ctx: &crate::codex::TurnContext,
+    sandbox: SandboxType,
+    escalated: bool,
+) {
+    let event = AuditEvent::SandboxAttempt {
+        call_id: tool_ctx.call_id.clone(),
+        tool_name: tool_ctx.tool_name.clone(),
+        sandbox: sandbox_name(sandbox),
+        escalated,
+        network_access: sandbox == SandboxType::None
+            || turn_ctx.sandbox_policy.has_full_network_access(),
+    };
+    tool_ctx.session.services.audit.record(event).await;
+}
+
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // Keep approval reason terse and stable for UX/tests, but accept the
     // output so we can evolve heuristics later without touching call sites.
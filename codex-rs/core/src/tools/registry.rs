@@ -1,9 +1,14 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::client_common::tools::ToolSpec;
+use crate::codex::Session;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookEvent;
+use crate::hooks::PostToolDecision;
+use crate::hooks::PreToolDecision;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -50,6 +55,12 @@ impl ToolRegistry {
         self.handlers.get(name).map(Arc::clone)
     }
 
+    /// Drops every spec and handler whose tool name is not accepted by `keep`.
+    pub fn retain_tools(&mut self, keep: impl Fn(&str) -> bool) {
+        self.specs.retain(|configured| keep(configured.spec.name()));
+        self.handlers.retain(|name, _| keep(name));
+    }
+
     // TODO(jif) for dynamic tools.
     // pub fn register(&mut self, name: impl Into<String>, handler: Arc<dyn ToolHandler>) {
     //     let name = name.into();
@@ -98,6 +109,36 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        // A hook approval is only meant for this call; drop it however the
+        // call ends.
+        let session = Arc::clone(&invocation.session);
+        let _approval_scope = session.services.hooks.approval_scope(&call_id_owned);
+        let pre_tool_event = HookEvent::PreToolUse {
+            thread_id: invocation.session.conversation_id().to_string(),
+            turn_id: invocation.turn.sub_id.clone(),
+            call_id: call_id_owned.clone(),
+            tool_name: tool_name.clone(),
+            cwd: invocation.turn.cwd.display().to_string(),
+            input: log_payload.to_string(),
+        };
+        if let PreToolDecision::Deny { reason } = invocation
+            .session
+            .services
+            .hooks
+            .pre_tool_use(&pre_tool_event, &invocation.turn.cwd)
+            .await
+        {
+            otel.tool_result(
+                tool_name.as_ref(),
+                &call_id_owned,
+                log_payload.as_ref(),
+                Duration::ZERO,
+                false,
+                &reason,
+            );
+            return Err(FunctionCallError::RespondToModel(reason));
+        }
+
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -115,7 +156,10 @@ impl ToolRegistry {
                             invocation.turn.tool_call_gate.wait_ready().await;
                             tracing::trace!("tool gate released");
                         }
-                        match handler.handle(invocation).await {
+                        let session = Arc::clone(&invocation.session);
+                        let cwd = invocation.turn.cwd.clone();
+                        let result = handler.handle(invocation).await;
+                        match apply_post_tool_hooks(&session, pre_tool_event, &cwd, result).await {
                             Ok(output) => {
                                 let preview = output.log_preview();
                                 let success = output.success_for_logging();
@@ -143,6 +187,49 @@ impl ToolRegistry {
     }
 }
 
+/// Runs the post-tool hooks over a handler result, which may replace the text
+/// returned to the model or turn the result into a rejection.
+async fn apply_post_tool_hooks(
+    session: &Session,
+    pre_tool_event: HookEvent,
+    cwd: &Path,
+    result: Result<ToolOutput, FunctionCallError>,
+) -> Result<ToolOutput, FunctionCallError> {
+    let (output, success) = match &result {
+        Ok(ToolOutput::Function {
+            content, success, ..
+        }) => (content.clone(), success.unwrap_or(true)),
+        Ok(output @ ToolOutput::Mcp { .. }) => (output.log_preview(), output.success_for_logging()),
+        Err(FunctionCallError::RespondToModel(message)) => (message.clone(), false),
+        Err(_) => return result,
+    };
+    let event = pre_tool_event.into_post_tool_use(output, success);
+
+    match session.services.hooks.post_tool_use(event, cwd).await {
+        PostToolDecision::Deny { reason } => Err(FunctionCallError::RespondToModel(reason)),
+        PostToolDecision::Continue { output: None } => result,
+        PostToolDecision::Continue {
+            output: Some(amended),
+        } => match result {
+            Ok(ToolOutput::Function {
+                content_items,
+                success,
+                ..
+            }) => Ok(ToolOutput::Function {
+                content: amended,
+                content_items,
+                success,
+            }),
+            Ok(ToolOutput::Mcp { .. }) => Ok(ToolOutput::Function {
+                content: amended,
+                content_items: None,
+                success: Some(success),
+            }),
+            Err(_) => Err(FunctionCallError::RespondToModel(amended)),
+        },
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfiguredToolSpec {
     pub spec: ToolSpec,
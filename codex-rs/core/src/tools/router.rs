@@ -116,6 +116,7 @@ impl ToolRouter {
                             timeout_ms: exec.timeout_ms,
                             with_escalated_permissions: None,
                             justification: None,
+                            env_profile: None,
                         };
                         Ok(Some(ToolCall {
                             tool_name: "local_shell".to_string(),
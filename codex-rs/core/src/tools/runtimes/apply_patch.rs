@@ -5,6 +5,8 @@
 //! `codex --codex-run-as-apply-patch`, and runs under the current
 //! `SandboxAttempt` with a minimal environment.
 use crate::CODEX_APPLY_PATCH_ARG1;
+use crate::codex::CommandApprovalRequest;
+use crate::config::types::ResourceLimits;
 use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::execute_env;
@@ -68,6 +70,7 @@ impl ApplyPatchRuntime {
             args: vec![CODEX_APPLY_PATCH_ARG1.to_string(), req.patch.clone()],
             cwd: req.cwd.clone(),
             timeout_ms: req.timeout_ms,
+            resource_limits: ResourceLimits::default(),
             // Run apply_patch with a minimal environment for determinism and to avoid leaks.
             env: HashMap::new(),
             with_escalated_permissions: None,
@@ -80,6 +83,7 @@ impl ApplyPatchRuntime {
             sub_id: ctx.turn.sub_id.clone(),
             call_id: ctx.call_id.clone(),
             tx_event: ctx.session.get_tx_event(),
+            audit: ctx.session.services.audit.clone(),
         })
     }
 }
@@ -122,11 +126,14 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
                     session
                         .request_command_approval(
                             turn,
-                            call_id,
-                            vec!["apply_patch".to_string()],
-                            cwd,
-                            Some(reason),
-                            risk,
+                            CommandApprovalRequest {
+                                call_id,
+                                command: vec!["apply_patch".to_string()],
+                                cwd,
+                                reason: Some(reason),
+                                risk,
+                                env_profile: None,
+                            },
                         )
                         .await
                 } else if user_explicitly_approved {
@@ -4,6 +4,7 @@ Runtime: shell
 Executes shell requests under the orchestrator: asks for approval when needed,
 builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
+use crate::codex::CommandApprovalRequest;
 use crate::command_safety::is_dangerous_command::requires_initial_appoval;
 use crate::exec::ExecToolCallOutput;
 use crate::protocol::SandboxPolicy;
@@ -33,6 +34,8 @@ pub struct ShellRequest {
     pub env: std::collections::HashMap<String, String>,
     pub with_escalated_permissions: Option<bool>,
     pub justification: Option<String>,
+    /// The `shell_environment_policy.profiles` entry `env` was built from.
+    pub env_profile: Option<String>,
 }
 
 impl ProvidesSandboxRetryData for ShellRequest {
@@ -52,6 +55,7 @@ pub(crate) struct ApprovalKey {
     command: Vec<String>,
     cwd: PathBuf,
     escalated: bool,
+    env_profile: Option<String>,
 }
 
 impl ShellRuntime {
@@ -64,6 +68,7 @@ impl ShellRuntime {
             sub_id: ctx.turn.sub_id.clone(),
             call_id: ctx.call_id.clone(),
             tx_event: ctx.session.get_tx_event(),
+            audit: ctx.session.services.audit.clone(),
         })
     }
 }
@@ -85,6 +90,7 @@ impl Approvable<ShellRequest> for ShellRuntime {
             command: req.command.clone(),
             cwd: req.cwd.clone(),
             escalated: req.with_escalated_permissions.unwrap_or(false),
+            env_profile: req.env_profile.clone(),
         }
     }
 
@@ -94,21 +100,22 @@ impl Approvable<ShellRequest> for ShellRuntime {
         ctx: ApprovalCtx<'a>,
     ) -> BoxFuture<'a, ReviewDecision> {
         let key = self.approval_key(req);
-        let command = req.command.clone();
-        let cwd = req.cwd.clone();
-        let reason = ctx
-            .retry_reason
-            .clone()
-            .or_else(|| req.justification.clone());
-        let risk = ctx.risk.clone();
+        let request = CommandApprovalRequest {
+            call_id: ctx.call_id.to_string(),
+            command: req.command.clone(),
+            cwd: req.cwd.clone(),
+            reason: ctx
+                .retry_reason
+                .clone()
+                .or_else(|| req.justification.clone()),
+            risk: ctx.risk.clone(),
+            env_profile: req.env_profile.clone(),
+        };
         let session = ctx.session;
         let turn = ctx.turn;
-        let call_id = ctx.call_id.to_string();
         Box::pin(async move {
             with_cached_approval(&session.services, key, move || async move {
-                session
-                    .request_command_approval(turn, call_id, command, cwd, reason, risk)
-                    .await
+                session.request_command_approval(turn, request).await
             })
             .await
         })
@@ -145,6 +152,7 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
             &req.cwd,
             &req.env,
             req.timeout_ms,
+            ctx.turn.resource_limits,
             req.with_escalated_permissions,
             req.justification.clone(),
         )?;
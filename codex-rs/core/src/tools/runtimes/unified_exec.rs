@@ -1,3 +1,4 @@
+use crate::codex::CommandApprovalRequest;
 use crate::command_safety::is_dangerous_command::requires_initial_appoval;
 /*
 Runtime: unified exec
@@ -7,6 +8,7 @@ the session manager to spawn PTYs once an ExecEnv is prepared.
 */
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
+use crate::exec::StdoutStream;
 use crate::tools::runtimes::build_command_spec;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
@@ -111,19 +113,20 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
         let key = self.approval_key(req);
         let session = ctx.session;
         let turn = ctx.turn;
-        let call_id = ctx.call_id.to_string();
-        let command = req.command.clone();
-        let cwd = req.cwd.clone();
-        let reason = ctx
-            .retry_reason
-            .clone()
-            .or_else(|| req.justification.clone());
-        let risk = ctx.risk.clone();
+        let request = CommandApprovalRequest {
+            call_id: ctx.call_id.to_string(),
+            command: req.command.clone(),
+            cwd: req.cwd.clone(),
+            reason: ctx
+                .retry_reason
+                .clone()
+                .or_else(|| req.justification.clone()),
+            risk: ctx.risk.clone(),
+            env_profile: None,
+        };
         Box::pin(async move {
             with_cached_approval(&session.services, key, || async move {
-                session
-                    .request_command_approval(turn, call_id, command, cwd, reason, risk)
-                    .await
+                session.request_command_approval(turn, request).await
             })
             .await
         })
@@ -153,13 +156,14 @@ impl<'a> ToolRuntime<UnifiedExecRequest, UnifiedExecSession> for UnifiedExecRunt
         &mut self,
         req: &UnifiedExecRequest,
         attempt: &SandboxAttempt<'_>,
-        _ctx: &ToolCtx<'_>,
+        ctx: &ToolCtx<'_>,
     ) -> Result<UnifiedExecSession, ToolError> {
         let spec = build_command_spec(
             &req.command,
             &req.cwd,
             &req.env,
             None,
+            ctx.turn.resource_limits,
             req.with_escalated_permissions,
             req.justification.clone(),
         )
@@ -167,8 +171,14 @@ impl<'a> ToolRuntime<UnifiedExecRequest, UnifiedExecSession> for UnifiedExecRunt
         let exec_env = attempt
             .env_for(&spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
+        let stdout_stream = StdoutStream {
+            sub_id: ctx.turn.sub_id.clone(),
+            call_id: ctx.call_id.clone(),
+            tx_event: ctx.session.get_tx_event(),
+            audit: ctx.session.services.audit.clone(),
+        };
         self.manager
-            .open_session_with_exec_env(&exec_env)
+            .open_session_with_exec_env(&exec_env, Some(stdout_stream))
             .await
             .map_err(|err| match err {
                 UnifiedExecError::SandboxDenied { output, .. } => {
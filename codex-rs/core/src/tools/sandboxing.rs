@@ -188,6 +188,7 @@ pub(crate) trait ToolRuntime<Req, Out>: Approvable<Req> + Sandboxable {
 pub(crate) struct SandboxAttempt<'a> {
     pub sandbox: crate::exec::SandboxType,
     pub policy: &'a crate::protocol::SandboxPolicy,
+    pub read_deny: &'a [String],
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
@@ -201,6 +202,7 @@ impl<'a> SandboxAttempt<'a> {
         self.manager.transform(
             spec,
             self.policy,
+            self.read_deny,
             self.sandbox,
             self.sandbox_cwd,
             self.codex_linux_sandbox_exe,
@@ -30,7 +30,15 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_spawn_agent_tool: bool,
+    pub include_memory_tools: bool,
+    pub include_read_tool_output: bool,
     pub experimental_supported_tools: Vec<String>,
+    /// When set, only tools with these names are registered.
+    pub enabled_tools: Option<Vec<String>>,
+    /// Names and descriptions of the `shell_environment_policy` profiles the
+    /// model may pick for a command.
+    pub env_profiles: Vec<(String, Option<String>)>,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -47,6 +55,9 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_view_image_tool = features.enabled(Feature::ViewImageTool);
+        let include_spawn_agent_tool = features.enabled(Feature::SpawnAgent);
+        let include_memory_tools = features.enabled(Feature::Memory);
+        let include_read_tool_output = features.enabled(Feature::ReadToolOutput);
 
         let shell_type = if features.enabled(Feature::UnifiedExec) {
             ConfigShellToolType::UnifiedExec
@@ -73,45 +84,81 @@ impl ToolsConfig {
             apply_patch_tool_type,
             web_search_request: include_web_search_request,
             include_view_image_tool,
+            include_spawn_agent_tool,
+            include_memory_tools,
+            include_read_tool_output,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            enabled_tools: None,
+            env_profiles: Vec::new(),
         }
     }
+
+    pub fn with_enabled_tools(mut self, enabled_tools: Option<Vec<String>>) -> Self {
+        self.enabled_tools = enabled_tools;
+        self
+    }
+
+    pub fn with_env_profiles(mut self, env_profiles: Vec<(String, Option<String>)>) -> Self {
+        self.env_profiles = env_profiles;
+        self
+    }
 }
 
-/// Generic JSON‑Schema subset needed for our tool definitions
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(tag = "type", rename_all = "lowercase")]
+/// Generic JSON‑Schema subset needed for our tool definitions.
+///
+/// Serialization is hand-written (see the `Serialize`/`Deserialize` impls
+/// below) because several constructs (`enum`, `const`, `anyOf`, `oneOf`) do
+/// not carry a `type` tag.
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum JsonSchema {
     Boolean {
-        #[serde(skip_serializing_if = "Option::is_none")]
         description: Option<String>,
     },
     String {
-        #[serde(skip_serializing_if = "Option::is_none")]
         description: Option<String>,
     },
-    /// MCP schema allows "number" | "integer" for Number
-    #[serde(alias = "integer")]
     Number {
-        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+    },
+    Integer {
+        description: Option<String>,
+        minimum: Option<i64>,
+        maximum: Option<i64>,
+    },
+    Null {
         description: Option<String>,
     },
     Array {
         items: Box<JsonSchema>,
-
-        #[serde(skip_serializing_if = "Option::is_none")]
         description: Option<String>,
     },
     Object {
         properties: BTreeMap<String, JsonSchema>,
-        #[serde(skip_serializing_if = "Option::is_none")]
         required: Option<Vec<String>>,
-        #[serde(
-            rename = "additionalProperties",
-            skip_serializing_if = "Option::is_none"
-        )]
         additional_properties: Option<AdditionalProperties>,
     },
+    /// `enum`: the value must be one of `values`.
+    Enum {
+        values: Vec<JsonValue>,
+        description: Option<String>,
+    },
+    /// `const`: the value must equal `value`.
+    Const {
+        value: JsonValue,
+        description: Option<String>,
+    },
+    /// `anyOf`; nullable types (`"type": ["string", "null"]`) also land here.
+    AnyOf {
+        variants: Vec<JsonSchema>,
+        description: Option<String>,
+    },
+    /// `oneOf`; down-converted to `anyOf` for APIs that lack it.
+    OneOf {
+        variants: Vec<JsonSchema>,
+        description: Option<String>,
+    },
 }
 
 /// Whether additional properties are allowed, and if so, any required schema
@@ -134,6 +181,347 @@ impl From<JsonSchema> for AdditionalProperties {
     }
 }
 
+impl Serialize for JsonSchema {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonSchema {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = JsonValue::deserialize(deserializer)?;
+        Ok(JsonSchema::parse(&value))
+    }
+}
+
+impl JsonSchema {
+    fn to_json(&self) -> JsonValue {
+        let mut map = serde_json::Map::new();
+        let description = match self {
+            JsonSchema::Boolean { description } => {
+                map.insert("type".to_string(), json!("boolean"));
+                description
+            }
+            JsonSchema::String { description } => {
+                map.insert("type".to_string(), json!("string"));
+                description
+            }
+            JsonSchema::Number {
+                description,
+                minimum,
+                maximum,
+            } => {
+                map.insert("type".to_string(), json!("number"));
+                insert_bounds(
+                    &mut map,
+                    minimum.map(JsonValue::from),
+                    maximum.map(JsonValue::from),
+                );
+                description
+            }
+            JsonSchema::Integer {
+                description,
+                minimum,
+                maximum,
+            } => {
+                map.insert("type".to_string(), json!("integer"));
+                insert_bounds(
+                    &mut map,
+                    minimum.map(JsonValue::from),
+                    maximum.map(JsonValue::from),
+                );
+                description
+            }
+            JsonSchema::Null { description } => {
+                map.insert("type".to_string(), json!("null"));
+                description
+            }
+            JsonSchema::Array { items, description } => {
+                map.insert("type".to_string(), json!("array"));
+                map.insert("items".to_string(), items.to_json());
+                description
+            }
+            JsonSchema::Object {
+                properties,
+                required,
+                additional_properties,
+            } => {
+                map.insert("type".to_string(), json!("object"));
+                let properties = properties
+                    .iter()
+                    .map(|(name, schema)| (name.clone(), schema.to_json()))
+                    .collect();
+                map.insert("properties".to_string(), JsonValue::Object(properties));
+                if let Some(required) = required {
+                    map.insert("required".to_string(), json!(required));
+                }
+                match additional_properties {
+                    Some(AdditionalProperties::Boolean(allowed)) => {
+                        map.insert("additionalProperties".to_string(), json!(allowed));
+                    }
+                    Some(AdditionalProperties::Schema(schema)) => {
+                        map.insert("additionalProperties".to_string(), schema.to_json());
+                    }
+                    None => {}
+                }
+                &None
+            }
+            JsonSchema::Enum {
+                values,
+                description,
+            } => {
+                if let Some(ty) = infer_type_of_values(values) {
+                    map.insert("type".to_string(), json!(ty));
+                }
+                map.insert("enum".to_string(), JsonValue::Array(values.clone()));
+                description
+            }
+            JsonSchema::Const { value, description } => {
+                if let Some(ty) = infer_type_of_values(std::slice::from_ref(value)) {
+                    map.insert("type".to_string(), json!(ty));
+                }
+                map.insert("const".to_string(), value.clone());
+                description
+            }
+            JsonSchema::AnyOf {
+                variants,
+                description,
+            } => {
+                let variants = variants.iter().map(JsonSchema::to_json).collect();
+                map.insert("anyOf".to_string(), JsonValue::Array(variants));
+                description
+            }
+            JsonSchema::OneOf {
+                variants,
+                description,
+            } => {
+                let variants = variants.iter().map(JsonSchema::to_json).collect();
+                map.insert("oneOf".to_string(), JsonValue::Array(variants));
+                description
+            }
+        };
+        if let Some(description) = description {
+            map.insert("description".to_string(), json!(description));
+        }
+        JsonValue::Object(map)
+    }
+
+    /// Leniently converts a JSON Schema into our subset. Anything we cannot
+    /// model, including `$ref`s that were not inlined upstream, falls back to
+    /// a plain string, which is what servers that omit `type` usually mean.
+    fn parse(value: &JsonValue) -> JsonSchema {
+        let Some(map) = value.as_object() else {
+            // JSON Schema boolean form (`true`/`false`): accept anything.
+            return JsonSchema::String { description: None };
+        };
+        let description = map
+            .get("description")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string);
+
+        if map.contains_key("$ref") {
+            return JsonSchema::String { description };
+        }
+        if let Some(value) = map.get("const") {
+            return JsonSchema::Const {
+                value: value.clone(),
+                description,
+            };
+        }
+        if let Some(JsonValue::Array(values)) = map.get("enum") {
+            return JsonSchema::Enum {
+                values: values.clone(),
+                description,
+            };
+        }
+        if let Some(JsonValue::Array(variants)) = map.get("anyOf") {
+            return JsonSchema::AnyOf {
+                variants: parse_all(variants),
+                description,
+            };
+        }
+        if let Some(JsonValue::Array(variants)) = map.get("oneOf") {
+            return JsonSchema::OneOf {
+                variants: parse_all(variants),
+                description,
+            };
+        }
+        if let Some(JsonValue::Array(parts)) = map.get("allOf")
+            && let [part] = parts.as_slice()
+        {
+            let resolved = JsonSchema::parse(part);
+            return match description {
+                Some(description) => resolved.with_description(description),
+                None => resolved,
+            };
+        }
+
+        match map.get("type") {
+            Some(JsonValue::String(ty)) => JsonSchema::parse_typed(ty, map, description),
+            // Type unions such as `["string", "null"]` become `anyOf`.
+            Some(JsonValue::Array(types)) => {
+                let mut variants: Vec<JsonSchema> = types
+                    .iter()
+                    .filter_map(JsonValue::as_str)
+                    .map(|ty| JsonSchema::parse_typed(ty, map, None))
+                    .collect();
+                match variants.len() {
+                    0 => JsonSchema::String { description },
+                    1 => {
+                        let only = variants.remove(0);
+                        match description {
+                            Some(description) => only.with_description(description),
+                            None => only,
+                        }
+                    }
+                    _ => JsonSchema::AnyOf {
+                        variants,
+                        description,
+                    },
+                }
+            }
+            _ => {
+                let ty = if ["properties", "required", "additionalProperties"]
+                    .iter()
+                    .any(|key| map.contains_key(*key))
+                {
+                    "object"
+                } else if map.contains_key("items") || map.contains_key("prefixItems") {
+                    "array"
+                } else if [
+                    "minimum",
+                    "maximum",
+                    "exclusiveMinimum",
+                    "exclusiveMaximum",
+                    "multipleOf",
+                ]
+                .iter()
+                .any(|key| map.contains_key(*key))
+                {
+                    "number"
+                } else {
+                    "string"
+                };
+                JsonSchema::parse_typed(ty, map, description)
+            }
+        }
+    }
+
+    fn parse_typed(
+        ty: &str,
+        map: &serde_json::Map<String, JsonValue>,
+        description: Option<String>,
+    ) -> JsonSchema {
+        match ty {
+            "boolean" => JsonSchema::Boolean { description },
+            "number" => JsonSchema::Number {
+                description,
+                minimum: map.get("minimum").and_then(JsonValue::as_f64),
+                maximum: map.get("maximum").and_then(JsonValue::as_f64),
+            },
+            "integer" => JsonSchema::Integer {
+                description,
+                minimum: map.get("minimum").and_then(JsonValue::as_i64),
+                maximum: map.get("maximum").and_then(JsonValue::as_i64),
+            },
+            "null" => JsonSchema::Null { description },
+            "array" => {
+                let items = match map.get("items") {
+                    Some(items) => JsonSchema::parse(items),
+                    None => JsonSchema::String { description: None },
+                };
+                JsonSchema::Array {
+                    items: Box::new(items),
+                    description,
+                }
+            }
+            "object" => {
+                let properties = map
+                    .get("properties")
+                    .and_then(JsonValue::as_object)
+                    .map(|properties| {
+                        properties
+                            .iter()
+                            .map(|(name, schema)| (name.clone(), JsonSchema::parse(schema)))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let required = map
+                    .get("required")
+                    .and_then(JsonValue::as_array)
+                    .map(|names| {
+                        names
+                            .iter()
+                            .filter_map(JsonValue::as_str)
+                            .map(str::to_string)
+                            .collect()
+                    });
+                let additional_properties = match map.get("additionalProperties") {
+                    Some(JsonValue::Bool(allowed)) => Some(AdditionalProperties::Boolean(*allowed)),
+                    Some(schema) => Some(JsonSchema::parse(schema).into()),
+                    None => None,
+                };
+                JsonSchema::Object {
+                    properties,
+                    required,
+                    additional_properties,
+                }
+            }
+            _ => JsonSchema::String { description },
+        }
+    }
+
+    /// Replaces the description, for schemas that carry one.
+    fn with_description(mut self, new_description: String) -> JsonSchema {
+        match &mut self {
+            JsonSchema::Boolean { description }
+            | JsonSchema::String { description }
+            | JsonSchema::Number { description, .. }
+            | JsonSchema::Integer { description, .. }
+            | JsonSchema::Null { description }
+            | JsonSchema::Array { description, .. }
+            | JsonSchema::Enum { description, .. }
+            | JsonSchema::Const { description, .. }
+            | JsonSchema::AnyOf { description, .. }
+            | JsonSchema::OneOf { description, .. } => *description = Some(new_description),
+            JsonSchema::Object { .. } => {}
+        }
+        self
+    }
+}
+
+fn parse_all(values: &[JsonValue]) -> Vec<JsonSchema> {
+    values.iter().map(JsonSchema::parse).collect()
+}
+
+fn insert_bounds(
+    map: &mut serde_json::Map<String, JsonValue>,
+    minimum: Option<JsonValue>,
+    maximum: Option<JsonValue>,
+) {
+    if let Some(minimum) = minimum {
+        map.insert("minimum".to_string(), minimum);
+    }
+    if let Some(maximum) = maximum {
+        map.insert("maximum".to_string(), maximum);
+    }
+}
+
+/// The JSON type shared by every value, if there is exactly one.
+fn infer_type_of_values(values: &[JsonValue]) -> Option<&'static str> {
+    let mut types = values.iter().map(|value| match value {
+        JsonValue::String(_) => "string",
+        JsonValue::Number(n) if n.is_f64() => "number",
+        JsonValue::Number(_) => "integer",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Null => "null",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    });
+    let first = types.next()?;
+    types.all(|ty| ty == first).then_some(first)
+}
+
 fn create_exec_command_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -171,6 +559,8 @@ fn create_exec_command_tool() -> ToolSpec {
             description: Some(
                 "How long to wait (in milliseconds) for output before yielding.".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
@@ -179,6 +569,8 @@ fn create_exec_command_tool() -> ToolSpec {
             description: Some(
                 "Maximum number of tokens to return. Excess output will be truncated.".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
@@ -220,6 +612,8 @@ fn create_write_stdin_tool() -> ToolSpec {
         "session_id".to_string(),
         JsonSchema::Number {
             description: Some("Identifier of the running unified exec session.".to_string()),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
@@ -234,6 +628,8 @@ fn create_write_stdin_tool() -> ToolSpec {
             description: Some(
                 "How long to wait (in milliseconds) for output before yielding.".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
@@ -242,6 +638,8 @@ fn create_write_stdin_tool() -> ToolSpec {
             description: Some(
                 "Maximum number of tokens to return. Excess output will be truncated.".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
 
@@ -259,7 +657,7 @@ fn create_write_stdin_tool() -> ToolSpec {
     })
 }
 
-fn create_shell_tool() -> ToolSpec {
+fn create_shell_tool(env_profiles: &[(String, Option<String>)]) -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "command".to_string(),
@@ -278,6 +676,8 @@ fn create_shell_tool() -> ToolSpec {
         "timeout_ms".to_string(),
         JsonSchema::Number {
             description: Some("The timeout for the command in milliseconds".to_string()),
+            minimum: None,
+            maximum: None,
         },
     );
 
@@ -293,6 +693,7 @@ fn create_shell_tool() -> ToolSpec {
             description: Some("Only set if with_escalated_permissions is true. 1-sentence explanation of why we want to run this command.".to_string()),
         },
     );
+    insert_env_profile_property(&mut properties, env_profiles);
 
     ToolSpec::Function(ResponsesApiTool {
         name: "shell".to_string(),
@@ -306,7 +707,7 @@ fn create_shell_tool() -> ToolSpec {
     })
 }
 
-fn create_shell_command_tool() -> ToolSpec {
+fn create_shell_command_tool(env_profiles: &[(String, Option<String>)]) -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "command".to_string(),
@@ -326,6 +727,8 @@ fn create_shell_command_tool() -> ToolSpec {
         "timeout_ms".to_string(),
         JsonSchema::Number {
             description: Some("The timeout for the command in milliseconds".to_string()),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
@@ -340,6 +743,7 @@ fn create_shell_command_tool() -> ToolSpec {
             description: Some("Only set if with_escalated_permissions is true. 1-sentence explanation of why we want to run this command.".to_string()),
         },
     );
+    insert_env_profile_property(&mut properties, env_profiles);
 
     ToolSpec::Function(ResponsesApiTool {
         name: "shell_command".to_string(),
@@ -353,6 +757,37 @@ fn create_shell_command_tool() -> ToolSpec {
     })
 }
 
+/// Adds an `env_profile` parameter listing the configured environment
+/// profiles. Nothing is added when there are none.
+fn insert_env_profile_property(
+    properties: &mut BTreeMap<String, JsonSchema>,
+    env_profiles: &[(String, Option<String>)],
+) {
+    if env_profiles.is_empty() {
+        return;
+    }
+    let profiles = env_profiles
+        .iter()
+        .map(|(name, description)| match description {
+            Some(description) => format!("`{name}` ({description})"),
+            None => format!("`{name}`"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    properties.insert(
+        "env_profile".to_string(),
+        JsonSchema::Enum {
+            values: env_profiles
+                .iter()
+                .map(|(name, _)| json!(name))
+                .collect(),
+            description: Some(format!(
+                "Optional environment profile that sets extra variables, such as credentials, for this command. Available: {profiles}."
+            )),
+        },
+    );
+}
+
 fn create_view_image_tool() -> ToolSpec {
     // Support only local filesystem path.
     let mut properties = BTreeMap::new();
@@ -377,12 +812,63 @@ fn create_view_image_tool() -> ToolSpec {
     })
 }
 
+fn create_spawn_agent_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "prompt".to_string(),
+        JsonSchema::String {
+            description: Some("Task for the sub-agent to carry out.".to_string()),
+        },
+    );
+    properties.insert(
+        "instructions".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional developer instructions that shape how the sub-agent works.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "tools".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some(
+                "Optional names of the tools the sub-agent may use. Defaults to all of your tools except spawn_agent."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "sandbox".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional sandbox for the sub-agent: \"read-only\" or \"workspace-write\". It can never be more permissive than your own sandbox. Defaults to your sandbox."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "spawn_agent".to_string(),
+        description: "Delegate a self-contained task to a sub-agent that runs in its own conversation and returns its final message. Use it for work that would otherwise flood your context, such as broad searches or focused investigations."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["prompt".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_test_sync_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "sleep_before_ms".to_string(),
         JsonSchema::Number {
             description: Some("Optional delay in milliseconds before any other action".to_string()),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
@@ -391,6 +877,8 @@ fn create_test_sync_tool() -> ToolSpec {
             description: Some(
                 "Optional delay in milliseconds after completing the barrier".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
 
@@ -409,12 +897,16 @@ fn create_test_sync_tool() -> ToolSpec {
             description: Some(
                 "Number of tool calls that must arrive before the barrier opens".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
     barrier_properties.insert(
         "timeout_ms".to_string(),
         JsonSchema::Number {
             description: Some("Maximum time in milliseconds to wait at the barrier".to_string()),
+            minimum: None,
+            maximum: None,
         },
     );
 
@@ -472,6 +964,8 @@ fn create_grep_files_tool() -> ToolSpec {
             description: Some(
                 "Maximum number of file paths to return (defaults to 100).".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
 
@@ -503,12 +997,16 @@ fn create_read_file_tool() -> ToolSpec {
             description: Some(
                 "The line number to start reading from. Must be 1 or greater.".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
         "limit".to_string(),
         JsonSchema::Number {
             description: Some("The maximum number of lines to return.".to_string()),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
@@ -529,6 +1027,8 @@ fn create_read_file_tool() -> ToolSpec {
             description: Some(
                 "Anchor line to center the indentation lookup on (defaults to offset).".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
     indentation_properties.insert(
@@ -537,6 +1037,8 @@ fn create_read_file_tool() -> ToolSpec {
             description: Some(
                 "How many parent indentation levels (smaller indents) to include.".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
     indentation_properties.insert(
@@ -562,6 +1064,8 @@ fn create_read_file_tool() -> ToolSpec {
             description: Some(
                 "Hard cap on the number of lines returned when using indentation mode.".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
@@ -601,12 +1105,16 @@ fn create_list_dir_tool() -> ToolSpec {
             description: Some(
                 "The entry number to start listing from. Must be 1 or greater.".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
         "limit".to_string(),
         JsonSchema::Number {
             description: Some("The maximum number of entries to return.".to_string()),
+            minimum: None,
+            maximum: None,
         },
     );
     properties.insert(
@@ -615,6 +1123,8 @@ fn create_list_dir_tool() -> ToolSpec {
             description: Some(
                 "The maximum directory depth to traverse. Must be 1 or greater.".to_string(),
             ),
+            minimum: None,
+            maximum: None,
         },
     );
 
@@ -747,12 +1257,16 @@ pub fn create_tools_json_for_responses_api(
     let mut tools_json = Vec::new();
 
     for tool in tools {
-        let json = serde_json::to_value(tool)?;
+        let mut json = serde_json::to_value(tool)?;
+        if let Some(parameters) = json.get_mut("parameters") {
+            downconvert_schema_for_openai(parameters);
+        }
         tools_json.push(json);
     }
 
     Ok(tools_json)
 }
+
 /// Returns JSON values that are compatible with Function Calling in the
 /// Chat Completions API:
 /// https://platform.openai.com/docs/guides/function-calling?api-mode=chat
@@ -784,6 +1298,136 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
     Ok(tools_json)
 }
 
+/// Returns JSON values for the Anthropic Messages API `tools` field. Only
+/// function tools are supported; freeform tools are dropped as they are for
+/// Chat Completions. Anthropic accepts full JSON Schema, so parameters are
+/// sent as-is.
+pub(crate) fn create_tools_json_for_anthropic_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let mut tools_json = Vec::new();
+    for tool in tools {
+        if let ToolSpec::Function(tool) = tool {
+            tools_json.push(json!({
+                "name": tool.name,
+                "description": tool.description,
+                "input_schema": serde_json::to_value(&tool.parameters)?,
+            }));
+        }
+    }
+    Ok(tools_json)
+}
+
+/// Returns the `functionDeclarations` for the Gemini `generateContent` API.
+/// Parameters are down-converted to the OpenAPI subset Gemini accepts; see
+/// [`downconvert_schema_for_gemini`].
+pub(crate) fn create_tools_json_for_gemini_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let mut tools_json = Vec::new();
+    for tool in tools {
+        if let ToolSpec::Function(tool) = tool {
+            let mut parameters = serde_json::to_value(&tool.parameters)?;
+            downconvert_schema_for_gemini(&mut parameters);
+            tools_json.push(json!({
+                "name": tool.name,
+                "description": tool.description,
+                "parameters": parameters,
+            }));
+        }
+    }
+    Ok(tools_json)
+}
+
+/// OpenAI function calling has no `oneOf`; `anyOf` is the closest match.
+fn downconvert_schema_for_openai(schema: &mut JsonValue) {
+    for_each_subschema(schema, downconvert_schema_for_openai);
+    if let Some(map) = schema.as_object_mut()
+        && let Some(variants) = map.remove("oneOf")
+    {
+        map.insert("anyOf".to_string(), variants);
+    }
+}
+
+/// Gemini accepts an OpenAPI 3.0 flavoured subset of JSON Schema:
+/// - no `additionalProperties`, `const` or `oneOf` (mapped to `anyOf`);
+/// - `enum` only for strings, so other enums keep just their `type`;
+/// - no `null` type; nullability is the `nullable` flag instead.
+fn downconvert_schema_for_gemini(schema: &mut JsonValue) {
+    for_each_subschema(schema, downconvert_schema_for_gemini);
+    let Some(map) = schema.as_object_mut() else {
+        return;
+    };
+    map.remove("additionalProperties");
+    if let Some(variants) = map.remove("oneOf") {
+        map.insert("anyOf".to_string(), variants);
+    }
+    if let Some(value) = map.remove("const")
+        && value.is_string()
+    {
+        map.insert("enum".to_string(), JsonValue::Array(vec![value]));
+    }
+    if let Some(JsonValue::Array(values)) = map.get_mut("enum") {
+        let len = values.len();
+        values.retain(|value| !value.is_null());
+        if values.len() != len {
+            map.insert("nullable".to_string(), JsonValue::Bool(true));
+        }
+        if map
+            .get("enum")
+            .and_then(JsonValue::as_array)
+            .is_some_and(|values| values.iter().all(JsonValue::is_string))
+        {
+            map.insert("type".to_string(), json!("string"));
+        } else if let Some(JsonValue::Array(values)) = map.remove("enum")
+            && let Some(ty) = infer_type_of_values(&values)
+        {
+            map.entry("type").or_insert_with(|| json!(ty));
+        }
+    }
+    if let Some(JsonValue::Array(variants)) = map.get_mut("anyOf") {
+        let len = variants.len();
+        variants.retain(|variant| variant.get("type") != Some(&json!("null")));
+        let nullable = variants.len() != len;
+        if variants.len() == 1 {
+            // `anyOf: [X, null]` is just a nullable X.
+            let only = variants.remove(0);
+            map.remove("anyOf");
+            if let JsonValue::Object(only) = only {
+                for (key, value) in only {
+                    map.entry(key).or_insert(value);
+                }
+            }
+        }
+        if nullable {
+            map.insert("nullable".to_string(), JsonValue::Bool(true));
+        }
+    }
+}
+
+/// Applies `f` to every schema nested directly inside `schema`.
+fn for_each_subschema(schema: &mut JsonValue, f: fn(&mut JsonValue)) {
+    let Some(map) = schema.as_object_mut() else {
+        return;
+    };
+    if let Some(JsonValue::Object(properties)) = map.get_mut("properties") {
+        properties.values_mut().for_each(f);
+    }
+    if let Some(items) = map.get_mut("items") {
+        f(items);
+    }
+    if let Some(additional) = map.get_mut("additionalProperties")
+        && additional.is_object()
+    {
+        f(additional);
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(JsonValue::Array(variants)) = map.get_mut(key) {
+            variants.iter_mut().for_each(f);
+        }
+    }
+}
+
 pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
@@ -802,14 +1446,10 @@ pub(crate) fn mcp_tool_to_openai_tool(
         input_schema.properties = Some(serde_json::Value::Object(serde_json::Map::new()));
     }
 
-    // Serialize to a raw JSON value so we can sanitize schemas coming from MCP
-    // servers. Some servers omit the top-level or nested `type` in JSON
-    // Schemas (e.g. using enum/anyOf), or use unsupported variants like
-    // `integer`. Our internal JsonSchema is a small subset and requires
-    // `type`, so we coerce/sanitize here for compatibility.
-    let mut serialized_input_schema = serde_json::to_value(input_schema)?;
-    sanitize_json_schema(&mut serialized_input_schema);
-    let input_schema = serde_json::from_value::<JsonSchema>(serialized_input_schema)?;
+    // `JsonSchema` deserialization is lenient: it infers missing `type`s and
+    // falls back to a plain string for anything it cannot model, including
+    // `$ref`s, so schemas from arbitrary MCP servers always convert.
+    let input_schema = serde_json::from_value::<JsonSchema>(serde_json::to_value(input_schema)?)?;
 
     Ok(ResponsesApiTool {
         name: fully_qualified_name,
@@ -819,115 +1459,157 @@ pub(crate) fn mcp_tool_to_openai_tool(
     })
 }
 
-/// Sanitize a JSON Schema (as serde_json::Value) so it can fit our limited
-/// JsonSchema enum. This function:
-/// - Ensures every schema object has a "type". If missing, infers it from
-///   common keywords (properties => object, items => array, enum/const/format => string)
-///   and otherwise defaults to "string".
-/// - Fills required child fields (e.g. array items, object properties) with
-///   permissive defaults when absent.
-fn sanitize_json_schema(value: &mut JsonValue) {
-    match value {
-        JsonValue::Bool(_) => {
-            // JSON Schema boolean form: true/false. Coerce to an accept-all string.
-            *value = json!({ "type": "string" });
-        }
-        JsonValue::Array(arr) => {
-            for v in arr.iter_mut() {
-                sanitize_json_schema(v);
-            }
-        }
-        JsonValue::Object(map) => {
-            // First, recursively sanitize known nested schema holders
-            if let Some(props) = map.get_mut("properties")
-                && let Some(props_map) = props.as_object_mut()
-            {
-                for (_k, v) in props_map.iter_mut() {
-                    sanitize_json_schema(v);
-                }
-            }
-            if let Some(items) = map.get_mut("items") {
-                sanitize_json_schema(items);
-            }
-            // Some schemas use oneOf/anyOf/allOf - sanitize their entries
-            for combiner in ["oneOf", "anyOf", "allOf", "prefixItems"] {
-                if let Some(v) = map.get_mut(combiner) {
-                    sanitize_json_schema(v);
-                }
-            }
+fn memory_scope_schema(description: &str) -> JsonSchema {
+    JsonSchema::Enum {
+        values: vec![json!("project"), json!("global")],
+        description: Some(description.to_string()),
+    }
+}
 
-            // Normalize/ensure type
-            let mut ty = map.get("type").and_then(|v| v.as_str()).map(str::to_string);
-
-            // If type is an array (union), pick first supported; else leave to inference
-            if ty.is_none()
-                && let Some(JsonValue::Array(types)) = map.get("type")
-            {
-                for t in types {
-                    if let Some(tt) = t.as_str()
-                        && matches!(
-                            tt,
-                            "object" | "array" | "string" | "number" | "integer" | "boolean"
-                        )
-                    {
-                        ty = Some(tt.to_string());
-                        break;
-                    }
-                }
-            }
+fn create_remember_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "memory".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "A short, self-contained fact to keep for future sessions.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "scope".to_string(),
+        memory_scope_schema(
+            "\"project\" for facts about this project (default), \"global\" for facts about the user that apply everywhere.",
+        ),
+    );
 
-            // Infer type if still missing
-            if ty.is_none() {
-                if map.contains_key("properties")
-                    || map.contains_key("required")
-                    || map.contains_key("additionalProperties")
-                {
-                    ty = Some("object".to_string());
-                } else if map.contains_key("items") || map.contains_key("prefixItems") {
-                    ty = Some("array".to_string());
-                } else if map.contains_key("enum")
-                    || map.contains_key("const")
-                    || map.contains_key("format")
-                {
-                    ty = Some("string".to_string());
-                } else if map.contains_key("minimum")
-                    || map.contains_key("maximum")
-                    || map.contains_key("exclusiveMinimum")
-                    || map.contains_key("exclusiveMaximum")
-                    || map.contains_key("multipleOf")
-                {
-                    ty = Some("number".to_string());
-                }
-            }
-            // If we still couldn't infer, default to string
-            let ty = ty.unwrap_or_else(|| "string".to_string());
-            map.insert("type".to_string(), JsonValue::String(ty.to_string()));
-
-            // Ensure object schemas have properties map
-            if ty == "object" {
-                if !map.contains_key("properties") {
-                    map.insert(
-                        "properties".to_string(),
-                        JsonValue::Object(serde_json::Map::new()),
-                    );
-                }
-                // If additionalProperties is an object schema, sanitize it too.
-                // Leave booleans as-is, since JSON Schema allows boolean here.
-                if let Some(ap) = map.get_mut("additionalProperties") {
-                    let is_bool = matches!(ap, JsonValue::Bool(_));
-                    if !is_bool {
-                        sanitize_json_schema(ap);
-                    }
-                }
-            }
+    ToolSpec::Function(ResponsesApiTool {
+        name: "remember".to_string(),
+        description: "Save a memory that will be shown to you at the start of future sessions. Only save durable facts such as user preferences or project conventions, not details of the current task."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["memory".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
 
-            // Ensure array schemas have items
-            if ty == "array" && !map.contains_key("items") {
-                map.insert("items".to_string(), json!({ "type": "string" }));
-            }
-        }
-        _ => {}
-    }
+fn create_recall_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "query".to_string(),
+        JsonSchema::String {
+            description: Some("Words to look for. Omit to list every memory.".to_string()),
+        },
+    );
+    properties.insert(
+        "scope".to_string(),
+        memory_scope_schema("Only search this scope. Defaults to both."),
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "recall".to_string(),
+        description: "Search the memories saved in earlier sessions.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_forget_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "memory".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "The memory to delete, or a part of its text that matches only that memory."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "scope".to_string(),
+        memory_scope_schema("Only delete from this scope. Defaults to both."),
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "forget".to_string(),
+        description: "Delete a saved memory that is wrong or no longer useful.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["memory".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_tool_output_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "call_id".to_string(),
+        JsonSchema::String {
+            description: Some("The call_id of the earlier tool call.".to_string()),
+        },
+    );
+    properties.insert(
+        "offset".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "The line number to start reading from. Must be 1 or greater.".to_string(),
+            ),
+            minimum: None,
+            maximum: None,
+        },
+    );
+    properties.insert(
+        "column".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "The byte column to start reading the `offset` line from, for continuing a long \
+                 line. Must be 1 or greater."
+                    .to_string(),
+            ),
+            minimum: None,
+            maximum: None,
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some("The maximum number of lines to return.".to_string()),
+            minimum: None,
+            maximum: None,
+        },
+    );
+    properties.insert(
+        "grep".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional regular expression; only matching lines are returned.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "read_tool_output".to_string(),
+        description: "Reads the full output of an earlier tool call in this session, with line \
+                      numbers. Use it when an output was truncated (`[... omitted N of M lines ...]`) \
+                      or elided during compaction, instead of running the command again. Lines \
+                      longer than 500 bytes are split into pieces labelled `L<line>:<column>`."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["call_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
 }
 
 /// Builds the tool registry builder while collecting tool specs for later serialization.
@@ -940,10 +1622,13 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::ListDirHandler;
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
+    use crate::tools::handlers::MemoryHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::ReadToolOutputHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::SpawnAgentHandler;
     use crate::tools::handlers::TestSyncHandler;
     use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::handlers::ViewImageHandler;
@@ -962,7 +1647,7 @@ pub(crate) fn build_specs(
 
     match &config.shell_type {
         ConfigShellToolType::Default => {
-            builder.push_spec(create_shell_tool());
+            builder.push_spec(create_shell_tool(&config.env_profiles));
         }
         ConfigShellToolType::Local => {
             builder.push_spec(ToolSpec::LocalShell {});
@@ -974,7 +1659,7 @@ pub(crate) fn build_specs(
             builder.register_handler("write_stdin", unified_exec_handler);
         }
         ConfigShellToolType::ShellCommand => {
-            builder.push_spec(create_shell_command_tool());
+            builder.push_spec(create_shell_command_tool(&config.env_profiles));
         }
     }
 
@@ -1052,6 +1737,26 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.include_spawn_agent_tool {
+        builder.push_spec_with_parallel_support(create_spawn_agent_tool(), true);
+        builder.register_handler("spawn_agent", Arc::new(SpawnAgentHandler));
+    }
+
+    if config.include_memory_tools {
+        let memory_handler = Arc::new(MemoryHandler);
+        builder.push_spec(create_remember_tool());
+        builder.push_spec_with_parallel_support(create_recall_tool(), true);
+        builder.push_spec(create_forget_tool());
+        builder.register_handler("remember", memory_handler.clone());
+        builder.register_handler("recall", memory_handler.clone());
+        builder.register_handler("forget", memory_handler);
+    }
+
+    if config.include_read_tool_output {
+        builder.push_spec_with_parallel_support(create_read_tool_output_tool(), true);
+        builder.register_handler("read_tool_output", Arc::new(ReadToolOutputHandler));
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -1069,6 +1774,10 @@ pub(crate) fn build_specs(
         }
     }
 
+    if let Some(enabled_tools) = &config.enabled_tools {
+        builder.retain_tools(|name| enabled_tools.iter().any(|tool| tool == name));
+    }
+
     builder
 }
 
@@ -1136,7 +1845,24 @@ mod tests {
         match schema {
             JsonSchema::Boolean { description }
             | JsonSchema::String { description }
-            | JsonSchema::Number { description } => {
+            | JsonSchema::Number { description, .. }
+            | JsonSchema::Integer { description, .. }
+            | JsonSchema::Null { description }
+            | JsonSchema::Enum { description, .. }
+            | JsonSchema::Const { description, .. } => {
+                *description = None;
+            }
+            JsonSchema::AnyOf {
+                variants,
+                description,
+            }
+            | JsonSchema::OneOf {
+                variants,
+                description,
+            } => {
+                for variant in variants {
+                    strip_descriptions_schema(variant);
+                }
                 *description = None;
             }
             JsonSchema::Array { items, description } => {
@@ -1406,6 +2132,73 @@ mod tests {
         assert_contains_tool_names(&tools, &subset);
     }
 
+    #[test]
+    fn test_build_specs_enabled_tools_filters_specs() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let mut features = Features::with_defaults();
+        features.enable(Feature::SpawnAgent);
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        })
+        .with_enabled_tools(Some(vec![
+            "update_plan".to_string(),
+            "spawn_agent".to_string(),
+        ]));
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+
+        let names: Vec<&str> = tools.iter().map(|t| tool_name(&t.spec)).collect();
+        assert_eq!(names, vec!["update_plan", "spawn_agent"]);
+    }
+
+    #[test]
+    fn test_build_specs_memory_tools_behind_feature() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let mut features = Features::with_defaults();
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+        assert!(
+            !tools
+                .iter()
+                .any(|t| ["remember", "recall", "forget"].contains(&tool_name(&t.spec)))
+        );
+
+        features.enable(Feature::Memory);
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+        assert_contains_tool_names(&tools, &["remember", "recall", "forget"]);
+    }
+
+    #[test]
+    fn test_build_specs_read_tool_output_behind_feature() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let mut features = Features::with_defaults();
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+        assert!(
+            !tools
+                .iter()
+                .any(|t| tool_name(&t.spec) == "read_tool_output")
+        );
+
+        features.enable(Feature::ReadToolOutput);
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+        assert_contains_tool_names(&tools, &["read_tool_output"]);
+    }
+
     #[test]
     fn test_build_specs_shell_command_present() {
         assert_model_tools(
@@ -1535,7 +2328,11 @@ mod tests {
                         ),
                         (
                             "number_argument".to_string(),
-                            JsonSchema::Number { description: None }
+                            JsonSchema::Number {
+                                description: None,
+                                minimum: None,
+                                maximum: None,
+                            }
                         ),
                         (
                             "object_argument".to_string(),
@@ -1547,7 +2344,11 @@ mod tests {
                                     ),
                                     (
                                         "number_property".to_string(),
-                                        JsonSchema::Number { description: None }
+                                        JsonSchema::Number {
+                                            description: None,
+                                            minimum: None,
+                                            maximum: None,
+                                        }
                                     ),
                                 ]),
                                 required: Some(vec![
@@ -1700,7 +2501,7 @@ mod tests {
     }
 
     #[test]
-    fn test_mcp_tool_integer_normalized_to_number() {
+    fn test_mcp_tool_integer_preserved() {
         let model_family = find_family_for_model("gpt-5-codex")
             .expect("gpt-5-codex should be a valid model family");
         let mut features = Features::with_defaults();
@@ -1741,7 +2542,11 @@ mod tests {
                 parameters: JsonSchema::Object {
                     properties: BTreeMap::from([(
                         "page".to_string(),
-                        JsonSchema::Number { description: None }
+                        JsonSchema::Integer {
+                            description: None,
+                            minimum: None,
+                            maximum: None,
+                        }
                     )]),
                     required: None,
                     additional_properties: None,
@@ -1810,7 +2615,7 @@ mod tests {
     }
 
     #[test]
-    fn test_mcp_tool_anyof_defaults_to_string() {
+    fn test_mcp_tool_anyof_preserved() {
         let model_family = find_family_for_model("gpt-5-codex")
             .expect("gpt-5-codex should be a valid model family");
         let mut features = Features::with_defaults();
@@ -1851,7 +2656,17 @@ mod tests {
                 parameters: JsonSchema::Object {
                     properties: BTreeMap::from([(
                         "value".to_string(),
-                        JsonSchema::String { description: None }
+                        JsonSchema::AnyOf {
+                            variants: vec![
+                                JsonSchema::String { description: None },
+                                JsonSchema::Number {
+                                    description: None,
+                                    minimum: None,
+                                    maximum: None,
+                                },
+                            ],
+                            description: None,
+                        }
                     )]),
                     required: None,
                     additional_properties: None,
@@ -1862,9 +2677,144 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mcp_tool_enum_const_and_bounds_preserved() {
+        let tool = mcp_tool_to_openai_tool(
+            "dash/query".to_string(),
+            mcp_types::Tool {
+                name: "query".to_string(),
+                input_schema: ToolInputSchema {
+                    properties: Some(serde_json::json!({
+                        "mode": { "type": "string", "enum": ["fast", "slow"] },
+                        "kind": { "const": "file" },
+                        "limit": { "type": "integer", "minimum": 1, "maximum": 100 },
+                        "name": { "type": ["string", "null"], "description": "Optional name" },
+                        "unresolved": { "$ref": "#/$defs/Missing" },
+                    })),
+                    required: None,
+                    r#type: "object".to_string(),
+                },
+                output_schema: None,
+                title: None,
+                annotations: None,
+                description: Some("Query".to_string()),
+            },
+        )
+        .expect("convert tool");
+
+        assert_eq!(
+            tool.parameters,
+            JsonSchema::Object {
+                properties: BTreeMap::from([
+                    (
+                        "kind".to_string(),
+                        JsonSchema::Const {
+                            value: json!("file"),
+                            description: None,
+                        },
+                    ),
+                    (
+                        "limit".to_string(),
+                        JsonSchema::Integer {
+                            description: None,
+                            minimum: Some(1),
+                            maximum: Some(100),
+                        },
+                    ),
+                    (
+                        "mode".to_string(),
+                        JsonSchema::Enum {
+                            values: vec![json!("fast"), json!("slow")],
+                            description: None,
+                        },
+                    ),
+                    (
+                        "name".to_string(),
+                        JsonSchema::AnyOf {
+                            variants: vec![
+                                JsonSchema::String { description: None },
+                                JsonSchema::Null { description: None },
+                            ],
+                            description: Some("Optional name".to_string()),
+                        },
+                    ),
+                    (
+                        "unresolved".to_string(),
+                        JsonSchema::String { description: None },
+                    ),
+                ]),
+                required: None,
+                additional_properties: None,
+            }
+        );
+
+        // Serializing keeps the constructs instead of flattening them.
+        assert_eq!(
+            serde_json::to_value(&tool.parameters).expect("serialize")["properties"]["mode"],
+            json!({ "type": "string", "enum": ["fast", "slow"] })
+        );
+    }
+
+    #[test]
+    fn test_tools_json_downconverted_per_wire_api() {
+        let tool = ToolSpec::Function(ResponsesApiTool {
+            name: "pick".to_string(),
+            description: "Pick a shape".to_string(),
+            strict: false,
+            parameters: JsonSchema::Object {
+                properties: BTreeMap::from([
+                    (
+                        "shape".to_string(),
+                        JsonSchema::OneOf {
+                            variants: vec![
+                                JsonSchema::String { description: None },
+                                JsonSchema::Null { description: None },
+                            ],
+                            description: None,
+                        },
+                    ),
+                    (
+                        "kind".to_string(),
+                        JsonSchema::Const {
+                            value: json!("circle"),
+                            description: None,
+                        },
+                    ),
+                ]),
+                required: None,
+                additional_properties: Some(false.into()),
+            },
+        });
+        let tools = [tool];
+
+        let responses = create_tools_json_for_responses_api(&tools).expect("responses json");
+        assert_eq!(
+            responses[0]["parameters"]["properties"]["shape"],
+            json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] })
+        );
+
+        let anthropic = create_tools_json_for_anthropic_api(&tools).expect("anthropic json");
+        assert_eq!(
+            anthropic[0]["input_schema"]["properties"]["shape"],
+            json!({ "oneOf": [{ "type": "string" }, { "type": "null" }] })
+        );
+
+        let gemini = create_tools_json_for_gemini_api(&tools).expect("gemini json");
+        assert_eq!(
+            gemini[0]["parameters"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "kind": { "type": "string", "enum": ["circle"] },
+                    "shape": { "type": "string", "nullable": true },
+                },
+            })
+        );
+    }
+
     #[test]
     fn test_shell_tool() {
-        let tool = super::create_shell_tool();
+        let tool = super::create_shell_tool(&[]);
         let ToolSpec::Function(ResponsesApiTool {
             description, name, ..
         }) = &tool
@@ -1877,9 +2827,44 @@ mod tests {
         assert_eq!(description, expected);
     }
 
+    #[test]
+    fn test_shell_tool_lists_env_profiles() {
+        let tool = super::create_shell_tool(&[
+            (
+                "aws-prod".to_string(),
+                Some("production AWS account".to_string()),
+            ),
+            ("npm".to_string(), None),
+        ]);
+        let ToolSpec::Function(ResponsesApiTool {
+            parameters: JsonSchema::Object { properties, .. },
+            ..
+        }) = &tool
+        else {
+            panic!("expected function tool");
+        };
+        assert_eq!(
+            properties.get("env_profile"),
+            Some(&JsonSchema::Enum {
+                values: vec![json!("aws-prod"), json!("npm")],
+                description: Some(
+                    "Optional environment profile that sets extra variables, such as credentials, for this command. Available: `aws-prod` (production AWS account), `npm`."
+                        .to_string()
+                ),
+            })
+        );
+        assert!(!matches!(
+            super::create_shell_tool(&[]),
+            ToolSpec::Function(ResponsesApiTool {
+                parameters: JsonSchema::Object { ref properties, .. },
+                ..
+            }) if properties.contains_key("env_profile")
+        ));
+    }
+
     #[test]
     fn test_shell_command_tool() {
-        let tool = super::create_shell_command_tool();
+        let tool = super::create_shell_command_tool(&[]);
         let ToolSpec::Function(ResponsesApiTool {
             description, name, ..
         }) = &tool
@@ -1964,7 +2949,11 @@ mod tests {
                         ),
                         (
                             "number_argument".to_string(),
-                            JsonSchema::Number { description: None }
+                            JsonSchema::Number {
+                                description: None,
+                                minimum: None,
+                                maximum: None,
+                            }
                         ),
                         (
                             "object_argument".to_string(),
@@ -1976,7 +2965,11 @@ mod tests {
                                     ),
                                     (
                                         "number_property".to_string(),
-                                        JsonSchema::Number { description: None }
+                                        JsonSchema::Number {
+                                            description: None,
+                                            minimum: None,
+                                            maximum: None,
+                                        }
                                     ),
                                 ]),
                                 required: Some(vec![
@@ -87,6 +87,8 @@ pub(crate) struct UnifiedExecResponse {
     pub chunk_id: String,
     pub wall_time: Duration,
     pub output: String,
+    /// Untruncated output; set only when `output` was truncated.
+    pub full_output: Option<String>,
     pub session_id: Option<i32>,
     pub exit_code: Option<i32>,
     pub original_token_count: Option<usize>,
@@ -14,6 +14,7 @@ use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StreamOutput;
 use crate::exec::is_likely_sandbox_denied;
+use crate::network_proxy::RunningNetworkProxy;
 use crate::truncate::truncate_middle;
 use codex_utils_pty::ExecCommandSession;
 use codex_utils_pty::SpawnedPty;
@@ -74,6 +75,7 @@ pub(crate) struct UnifiedExecSession {
     output_notify: Arc<Notify>,
     output_task: JoinHandle<()>,
     sandbox_type: SandboxType,
+    _network_proxy: Option<RunningNetworkProxy>,
 }
 
 impl UnifiedExecSession {
@@ -108,9 +110,15 @@ impl UnifiedExecSession {
             output_notify,
             output_task,
             sandbox_type,
+            _network_proxy: None,
         }
     }
 
+    /// Keep the sandbox network proxy serving for as long as this session lives.
+    pub(super) fn set_network_proxy(&mut self, network_proxy: Option<RunningNetworkProxy>) {
+        self._network_proxy = network_proxy;
+    }
+
     pub(super) fn writer_sender(&self) -> mpsc::Sender<Vec<u8>> {
         self.session.writer_sender()
     }
@@ -130,6 +138,10 @@ impl UnifiedExecSession {
         self.session.exit_code()
     }
 
+    pub(super) fn exit_signal(&self) -> Option<i32> {
+        self.session.exit_signal()
+    }
+
     async fn snapshot_output(&self) -> Vec<Vec<u8>> {
         let guard = self.output_buffer.lock().await;
         guard.snapshot()
@@ -162,6 +174,7 @@ impl UnifiedExecSession {
             aggregated_output: StreamOutput::new(aggregated_text.clone()),
             duration: Duration::ZERO,
             timed_out: false,
+            resource_limit_exceeded: None,
         };
 
         if is_likely_sandbox_denied(self.sandbox_type(), &exec_output) {
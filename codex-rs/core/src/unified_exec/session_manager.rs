@@ -9,7 +9,9 @@ use tokio::time::Instant;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::StdoutStream;
 use crate::exec::StreamOutput;
+use crate::exec::detect_exceeded_resource_limit;
 use crate::exec_env::create_env;
 use crate::protocol::BackgroundEventEvent;
 use crate::protocol::EventMsg;
@@ -71,17 +73,19 @@ impl UnifiedExecSessionManager {
 
         let text = String::from_utf8_lossy(&collected).to_string();
         let (output, original_token_count) = truncate_output_to_tokens(&text, max_tokens);
+        let full_output = original_token_count.is_some().then_some(text);
         let chunk_id = generate_chunk_id();
         let has_exited = session.has_exited();
         let stored_id = self
             .store_session(session, context, &request.command, cwd.clone(), start)
             .await;
-        let exit_code = self
+        let (exit_code, exit_signal) = self
             .sessions
             .lock()
             .await
             .get(&stored_id)
-            .map(|entry| entry.session.exit_code());
+            .map(|entry| (entry.session.exit_code(), entry.session.exit_signal()))
+            .unzip();
         // Only include a session_id in the response if the process is still alive.
         let session_id = if has_exited { None } else { Some(stored_id) };
 
@@ -90,6 +94,7 @@ impl UnifiedExecSessionManager {
             chunk_id,
             wall_time,
             output,
+            full_output,
             session_id,
             exit_code: exit_code.flatten(),
             original_token_count,
@@ -109,6 +114,7 @@ impl UnifiedExecSessionManager {
                 cwd,
                 response.output.clone(),
                 exit,
+                exit_signal.flatten(),
                 response.wall_time,
             )
             .await;
@@ -176,6 +182,7 @@ impl UnifiedExecSessionManager {
 
         let text = String::from_utf8_lossy(&collected).to_string();
         let (output, original_token_count) = truncate_output_to_tokens(&text, max_tokens);
+        let full_output = original_token_count.is_some().then_some(text);
         let chunk_id = generate_chunk_id();
 
         let status = self.refresh_session_state(session_id).await;
@@ -197,6 +204,7 @@ impl UnifiedExecSessionManager {
             chunk_id,
             wall_time,
             output,
+            full_output,
             session_id,
             exit_code,
             original_token_count,
@@ -210,6 +218,7 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(response.output.clone()),
             duration: response.wall_time,
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         interaction_emitter
             .emit(
@@ -345,6 +354,10 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(aggregated_output),
             duration,
             timed_out: false,
+            resource_limit_exceeded: detect_exceeded_resource_limit(
+                &entry.turn_ref.resource_limits,
+                entry.session.exit_signal(),
+            ),
         };
         let event_ctx = ToolEventCtx::new(
             entry.session_ref.as_ref(),
@@ -369,6 +382,7 @@ impl UnifiedExecSessionManager {
         cwd: PathBuf,
         aggregated_output: String,
         exit_code: i32,
+        exit_signal: Option<i32>,
         duration: Duration,
     ) {
         let output = ExecToolCallOutput {
@@ -378,6 +392,10 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(aggregated_output),
             duration,
             timed_out: false,
+            resource_limit_exceeded: detect_exceeded_resource_limit(
+                &context.turn.resource_limits,
+                exit_signal,
+            ),
         };
         let event_ctx = ToolEventCtx::new(
             context.session.as_ref(),
@@ -407,15 +425,24 @@ impl UnifiedExecSessionManager {
             .await;
     }
 
+    /// Opens a PTY session for `env`. Requests denied by the sandbox network
+    /// proxy are reported on `stdout_stream`.
     pub(crate) async fn open_session_with_exec_env(
         &self,
         env: &ExecEnv,
+        stdout_stream: Option<StdoutStream>,
     ) -> Result<UnifiedExecSession, UnifiedExecError> {
         let (program, args) = env
             .command
             .split_first()
             .ok_or(UnifiedExecError::MissingCommandLine)?;
 
+        let network_proxy = env
+            .network_proxy
+            .as_ref()
+            .map(|proxy| proxy.start(stdout_stream))
+            .transpose()
+            .map_err(|err| UnifiedExecError::create_session(err.to_string()))?;
         let spawned = codex_utils_pty::spawn_pty_process(
             program,
             args,
@@ -425,7 +452,9 @@ impl UnifiedExecSessionManager {
         )
         .await
         .map_err(|err| UnifiedExecError::create_session(err.to_string()))?;
-        UnifiedExecSession::from_spawned(spawned, env.sandbox).await
+        let mut session = UnifiedExecSession::from_spawned(spawned, env.sandbox).await?;
+        session.set_network_proxy(network_proxy);
+        Ok(session)
     }
 
     pub(super) async fn open_session_with_sandbox(
@@ -0,0 +1,271 @@
+use std::sync::Arc;
+
+use codex_app_server_protocol::AuthMode;
+use codex_core::ContentItem;
+use codex_core::ModelClient;
+use codex_core::ModelProviderInfo;
+use codex_core::Prompt;
+use codex_core::ResponseEvent;
+use codex_core::ResponseItem;
+use codex_core::WireApi;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::ConversationId;
+use codex_protocol::models::FunctionCallOutputPayload;
+use core_test_support::load_default_config_for_test;
+use core_test_support::skip_if_no_network;
+use futures::StreamExt;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::header;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+async fn run_stream(sse_body: &str, input: Vec<ResponseItem>) -> (Vec<ResponseEvent>, Value) {
+    let server = MockServer::start().await;
+
+    let template = ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_string(sse_body.to_string());
+
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(header("anthropic-version", "2023-06-01"))
+        .respond_with(template)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = ModelProviderInfo {
+        name: "mock".into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        env_key: None,
+        env_key_instructions: None,
+        experimental_bearer_token: None,
+        wire_api: WireApi::Anthropic,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(5_000),
+        requires_openai_auth: false,
+    };
+
+    let codex_home = match TempDir::new() {
+        Ok(dir) => dir,
+        Err(e) => panic!("failed to create TempDir: {e}"),
+    };
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider_id = provider.name.clone();
+    config.model_provider = provider.clone();
+    let summary = config.model_reasoning_summary;
+    let config = Arc::new(config);
+
+    let conversation_id = ConversationId::new();
+
+    let otel_event_manager = OtelEventManager::new(
+        conversation_id,
+        config.model.as_str(),
+        config.model_family.slug.as_str(),
+        None,
+        Some("test@test.com".to_string()),
+        Some(AuthMode::ApiKey),
+        false,
+        "test".to_string(),
+    );
+
+    let client = ModelClient::new(
+        Arc::clone(&config),
+        None,
+        otel_event_manager,
+        provider,
+        None,
+        summary,
+        conversation_id,
+        codex_protocol::protocol::SessionSource::Exec,
+    );
+
+    let mut prompt = Prompt::default();
+    prompt.input = input;
+
+    let mut stream = match client.stream(&prompt).await {
+        Ok(s) => s,
+        Err(e) => panic!("stream anthropic failed: {e}"),
+    };
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(ev) => events.push(ev),
+            Err(_e) => break,
+        }
+    }
+
+    let requests = match server.received_requests().await {
+        Some(requests) => requests,
+        None => panic!("request recording disabled"),
+    };
+    let body = match requests.first() {
+        Some(request) => request.body_json::<Value>().unwrap_or_default(),
+        None => panic!("no request captured"),
+    };
+    (events, body)
+}
+
+fn user_message(text: &str) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: text.to_string(),
+        }],
+    }
+}
+
+fn sse(events: &[Value]) -> String {
+    events
+        .iter()
+        .map(|event| {
+            let kind = event["type"].as_str().unwrap_or_default();
+            format!("event: {kind}\ndata: {event}\n\n")
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streams_text_and_tool_use() {
+    skip_if_no_network!();
+
+    let body = sse(&[
+        json!({"type": "message_start", "message": {"id": "msg_1", "usage": {"input_tokens": 10, "cache_read_input_tokens": 4}}}),
+        json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Hi "}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "there"}}),
+        json!({"type": "content_block_stop", "index": 0}),
+        json!({"type": "content_block_start", "index": 1, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {}}}),
+        json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "{\"command\":"}}),
+        json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "[\"ls\"]}"}}),
+        json!({"type": "content_block_stop", "index": 1}),
+        json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"}, "usage": {"output_tokens": 7}}),
+        json!({"type": "message_stop"}),
+    ]);
+
+    let (events, _) = run_stream(&body, vec![user_message("hello")]).await;
+
+    let done: Vec<&ResponseItem> = events
+        .iter()
+        .filter_map(|ev| match ev {
+            ResponseEvent::OutputItemDone(item) => Some(item),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(done.len(), 2, "unexpected events: {events:?}");
+
+    match done[0] {
+        ResponseItem::Message { content, .. } => assert_eq!(
+            content,
+            &vec![ContentItem::OutputText {
+                text: "Hi there".to_string()
+            }]
+        ),
+        other => panic!("expected message, got {other:?}"),
+    }
+    match done[1] {
+        ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        } => {
+            assert_eq!(name, "shell");
+            assert_eq!(call_id, "toolu_1");
+            assert_eq!(arguments, "{\"command\":[\"ls\"]}");
+        }
+        other => panic!("expected function call, got {other:?}"),
+    }
+
+    match events.last() {
+        Some(ResponseEvent::Completed {
+            response_id,
+            token_usage: Some(usage),
+        }) => {
+            assert_eq!(response_id, "msg_1");
+            assert_eq!(usage.input_tokens, 14);
+            assert_eq!(usage.cached_input_tokens, 4);
+            assert_eq!(usage.output_tokens, 7);
+        }
+        other => panic!("expected completed event, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn errors_when_stream_ends_without_message_stop() {
+    skip_if_no_network!();
+
+    let body = sse(&[
+        json!({"type": "message_start", "message": {"id": "msg_1", "usage": {"input_tokens": 1}}}),
+        json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+    ]);
+
+    let (events, _) = run_stream(&body, vec![user_message("hello")]).await;
+
+    assert!(
+        !events
+            .iter()
+            .any(|ev| matches!(ev, ResponseEvent::Completed { .. })),
+        "unexpected completion: {events:?}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn request_maps_tool_history_to_content_blocks() {
+    skip_if_no_network!();
+
+    let body = sse(&[
+        json!({"type": "message_start", "message": {"id": "msg_1", "usage": {}}}),
+        json!({"type": "message_stop"}),
+    ]);
+    let input = vec![
+        user_message("list files"),
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{\"command\":[\"ls\"]}".to_string(),
+            call_id: "toolu_1".to_string(),
+        },
+        ResponseItem::FunctionCallOutput {
+            call_id: "toolu_1".to_string(),
+            output: FunctionCallOutputPayload {
+                content: "README.md".to_string(),
+                ..Default::default()
+            },
+        },
+    ];
+
+    let (_, request) = run_stream(&body, input).await;
+
+    assert!(request["system"].is_string());
+    assert_eq!(request["stream"], json!(true));
+    assert_eq!(
+        request["messages"],
+        json!([
+            {"role": "user", "content": [{"type": "text", "text": "list files"}]},
+            {"role": "assistant", "content": [{
+                "type": "tool_use",
+                "id": "toolu_1",
+                "name": "shell",
+                "input": {"command": ["ls"]},
+            }]},
+            {"role": "user", "content": [{
+                "type": "tool_result",
+                "tool_use_id": "toolu_1",
+                "content": "README.md",
+                "is_error": false,
+            }]},
+        ])
+    );
+}
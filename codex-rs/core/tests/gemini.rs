@@ -0,0 +1,266 @@
+use std::sync::Arc;
+
+use codex_app_server_protocol::AuthMode;
+use codex_core::ContentItem;
+use codex_core::ModelClient;
+use codex_core::ModelProviderInfo;
+use codex_core::Prompt;
+use codex_core::ResponseEvent;
+use codex_core::ResponseItem;
+use codex_core::WireApi;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::ConversationId;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ReasoningItemContent;
+use core_test_support::load_default_config_for_test;
+use core_test_support::skip_if_no_network;
+use futures::StreamExt;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path_regex;
+use wiremock::matchers::query_param;
+
+async fn run_stream(sse_body: &str, input: Vec<ResponseItem>) -> (Vec<ResponseEvent>, Value) {
+    let server = MockServer::start().await;
+
+    let template = ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_string(sse_body.to_string());
+
+    Mock::given(method("POST"))
+        .and(path_regex(r"^/v1beta/models/[^/]+:streamGenerateContent$"))
+        .and(query_param("alt", "sse"))
+        .respond_with(template)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = ModelProviderInfo {
+        name: "mock".into(),
+        base_url: Some(format!("{}/v1beta", server.uri())),
+        env_key: None,
+        env_key_instructions: None,
+        experimental_bearer_token: None,
+        wire_api: WireApi::Gemini,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(5_000),
+        requires_openai_auth: false,
+    };
+
+    let codex_home = match TempDir::new() {
+        Ok(dir) => dir,
+        Err(e) => panic!("failed to create TempDir: {e}"),
+    };
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider_id = provider.name.clone();
+    config.model_provider = provider.clone();
+    let summary = config.model_reasoning_summary;
+    let config = Arc::new(config);
+
+    let conversation_id = ConversationId::new();
+
+    let otel_event_manager = OtelEventManager::new(
+        conversation_id,
+        config.model.as_str(),
+        config.model_family.slug.as_str(),
+        None,
+        Some("test@test.com".to_string()),
+        Some(AuthMode::ApiKey),
+        false,
+        "test".to_string(),
+    );
+
+    let client = ModelClient::new(
+        Arc::clone(&config),
+        None,
+        otel_event_manager,
+        provider,
+        None,
+        summary,
+        conversation_id,
+        codex_protocol::protocol::SessionSource::Exec,
+    );
+
+    let mut prompt = Prompt::default();
+    prompt.input = input;
+
+    let mut stream = match client.stream(&prompt).await {
+        Ok(s) => s,
+        Err(e) => panic!("stream gemini failed: {e}"),
+    };
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(ev) => events.push(ev),
+            Err(_e) => break,
+        }
+    }
+
+    let requests = match server.received_requests().await {
+        Some(requests) => requests,
+        None => panic!("request recording disabled"),
+    };
+    let body = match requests.first() {
+        Some(request) => request.body_json::<Value>().unwrap_or_default(),
+        None => panic!("no request captured"),
+    };
+    (events, body)
+}
+
+fn user_message(text: &str) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: text.to_string(),
+        }],
+    }
+}
+
+fn sse(chunks: &[Value]) -> String {
+    chunks
+        .iter()
+        .map(|chunk| format!("data: {chunk}\n\n"))
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streams_thoughts_text_and_function_call() {
+    skip_if_no_network!();
+
+    let body = sse(&[
+        json!({"responseId": "resp_1", "candidates": [{"content": {"role": "model", "parts": [
+            {"text": "Thinking about it", "thought": true},
+        ]}}]}),
+        json!({"responseId": "resp_1", "candidates": [{"content": {"role": "model", "parts": [
+            {"text": "Listing "},
+            {"text": "files"},
+        ]}}]}),
+        json!({"responseId": "resp_1", "candidates": [{"content": {"role": "model", "parts": [
+            {"functionCall": {"name": "shell", "args": {"command": ["ls"]}}, "thoughtSignature": "sig"},
+        ]}, "finishReason": "STOP"}], "usageMetadata": {
+            "promptTokenCount": 12,
+            "cachedContentTokenCount": 2,
+            "candidatesTokenCount": 5,
+            "thoughtsTokenCount": 3,
+            "totalTokenCount": 20,
+        }}),
+    ]);
+
+    let (events, _) = run_stream(&body, vec![user_message("hello")]).await;
+
+    let done: Vec<&ResponseItem> = events
+        .iter()
+        .filter_map(|ev| match ev {
+            ResponseEvent::OutputItemDone(item) => Some(item),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(done.len(), 3, "unexpected events: {events:?}");
+
+    match done[0] {
+        ResponseItem::Reasoning {
+            content: Some(content),
+            encrypted_content,
+            ..
+        } => {
+            assert_eq!(
+                content,
+                &vec![ReasoningItemContent::Text {
+                    text: "Thinking about it".to_string()
+                }]
+            );
+            assert_eq!(encrypted_content.as_deref(), Some("sig"));
+        }
+        other => panic!("expected reasoning, got {other:?}"),
+    }
+    match done[1] {
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => {
+            assert_eq!(name, "shell");
+            assert_eq!(arguments, "{\"command\":[\"ls\"]}");
+        }
+        other => panic!("expected function call, got {other:?}"),
+    }
+    match done[2] {
+        ResponseItem::Message { content, .. } => assert_eq!(
+            content,
+            &vec![ContentItem::OutputText {
+                text: "Listing files".to_string()
+            }]
+        ),
+        other => panic!("expected message, got {other:?}"),
+    }
+
+    match events.last() {
+        Some(ResponseEvent::Completed {
+            response_id,
+            token_usage: Some(usage),
+        }) => {
+            assert_eq!(response_id, "resp_1");
+            assert_eq!(usage.input_tokens, 12);
+            assert_eq!(usage.cached_input_tokens, 2);
+            assert_eq!(usage.output_tokens, 8);
+            assert_eq!(usage.reasoning_output_tokens, 3);
+            assert_eq!(usage.total_tokens, 20);
+        }
+        other => panic!("expected completed event, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn request_maps_tool_history_to_parts() {
+    skip_if_no_network!();
+
+    let body = sse(&[json!({"responseId": "resp_1", "candidates": []})]);
+    let input = vec![
+        user_message("list files"),
+        ResponseItem::Reasoning {
+            id: String::new(),
+            summary: Vec::new(),
+            content: None,
+            encrypted_content: Some("sig".to_string()),
+        },
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{\"command\":[\"ls\"]}".to_string(),
+            call_id: "call_1".to_string(),
+        },
+        ResponseItem::FunctionCallOutput {
+            call_id: "call_1".to_string(),
+            output: FunctionCallOutputPayload {
+                content: "README.md".to_string(),
+                ..Default::default()
+            },
+        },
+    ];
+
+    let (_, request) = run_stream(&body, input).await;
+
+    assert!(request["systemInstruction"]["parts"][0]["text"].is_string());
+    assert_eq!(
+        request["contents"],
+        json!([
+            {"role": "user", "parts": [{"text": "list files"}]},
+            {"role": "model", "parts": [{
+                "functionCall": {"name": "shell", "args": {"command": ["ls"]}},
+                "thoughtSignature": "sig",
+            }]},
+            {"role": "user", "parts": [{
+                "functionResponse": {"name": "shell", "response": {"content": "README.md"}},
+            }]},
+        ])
+    );
+}
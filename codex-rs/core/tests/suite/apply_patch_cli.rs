@@ -558,8 +558,11 @@ async fn apply_patch_cli_rejects_path_traversal_outside_workspace(
     let sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![],
         network_access: false,
+        network_allowlist: vec![],
+        read_only_subpaths: vec![],
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        container: false,
     };
     harness
         .submit_with_policy(
@@ -613,8 +616,11 @@ async fn apply_patch_cli_rejects_move_path_traversal_outside_workspace(
     let sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![],
         network_access: false,
+        network_allowlist: vec![],
+        read_only_subpaths: vec![],
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        container: false,
     };
     harness
         .submit_with_policy("attempt move traversal via apply_patch", sandbox_policy)
@@ -573,8 +573,11 @@ fn scenarios() -> Vec<ScenarioSpec> {
     let workspace_write = |network_access| SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![],
         network_access,
+        network_allowlist: vec![],
+        read_only_subpaths: vec![],
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        container: false,
     };
 
     vec![
@@ -0,0 +1,172 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use anyhow::Result;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::SandboxRiskLevel;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::mount_sse_once_match;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wiremock::MockServer;
+use wiremock::matchers::body_string_contains;
+
+const CALL_ID: &str = "assessed-call";
+
+fn workspace_write() -> SandboxPolicy {
+    SandboxPolicy::new_workspace_write_policy()
+}
+
+async fn build(server: &MockServer) -> Result<TestCodex> {
+    let mut builder = test_codex().with_config(|config| {
+        config.approval_policy = AskForApproval::OnRequest;
+        config.sandbox_policy = workspace_write();
+        config.command_assessment.enabled = true;
+    });
+    builder.build(server).await
+}
+
+/// Mounts the assessment verdict first so it wins over the turn responses,
+/// which match any request.
+async fn mount_turn(server: &MockServer, risk_level: &str, command: &[&str]) -> Result<()> {
+    let verdict = json!({
+        "description": "Creates a file in the workspace.",
+        "risk_level": risk_level,
+    });
+    mount_sse_once_match(
+        server,
+        body_string_contains("security analyst"),
+        sse(vec![
+            ev_response_created("resp-assessment"),
+            ev_assistant_message("msg-assessment", &verdict.to_string()),
+            ev_completed("resp-assessment"),
+        ]),
+    )
+    .await;
+
+    let args = serde_json::to_string(&json!({
+        "command": command,
+        "timeout_ms": 5_000,
+    }))?;
+    mount_sse_once(
+        server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call(CALL_ID, "shell", &args),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    mount_sse_once(
+        server,
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+    Ok(())
+}
+
+async fn submit_turn(test: &TestCodex) -> Result<()> {
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "create the file".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::OnRequest,
+            sandbox_policy: workspace_write(),
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn low_risk_commands_run_without_prompting() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = build(&server).await?;
+    mount_turn(&server, "low", &["touch", "assessed.txt"]).await?;
+
+    submit_turn(&test).await?;
+
+    let event = wait_for_event(&test.codex, |event| {
+        matches!(
+            event,
+            EventMsg::ExecApprovalRequest(_) | EventMsg::TaskComplete(_)
+        )
+    })
+    .await;
+    match event {
+        EventMsg::TaskComplete(_) => {}
+        EventMsg::ExecApprovalRequest(event) => {
+            panic!("unexpected approval request: {:?}", event.command)
+        }
+        other => panic!("unexpected event: {other:?}"),
+    }
+    assert!(test.cwd.path().join("assessed.txt").exists());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn flagged_commands_ask_with_the_assessment() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = build(&server).await?;
+    mount_turn(&server, "high", &["touch", "flagged.txt"]).await?;
+
+    submit_turn(&test).await?;
+
+    let event = wait_for_event(&test.codex, |event| {
+        matches!(
+            event,
+            EventMsg::ExecApprovalRequest(_) | EventMsg::TaskComplete(_)
+        )
+    })
+    .await;
+    let EventMsg::ExecApprovalRequest(approval) = event else {
+        panic!("expected approval request before completion, got {event:?}");
+    };
+    assert_eq!(approval.call_id, CALL_ID);
+    assert_eq!(
+        approval.risk.map(|risk| risk.risk_level),
+        Some(SandboxRiskLevel::High)
+    );
+
+    test.codex
+        .submit(Op::ExecApproval {
+            id: "0".into(),
+            decision: ReviewDecision::Denied,
+        })
+        .await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    assert!(!test.cwd.path().join("flagged.txt").exists());
+
+    Ok(())
+}
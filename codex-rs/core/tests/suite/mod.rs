@@ -23,6 +23,7 @@ mod auth_refresh;
 mod cli_stream;
 mod client;
 mod codex_delegate;
+mod command_assessment;
 mod compact;
 mod compact_resume_fork;
 mod deprecation_notice;
@@ -355,8 +355,11 @@ async fn overrides_turn_context_but_keeps_cached_prefix_and_key_constant() -> an
             sandbox_policy: Some(SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![writable.path().to_path_buf()],
                 network_access: true,
+                network_allowlist: vec![],
+                read_only_subpaths: vec![],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                container: false,
             }),
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
@@ -459,8 +462,11 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() -> anyhow::Res
             sandbox_policy: SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![writable.path().to_path_buf()],
                 network_access: true,
+                network_allowlist: vec![],
+                read_only_subpaths: vec![],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                container: false,
             },
             model: "o3".to_string(),
             effort: Some(ReasoningEffort::High),
@@ -78,8 +78,11 @@ async fn if_parent_of_repo_is_writable_then_dot_git_folder_is_writable() {
     let policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![test_scenario.repo_parent.clone()],
         network_access: false,
+        network_allowlist: vec![],
+        read_only_subpaths: vec![],
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        container: false,
     };
 
     test_scenario
@@ -104,8 +107,11 @@ async fn if_git_repo_is_writable_root_then_dot_git_folder_is_read_only() {
     let policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![test_scenario.repo_root.clone()],
         network_access: false,
+        network_allowlist: vec![],
+        read_only_subpaths: vec![],
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        container: false,
     };
 
     test_scenario
@@ -190,6 +196,7 @@ async fn python_getpwuid_works_under_seatbelt() {
         command_cwd,
         &policy,
         sandbox_cwd.as_path(),
+        &[],
         StdioPolicy::RedirectForShellTool,
         HashMap::new(),
     )
@@ -242,6 +249,7 @@ async fn java_home_finds_runtime_under_seatbelt() {
         command_cwd,
         &policy,
         sandbox_cwd.as_path(),
+        &[],
         StdioPolicy::RedirectForShellTool,
         env,
     )
@@ -298,6 +306,7 @@ async fn touch(path: &Path, policy: &SandboxPolicy) -> bool {
         command_cwd,
         policy,
         sandbox_cwd.as_path(),
+        &[],
         StdioPolicy::RedirectForShellTool,
         HashMap::new(),
     )
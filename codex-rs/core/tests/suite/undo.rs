@@ -489,3 +489,53 @@ async fn undo_overwrites_manual_edits_after_turn() -> Result<()> {
 
     Ok(())
 }
+
+async fn invoke_restore_checkpoint(
+    codex: &Arc<CodexConversation>,
+    turn: usize,
+) -> Result<UndoCompletedEvent> {
+    codex.submit(Op::RestoreCheckpoint { turn }).await?;
+    let event = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::UndoCompleted(done) => Some(done.clone()),
+        _ => None,
+    })
+    .await;
+    Ok(event)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn restore_checkpoint_rewinds_several_turns() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let add_patch = "*** Begin Patch\n*** Add File: story.txt\n+first version\n*** End Patch";
+    run_apply_patch_turn(&harness, "create story", "rewind-one", add_patch, "done").await?;
+    let update_patch = "*** Begin Patch\n*** Update File: story.txt\n@@\n-first version\n+second version\n*** End Patch";
+    run_apply_patch_turn(&harness, "revise story", "rewind-two", update_patch, "done").await?;
+    let extra_patch = "*** Begin Patch\n*** Add File: extra.txt\n+extra\n*** End Patch";
+    run_apply_patch_turn(&harness, "add extra", "rewind-three", extra_patch, "done").await?;
+
+    let story = harness.path("story.txt");
+    let extra = harness.path("extra.txt");
+    assert_eq!(fs::read_to_string(&story)?, "second version\n");
+    assert!(extra.exists());
+
+    let codex = Arc::clone(&harness.test().codex);
+    let completed = invoke_restore_checkpoint(&codex, 1).await?;
+    assert!(completed.success, "rewind failed: {:?}", completed.message);
+
+    assert_eq!(fs::read_to_string(&story)?, "first version\n");
+    assert!(!extra.exists());
+
+    // Turns 1 and 2 are gone, so there is nothing left to rewind to there.
+    let completed = invoke_restore_checkpoint(&codex, 2).await?;
+    assert!(!completed.success);
+    assert_eq!(
+        completed.message.as_deref(),
+        Some("No turn 2 to rewind to.")
+    );
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use clap::ArgGroup;
 use clap::Parser;
 use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
@@ -60,6 +61,14 @@ pub struct Cli {
     #[arg(long = "output-schema", value_name = "FILE")]
     pub output_schema: Option<PathBuf>,
 
+    /// Stop the run once it has used this many tokens in total.
+    #[arg(long = "max-tokens", value_name = "TOKENS")]
+    pub max_tokens: Option<u64>,
+
+    /// Stop the run once its estimated cost reaches this many US dollars.
+    #[arg(long = "max-cost", value_name = "USD")]
+    pub max_cost: Option<f64>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -71,6 +80,24 @@ pub struct Cli {
     #[arg(long = "json", alias = "experimental-json", default_value_t = false)]
     pub json: bool,
 
+    /// Read user turns, approval decisions and interrupts from stdin as JSONL
+    /// and run them in order against one conversation. Implies `--json`.
+    #[arg(
+        long = "input-jsonl",
+        default_value_t = false,
+        conflicts_with_all = ["prompt", "images"]
+    )]
+    pub input_jsonl: bool,
+
+    /// With `--input-jsonl`, keep the configured approval policy instead of
+    /// never asking, so that `approval` lines can answer the requests.
+    #[arg(
+        long = "scripted-approvals",
+        default_value_t = false,
+        requires = "input_jsonl"
+    )]
+    pub scripted_approvals: bool,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
@@ -85,6 +112,9 @@ pub struct Cli {
 pub enum Command {
     /// Resume a previous session by id or pick the most recent with --last.
     Resume(ResumeArgs),
+
+    /// Review code changes and export the findings, e.g. to gate merges in CI.
+    Review(ReviewArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -103,6 +133,44 @@ pub struct ResumeArgs {
     pub prompt: Option<String>,
 }
 
+#[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("target").args(["base", "commit", "range", "uncommitted"])))]
+pub struct ReviewArgs {
+    /// Review the changes that the current branch would merge into BRANCH.
+    #[arg(long = "base", value_name = "BRANCH")]
+    pub base: Option<String>,
+
+    /// Review the changes introduced by a single commit.
+    #[arg(long = "commit", value_name = "SHA")]
+    pub commit: Option<String>,
+
+    /// Review the changes introduced by a commit range such as `main..HEAD`.
+    #[arg(long = "range", value_name = "RANGE")]
+    pub range: Option<String>,
+
+    /// Review staged, unstaged and untracked changes. This is the default.
+    #[arg(long = "uncommitted", default_value_t = false)]
+    pub uncommitted: bool,
+
+    /// Write the findings to FILE as SARIF 2.1.0.
+    #[arg(long = "sarif", value_name = "FILE")]
+    pub sarif: Option<PathBuf>,
+
+    /// Write the findings to FILE as a JSON array of `{path, line, body}`
+    /// inline comments.
+    #[arg(long = "comments", value_name = "FILE")]
+    pub comments: Option<PathBuf>,
+
+    /// Exit with status 3 when a finding has this priority or a more urgent
+    /// one (0 for P0 through 3 for P3).
+    #[arg(
+        long = "fail-on-priority",
+        value_name = "PRIORITY",
+        value_parser = clap::value_parser!(i32).range(0..=3)
+    )]
+    pub fail_on_priority: Option<i32>,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
@@ -1,6 +1,7 @@
 use codex_common::elapsed::format_duration;
 use codex_common::elapsed::format_elapsed;
 use codex_core::config::Config;
+use codex_core::error::describe_resource_limit;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
@@ -14,8 +15,10 @@ use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::NetworkRequestDeniedEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::SecretsRedactedEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
@@ -241,6 +244,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 aggregated_output,
                 duration,
                 exit_code,
+                resource_limit_exceeded,
                 ..
             }) => {
                 let duration = format!(" in {}", format_duration(duration));
@@ -250,8 +254,15 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     .take(MAX_OUTPUT_LINES_FOR_EXEC_TOOL_CALL)
                     .collect::<Vec<_>>()
                     .join("\n");
-                match exit_code {
-                    0 => {
+                match (exit_code, resource_limit_exceeded) {
+                    (_, Some(limit)) => {
+                        let title = format!(
+                            " exceeded its {} limit{duration}:",
+                            describe_resource_limit(Some(limit))
+                        );
+                        ts_msg!(self, "{}", title.style(self.red));
+                    }
+                    (0, None) => {
                         let title = format!(" succeeded{duration}:");
                         ts_msg!(self, "{}", title.style(self.green));
                     }
@@ -303,6 +314,29 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     }
                 }
             }
+            EventMsg::NetworkRequestDenied(NetworkRequestDeniedEvent {
+                call_id: _,
+                host,
+                port,
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} {host}:{port} is not in the network allowlist",
+                    "network blocked:".style(self.yellow).style(self.bold)
+                );
+            }
+            EventMsg::SecretsRedacted(SecretsRedactedEvent {
+                call_id: _,
+                count,
+                session_total: _,
+            }) => {
+                let noun = if count == 1 { "secret" } else { "secrets" };
+                ts_msg!(
+                    self,
+                    "{} masked {count} {noun} in tool output",
+                    "redacted:".style(self.dimmed)
+                );
+            }
             EventMsg::WebSearchEnd(WebSearchEndEvent { call_id: _, query }) => {
                 ts_msg!(self, "🌐 Searched: {query}");
             }
@@ -512,6 +546,10 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 TurnAbortReason::ReviewEnded => {
                     ts_msg!(self, "task aborted: review ended");
                 }
+                TurnAbortReason::BudgetExceeded => {
+                    ts_msg!(self, "task aborted: budget exceeded");
+                    return CodexStatus::InitiateShutdown;
+                }
             },
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::WebSearchBegin(_)
@@ -534,7 +572,8 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::ReasoningContentDelta(_)
             | EventMsg::ReasoningRawContentDelta(_)
             | EventMsg::UndoCompleted(_)
-            | EventMsg::UndoStarted(_) => {}
+            | EventMsg::UndoStarted(_)
+            | EventMsg::CheckpointRestored(_) => {}
         }
         CodexStatus::Running
     }
@@ -6,6 +6,8 @@ use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
 use crate::event_processor::handle_last_message;
 use crate::exec_events::AgentMessageItem;
+use crate::exec_events::ApprovalRequestDetails;
+use crate::exec_events::ApprovalRequestedEvent;
 use crate::exec_events::CommandExecutionItem;
 use crate::exec_events::CommandExecutionStatus;
 use crate::exec_events::ErrorItem;
@@ -36,8 +38,10 @@ use crate::exec_events::WebSearchItem;
 use codex_core::config::Config;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningEvent;
+use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::FileChange;
@@ -48,6 +52,8 @@ use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TaskStartedEvent;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnAbortedEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -123,6 +129,9 @@ impl EventProcessorWithJsonOutput {
             }
             EventMsg::TaskStarted(ev) => self.handle_task_started(ev),
             EventMsg::TaskComplete(_) => self.handle_task_complete(),
+            EventMsg::TurnAborted(ev) => self.handle_turn_aborted(ev),
+            EventMsg::ExecApprovalRequest(ev) => self.handle_exec_approval_request(ev),
+            EventMsg::ApplyPatchApprovalRequest(ev) => self.handle_patch_approval_request(ev),
             EventMsg::Error(ev) => {
                 let error = ThreadErrorEvent {
                     message: ev.message.clone(),
@@ -336,6 +345,16 @@ impl EventProcessorWithJsonOutput {
         }
     }
 
+    fn file_update_changes(&self, changes: &HashMap<PathBuf, FileChange>) -> Vec<FileUpdateChange> {
+        changes
+            .iter()
+            .map(|(path, change)| FileUpdateChange {
+                path: path.to_str().unwrap_or("").to_string(),
+                kind: self.map_change_kind(change),
+            })
+            .collect()
+    }
+
     fn handle_patch_apply_end(&mut self, ev: &PatchApplyEndEvent) -> Vec<ThreadEvent> {
         if let Some(running_patch_apply) = self.running_patch_applies.remove(&ev.call_id) {
             let status = if ev.success {
@@ -347,14 +366,7 @@ impl EventProcessorWithJsonOutput {
                 id: self.get_next_item_id(),
 
                 details: ThreadItemDetails::FileChange(FileChangeItem {
-                    changes: running_patch_apply
-                        .changes
-                        .iter()
-                        .map(|(path, change)| FileUpdateChange {
-                            path: path.to_str().unwrap_or("").to_string(),
-                            kind: self.map_change_kind(change),
-                        })
-                        .collect(),
+                    changes: self.file_update_changes(&running_patch_apply.changes),
                     status,
                 }),
             };
@@ -427,6 +439,32 @@ impl EventProcessorWithJsonOutput {
         vec![ThreadEvent::ItemStarted(ItemStartedEvent { item })]
     }
 
+    fn handle_exec_approval_request(&self, ev: &ExecApprovalRequestEvent) -> Vec<ThreadEvent> {
+        let command = shlex::try_join(ev.command.iter().map(String::as_str))
+            .unwrap_or_else(|_| ev.command.join(" "));
+        vec![ThreadEvent::ApprovalRequested(ApprovalRequestedEvent {
+            call_id: ev.call_id.clone(),
+            reason: ev.reason.clone(),
+            details: ApprovalRequestDetails::CommandExecution {
+                command,
+                cwd: ev.cwd.to_string_lossy().into_owned(),
+            },
+        })]
+    }
+
+    fn handle_patch_approval_request(
+        &self,
+        ev: &ApplyPatchApprovalRequestEvent,
+    ) -> Vec<ThreadEvent> {
+        vec![ThreadEvent::ApprovalRequested(ApprovalRequestedEvent {
+            call_id: ev.call_id.clone(),
+            reason: ev.reason.clone(),
+            details: ApprovalRequestDetails::FileChange {
+                changes: self.file_update_changes(&ev.changes),
+            },
+        })]
+    }
+
     fn handle_task_started(&mut self, _: &TaskStartedEvent) -> Vec<ThreadEvent> {
         self.last_critical_error = None;
         vec![ThreadEvent::TurnStarted(TurnStartedEvent {})]
@@ -463,6 +501,23 @@ impl EventProcessorWithJsonOutput {
 
         items
     }
+
+    /// A turn stopped by its budget ends like a completed one; any other
+    /// aborted turn ends as failed, unless an error already failed it.
+    fn handle_turn_aborted(&mut self, ev: &TurnAbortedEvent) -> Vec<ThreadEvent> {
+        let message = match ev.reason {
+            TurnAbortReason::BudgetExceeded => return self.handle_task_complete(),
+            TurnAbortReason::Interrupted => "turn interrupted",
+            TurnAbortReason::Replaced => "turn replaced by a new one",
+            TurnAbortReason::ReviewEnded => "turn ended with the review",
+        };
+        if self.last_critical_error.is_none() {
+            self.last_critical_error = Some(ThreadErrorEvent {
+                message: message.to_string(),
+            });
+        }
+        self.handle_task_complete()
+    }
 }
 
 impl EventProcessor for EventProcessorWithJsonOutput {
@@ -489,13 +544,17 @@ impl EventProcessor for EventProcessorWithJsonOutput {
 
         let Event { msg, .. } = event;
 
-        if let EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) = msg {
-            if let Some(output_file) = self.last_message_path.as_deref() {
-                handle_last_message(last_agent_message.as_deref(), output_file);
+        match msg {
+            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                if let Some(output_file) = self.last_message_path.as_deref() {
+                    handle_last_message(last_agent_message.as_deref(), output_file);
+                }
+                CodexStatus::InitiateShutdown
             }
-            CodexStatus::InitiateShutdown
-        } else {
-            CodexStatus::Running
+            EventMsg::TurnAborted(ev) if ev.reason == TurnAbortReason::BudgetExceeded => {
+                CodexStatus::InitiateShutdown
+            }
+            _ => CodexStatus::Running,
         }
     }
 }
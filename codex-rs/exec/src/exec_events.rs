@@ -30,6 +30,10 @@ pub enum ThreadEvent {
     /// Signals that an item has reached a terminal state—either success or failure.
     #[serde(rename = "item.completed")]
     ItemCompleted(ItemCompletedEvent),
+    /// Emitted when the running turn waits for the user to approve a command
+    /// or a file change before it continues.
+    #[serde(rename = "approval.requested")]
+    ApprovalRequested(ApprovalRequestedEvent),
     /// Represents an unrecoverable error emitted directly by the event stream.
     #[serde(rename = "error")]
     Error(ThreadErrorEvent),
@@ -87,6 +91,26 @@ pub struct ThreadErrorEvent {
     pub message: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ApprovalRequestedEvent {
+    /// The id of the tool call waiting for approval.
+    pub call_id: String,
+    /// Why the approval is needed, e.g. a retry without the sandbox.
+    pub reason: Option<String>,
+    #[serde(flatten)]
+    pub details: ApprovalRequestDetails,
+}
+
+/// What the turn asks to be approved.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ApprovalRequestDetails {
+    /// A command to run, in its working directory.
+    CommandExecution { command: String, cwd: String },
+    /// A set of file changes to apply.
+    FileChange { changes: Vec<FileUpdateChange> },
+}
+
 /// Canonical representation of a thread item and its domain-specific payload.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ThreadItem {
@@ -0,0 +1,273 @@
+//! `codex exec --input-jsonl`: drives a single conversation from a stream of
+//! JSONL commands on stdin.
+//!
+//! Commands are applied in order, each as soon as the conversation is ready
+//! for it: a user turn waits for the previous turn to finish, and an approval
+//! waits for the running turn to ask for one. An interrupt stops whichever
+//! turn is running when it is reached, so one queued behind a user turn waits
+//! for that turn to start and then interrupts it. The run ends once stdin is
+//! closed and the last turn finished.
+
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use codex_core::CodexConversation;
+use codex_core::config::Config;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use codex_core::protocol::TurnAbortReason;
+use codex_protocol::user_input::UserInput;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::unbounded_channel;
+
+use crate::event_processor::CodexStatus;
+use crate::event_processor::EventProcessor;
+
+/// One line of `--input-jsonl` input.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum InputCommand {
+    /// Starts a turn once the previous one has finished.
+    UserTurn {
+        text: String,
+        #[serde(default)]
+        images: Vec<PathBuf>,
+    },
+    /// Answers the oldest approval request of the running turn.
+    Approval { decision: ReviewDecision },
+    /// Interrupts the running turn, if there is one.
+    Interrupt,
+}
+
+/// Approval requests are answered with the id of the event that asked.
+enum PendingApproval {
+    Exec(String),
+    Patch(String),
+}
+
+impl PendingApproval {
+    fn answer(self, decision: ReviewDecision) -> Op {
+        match self {
+            PendingApproval::Exec(id) => Op::ExecApproval { id, decision },
+            PendingApproval::Patch(id) => Op::PatchApproval { id, decision },
+        }
+    }
+}
+
+struct Driver<'a> {
+    config: &'a Config,
+    output_schema: Option<Value>,
+    queue: VecDeque<InputCommand>,
+    approvals: VecDeque<PendingApproval>,
+    input_closed: bool,
+    turn_running: bool,
+    stopped: bool,
+}
+
+impl<'a> Driver<'a> {
+    fn new(config: &'a Config, output_schema: Option<Value>) -> Self {
+        Self {
+            config,
+            output_schema,
+            queue: VecDeque::new(),
+            approvals: VecDeque::new(),
+            input_closed: false,
+            turn_running: false,
+            stopped: false,
+        }
+    }
+
+    fn on_event(&mut self, event: &Event) {
+        match &event.msg {
+            EventMsg::ExecApprovalRequest(_) => {
+                self.approvals
+                    .push_back(PendingApproval::Exec(event.id.clone()));
+            }
+            EventMsg::ApplyPatchApprovalRequest(_) => {
+                self.approvals
+                    .push_back(PendingApproval::Patch(event.id.clone()));
+            }
+            EventMsg::TaskComplete(_) => self.end_turn(),
+            EventMsg::TurnAborted(ev) => {
+                self.end_turn();
+                if ev.reason == TurnAbortReason::BudgetExceeded {
+                    self.stopped = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end_turn(&mut self) {
+        self.turn_running = false;
+        self.approvals.clear();
+    }
+
+    /// Returns the ops for the queued commands the conversation is ready for.
+    /// Fails when the input can no longer make progress, e.g. because the
+    /// turn waits for an approval that the input does not give.
+    fn next_ops(&mut self) -> anyhow::Result<Vec<Op>> {
+        let mut ops = Vec::new();
+        while let Some(command) = self.queue.front() {
+            match command {
+                InputCommand::UserTurn { .. } if self.turn_running => {
+                    if !self.approvals.is_empty() {
+                        anyhow::bail!(
+                            "the turn is waiting for an approval but the next input is a user turn"
+                        );
+                    }
+                    break;
+                }
+                InputCommand::Approval { .. } if self.approvals.is_empty() => {
+                    if !self.turn_running {
+                        anyhow::bail!("received an approval while no approval was requested");
+                    }
+                    break;
+                }
+                _ => {}
+            }
+            let Some(command) = self.queue.pop_front() else {
+                break;
+            };
+            match command {
+                InputCommand::UserTurn { text, images } => {
+                    self.turn_running = true;
+                    ops.push(self.user_turn(text, images));
+                }
+                InputCommand::Approval { decision } => {
+                    if let Some(pending) = self.approvals.pop_front() {
+                        ops.push(pending.answer(decision));
+                    }
+                }
+                InputCommand::Interrupt => {
+                    if self.turn_running {
+                        ops.push(Op::Interrupt);
+                    }
+                }
+            }
+        }
+        if self.input_closed && self.queue.is_empty() && !self.approvals.is_empty() {
+            anyhow::bail!("input ended while the turn was waiting for an approval");
+        }
+        Ok(ops)
+    }
+
+    fn finished(&self) -> bool {
+        self.stopped || (self.input_closed && self.queue.is_empty() && !self.turn_running)
+    }
+
+    fn user_turn(&self, text: String, images: Vec<PathBuf>) -> Op {
+        let mut items: Vec<UserInput> = images
+            .into_iter()
+            .map(|path| UserInput::LocalImage { path })
+            .collect();
+        items.push(UserInput::Text { text });
+        Op::UserTurn {
+            items,
+            cwd: self.config.cwd.clone(),
+            approval_policy: self.config.approval_policy,
+            sandbox_policy: self.config.sandbox_policy.clone(),
+            model: self.config.model.clone(),
+            effort: self.config.model_reasoning_effort,
+            summary: self.config.model_reasoning_summary,
+            final_output_json_schema: self.output_schema.clone(),
+        }
+    }
+}
+
+/// Runs the commands read from stdin against `conversation` until the input
+/// is exhausted and the conversation has shut down. Returns whether an error
+/// was reported along the way.
+pub(crate) async fn run_input_jsonl(
+    conversation: &CodexConversation,
+    events: &mut UnboundedReceiver<Event>,
+    event_processor: &mut dyn EventProcessor,
+    config: &Config,
+    output_schema: Option<Value>,
+) -> bool {
+    let mut input = spawn_stdin_reader();
+    let mut driver = Driver::new(config, output_schema);
+    let mut error_seen = false;
+    let mut shutting_down = false;
+
+    loop {
+        if !shutting_down {
+            match driver.next_ops() {
+                Ok(ops) => {
+                    for op in ops {
+                        if let Err(err) = conversation.submit(op).await {
+                            eprintln!("Failed to submit input: {err}");
+                            error_seen = true;
+                            driver.stopped = true;
+                        }
+                    }
+                }
+                Err(err) => {
+                    eprintln!("{err}");
+                    error_seen = true;
+                    driver.stopped = true;
+                }
+            }
+            if driver.finished() {
+                shutting_down = true;
+                if let Err(err) = conversation.submit(Op::Shutdown).await {
+                    eprintln!("Failed to shut down: {err}");
+                    return true;
+                }
+            }
+        }
+
+        tokio::select! {
+            command = input.recv(), if !driver.input_closed => match command {
+                Some(Ok(command)) => driver.queue.push_back(command),
+                Some(Err(err)) => {
+                    eprintln!("{err:#}");
+                    error_seen = true;
+                    driver.stopped = true;
+                }
+                None => driver.input_closed = true,
+            },
+            event = events.recv() => {
+                let Some(event) = event else {
+                    break;
+                };
+                if matches!(event.msg, EventMsg::Error(_)) {
+                    error_seen = true;
+                }
+                driver.on_event(&event);
+                if let CodexStatus::Shutdown = event_processor.process_event(event) {
+                    break;
+                }
+            }
+        }
+    }
+    error_seen
+}
+
+/// Reads commands from stdin, skipping blank lines. Reading stops at the
+/// first line that is not a valid command. A plain thread is used so that a
+/// read blocked on stdin does not keep the runtime from shutting down.
+fn spawn_stdin_reader() -> UnboundedReceiver<anyhow::Result<InputCommand>> {
+    let (tx, rx) = unbounded_channel();
+    std::thread::spawn(move || {
+        for (index, line) in std::io::stdin().lock().lines().enumerate() {
+            let command = match line {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => serde_json::from_str::<InputCommand>(&line)
+                    .with_context(|| format!("Invalid input on line {}", index + 1)),
+                Err(err) => Err(anyhow::Error::new(err).context("Failed to read input")),
+            };
+            let failed = command.is_err();
+            if tx.send(command).is_err() || failed {
+                break;
+            }
+        }
+    });
+    rx
+}
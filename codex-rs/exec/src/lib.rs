@@ -9,6 +9,8 @@ mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+mod input_jsonl;
+mod review;
 
 pub use cli::Cli;
 use codex_core::AuthManager;
@@ -66,21 +68,46 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         color,
         last_message_file,
         json: json_mode,
+        input_jsonl,
+        scripted_approvals,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
-        config_overrides,
+        max_tokens,
+        max_cost,
+        mut config_overrides,
     } = cli;
 
+    let review_request = match &command {
+        Some(ExecCommand::Review(args)) => Some(review::review_request(args)),
+        _ => None,
+    };
+
     // Determine the prompt source (parent or subcommand) and read from stdin if needed.
     let prompt_arg = match &command {
         // Allow prompt before the subcommand by falling back to the parent-level prompt
         // when the Resume subcommand did not provide its own prompt.
         Some(ExecCommand::Resume(args)) => args.prompt.clone().or(prompt),
+        Some(ExecCommand::Review(_)) => review_request
+            .as_ref()
+            .map(|request| format!("Code review: {}", request.hint())),
         None => prompt,
     };
 
+    // With --input-jsonl, stdin carries the turns and output is always JSONL.
+    let json_mode = json_mode || input_jsonl;
     let prompt = match prompt_arg {
+        _ if input_jsonl => {
+            if matches!(command, Some(ExecCommand::Review(_))) {
+                eprintln!("--input-jsonl cannot be used with the review subcommand.");
+                std::process::exit(1);
+            }
+            if prompt_arg.is_some() {
+                eprintln!("--input-jsonl reads the prompts from stdin; do not pass one.");
+                std::process::exit(1);
+            }
+            String::new()
+        }
         Some(p) if p != "-" => p,
         // Either `-` was passed or no positional arg.
         maybe_dash => {
@@ -169,7 +196,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         review_model: None,
         config_profile,
         // Default to never ask for approvals in headless mode. Feature flags can override.
-        approval_policy: Some(AskForApproval::Never),
+        // --scripted-approvals keeps the configured policy so the input can answer them.
+        approval_policy: (!scripted_approvals).then_some(AskForApproval::Never),
         sandbox_mode,
         cwd: cwd.map(|p| p.canonicalize().unwrap_or(p)),
         model_provider,
@@ -183,6 +211,18 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         experimental_sandbox_command_assessment: None,
         additional_writable_roots: add_dir,
     };
+    // Map the run budget flags onto the session budget so they act as a hard
+    // ceiling for the whole exec run.
+    if let Some(max_tokens) = max_tokens {
+        config_overrides
+            .raw_overrides
+            .push(format!("budget.session_tokens={max_tokens}"));
+    }
+    if let Some(max_cost) = max_cost {
+        config_overrides
+            .raw_overrides
+            .push(format!("budget.session_cost_usd={max_cost:?}"));
+    }
     // Parse `-c` overrides.
     let cli_kv_overrides = match config_overrides.parse_overrides() {
         Ok(v) => v,
@@ -262,8 +302,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         conversation_id: _,
         conversation,
         session_configured,
-    } = if let Some(ExecCommand::Resume(args)) = command {
-        let resume_path = resolve_resume_path(&config, &args).await?;
+    } = if let Some(ExecCommand::Resume(args)) = &command {
+        let resume_path = resolve_resume_path(&config, args).await?;
 
         if let Some(path) = resume_path {
             conversation_manager
@@ -324,33 +364,56 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         });
     }
 
-    // Package images and prompt into a single user input turn.
-    let mut items: Vec<UserInput> = images
-        .into_iter()
-        .map(|path| UserInput::LocalImage { path })
-        .collect();
-    items.push(UserInput::Text { text: prompt });
-    let initial_prompt_task_id = conversation
-        .submit(Op::UserTurn {
-            items,
-            cwd: default_cwd,
-            approval_policy: default_approval_policy,
-            sandbox_policy: default_sandbox_policy,
-            model: default_model,
-            effort: default_effort,
-            summary: default_summary,
-            final_output_json_schema: output_schema,
-        })
-        .await?;
+    if input_jsonl {
+        let error_seen = input_jsonl::run_input_jsonl(
+            &conversation,
+            &mut rx,
+            event_processor.as_mut(),
+            &config,
+            output_schema,
+        )
+        .await;
+        event_processor.print_final_output();
+        if error_seen {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let initial_prompt_task_id = if let Some(review_request) = review_request {
+        conversation.submit(Op::Review { review_request }).await?
+    } else {
+        // Package images and prompt into a single user input turn.
+        let mut items: Vec<UserInput> = images
+            .into_iter()
+            .map(|path| UserInput::LocalImage { path })
+            .collect();
+        items.push(UserInput::Text { text: prompt });
+        conversation
+            .submit(Op::UserTurn {
+                items,
+                cwd: default_cwd.clone(),
+                approval_policy: default_approval_policy,
+                sandbox_policy: default_sandbox_policy,
+                model: default_model,
+                effort: default_effort,
+                summary: default_summary,
+                final_output_json_schema: output_schema,
+            })
+            .await?
+    };
     info!("Sent prompt with event ID: {initial_prompt_task_id}");
 
     // Run the loop until the task is complete.
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
+    let mut review_output = None;
     while let Some(event) = rx.recv().await {
-        if matches!(event.msg, EventMsg::Error(_)) {
-            error_seen = true;
+        match &event.msg {
+            EventMsg::Error(_) => error_seen = true,
+            EventMsg::ExitedReviewMode(exited) => review_output = exited.review_output.clone(),
+            _ => {}
         }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
@@ -368,6 +431,24 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         std::process::exit(1);
     }
 
+    if let Some(ExecCommand::Review(args)) = &command {
+        let Some(review_output) = review_output else {
+            eprintln!("The review did not complete.");
+            std::process::exit(1);
+        };
+        if !json_mode {
+            #[allow(clippy::print_stdout)]
+            {
+                println!("{}", review::format_review(&review_output));
+            }
+        }
+        let blocking = review::finish_review(args, &review_output, &default_cwd)?;
+        if blocking > 0 {
+            eprintln!("{blocking} review finding(s) at or above the --fail-on-priority threshold.");
+            std::process::exit(review::FINDINGS_EXIT_CODE);
+        }
+    }
+
     Ok(())
 }
 
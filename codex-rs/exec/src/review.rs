@@ -0,0 +1,239 @@
+//! `codex exec review`: runs a code review and exports its findings for CI.
+//!
+//! Findings can be written as SARIF 2.1.0, for code scanning dashboards, and
+//! as a JSON array of `{path, line, body}` objects that a bot can post as
+//! inline pull request comments.
+
+use std::path::Path;
+
+use anyhow::Context;
+use codex_core::git_info::get_git_repo_root;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::ReviewTarget;
+use codex_core::review_format::format_review_findings_block;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use url::Url;
+
+use crate::cli::ReviewArgs;
+
+/// Exit status when findings meet `--fail-on-priority`, distinct from 1 (the
+/// review failed) and 2 (invalid arguments) so CI can tell them apart.
+pub(crate) const FINDINGS_EXIT_CODE: i32 = 3;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Short descriptions of the `[P0]`..`[P3]` priorities used by the reviewer.
+const PRIORITIES: [&str; 4] = [
+    "Drop everything to fix; blocks release or major usage.",
+    "Urgent; should be addressed in the next cycle.",
+    "Normal; to be fixed eventually.",
+    "Low; nice to have.",
+];
+
+/// An inline comment for a pull request review bot.
+#[derive(Debug, Serialize)]
+struct InlineComment {
+    /// Path relative to the repository root.
+    path: String,
+    /// First line of the finding.
+    line: u32,
+    body: String,
+}
+
+pub(crate) fn review_request(args: &ReviewArgs) -> ReviewRequest {
+    let target = if let Some(branch) = &args.base {
+        ReviewTarget::BaseBranch {
+            branch: branch.clone(),
+        }
+    } else if let Some(sha) = &args.commit {
+        ReviewTarget::Commit {
+            sha: sha.clone(),
+            title: None,
+        }
+    } else if let Some(range) = &args.range {
+        ReviewTarget::CommitRange {
+            range: range.clone(),
+        }
+    } else {
+        ReviewTarget::UncommittedChanges
+    };
+    ReviewRequest::new(target)
+}
+
+/// Writes the requested reports and returns the number of findings at or
+/// above `--fail-on-priority`.
+pub(crate) fn finish_review(
+    args: &ReviewArgs,
+    output: &ReviewOutputEvent,
+    cwd: &Path,
+) -> anyhow::Result<usize> {
+    let root = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+    if let Some(path) = &args.sarif {
+        write_json(path, &to_sarif(output, &root))?;
+    }
+    if let Some(path) = &args.comments {
+        write_json(path, &to_inline_comments(output, &root))?;
+    }
+    Ok(match args.fail_on_priority {
+        Some(threshold) => output
+            .findings
+            .iter()
+            .filter(|finding| finding.priority <= threshold)
+            .count(),
+        None => 0,
+    })
+}
+
+/// The review as plain text: the overall explanation followed by the findings.
+pub(crate) fn format_review(output: &ReviewOutputEvent) -> String {
+    let mut text = output.overall_explanation.trim().to_string();
+    if !output.findings.is_empty() {
+        text.push('\n');
+        text.push_str(&format_review_findings_block(&output.findings, None));
+    }
+    text
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> anyhow::Result<()> {
+    let contents = serde_json::to_string_pretty(value)?;
+    std::fs::write(path, contents).with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn to_sarif(output: &ReviewOutputEvent, root: &Path) -> Value {
+    let rules = (0..)
+        .zip(PRIORITIES)
+        .map(|(priority, description)| {
+            json!({
+                "id": rule_id(priority),
+                "name": format!("P{priority}"),
+                "shortDescription": { "text": description },
+            })
+        })
+        .collect::<Vec<_>>();
+    let results = output
+        .findings
+        .iter()
+        .map(|finding| {
+            let range = &finding.code_location.line_range;
+            let start_line = range.start.max(1);
+            json!({
+                "ruleId": rule_id(finding.priority),
+                "level": sarif_level(finding.priority),
+                "message": { "text": format!("{}\n\n{}", finding.title, finding.body) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": artifact_location(finding, root),
+                        "region": {
+                            "startLine": start_line,
+                            "endLine": range.end.max(start_line),
+                        },
+                    },
+                }],
+                "properties": {
+                    "priority": finding.priority,
+                    "confidence": finding.confidence_score,
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "codex",
+                    "informationUri": "https://github.com/openai/codex",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "originalUriBaseIds": {
+                "%SRCROOT%": { "uri": directory_uri(root) },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn to_inline_comments(output: &ReviewOutputEvent, root: &Path) -> Vec<InlineComment> {
+    output
+        .findings
+        .iter()
+        .map(|finding| InlineComment {
+            path: repo_relative_path(finding, root).unwrap_or_else(|| {
+                finding
+                    .code_location
+                    .absolute_file_path
+                    .display()
+                    .to_string()
+            }),
+            line: finding.code_location.line_range.start.max(1),
+            body: format!("**{}**\n\n{}", finding.title, finding.body),
+        })
+        .collect()
+}
+
+fn rule_id(priority: i32) -> String {
+    format!("codex-review/P{priority}")
+}
+
+fn sarif_level(priority: i32) -> &'static str {
+    match priority {
+        0 | 1 => "error",
+        2 => "warning",
+        _ => "note",
+    }
+}
+
+fn artifact_location(finding: &ReviewFinding, root: &Path) -> Value {
+    match repo_relative_path(finding, root) {
+        Some(path) => json!({ "uri": relative_uri(&path), "uriBaseId": "%SRCROOT%" }),
+        None => json!({ "uri": file_uri(&finding.code_location.absolute_file_path) }),
+    }
+}
+
+/// A `/`-separated relative path as a URI reference, with each segment
+/// percent-encoded so spaces and `#` survive.
+fn relative_uri(path: &str) -> String {
+    path.split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn file_uri(path: &Path) -> String {
+    Url::from_file_path(path)
+        .map(String::from)
+        .unwrap_or_else(|()| format!("file://{}", relative_uri(&path.to_string_lossy())))
+}
+
+/// The `file://` URI of a directory, with the trailing `/` SARIF expects of a
+/// base URI.
+fn directory_uri(path: &Path) -> String {
+    Url::from_directory_path(path)
+        .map(String::from)
+        .unwrap_or_else(|()| format!("file://{}/", relative_uri(&path.to_string_lossy())))
+}
+
+/// The finding's path relative to `root`, with `/` separators, or `None` when
+/// it lies outside the repository.
+fn repo_relative_path(finding: &ReviewFinding, root: &Path) -> Option<String> {
+    let relative = finding
+        .code_location
+        .absolute_file_path
+        .strip_prefix(root)
+        .ok()?;
+    Some(
+        relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
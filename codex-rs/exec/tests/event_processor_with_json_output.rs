@@ -17,6 +17,8 @@ use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_exec::event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
 use codex_exec::exec_events::AgentMessageItem;
+use codex_exec::exec_events::ApprovalRequestDetails;
+use codex_exec::exec_events::ApprovalRequestedEvent;
 use codex_exec::exec_events::CommandExecutionItem;
 use codex_exec::exec_events::CommandExecutionStatus;
 use codex_exec::exec_events::ErrorItem;
@@ -658,6 +660,7 @@ fn exec_command_end_success_produces_completed_command_item() {
             exit_code: 0,
             duration: Duration::from_millis(5),
             formatted_output: String::new(),
+            resource_limit_exceeded: None,
         }),
     );
     let out_ok = ep.collect_thread_events(&end_ok);
@@ -719,6 +722,7 @@ fn exec_command_end_failure_produces_failed_command_item() {
             exit_code: 1,
             duration: Duration::from_millis(2),
             formatted_output: String::new(),
+            resource_limit_exceeded: None,
         }),
     );
     let out_fail = ep.collect_thread_events(&end_fail);
@@ -753,6 +757,7 @@ fn exec_command_end_without_begin_is_ignored() {
             exit_code: 0,
             duration: Duration::from_millis(1),
             formatted_output: String::new(),
+            resource_limit_exceeded: None,
         }),
     );
     let out = ep.collect_thread_events(&end_only);
@@ -940,3 +945,52 @@ fn task_complete_produces_turn_completed_with_usage() {
         })]
     );
 }
+
+#[test]
+fn interrupted_turn_produces_turn_failed() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+
+    let aborted = event(
+        "e1",
+        EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
+            reason: codex_core::protocol::TurnAbortReason::Interrupted,
+        }),
+    );
+    assert_eq!(
+        ep.collect_thread_events(&aborted),
+        vec![ThreadEvent::TurnFailed(TurnFailedEvent {
+            error: ThreadErrorEvent {
+                message: "turn interrupted".to_string(),
+            },
+        })]
+    );
+}
+
+#[test]
+fn exec_approval_request_produces_approval_requested() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+
+    let request = event(
+        "e1",
+        EventMsg::ExecApprovalRequest(codex_core::protocol::ExecApprovalRequestEvent {
+            call_id: "call-1".to_string(),
+            command: vec!["touch".to_string(), "a file.txt".to_string()],
+            cwd: PathBuf::from("/work"),
+            reason: Some("retry without sandbox".to_string()),
+            risk: None,
+            env_profile: None,
+            parsed_cmd: Vec::new(),
+        }),
+    );
+    assert_eq!(
+        ep.collect_thread_events(&request),
+        vec![ThreadEvent::ApprovalRequested(ApprovalRequestedEvent {
+            call_id: "call-1".to_string(),
+            reason: Some("retry without sandbox".to_string()),
+            details: ApprovalRequestDetails::CommandExecution {
+                command: "touch 'a file.txt'".to_string(),
+                cwd: "/work".to_string(),
+            },
+        })]
+    );
+}
@@ -0,0 +1,164 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+fn event_types(stdout: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("stdout should be JSONL"))
+        .filter_map(|event| event["type"].as_str().map(str::to_string))
+        .collect()
+}
+
+/// Each `user_turn` line runs as a new turn of the same conversation, after
+/// the previous turn has finished.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn input_jsonl_runs_turns_in_one_conversation() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let server = responses::start_mock_server().await;
+    let response_mock = responses::mount_sse_sequence(
+        &server,
+        vec![
+            responses::sse(vec![
+                responses::ev_response_created("resp1"),
+                responses::ev_assistant_message("m1", "first answer"),
+                responses::ev_completed("resp1"),
+            ]),
+            responses::sse(vec![
+                responses::ev_response_created("resp2"),
+                responses::ev_assistant_message("m2", "second answer"),
+                responses::ev_completed("resp2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let input = [
+        json!({"type": "user_turn", "text": "first question"}),
+        json!({"type": "user_turn", "text": "second question"}),
+    ]
+    .map(|line| line.to_string())
+    .join("\n");
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--input-jsonl")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    let types = event_types(&output.stdout);
+    assert_eq!(
+        types.iter().filter(|ty| *ty == "turn.completed").count(),
+        2,
+        "{types:?}"
+    );
+    assert_eq!(types.first().map(String::as_str), Some("thread.started"));
+
+    let requests = response_mock.requests();
+    assert_eq!(requests.len(), 2);
+    let second_turn = requests[1].message_input_texts("user");
+    assert!(
+        second_turn.iter().any(|text| text == "first question"),
+        "{second_turn:?}"
+    );
+    assert!(
+        second_turn.iter().any(|text| text == "second question"),
+        "{second_turn:?}"
+    );
+
+    Ok(())
+}
+
+/// With `--scripted-approvals`, an `approval` line answers the approval
+/// request of the running turn, which is reported on stdout.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn input_jsonl_answers_approval_requests() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let args = json!({
+        "command": ["touch", "denied.txt"],
+        "timeout_ms": 1_000,
+        "with_escalated_permissions": true,
+    });
+    let server = responses::start_mock_server().await;
+    let response_mock = responses::mount_sse_sequence(
+        &server,
+        vec![
+            responses::sse(vec![
+                responses::ev_response_created("resp1"),
+                responses::ev_function_call("call-1", "shell", &args.to_string()),
+                responses::ev_completed("resp1"),
+            ]),
+            responses::sse(vec![
+                responses::ev_response_created("resp2"),
+                responses::ev_assistant_message("m1", "done"),
+                responses::ev_completed("resp2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let input = [
+        json!({"type": "user_turn", "text": "create a file"}),
+        json!({"type": "approval", "decision": "denied"}),
+    ]
+    .map(|line| line.to_string())
+    .join("\n");
+    let stdout = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(test.cwd_path())
+        .arg("-s")
+        .arg("read-only")
+        .arg("-m")
+        .arg("gpt-5")
+        .arg("-c")
+        .arg("approval_policy=\"on-request\"")
+        .arg("--input-jsonl")
+        .arg("--scripted-approvals")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let types = event_types(&stdout);
+    assert!(
+        types.iter().any(|ty| ty == "approval.requested"),
+        "{types:?}"
+    );
+
+    let output = response_mock
+        .function_call_output_text("call-1")
+        .expect("the shell call should have an output");
+    assert!(output.contains("rejected by user"), "{output}");
+    assert!(!test.cwd_path().join("denied.txt").exists());
+
+    Ok(())
+}
+
+/// Input that can never be applied fails the run instead of hanging.
+#[test]
+fn input_jsonl_rejects_unrequested_approvals() {
+    let test = test_codex_exec();
+    test.cmd()
+        .arg("--skip-git-repo-check")
+        .arg("--input-jsonl")
+        .write_stdin(json!({"type": "approval", "decision": "approved"}).to_string())
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains(
+            "received an approval while no approval was requested",
+        ));
+}
@@ -0,0 +1,111 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+/// `codex-exec review` writes SARIF and inline comments, and exits with
+/// status 3 when a finding meets the `--fail-on-priority` threshold.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn review_exports_findings_and_gates_on_priority() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let cwd = test.cwd_path().canonicalize()?;
+
+    let review = json!({
+        "findings": [
+            {
+                "title": "[P1] Off-by-one in pagination",
+                "body": "The last page is never fetched.",
+                "confidence_score": 0.8,
+                "priority": 1,
+                "code_location": {
+                    "absolute_file_path": cwd.join("src/page.rs"),
+                    "line_range": {"start": 12, "end": 14}
+                }
+            },
+            {
+                "title": "[P3] Typo in comment",
+                "body": "\"recieve\" should be \"receive\".",
+                "confidence_score": 0.5,
+                "priority": 3,
+                "code_location": {
+                    "absolute_file_path": cwd.join("src/old notes#1.rs"),
+                    "line_range": {"start": 3, "end": 3}
+                }
+            }
+        ],
+        "overall_correctness": "patch is incorrect",
+        "overall_explanation": "Pagination drops results.",
+        "overall_confidence_score": 0.7
+    });
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", &review.to_string()),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    let sarif_path = cwd.join("review.sarif");
+    let comments_path = cwd.join("comments.json");
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(&cwd)
+        .arg("review")
+        .arg("--uncommitted")
+        .arg("--sarif")
+        .arg(&sarif_path)
+        .arg("--comments")
+        .arg(&comments_path)
+        .arg("--fail-on-priority")
+        .arg("1")
+        .assert()
+        .code(3);
+
+    let comments: Value = serde_json::from_str(&std::fs::read_to_string(&comments_path)?)?;
+    assert_eq!(
+        comments,
+        json!([
+            {
+                "path": "src/page.rs",
+                "line": 12,
+                "body": "**[P1] Off-by-one in pagination**\n\nThe last page is never fetched."
+            },
+            {
+                "path": "src/old notes#1.rs",
+                "line": 3,
+                "body": "**[P3] Typo in comment**\n\n\"recieve\" should be \"receive\"."
+            }
+        ])
+    );
+
+    let sarif: Value = serde_json::from_str(&std::fs::read_to_string(&sarif_path)?)?;
+    assert_eq!(sarif["version"], "2.1.0");
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["ruleId"], "codex-review/P1");
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"],
+        json!({
+            "artifactLocation": {"uri": "src/page.rs", "uriBaseId": "%SRCROOT%"},
+            "region": {"startLine": 12, "endLine": 14}
+        })
+    );
+    assert_eq!(results[1]["level"], "note");
+    assert_eq!(
+        results[1]["locations"][0]["physicalLocation"]["artifactLocation"],
+        json!({"uri": "src/old%20notes%231.rs", "uriBaseId": "%SRCROOT%"})
+    );
+    let root_uri = url::Url::from_directory_path(&cwd).unwrap();
+    assert_eq!(
+        sarif["runs"][0]["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+        root_uri.as_str()
+    );
+
+    Ok(())
+}
@@ -25,6 +25,7 @@ async fn spawn_command_under_sandbox(
         command_cwd,
         sandbox_policy,
         sandbox_cwd,
+        &[],
         stdio_policy,
         env,
     )
@@ -48,6 +49,7 @@ async fn spawn_command_under_sandbox(
         command_cwd,
         sandbox_policy,
         sandbox_cwd,
+        &[],
         stdio_policy,
         env,
     )
@@ -70,8 +72,11 @@ async fn python_multiprocessing_lock_works_under_sandbox() {
     let policy = SandboxPolicy::WorkspaceWrite {
         writable_roots,
         network_access: false,
+        network_allowlist: vec![],
+        read_only_subpaths: vec![],
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        container: false,
     };
 
     let python_code = r#"import multiprocessing
@@ -130,8 +135,11 @@ async fn sandbox_distinguishes_command_and_policy_cwds() {
     let policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![],
         network_access: false,
+        network_allowlist: vec![],
+        read_only_subpaths: vec![],
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        container: false,
     };
 
     // Attempt to write inside the command cwd, which is outside of the sandbox policy cwd.
@@ -0,0 +1,641 @@
+//! Rootless container used for `sandbox_mode = "container"`.
+//!
+//! The helper unshares user, mount, pid and (unless the policy grants network
+//! access) network namespaces, then builds a new root file-system on a tmpfs
+//! that only exposes the system directories read-only, a private `/tmp`,
+//! minimal `/dev` and `/proc` mounts, and the paths the policy makes visible.
+//! The writable roots are bind-mounted read-write with their read-only
+//! subpaths mounted read-only on top, and existing read-deny paths, including
+//! `.env` files found in the working directories and writable roots, are
+//! covered by empty mounts. Everything else on the host, including the home
+//! directory, is absent.
+//!
+//! The process tree looks like this:
+//!
+//! ```text
+//! codex-linux-sandbox (host pid namespace, waits and forwards the status)
+//! └── init (pid 1 in the container, sets up the mounts and reaps orphans)
+//!     └── command
+//! ```
+//!
+//! When the command exits, the init process exits too, which kills anything
+//! the command left running in the background.
+//!
+//! With a network allowlist, the helper first forks the relay described in
+//! [`crate::proxy_relay`] and builds the container inside the relay's network
+//! namespace, so the proxy port on the container's loopback reaches the
+//! proxy.
+
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::config::types::ResourceLimits;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::spawn::apply_resource_limits;
+
+use crate::landlock::resolve_denied_paths;
+use crate::proxy_relay::confine_network_to_proxy;
+
+/// Host directories exposed read-only inside the container. Symlinks, such as
+/// `/bin` on merged-`/usr` systems, are recreated instead of mounted.
+const SYSTEM_PATHS: &[&str] = &[
+    "/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/libx32", "/etc", "/opt", "/nix", "/sys",
+];
+
+/// Device nodes bind-mounted from the host `/dev`.
+const DEVICES: &[&str] = &["null", "zero", "full", "random", "urandom", "tty"];
+
+/// Staging directories below the tmpfs that replaces `/` while the new root
+/// is assembled.
+const OLD_ROOT: &str = "/oldroot";
+const NEW_ROOT: &str = "/newroot";
+
+/// Paths the container exposes, all canonical.
+#[derive(Debug, Default)]
+struct ContainerLayout {
+    read_only: Vec<PathBuf>,
+    writable: Vec<PathBuf>,
+    read_only_subpaths: Vec<PathBuf>,
+    hidden: Vec<PathBuf>,
+    cwd: PathBuf,
+}
+
+impl ContainerLayout {
+    fn new(
+        sandbox_policy: &SandboxPolicy,
+        sandbox_policy_cwd: &Path,
+        read_deny: &[String],
+    ) -> io::Result<Self> {
+        let cwd = std::env::current_dir()?.canonicalize()?;
+        let mut layout = Self {
+            // The working directories stay visible even under a read-only
+            // policy.
+            read_only: canonical_paths([cwd.clone(), sandbox_policy_cwd.to_path_buf()]),
+            cwd,
+            ..Self::default()
+        };
+        if sandbox_policy.has_full_disk_write_access() {
+            layout.writable.push(PathBuf::from("/"));
+        }
+        for writable_root in sandbox_policy.get_writable_roots_with_cwd(sandbox_policy_cwd) {
+            layout
+                .writable
+                .extend(canonical_paths([writable_root.root]));
+            layout
+                .read_only_subpaths
+                .extend(canonical_paths(writable_root.read_only_subpaths));
+        }
+        layout.hidden = resolve_denied_paths(
+            sandbox_policy,
+            sandbox_policy_cwd,
+            read_deny,
+            &layout.writable,
+        );
+        // Parents are mounted before their children so they do not shadow
+        // them.
+        for paths in [
+            &mut layout.read_only,
+            &mut layout.writable,
+            &mut layout.read_only_subpaths,
+        ] {
+            paths.sort_by_key(|path| path.components().count());
+            paths.dedup();
+        }
+        Ok(layout)
+    }
+}
+
+/// Runs `command` inside the container and exits with its status. With
+/// `network_proxy_port`, the container's network namespace is the one
+/// [`confine_network_to_proxy`] creates, so the proxy stays reachable.
+pub(crate) fn run_in_container(
+    sandbox_policy: &SandboxPolicy,
+    sandbox_policy_cwd: &Path,
+    read_deny: &[String],
+    network_proxy_port: Option<u16>,
+    resource_limits: &ResourceLimits,
+    command: &[String],
+) -> ! {
+    let layout = match ContainerLayout::new(sandbox_policy, sandbox_policy_cwd, read_deny) {
+        Ok(layout) => layout,
+        Err(e) => panic!("error preparing container: {e}"),
+    };
+    let mut isolate_network = !sandbox_policy.has_full_network_access();
+    if isolate_network && let Some(port) = network_proxy_port {
+        match confine_network_to_proxy(port) {
+            Ok(()) => isolate_network = false,
+            Err(e) => eprintln!(
+                "codex-linux-sandbox: network access is blocked because the network proxy could not be set up: {e}"
+            ),
+        }
+    }
+    if let Err(e) = enter_namespaces(isolate_network) {
+        panic!("error creating container namespaces: {e}");
+    }
+
+    // The first child becomes pid 1 of the new pid namespace.
+    match fork() {
+        Ok(0) => {}
+        Ok(init) => forward_exit_status(init),
+        Err(e) => panic!("error starting container init: {e}"),
+    }
+
+    // The container must not outlive the helper.
+    unsafe {
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+    }
+    if let Err(e) = setup_mounts(&layout) {
+        panic!("error setting up container file-system: {e}");
+    }
+    let child = match fork() {
+        Ok(0) => exec_command(resource_limits, command),
+        Ok(child) => child,
+        Err(e) => panic!("error starting container command: {e}"),
+    };
+    reap_until_exit(child)
+}
+
+fn enter_namespaces(isolate_network: bool) -> io::Result<()> {
+    let uid = unsafe { libc::getuid() };
+    let gid = unsafe { libc::getgid() };
+    let mut flags = libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWPID;
+    if isolate_network {
+        flags |= libc::CLONE_NEWNET;
+    }
+    check(unsafe { libc::unshare(flags) }, "unshare")?;
+    map_current_user(uid, gid)?;
+
+    if isolate_network {
+        bring_up_loopback()?;
+    }
+    Ok(())
+}
+
+/// Maps the caller, whose ids were read before entering the new user
+/// namespace, onto itself so file ownership looks the same as on the host.
+pub(crate) fn map_current_user(uid: libc::uid_t, gid: libc::gid_t) -> io::Result<()> {
+    fs::write("/proc/self/setgroups", "deny")?;
+    fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))
+}
+
+/// Moves the caller into new user and mount namespaces in which every path in
+/// `read_only` is bind-mounted read-only onto itself and every path in
+/// `hidden` is covered by an empty read-only mount.
+pub(crate) fn enter_mount_namespace(read_only: &[PathBuf], hidden: &[PathBuf]) -> io::Result<()> {
+    let uid = unsafe { libc::getuid() };
+    let gid = unsafe { libc::getgid() };
+    check(
+        unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNS) },
+        "unshare",
+    )?;
+    map_current_user(uid, gid)?;
+    mount(
+        None,
+        Path::new("/"),
+        None,
+        libc::MS_REC | libc::MS_PRIVATE,
+        None,
+    )?;
+    for path in read_only {
+        bind(path, path, true)?;
+    }
+    for path in hidden {
+        hide(path, Path::new("/dev/null"))?;
+    }
+    Ok(())
+}
+
+/// Enables `lo` so servers and clients inside the namespace can still talk
+/// to each other.
+pub(crate) fn bring_up_loopback() -> io::Result<()> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    check(fd, "socket")?;
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo\0") {
+        *dst = *src as libc::c_char;
+    }
+    let result = (|| {
+        check(
+            unsafe { libc::ioctl(fd, libc::SIOCGIFFLAGS, &mut request as *mut libc::ifreq) },
+            "SIOCGIFFLAGS",
+        )?;
+        unsafe {
+            request.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short;
+        }
+        check(
+            unsafe { libc::ioctl(fd, libc::SIOCSIFFLAGS, &request as *const libc::ifreq) },
+            "SIOCSIFFLAGS",
+        )
+    })();
+    unsafe {
+        libc::close(fd);
+    }
+    result
+}
+
+fn setup_mounts(layout: &ContainerLayout) -> io::Result<()> {
+    // Keep every mount below private to this namespace.
+    mount(
+        None,
+        Path::new("/"),
+        None,
+        libc::MS_REC | libc::MS_PRIVATE,
+        None,
+    )?;
+
+    // Assemble the new root on a tmpfs, with the host root moved aside.
+    let base = Path::new("/tmp");
+    mount_tmpfs(base, "0755")?;
+    fs::create_dir(base.join("oldroot"))?;
+    fs::create_dir(base.join("newroot"))?;
+    pivot_root(base, &base.join("oldroot"))?;
+    std::env::set_current_dir("/")?;
+    let new_root = Path::new(NEW_ROOT);
+    mount_tmpfs(new_root, "0755")?;
+
+    for system_path in SYSTEM_PATHS {
+        let host = under(OLD_ROOT, Path::new(system_path));
+        let target = under(NEW_ROOT, Path::new(system_path));
+        match fs::symlink_metadata(&host) {
+            Ok(metadata) if metadata.is_symlink() => {
+                std::os::unix::fs::symlink(fs::read_link(&host)?, &target)?;
+            }
+            Ok(_) => bind(&host, &target, true)?,
+            Err(_) => {}
+        }
+    }
+
+    setup_dev(&new_root.join("dev"))?;
+
+    let proc = new_root.join("proc");
+    fs::create_dir(&proc)?;
+    let proc_flags = libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC;
+    if mount(
+        Some(Path::new("proc")),
+        &proc,
+        Some("proc"),
+        proc_flags,
+        None,
+    )
+    .is_err()
+    {
+        // A fresh procfs can be refused when parts of the host one are
+        // masked; fall back to the host view.
+        bind(&under(OLD_ROOT, Path::new("/proc")), &proc, false)?;
+    }
+
+    let tmp = new_root.join("tmp");
+    fs::create_dir(&tmp)?;
+    mount_tmpfs(&tmp, "1777")?;
+
+    for path in &layout.read_only {
+        bind(&under(OLD_ROOT, path), &under(NEW_ROOT, path), true)?;
+    }
+    for path in &layout.writable {
+        bind(&under(OLD_ROOT, path), &under(NEW_ROOT, path), false)?;
+    }
+    for path in &layout.read_only_subpaths {
+        let target = under(NEW_ROOT, path);
+        bind(&target, &target, true)?;
+    }
+    for path in &layout.hidden {
+        hide(&under(NEW_ROOT, path), &new_root.join("dev/null"))?;
+    }
+
+    // Switch to the new root and drop the host one.
+    std::env::set_current_dir(new_root)?;
+    pivot_root(Path::new("."), Path::new("."))?;
+    let dot = CString::new(".")?;
+    check(
+        unsafe { libc::umount2(dot.as_ptr(), libc::MNT_DETACH) },
+        "umount old root",
+    )?;
+    std::env::set_current_dir("/")?;
+    mount(
+        None,
+        Path::new("/"),
+        None,
+        libc::MS_REMOUNT | libc::MS_BIND | libc::MS_RDONLY | libc::MS_NOSUID | libc::MS_NODEV,
+        None,
+    )?;
+    std::env::set_current_dir(&layout.cwd)
+}
+
+fn setup_dev(dev: &Path) -> io::Result<()> {
+    fs::create_dir(dev)?;
+    mount(
+        Some(Path::new("tmpfs")),
+        dev,
+        Some("tmpfs"),
+        libc::MS_NOSUID | libc::MS_NOEXEC,
+        Some("mode=0755"),
+    )?;
+    for device in DEVICES {
+        let host = Path::new(OLD_ROOT).join("dev").join(device);
+        if host.exists() {
+            bind(&host, &dev.join(device), false)?;
+        }
+    }
+    for (link, target) in [
+        ("fd", "/proc/self/fd"),
+        ("stdin", "/proc/self/fd/0"),
+        ("stdout", "/proc/self/fd/1"),
+        ("stderr", "/proc/self/fd/2"),
+        ("ptmx", "pts/ptmx"),
+    ] {
+        std::os::unix::fs::symlink(target, dev.join(link))?;
+    }
+    let pts = dev.join("pts");
+    fs::create_dir(&pts)?;
+    // Pseudo-terminals are optional; commands run without one by default.
+    let _ = mount(
+        Some(Path::new("devpts")),
+        &pts,
+        Some("devpts"),
+        libc::MS_NOSUID | libc::MS_NOEXEC,
+        Some("newinstance,ptmxmode=0666,mode=620"),
+    );
+    let shm = dev.join("shm");
+    fs::create_dir(&shm)?;
+    mount_tmpfs(&shm, "1777")
+}
+
+fn exec_command(resource_limits: &ResourceLimits, command: &[String]) -> ! {
+    if let Err(e) = drop_privileges() {
+        panic!("error dropping container privileges: {e}");
+    }
+    if let Err(e) = apply_resource_limits(resource_limits) {
+        panic!("error applying resource limits: {e:?}");
+    }
+    let Some(program) = command.first() else {
+        panic!("No command specified to execute.");
+    };
+
+    #[expect(clippy::expect_used)]
+    let c_args: Vec<CString> = command
+        .iter()
+        .map(|arg| CString::new(arg.as_str()).expect("Failed to convert arg to CString"))
+        .collect();
+    let mut c_args_ptrs: Vec<*const libc::c_char> = c_args.iter().map(|arg| arg.as_ptr()).collect();
+    c_args_ptrs.push(std::ptr::null());
+
+    unsafe {
+        libc::execvp(c_args[0].as_ptr(), c_args_ptrs.as_ptr());
+    }
+    let err = io::Error::last_os_error();
+    panic!("Failed to execvp {program}: {err}");
+}
+
+/// Drops every capability so the command cannot undo the mounts, which
+/// matters when the caller is root and therefore keeps its capabilities
+/// across `execve`.
+fn drop_privileges() -> io::Result<()> {
+    check(
+        unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) },
+        "PR_SET_NO_NEW_PRIVS",
+    )?;
+    for capability in 0..=libc::c_ulong::from(u8::MAX) {
+        if unsafe { libc::prctl(libc::PR_CAPBSET_DROP, capability, 0, 0, 0) } != 0 {
+            break;
+        }
+    }
+
+    #[repr(C)]
+    struct CapUserHeader {
+        version: u32,
+        pid: libc::c_int,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct CapUserData {
+        effective: u32,
+        permitted: u32,
+        inheritable: u32,
+    }
+    const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+    let mut header = CapUserHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let data = [CapUserData {
+        effective: 0,
+        permitted: 0,
+        inheritable: 0,
+    }; 2];
+    let result = unsafe { libc::syscall(libc::SYS_capset, &mut header, data.as_ptr()) };
+    check(result as libc::c_int, "capset")
+}
+
+/// Waits for `child` while reaping orphans re-parented to this init process,
+/// then exits with the child's status.
+fn reap_until_exit(child: libc::pid_t) -> ! {
+    loop {
+        let mut status = 0;
+        let pid = unsafe { libc::waitpid(-1, &mut status, 0) };
+        if pid == child {
+            let code = if libc::WIFSIGNALED(status) {
+                128 + libc::WTERMSIG(status)
+            } else {
+                libc::WEXITSTATUS(status)
+            };
+            unsafe { libc::_exit(code) }
+        }
+        if pid < 0 && io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+            unsafe { libc::_exit(1) }
+        }
+    }
+}
+
+/// Waits for `init` and exits the same way it did.
+pub(crate) fn forward_exit_status(init: libc::pid_t) -> ! {
+    let mut status = 0;
+    while unsafe { libc::waitpid(init, &mut status, 0) } < 0 {
+        if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+            std::process::exit(1);
+        }
+    }
+    if libc::WIFSIGNALED(status) {
+        let signal = libc::WTERMSIG(status);
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::kill(libc::getpid(), signal);
+        }
+        std::process::exit(128 + signal);
+    }
+    std::process::exit(libc::WEXITSTATUS(status));
+}
+
+/// Bind-mounts `src` onto `dst`, creating the mount point as needed.
+fn bind(src: &Path, dst: &Path, read_only: bool) -> io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+    } else if !dst.exists() {
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::File::create(dst)?;
+    }
+    mount(Some(src), dst, None, libc::MS_BIND | libc::MS_REC, None)?;
+    if read_only {
+        remount_read_only(dst)?;
+    }
+    Ok(())
+}
+
+/// Makes `target` and every mount below it read-only. Flags the user
+/// namespace is not allowed to clear, such as `nosuid`, are carried over.
+fn remount_read_only(target: &Path) -> io::Result<()> {
+    const PRESERVED_FLAGS: libc::c_ulong = libc::MS_NOSUID
+        | libc::MS_NODEV
+        | libc::MS_NOEXEC
+        | libc::MS_NOATIME
+        | libc::MS_NODIRATIME
+        | libc::MS_RELATIME;
+    for mount_point in mount_points_under(target)? {
+        let path = CString::new(mount_point.as_os_str().as_bytes())?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        check(
+            unsafe { libc::statvfs(path.as_ptr(), &mut stat) },
+            format_args!("statvfs {}", mount_point.display()),
+        )?;
+        let flags = libc::MS_REMOUNT
+            | libc::MS_BIND
+            | libc::MS_RDONLY
+            | (stat.f_flag as libc::c_ulong & PRESERVED_FLAGS);
+        mount(None, &mount_point, None, flags, None)?;
+    }
+    Ok(())
+}
+
+/// Mount points at or below `target`, read from the host `/proc`: below
+/// [`OLD_ROOT`] while a container root is assembled, in place when paths are
+/// hidden without one.
+fn mount_points_under(target: &Path) -> io::Result<Vec<PathBuf>> {
+    const MOUNTINFO: &str = "/proc/self/mountinfo";
+    let mountinfo = match fs::read_to_string(under(OLD_ROOT, Path::new(MOUNTINFO))) {
+        Ok(mountinfo) => mountinfo,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => fs::read_to_string(MOUNTINFO)?,
+        Err(e) => return Err(e),
+    };
+    let mut mount_points: Vec<PathBuf> = mountinfo
+        .lines()
+        .filter_map(|line| line.split(' ').nth(4))
+        .map(|field| PathBuf::from(unescape_mountinfo(field)))
+        .filter(|path| path.starts_with(target))
+        .collect();
+    mount_points.dedup();
+    if mount_points.is_empty() {
+        mount_points.push(target.to_path_buf());
+    }
+    Ok(mount_points)
+}
+
+/// Decodes the octal escapes (`\040` for a space) used in mountinfo paths.
+fn unescape_mountinfo(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(index) = rest.find('\\') {
+        out.push_str(&rest[..index]);
+        let escape = rest.get(index + 1..index + 4);
+        match escape.and_then(|digits| u8::from_str_radix(digits, 8).ok()) {
+            Some(byte) => {
+                out.push(char::from(byte));
+                rest = &rest[index + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Covers a directory with an empty tmpfs and a file with `dev_null`, both
+/// read-only.
+fn hide(target: &Path, dev_null: &Path) -> io::Result<()> {
+    if target.is_dir() {
+        mount_tmpfs(target, "0000")?;
+        remount_read_only(target)
+    } else if target.exists() {
+        bind(dev_null, target, true)
+    } else {
+        Ok(())
+    }
+}
+
+fn mount_tmpfs(target: &Path, mode: &str) -> io::Result<()> {
+    mount(
+        Some(Path::new("tmpfs")),
+        target,
+        Some("tmpfs"),
+        libc::MS_NOSUID | libc::MS_NODEV,
+        Some(&format!("mode={mode}")),
+    )
+}
+
+fn mount(
+    source: Option<&Path>,
+    target: &Path,
+    fstype: Option<&str>,
+    flags: libc::c_ulong,
+    data: Option<&str>,
+) -> io::Result<()> {
+    let source = source
+        .map(|source| CString::new(source.as_os_str().as_bytes()))
+        .transpose()?;
+    let c_target = CString::new(target.as_os_str().as_bytes())?;
+    let fstype = fstype.map(CString::new).transpose()?;
+    let data = data.map(CString::new).transpose()?;
+    let result = unsafe {
+        libc::mount(
+            source.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+            c_target.as_ptr(),
+            fstype.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+            flags,
+            data.as_ref()
+                .map_or(std::ptr::null(), |s| s.as_ptr().cast::<libc::c_void>()),
+        )
+    };
+    check(result, format_args!("mount {}", target.display()))
+}
+
+fn pivot_root(new_root: &Path, put_old: &Path) -> io::Result<()> {
+    let new_root = CString::new(new_root.as_os_str().as_bytes())?;
+    let put_old = CString::new(put_old.as_os_str().as_bytes())?;
+    let result =
+        unsafe { libc::syscall(libc::SYS_pivot_root, new_root.as_ptr(), put_old.as_ptr()) };
+    check(result as libc::c_int, "pivot_root")
+}
+
+pub(crate) fn fork() -> io::Result<libc::pid_t> {
+    let pid = unsafe { libc::fork() };
+    check(pid, "fork")?;
+    Ok(pid)
+}
+
+/// Joins an absolute `path` below `base`.
+fn under(base: &str, path: &Path) -> PathBuf {
+    Path::new(base).join(path.strip_prefix("/").unwrap_or(path))
+}
+
+fn canonical_paths(paths: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+    paths
+        .into_iter()
+        .filter_map(|path| path.canonicalize().ok())
+        .collect()
+}
+
+pub(crate) fn check(result: libc::c_int, what: impl std::fmt::Display) -> io::Result<()> {
+    if result < 0 {
+        let err = io::Error::last_os_error();
+        return Err(io::Error::new(err.kind(), format!("{what}: {err}")));
+    }
+    Ok(())
+}
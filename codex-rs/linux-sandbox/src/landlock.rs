@@ -6,12 +6,16 @@ use codex_core::error::CodexErr;
 use codex_core::error::Result;
 use codex_core::error::SandboxErr;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::sandboxing::read_deny::MAX_SCANNED_ENTRIES;
+use codex_core::sandboxing::read_deny::ReadDenyList;
 
 use landlock::ABI;
 use landlock::Access;
 use landlock::AccessFs;
+use landlock::AccessNet;
 use landlock::CompatLevel;
 use landlock::Compatible;
+use landlock::NetPort;
 use landlock::Ruleset;
 use landlock::RulesetAttr;
 use landlock::RulesetCreatedAttr;
@@ -25,47 +29,173 @@ use seccompiler::SeccompRule;
 use seccompiler::TargetArch;
 use seccompiler::apply_filter;
 
+use crate::container::enter_mount_namespace;
+
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
+///
+/// When `network_proxy_port` is set, the caller has already confined the
+/// command to a network namespace that only reaches the proxy, and TCP
+/// connections to that port stay allowed. Otherwise network access is blocked
+/// entirely.
+///
+/// `read_deny` holds the configured read-deny globs on top of the defaults.
+/// Denied paths outside the writable roots are left out of the Landlock read
+/// rules. Landlock cannot take access away beneath a rule, so denied paths
+/// inside a writable root are covered with empty mounts in a new mount
+/// namespace instead, and existing read-only subpaths are bind-mounted
+/// read-only there while the rest of their root stays writable.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    read_deny: &[String],
+    network_proxy_port: Option<u16>,
 ) -> Result<()> {
-    if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
-    }
-
     if !sandbox_policy.has_full_disk_write_access() {
-        let writable_roots = sandbox_policy
-            .get_writable_roots_with_cwd(cwd)
-            .into_iter()
-            .map(|writable_root| writable_root.root)
-            .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        let mut canonical_roots = Vec::new();
+        let mut read_only_subpaths = Vec::new();
+        let mut missing_subpaths = Vec::new();
+        for writable_root in sandbox_policy.get_writable_roots_with_cwd(cwd) {
+            // The automatic top-level `.git` protection is left to the
+            // approval flow so that git commands keep working.
+            let top_level_git = writable_root.root.join(".git");
+            for subpath in &writable_root.read_only_subpaths {
+                if *subpath == top_level_git {
+                    continue;
+                }
+                match subpath.canonicalize() {
+                    Ok(subpath) => read_only_subpaths.push(subpath),
+                    Err(_) => missing_subpaths.push(canonicalize_existing_prefix(subpath)),
+                }
+            }
+            canonical_roots.push(
+                writable_root
+                    .root
+                    .canonicalize()
+                    .unwrap_or(writable_root.root),
+            );
+        }
+        let (inside_roots, outside_roots): (Vec<PathBuf>, Vec<PathBuf>) =
+            resolve_denied_paths(sandbox_policy, cwd, read_deny, &canonical_roots)
+                .into_iter()
+                .partition(|path| canonical_roots.iter().any(|root| path.starts_with(root)));
+
+        // A path that does not exist yet cannot be mounted over. Splitting the
+        // Landlock rules around it keeps it from being created, at the cost of
+        // new entries next to it, and is the fallback for every read-only
+        // subpath without a mount namespace.
+        let mut split_around = missing_subpaths;
+        if !(read_only_subpaths.is_empty() && inside_roots.is_empty())
+            && let Err(e) = enter_mount_namespace(&read_only_subpaths, &inside_roots)
+        {
+            eprintln!(
+                "codex-linux-sandbox: warning: could not create a mount namespace, so {} read-denied paths inside writable roots stay readable and no new entries can be created next to read-only subpaths: {e}",
+                inside_roots.len()
+            );
+            split_around.extend(read_only_subpaths);
+        }
+        let mut writable_paths = Vec::new();
+        for root in &canonical_roots {
+            split_access(root, &split_around, &mut writable_paths, &mut Vec::new());
+        }
+        install_filesystem_landlock_rules_on_current_thread(writable_paths, &outside_roots)?;
     }
 
-    // TODO(ragona): Add appropriate restrictions if
-    // `sandbox_policy.has_full_disk_read_access()` is `false`.
+    // Hiding read-denied paths needs mounts, which a Landlock domain would
+    // refuse, so the network rules are only added once that is done.
+    if !sandbox_policy.has_full_network_access() {
+        if let Some(port) = network_proxy_port
+            && let Err(e) = install_proxy_port_landlock_rules_on_current_thread(port)
+        {
+            // The network namespace still confines the command to the
+            // proxy; Landlock only adds a second layer.
+            eprintln!(
+                "codex-linux-sandbox: warning: this kernel does not support Landlock network rules (ABI v4), so connections are only confined by the network namespace: {e:?}"
+            );
+        }
+        install_network_seccomp_filter_on_current_thread(network_proxy_port.is_some())?;
+    }
 
     Ok(())
 }
 
+/// Existing paths that the read-deny globs cover: the concrete paths they
+/// name, plus matches of name globs such as `.env` beneath the working
+/// directories and `writable_roots`.
+pub(crate) fn resolve_denied_paths(
+    sandbox_policy: &SandboxPolicy,
+    sandbox_policy_cwd: &Path,
+    read_deny: &[String],
+    writable_roots: &[PathBuf],
+) -> Vec<PathBuf> {
+    let read_deny = ReadDenyList::new(sandbox_policy, read_deny);
+    let mut scan_roots: Vec<PathBuf> = writable_roots.to_vec();
+    scan_roots.extend(std::env::current_dir().ok());
+    scan_roots.push(sandbox_policy_cwd.to_path_buf());
+    let mut scan_roots: Vec<PathBuf> = scan_roots
+        .into_iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .collect();
+    scan_roots.sort();
+    scan_roots.dedup();
+    let nested: Vec<PathBuf> = scan_roots
+        .iter()
+        .filter(|root| {
+            scan_roots
+                .iter()
+                .any(|other| other != *root && root.starts_with(other))
+        })
+        .cloned()
+        .collect();
+    scan_roots.retain(|root| !nested.contains(root));
+
+    let found = read_deny.find_denied_paths(&scan_roots);
+    if !found.complete {
+        eprintln!(
+            "codex-linux-sandbox: warning: stopped looking for read-denied files such as `.env` after {MAX_SCANNED_ENTRIES} entries; files further down stay readable to this command"
+        );
+    }
+    let mut paths = read_deny.denied_paths();
+    paths.extend(found.paths);
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
 /// Installs Landlock file-system rules on the current thread allowing read
-/// access to the entire file-system while restricting write access to
-/// `/dev/null` and the provided list of `writable_roots`.
+/// access to the entire file-system except `denied_paths` while restricting
+/// write access to `/dev/null` and the provided list of `writable_roots`.
+/// Read-only subpaths that are not mounted read-only must already be carved
+/// out of `writable_roots`.
 ///
 /// # Errors
 /// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
-fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathBuf>) -> Result<()> {
+fn install_filesystem_landlock_rules_on_current_thread(
+    writable_roots: Vec<PathBuf>,
+    denied_paths: &[PathBuf],
+) -> Result<()> {
     let abi = ABI::V5;
     let access_rw = AccessFs::from_all(abi);
     let access_ro = AccessFs::from_read(abi);
 
+    let mut readable_paths = Vec::new();
+    let mut listable_paths = Vec::new();
+    split_access(
+        Path::new("/"),
+        denied_paths,
+        &mut readable_paths,
+        &mut listable_paths,
+    );
+
     let mut ruleset = Ruleset::default()
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
         .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
+        .add_rules(landlock::path_beneath_rules(&readable_paths, access_ro))?
+        .add_rules(landlock::path_beneath_rules(
+            &listable_paths,
+            AccessFs::ReadDir,
+        ))?
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
         .set_no_new_privs(true);
 
@@ -82,12 +212,107 @@ fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathB
     Ok(())
 }
 
+/// Canonicalizes the longest existing prefix of a path that does not exist
+/// yet, so it still lines up with the canonical writable roots.
+fn canonicalize_existing_prefix(path: &Path) -> PathBuf {
+    for ancestor in path.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize()
+            && let Ok(rest) = path.strip_prefix(ancestor)
+        {
+            return canonical.join(rest);
+        }
+    }
+    path.to_path_buf()
+}
+
+/// Landlock rules apply to whole hierarchies, so access to `dir` is split
+/// around `excluded` paths: subtrees that contain no excluded path are pushed
+/// to `included`, while directories on the way to an excluded path are pushed
+/// to `ancestors` and get no rule of their own. Symlinks are skipped; their
+/// targets are covered by their own rules.
+fn split_access(
+    dir: &Path,
+    excluded: &[PathBuf],
+    included: &mut Vec<PathBuf>,
+    ancestors: &mut Vec<PathBuf>,
+) {
+    let excluded_here: Vec<PathBuf> = excluded
+        .iter()
+        .filter(|path| path.starts_with(dir))
+        .cloned()
+        .collect();
+    if excluded_here.is_empty() {
+        included.push(dir.to_path_buf());
+        return;
+    }
+    if excluded_here.iter().any(|path| path == dir) {
+        return;
+    }
+
+    ancestors.push(dir.to_path_buf());
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry
+            .file_type()
+            .is_ok_and(|file_type| file_type.is_symlink())
+        {
+            continue;
+        }
+        split_access(&entry.path(), &excluded_here, included, ancestors);
+    }
+}
+
+/// Installs Landlock network rules on the current thread that only allow TCP
+/// connections to `proxy_port` and no TCP binds. Landlock matches ports, not
+/// addresses; the network namespace is what keeps other hosts out of reach.
+///
+/// # Errors
+/// Fails when the kernel does not support Landlock network rules (ABI v4).
+fn install_proxy_port_landlock_rules_on_current_thread(proxy_port: u16) -> Result<()> {
+    let status = Ruleset::default()
+        .set_compatibility(CompatLevel::HardRequirement)
+        .handle_access(AccessNet::from_all(ABI::V4))?
+        .create()?
+        .add_rule(NetPort::new(proxy_port, AccessNet::ConnectTcp))?
+        .set_no_new_privs(true)
+        .restrict_self()?;
+
+    if status.ruleset != landlock::RulesetStatus::FullyEnforced {
+        return Err(CodexErr::Sandbox(SandboxErr::LandlockRestrict));
+    }
+
+    Ok(())
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
-fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+///
+/// With `proxy_only`, TCP sockets are also allowed because the network
+/// namespace limits them to the network proxy; datagram and raw sockets stay
+/// blocked.
+fn install_network_seccomp_filter_on_current_thread(
+    proxy_only: bool,
+) -> std::result::Result<(), SandboxErr> {
     // Build rule map.
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
 
+    if proxy_only {
+        for nr in [
+            libc::SYS_accept,
+            libc::SYS_accept4,
+            libc::SYS_bind,
+            libc::SYS_listen,
+            libc::SYS_ptrace,
+        ] {
+            rules.insert(nr, vec![]);
+        }
+        rules.insert(libc::SYS_socket, proxy_only_socket_rules()?);
+        rules.insert(libc::SYS_socketpair, vec![unix_only_socket_rule()?]);
+        return apply_network_seccomp_rules(rules);
+    }
+
     // Helper – insert unconditional deny rule for syscall number.
     let mut deny_syscall = |nr: i64| {
         rules.insert(nr, vec![]); // empty rule vec = unconditional match
@@ -114,16 +339,69 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
     deny_syscall(libc::SYS_ptrace);
 
     // For `socket` we allow AF_UNIX (arg0 == AF_UNIX) and deny everything else.
-    let unix_only_rule = SeccompRule::new(vec![SeccompCondition::new(
+    let unix_only_rule = unix_only_socket_rule()?;
+
+    rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
+    rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
+
+    apply_network_seccomp_rules(rules)
+}
+
+/// Rule matching every `socket`/`socketpair` call whose domain is not AF_UNIX.
+fn unix_only_socket_rule() -> std::result::Result<SeccompRule, SandboxErr> {
+    Ok(SeccompRule::new(vec![SeccompCondition::new(
         0, // first argument (domain)
         SeccompCmpArgLen::Dword,
         SeccompCmpOp::Ne,
         libc::AF_UNIX as u64,
-    )?])?;
+    )?])?)
+}
 
-    rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
-    rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
+/// Rules matching every `socket` call other than AF_UNIX sockets and IPv4/IPv6
+/// stream sockets.
+fn proxy_only_socket_rules() -> std::result::Result<Vec<SeccompRule>, SandboxErr> {
+    let domain_is = |domain: i32| {
+        SeccompCondition::new(0, SeccompCmpArgLen::Dword, SeccompCmpOp::Eq, domain as u64)
+    };
+    let domain_is_not = |domain: i32| {
+        SeccompCondition::new(0, SeccompCmpArgLen::Dword, SeccompCmpOp::Ne, domain as u64)
+    };
+    // The low bits of the second argument hold the socket type; the rest are
+    // flags such as SOCK_CLOEXEC.
+    let type_is = |socket_type: i32| {
+        SeccompCondition::new(
+            1,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::MaskedEq(0xf),
+            socket_type as u64,
+        )
+    };
+
+    let mut rules = vec![SeccompRule::new(vec![
+        domain_is_not(libc::AF_UNIX)?,
+        domain_is_not(libc::AF_INET)?,
+        domain_is_not(libc::AF_INET6)?,
+    ])?];
+    for domain in [libc::AF_INET, libc::AF_INET6] {
+        for socket_type in [
+            libc::SOCK_DGRAM,
+            libc::SOCK_RAW,
+            libc::SOCK_RDM,
+            libc::SOCK_SEQPACKET,
+            libc::SOCK_PACKET,
+        ] {
+            rules.push(SeccompRule::new(vec![
+                domain_is(domain)?,
+                type_is(socket_type)?,
+            ])?);
+        }
+    }
+    Ok(rules)
+}
 
+fn apply_network_seccomp_rules(
+    rules: BTreeMap<i64, Vec<SeccompRule>>,
+) -> std::result::Result<(), SandboxErr> {
     let filter = SeccompFilter::new(
         rules,
         SeccompAction::Allow,                     // default – allow
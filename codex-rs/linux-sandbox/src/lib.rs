@@ -1,7 +1,11 @@
 #[cfg(target_os = "linux")]
+mod container;
+#[cfg(target_os = "linux")]
 mod landlock;
 #[cfg(target_os = "linux")]
 mod linux_run_main;
+#[cfg(target_os = "linux")]
+mod proxy_relay;
 
 #[cfg(target_os = "linux")]
 pub fn run_main() -> ! {
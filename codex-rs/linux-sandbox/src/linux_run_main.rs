@@ -2,7 +2,12 @@ use clap::Parser;
 use std::ffi::CString;
 use std::path::PathBuf;
 
+use codex_core::config::types::ResourceLimits;
+use codex_core::spawn::apply_resource_limits;
+
+use crate::container::run_in_container;
 use crate::landlock::apply_sandbox_policy_to_current_thread;
+use crate::proxy_relay::confine_network_to_proxy;
 
 #[derive(Debug, Parser)]
 pub struct LandlockCommand {
@@ -14,6 +19,38 @@ pub struct LandlockCommand {
     #[arg(long = "sandbox-policy")]
     pub sandbox_policy: codex_core::protocol::SandboxPolicy,
 
+    /// Globs for paths the command may not read, on top of the built-in
+    /// secret locations. May be repeated.
+    #[arg(long = "read-deny")]
+    pub read_deny: Vec<String>,
+
+    /// Loopback port of the network proxy that enforces the policy's network
+    /// allowlist. The command runs in its own network namespace in which
+    /// connections to this port reach the proxy.
+    #[arg(long = "network-proxy-port")]
+    pub network_proxy_port: Option<u16>,
+
+    /// Run the command inside rootless namespaces instead of under
+    /// Landlock and seccomp.
+    #[arg(long = "container")]
+    pub container: bool,
+
+    /// Maximum address space of each process, in MiB.
+    #[arg(long = "memory-limit-mb")]
+    pub memory_limit_mb: Option<u64>,
+
+    /// Maximum CPU time of each process, in seconds.
+    #[arg(long = "cpu-limit-seconds")]
+    pub cpu_limit_seconds: Option<u64>,
+
+    /// Maximum number of processes of the user.
+    #[arg(long = "process-limit")]
+    pub process_limit: Option<u64>,
+
+    /// Largest file the command may write, in MiB.
+    #[arg(long = "file-size-limit-mb")]
+    pub file_size_limit_mb: Option<u64>,
+
     /// Full command args to run under landlock.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
@@ -23,13 +60,62 @@ pub fn run_main() -> ! {
     let LandlockCommand {
         sandbox_policy_cwd,
         sandbox_policy,
+        read_deny,
+        network_proxy_port,
+        container,
+        memory_limit_mb,
+        cpu_limit_seconds,
+        process_limit,
+        file_size_limit_mb,
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    let resource_limits = ResourceLimits {
+        memory_mb: memory_limit_mb,
+        cpu_seconds: cpu_limit_seconds,
+        processes: process_limit,
+        file_size_mb: file_size_limit_mb,
+        output_mb: None,
+    };
+    if container {
+        run_in_container(
+            &sandbox_policy,
+            &sandbox_policy_cwd,
+            &read_deny,
+            network_proxy_port,
+            &resource_limits,
+            &command,
+        );
+    }
+
+    // Without a namespace to confine it, the command gets no network access
+    // at all rather than access to the proxy port on every host.
+    let network_proxy_port = network_proxy_port
+        .filter(|_| !sandbox_policy.has_full_network_access())
+        .filter(|&port| match confine_network_to_proxy(port) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!(
+                    "codex-linux-sandbox: network access is blocked because the network proxy could not be set up: {e}"
+                );
+                false
+            }
+        });
+
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        &read_deny,
+        network_proxy_port,
+    ) {
         panic!("error running landlock: {e:?}");
     }
 
+    // The limits carry over to the command through execvp below.
+    if let Err(e) = apply_resource_limits(&resource_limits) {
+        panic!("error applying resource limits: {e:?}");
+    }
+
     if command.is_empty() {
         panic!("No command specified to execute.");
     }
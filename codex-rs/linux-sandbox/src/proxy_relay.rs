@@ -0,0 +1,183 @@
+//! Confines a command's network access to the sandbox network proxy.
+//!
+//! Landlock restricts TCP connections by port only, so a rule allowing the
+//! proxy port would also allow that port on every other host. Instead, the
+//! command runs in its own network namespace, where the only interface is
+//! `lo`. The helper forks before entering the namespace: the parent stays in
+//! the host namespace and relays every connection made to the proxy port
+//! inside the namespace to the proxy on the host loopback.
+//!
+//! ```text
+//! codex-linux-sandbox (host network namespace, relays to the proxy)
+//! └── command (own network namespace with only `lo`)
+//! ```
+//!
+//! The listening socket is created inside the namespace and handed to the
+//! parent over a Unix socket; a socket stays in the namespace it was created
+//! in, so the parent can accept connections from the command while
+//! connecting to the proxy on the host.
+
+use std::io;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+
+use crate::container::bring_up_loopback;
+use crate::container::check;
+use crate::container::fork;
+use crate::container::forward_exit_status;
+use crate::container::map_current_user;
+
+/// Runs the rest of this process in a network namespace whose proxy port is
+/// relayed to `proxy_port` on the host loopback. Only returns in the forked
+/// child; the parent relays connections and exits the way the child does.
+pub(crate) fn confine_network_to_proxy(proxy_port: u16) -> io::Result<()> {
+    let (parent_end, child_end) = socket_pair()?;
+    let parent = unsafe { libc::getpid() };
+    match fork()? {
+        0 => {}
+        child => {
+            drop(child_end);
+            relay_until_exit(&parent_end, proxy_port, child)
+        }
+    }
+    drop(parent_end);
+
+    // The command must not outlive the relay.
+    unsafe {
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+        if libc::getppid() != parent {
+            libc::_exit(1);
+        }
+    }
+
+    let uid = unsafe { libc::getuid() };
+    let gid = unsafe { libc::getgid() };
+    check(
+        unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) },
+        "unshare",
+    )?;
+    map_current_user(uid, gid)?;
+    bring_up_loopback()?;
+
+    let listener = TcpListener::bind(("127.0.0.1", proxy_port))?;
+    send_fd(&child_end, listener.as_raw_fd())
+}
+
+/// Relays connections from the namespace once the child has handed over its
+/// listener, then exits with the child's status. Without a listener, e.g.
+/// because the child could not create the namespace, it only waits.
+fn relay_until_exit(socket: &OwnedFd, proxy_port: u16, child: libc::pid_t) -> ! {
+    if let Ok(listener) = recv_fd(socket) {
+        let listener = TcpListener::from(listener);
+        std::thread::spawn(move || {
+            for inbound in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    if let Ok(outbound) = TcpStream::connect(("127.0.0.1", proxy_port)) {
+                        relay(inbound, outbound);
+                    }
+                });
+            }
+        });
+    }
+    forward_exit_status(child)
+}
+
+/// Copies bytes both ways until each side has closed its half.
+fn relay(inbound: TcpStream, outbound: TcpStream) {
+    let (Ok(mut inbound_reader), Ok(mut outbound_writer)) =
+        (inbound.try_clone(), outbound.try_clone())
+    else {
+        return;
+    };
+    let upstream = std::thread::spawn(move || {
+        let _ = io::copy(&mut inbound_reader, &mut outbound_writer);
+        let _ = outbound_writer.shutdown(Shutdown::Write);
+    });
+    let (mut outbound_reader, mut inbound_writer) = (outbound, inbound);
+    let _ = io::copy(&mut outbound_reader, &mut inbound_writer);
+    let _ = inbound_writer.shutdown(Shutdown::Write);
+    let _ = upstream.join();
+}
+
+fn socket_pair() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds: [RawFd; 2] = [-1; 2];
+    check(
+        unsafe {
+            libc::socketpair(
+                libc::AF_UNIX,
+                libc::SOCK_STREAM | libc::SOCK_CLOEXEC,
+                0,
+                fds.as_mut_ptr(),
+            )
+        },
+        "socketpair",
+    )?;
+    Ok(unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
+}
+
+/// Control message buffer, aligned for `cmsghdr` and large enough for one
+/// file descriptor.
+#[repr(C)]
+union FdControl {
+    header: libc::cmsghdr,
+    buf: [u8; 32],
+}
+
+fn send_fd(socket: &OwnedFd, fd: RawFd) -> io::Result<()> {
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let mut control = FdControl { buf: [0; 32] };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = (&mut control as *mut FdControl).cast();
+    msg.msg_controllen = unsafe { libc::CMSG_SPACE(size_of::<RawFd>() as u32) } as _;
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(size_of::<RawFd>() as u32) as _;
+        std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>(), fd);
+    }
+    let sent = unsafe { libc::sendmsg(socket.as_raw_fd(), &msg, 0) };
+    check(sent as libc::c_int, "sendmsg")
+}
+
+fn recv_fd(socket: &OwnedFd) -> io::Result<OwnedFd> {
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let mut control = FdControl { buf: [0; 32] };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = (&mut control as *mut FdControl).cast();
+    msg.msg_controllen = size_of::<FdControl>() as _;
+    let received = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC) };
+    check(received as libc::c_int, "recvmsg")?;
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        if received == 0
+            || cmsg.is_null()
+            || (*cmsg).cmsg_level != libc::SOL_SOCKET
+            || (*cmsg).cmsg_type != libc::SCM_RIGHTS
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "no listener received from the sandboxed process",
+            ));
+        }
+        let fd = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>());
+        Ok(OwnedFd::from_raw_fd(fd))
+    }
+}
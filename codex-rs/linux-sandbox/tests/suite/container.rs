@@ -0,0 +1,153 @@
+#![cfg(target_os = "linux")]
+use codex_core::config::types::ShellEnvironmentPolicy;
+use codex_core::error::CodexErr;
+use codex_core::error::Result;
+use codex_core::error::SandboxErr;
+use codex_core::exec::ExecParams;
+use codex_core::exec::ExecToolCallOutput;
+use codex_core::exec::SandboxType;
+use codex_core::exec::process_exec_tool_call;
+use codex_core::exec_env::create_env;
+use codex_core::protocol::SandboxPolicy;
+use std::path::PathBuf;
+
+const TIMEOUT_MS: u64 = 5_000;
+
+#[expect(clippy::expect_used)]
+async fn run_in_container(
+    cmd: &[&str],
+    writable_roots: &[PathBuf],
+    read_only_subpaths: &[PathBuf],
+) -> Result<ExecToolCallOutput> {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let sandbox_cwd = cwd.clone();
+    let params = ExecParams {
+        command: cmd.iter().copied().map(str::to_owned).collect(),
+        cwd,
+        timeout_ms: Some(TIMEOUT_MS),
+        env: create_env(&ShellEnvironmentPolicy::default()),
+        with_escalated_permissions: None,
+        justification: None,
+        arg0: None,
+    };
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: writable_roots.to_vec(),
+        network_access: false,
+        network_allowlist: vec![],
+        read_only_subpaths: read_only_subpaths.to_vec(),
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+        container: true,
+    };
+    let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
+    process_exec_tool_call(
+        params,
+        SandboxType::LinuxNamespaces,
+        &sandbox_policy,
+        &[],
+        sandbox_cwd.as_path(),
+        &Some(PathBuf::from(sandbox_program)),
+        None,
+    )
+    .await
+}
+
+/// Unprivileged user namespaces are disabled on some hosts, in which case the
+/// container tests are skipped.
+#[expect(clippy::print_stdout)]
+async fn containers_supported() -> bool {
+    match run_in_container(&["true"], &[], &[]).await {
+        Ok(output) if output.exit_code == 0 => true,
+        Ok(output) => {
+            println!("skipping container test: {}", output.stderr.text);
+            false
+        }
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => {
+            println!("skipping container test: {}", output.stderr.text);
+            false
+        }
+        Err(err) => panic!("unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn container_writes_to_writable_root() {
+    if !containers_supported().await {
+        return;
+    }
+    let tmpdir = tempfile::tempdir().unwrap();
+    let file_path = tmpdir.path().join("test");
+    let output = run_in_container(
+        &[
+            "bash",
+            "-c",
+            &format!("echo blah > {}", file_path.to_string_lossy()),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+    assert_eq!(std::fs::read_to_string(file_path).unwrap(), "blah\n");
+}
+
+#[tokio::test]
+async fn container_hides_paths_outside_the_workspace() {
+    if !containers_supported().await {
+        return;
+    }
+    let workspace = tempfile::tempdir().unwrap();
+    let elsewhere = tempfile::tempdir().unwrap();
+    let secret = elsewhere.path().join("secret");
+    std::fs::write(&secret, "hunter2").unwrap();
+    let output = run_in_container(
+        &["cat", &secret.to_string_lossy()],
+        &[workspace.path().to_path_buf()],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_ne!(output.exit_code, 0);
+    assert!(!output.stdout.text.contains("hunter2"));
+}
+
+#[tokio::test]
+async fn container_keeps_read_only_subpaths_read_only() {
+    if !containers_supported().await {
+        return;
+    }
+    let tmpdir = tempfile::tempdir().unwrap();
+    let migrations = tmpdir.path().join("migrations");
+    std::fs::create_dir(&migrations).unwrap();
+    let result = run_in_container(
+        &[
+            "bash",
+            "-c",
+            &format!(
+                "echo blah > {}",
+                migrations.join("0001.sql").to_string_lossy()
+            ),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        &[PathBuf::from("migrations")],
+    )
+    .await;
+    assert!(
+        matches!(result, Err(CodexErr::Sandbox(SandboxErr::Denied { .. }))),
+        "expected sandbox denial, got {result:?}"
+    );
+    assert!(!migrations.join("0001.sql").exists());
+}
+
+#[tokio::test]
+async fn container_blocks_network() {
+    if !containers_supported().await {
+        return;
+    }
+    let result = run_in_container(&["bash", "-c", "echo hi > /dev/tcp/1.1.1.1/80"], &[], &[]).await;
+    assert!(
+        matches!(result, Err(CodexErr::Sandbox(SandboxErr::Denied { .. }))),
+        "expected sandbox denial, got {result:?}"
+    );
+}
@@ -3,6 +3,7 @@ use codex_core::config::types::ShellEnvironmentPolicy;
 use codex_core::error::CodexErr;
 use codex_core::error::SandboxErr;
 use codex_core::exec::ExecParams;
+use codex_core::exec::ExecToolCallOutput;
 use codex_core::exec::SandboxType;
 use codex_core::exec::process_exec_tool_call;
 use codex_core::exec_env::create_env;
@@ -33,8 +34,41 @@ fn create_env_from_core_vars() -> HashMap<String, String> {
     create_env(&policy)
 }
 
-#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
 async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
+    run_cmd_with_restrictions(cmd, writable_roots, &[], &[], timeout_ms).await;
+}
+
+#[expect(clippy::print_stdout)]
+async fn run_cmd_with_restrictions(
+    cmd: &[&str],
+    writable_roots: &[PathBuf],
+    read_deny: &[String],
+    read_only_subpaths: &[PathBuf],
+    timeout_ms: u64,
+) {
+    let res = exec_with_restrictions(
+        cmd,
+        writable_roots,
+        read_deny,
+        read_only_subpaths,
+        timeout_ms,
+    )
+    .await;
+    if res.exit_code != 0 {
+        println!("stdout:\n{}", res.stdout.text);
+        println!("stderr:\n{}", res.stderr.text);
+        panic!("exit code: {}", res.exit_code);
+    }
+}
+
+#[expect(clippy::expect_used, clippy::unwrap_used)]
+async fn exec_with_restrictions(
+    cmd: &[&str],
+    writable_roots: &[PathBuf],
+    read_deny: &[String],
+    read_only_subpaths: &[PathBuf],
+    timeout_ms: u64,
+) -> ExecToolCallOutput {
     let cwd = std::env::current_dir().expect("cwd should exist");
     let sandbox_cwd = cwd.clone();
     let params = ExecParams {
@@ -50,30 +84,28 @@ async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
     let sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: writable_roots.to_vec(),
         network_access: false,
+        network_allowlist: vec![],
+        read_only_subpaths: read_only_subpaths.to_vec(),
         // Exclude tmp-related folders from writable roots because we need a
         // folder that is writable by tests but that we intentionally disallow
         // writing to in the sandbox.
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        container: false,
     };
     let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
     let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
-    let res = process_exec_tool_call(
+    process_exec_tool_call(
         params,
         SandboxType::LinuxSeccomp,
         &sandbox_policy,
+        read_deny,
         sandbox_cwd.as_path(),
         &codex_linux_sandbox_exe,
         None,
     )
     .await
-    .unwrap();
-
-    if res.exit_code != 0 {
-        println!("stdout:\n{}", res.stdout.text);
-        println!("stderr:\n{}", res.stderr.text);
-        panic!("exit code: {}", res.exit_code);
-    }
+    .unwrap()
 }
 
 #[tokio::test]
@@ -124,6 +156,162 @@ async fn test_writable_root() {
     .await;
 }
 
+#[tokio::test]
+#[should_panic(expected = "Sandbox(Denied")]
+async fn test_read_denied_path() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secret = tmpdir.path().join("secret");
+    std::fs::write(&secret, "hunter2").unwrap();
+    run_cmd_with_restrictions(
+        &["cat", &secret.to_string_lossy()],
+        &[],
+        &[tmpdir.path().to_string_lossy().into_owned()],
+        &[],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_read_next_to_denied_path() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secrets = tmpdir.path().join("secrets");
+    std::fs::create_dir(&secrets).unwrap();
+    let notes = tmpdir.path().join("notes");
+    std::fs::write(&notes, "hello").unwrap();
+    run_cmd_with_restrictions(
+        &["cat", &notes.to_string_lossy()],
+        &[],
+        &[secrets.to_string_lossy().into_owned()],
+        &[],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[expect(clippy::print_stdout)]
+async fn test_env_file_in_writable_root_is_hidden() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let env_file = tmpdir.path().join(".env");
+    std::fs::write(&env_file, "TOKEN=hunter2").unwrap();
+    let output = exec_with_restrictions(
+        &["cat", &env_file.to_string_lossy()],
+        &[tmpdir.path().to_path_buf()],
+        &[],
+        &[],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+    // Hiding needs unprivileged user namespaces, which some hosts disable.
+    if output
+        .stderr
+        .text
+        .contains("could not create a mount namespace")
+    {
+        println!("skipping: {}", output.stderr.text);
+        return;
+    }
+    assert_eq!(output.exit_code, 0, "{}", output.stderr.text);
+    assert!(
+        !output.stdout.text.contains("hunter2"),
+        "{}",
+        output.stdout.text
+    );
+}
+
+#[tokio::test]
+#[should_panic(expected = "Sandbox(Denied")]
+async fn test_write_into_read_only_subpath() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let migrations = tmpdir.path().join("migrations");
+    std::fs::create_dir(&migrations).unwrap();
+    run_cmd_with_restrictions(
+        &[
+            "bash",
+            "-lc",
+            &format!(
+                "echo blah > {}",
+                migrations.join("0001.sql").to_string_lossy()
+            ),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        &[],
+        &[PathBuf::from("migrations")],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Sandbox(Denied")]
+async fn test_create_missing_read_only_subpath() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let migrations = tmpdir.path().join("migrations");
+    run_cmd_with_restrictions(
+        &[
+            "bash",
+            "-lc",
+            &format!("mkdir {}", migrations.to_string_lossy()),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        &[],
+        &[PathBuf::from("migrations")],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[expect(clippy::print_stdout)]
+async fn test_create_file_next_to_read_only_subpath() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(tmpdir.path().join("migrations")).unwrap();
+    let readme = tmpdir.path().join("README.md");
+    let output = exec_with_restrictions(
+        &[
+            "bash",
+            "-lc",
+            &format!("echo blah > {} || true", readme.to_string_lossy()),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        &[],
+        &[PathBuf::from("migrations")],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+    // Without a mount namespace the whole root is split around the subpath.
+    if output
+        .stderr
+        .text
+        .contains("could not create a mount namespace")
+    {
+        println!("skipping: {}", output.stderr.text);
+        return;
+    }
+    assert!(readme.exists(), "{}", output.stderr.text);
+}
+
+#[tokio::test]
+async fn test_write_next_to_read_only_subpath() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let src = tmpdir.path().join("src");
+    std::fs::create_dir(&src).unwrap();
+    std::fs::create_dir(tmpdir.path().join("migrations")).unwrap();
+    run_cmd_with_restrictions(
+        &[
+            "bash",
+            "-lc",
+            &format!("echo blah > {}", src.join("lib.rs").to_string_lossy()),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        &[],
+        &[PathBuf::from("migrations")],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
 #[tokio::test]
 #[should_panic(expected = "Sandbox(Timeout")]
 async fn test_timeout() {
@@ -157,6 +345,7 @@ async fn assert_network_blocked(cmd: &[&str]) {
         params,
         SandboxType::LinuxSeccomp,
         &sandbox_policy,
+        &[],
         sandbox_cwd.as_path(),
         &codex_linux_sandbox_exe,
         None,
@@ -179,6 +179,7 @@ async fn run_codex_tool_session_inner(
                         call_id,
                         reason: _,
                         risk,
+                        env_profile: _,
                         parsed_cmd,
                     }) => {
                         handle_exec_approval_request(
@@ -273,6 +274,8 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::ListCustomPromptsResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::ExecCommandOutputDelta(_)
+                    | EventMsg::NetworkRequestDenied(_)
+                    | EventMsg::SecretsRedacted(_)
                     | EventMsg::ExecCommandEnd(_)
                     | EventMsg::BackgroundEvent(_)
                     | EventMsg::StreamError(_)
@@ -296,6 +299,7 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::ReasoningRawContentDelta(_)
                     | EventMsg::UndoStarted(_)
                     | EventMsg::UndoCompleted(_)
+                    | EventMsg::CheckpointRestored(_)
                     | EventMsg::ExitedReviewMode(_)
                     | EventMsg::DeprecationNotice(_) => {
                         // For now, we do not do anything extra for these
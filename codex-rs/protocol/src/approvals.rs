@@ -46,6 +46,10 @@ pub struct ExecApprovalRequestEvent {
     /// Optional model-provided risk assessment describing the blocked command.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub risk: Option<SandboxCommandAssessment>,
+    /// The `shell_environment_policy.profiles` entry the command runs with,
+    /// which may inject secrets into its environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_profile: Option<String>,
     pub parsed_cmd: Vec<ParsedCommand>,
 }
 
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
@@ -89,6 +91,32 @@ pub enum SandboxMode {
 
     #[serde(rename = "danger-full-access")]
     DangerFullAccess,
+
+    /// Like `workspace-write`, but commands run inside rootless Linux
+    /// namespaces that only expose the system directories and the writable
+    /// roots.
+    #[serde(rename = "container")]
+    Container,
+}
+
+/// How conversation history is compacted when it outgrows the context window.
+#[derive(
+    Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Display, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum CompactionStrategy {
+    /// Replace the history with a summary written by the model.
+    #[default]
+    ModelSummary,
+    /// Remove the oldest tool calls and their outputs until the history fits.
+    DropToolOutputs,
+    /// Replace the oldest large tool outputs with short stubs that keep their
+    /// call IDs until the history fits.
+    ElideToolOutputs,
+    /// Summarize the conversation since the last compaction and condense the
+    /// earlier summaries into a single summary of summaries.
+    HierarchicalSummary,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Display, JsonSchema, TS)]
@@ -98,3 +126,17 @@ pub enum ForcedLoginMethod {
     Chatgpt,
     Api,
 }
+
+/// A standing approval for commands, configured as `[[approval_rules]]` in
+/// `config.toml`. A command is approved when every condition set on the rule
+/// holds; a rule without conditions approves nothing.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default, JsonSchema, TS)]
+pub struct ApprovalRule {
+    /// Words the command must start with, e.g. `["cargo", "test"]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<Vec<String>>,
+
+    /// Directory the command must run in or beneath.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<PathBuf>,
+}
@@ -11,6 +11,8 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use crate::ConversationId;
+use crate::config_types::ApprovalRule;
+use crate::config_types::CompactionStrategy;
 use crate::config_types::ReasoningEffort as ReasoningEffortConfig;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use crate::custom_prompts::CustomPrompt;
@@ -153,6 +155,10 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Replace the standing approval rules of the session. Commands matching
+    /// a rule run without prompting.
+    SetApprovalRules { rules: Vec<ApprovalRule> },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -180,6 +186,13 @@ pub enum Op {
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
 
+    /// Rewind to the checkpoint taken at the start of a user turn.
+    ///
+    /// Restores the working tree from that turn's ghost snapshot and drops the
+    /// turn, and everything after it, from the conversation history. `turn` is
+    /// the 0-based index of the user message in the conversation.
+    RestoreCheckpoint { turn: usize },
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
@@ -265,6 +278,18 @@ pub enum SandboxPolicy {
         #[serde(default)]
         network_access: bool,
 
+        /// Hosts that stay reachable through the sandbox network proxy when
+        /// `network_access` is `false`. Entries are `host` or `host:port`; a
+        /// leading `*.` also matches any subdomain.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        network_allowlist: Vec<String>,
+
+        /// Subpaths that stay read-only even though they are under a writable
+        /// root. Relative entries apply beneath every writable root; absolute
+        /// entries apply to the root that contains them.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        read_only_subpaths: Vec<PathBuf>,
+
         /// When set to `true`, will NOT include the per-user `TMPDIR`
         /// environment variable among the default writable roots. Defaults to
         /// `false`.
@@ -275,13 +300,36 @@ pub enum SandboxPolicy {
         /// writable roots on UNIX. Defaults to `false`.
         #[serde(default)]
         exclude_slash_tmp: bool,
+
+        /// When set to `true`, commands run in a rootless namespace container
+        /// on Linux instead of under Landlock and seccomp. Other platforms
+        /// reject such a policy at config load.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        container: bool,
     },
 }
 
+/// Globs for secrets that sandboxed commands and tools may never read unless
+/// the policy is [`SandboxPolicy::DangerFullAccess`]. Patterns starting with
+/// `~/` are relative to the home directory; patterns without a `/` match a
+/// file or directory name at any depth.
+pub const DEFAULT_READ_DENY_GLOBS: &[&str] = &[
+    "~/.ssh",
+    "~/.aws",
+    "~/.gnupg",
+    "~/.azure",
+    "~/.config/gcloud",
+    "~/.docker/config.json",
+    "~/.netrc",
+    "~/.git-credentials",
+    ".env",
+    ".env.*",
+];
+
 /// A writable root path accompanied by a list of subpaths that should remain
-/// read‑only even when the root is writable. This is primarily used to ensure
-/// top‑level VCS metadata directories (e.g. `.git`) under a writable root are
-/// not modified by the agent.
+/// read‑only even when the root is writable. This is used to ensure top‑level
+/// VCS metadata directories (e.g. `.git`) and the configured
+/// `read_only_subpaths` under a writable root are not modified by the agent.
 #[derive(Debug, Clone, PartialEq, Eq, JsonSchema)]
 pub struct WritableRoot {
     /// Absolute path, by construction.
@@ -330,12 +378,16 @@ impl SandboxPolicy {
         SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            network_allowlist: vec![],
+            read_only_subpaths: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            container: false,
         }
     }
 
-    /// Always returns `true`; restricting read access is not supported.
+    /// Always returns `true`; reads are only narrowed by the read-deny globs,
+    /// which are configured separately from the policy.
     pub fn has_full_disk_read_access(&self) -> bool {
         true
     }
@@ -356,6 +408,30 @@ impl SandboxPolicy {
         }
     }
 
+    /// Whether commands run in the rootless namespace container on Linux.
+    pub fn uses_container(&self) -> bool {
+        matches!(
+            self,
+            SandboxPolicy::WorkspaceWrite {
+                container: true,
+                ..
+            }
+        )
+    }
+
+    /// Hosts that should be reachable through the network proxy. Empty unless
+    /// network access is otherwise restricted.
+    pub fn network_allowlist(&self) -> &[String] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                network_allowlist,
+                ..
+            } => network_allowlist,
+            _ => &[],
+        }
+    }
+
     /// Returns the list of writable roots (tailored to the current working
     /// directory) together with subpaths that should remain read‑only under
     /// each writable root.
@@ -368,6 +444,9 @@ impl SandboxPolicy {
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
                 network_access: _,
+                network_allowlist: _,
+                read_only_subpaths,
+                container: _,
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<PathBuf> = writable_roots.clone();
@@ -408,6 +487,13 @@ impl SandboxPolicy {
                         if top_level_git.is_dir() {
                             subpaths.push(top_level_git);
                         }
+                        for subpath in read_only_subpaths {
+                            if subpath.is_relative() {
+                                subpaths.push(writable_root.join(subpath));
+                            } else if subpath.starts_with(&writable_root) {
+                                subpaths.push(subpath.clone());
+                            }
+                        }
                         WritableRoot {
                             root: writable_root,
                             read_only_subpaths: subpaths,
@@ -494,6 +580,12 @@ pub enum EventMsg {
 
     ExecCommandEnd(ExecCommandEndEvent),
 
+    /// The sandbox network proxy refused a connection from a running command.
+    NetworkRequestDenied(NetworkRequestDeniedEvent),
+
+    /// Secrets were masked in a tool output before it was sent to the model.
+    SecretsRedacted(SecretsRedactedEvent),
+
     /// Notification that the agent attached a local image via the view_image tool.
     ViewImageToolCall(ViewImageToolCallEvent),
 
@@ -511,6 +603,10 @@ pub enum EventMsg {
 
     UndoCompleted(UndoCompletedEvent),
 
+    /// The conversation was rewound to a checkpoint. Persisted in rollouts so
+    /// that replaying a session drops the rewound turns.
+    CheckpointRestored(CheckpointRestoredEvent),
+
     /// Notification that a model stream experienced an error or disconnect
     /// and the system is handling it (e.g., retrying with backoff).
     StreamError(StreamErrorEvent),
@@ -1068,6 +1164,8 @@ pub enum SessionSource {
 pub enum SubAgentSource {
     Review,
     Compact,
+    /// Spawned by the model through the `spawn_agent` tool.
+    Agent,
     Other(String),
 }
 
@@ -1117,9 +1215,20 @@ pub enum RolloutItem {
     EventMsg(EventMsg),
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema, TS)]
 pub struct CompactedItem {
     pub message: String,
+    /// Strategy that produced this compaction. Rollouts written before
+    /// strategies existed always used a model summary.
+    #[serde(default)]
+    pub strategy: CompactionStrategy,
+    /// Tool calls whose outputs were dropped or elided.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub call_ids: Vec<String>,
+    /// Condensed summary of the summaries before `message`, kept by
+    /// hierarchical compaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub earlier_summary: Option<String>,
 }
 
 impl From<CompactedItem> for ResponseItem {
@@ -1166,10 +1275,119 @@ pub struct GitInfo {
 }
 
 /// Review request sent to the review session.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+///
+/// Also deserializes the older `{prompt, user_facing_hint}` shape, still found
+/// in rollouts and sent by older clients, as a [`ReviewTarget::Custom`] review.
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema, TS)]
 pub struct ReviewRequest {
-    pub prompt: String,
-    pub user_facing_hint: String,
+    pub target: ReviewTarget,
+    /// Shown while the review runs. Derived from `target` when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_facing_hint: Option<String>,
+}
+
+impl ReviewRequest {
+    pub fn new(target: ReviewTarget) -> Self {
+        Self {
+            target,
+            user_facing_hint: None,
+        }
+    }
+
+    /// Short description of what is being reviewed, for UIs.
+    pub fn hint(&self) -> String {
+        self.user_facing_hint
+            .clone()
+            .unwrap_or_else(|| self.target.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ReviewRequest {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Wire {
+            Current {
+                target: ReviewTarget,
+                #[serde(default)]
+                user_facing_hint: Option<String>,
+            },
+            Legacy {
+                prompt: String,
+                #[serde(default)]
+                user_facing_hint: String,
+            },
+        }
+
+        Ok(match Wire::deserialize(deserializer)? {
+            Wire::Current {
+                target,
+                user_facing_hint,
+            } => Self {
+                target,
+                user_facing_hint,
+            },
+            Wire::Legacy {
+                prompt,
+                user_facing_hint,
+            } => Self {
+                target: ReviewTarget::Custom {
+                    instructions: prompt,
+                },
+                user_facing_hint: Some(user_facing_hint).filter(|hint| !hint.is_empty()),
+            },
+        })
+    }
+}
+
+/// What a review looks at. Core turns the target into the review prompt and
+/// attaches the corresponding diff.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReviewTarget {
+    /// Staged, unstaged and untracked changes.
+    UncommittedChanges,
+    /// Changes the current branch would merge into `branch`.
+    BaseBranch { branch: String },
+    /// Changes introduced by a single commit.
+    Commit {
+        sha: String,
+        /// Commit subject, shown to the reviewer and in UIs.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+    },
+    /// Changes introduced by a commit range such as `main..HEAD`.
+    CommitRange { range: String },
+    /// The listed files, including any uncommitted changes to them.
+    Files { paths: Vec<PathBuf> },
+    /// Free-form review instructions.
+    Custom { instructions: String },
+}
+
+impl fmt::Display for ReviewTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewTarget::UncommittedChanges => f.write_str("current changes"),
+            ReviewTarget::BaseBranch { branch } => write!(f, "changes against '{branch}'"),
+            ReviewTarget::Commit { sha, .. } => {
+                let short: String = sha.chars().take(7).collect();
+                write!(f, "commit {short}")
+            }
+            ReviewTarget::CommitRange { range } => write!(f, "commits {range}"),
+            ReviewTarget::Files { paths } => {
+                let paths = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "files {paths}")
+            }
+            ReviewTarget::Custom { instructions } => f.write_str(instructions),
+        }
+    }
 }
 
 /// Structured review result produced by a child review session.
@@ -1267,6 +1485,43 @@ pub struct ExecCommandEndEvent {
     pub duration: Duration,
     /// Formatted output from the command, as seen by the model.
     pub formatted_output: String,
+    /// Set when the command was stopped by a configured resource limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_limit_exceeded: Option<ResourceLimit>,
+}
+
+/// Resource limit that was identified as having stopped a command started
+/// by a shell tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Display, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ResourceLimit {
+    /// CPU time of a single process.
+    CpuTime,
+    /// Size of a single file written by the command.
+    FileSize,
+    /// Combined stdout and stderr captured from the command.
+    Output,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct NetworkRequestDeniedEvent {
+    /// Identifier for the command that attempted the connection.
+    pub call_id: String,
+    /// Host the command tried to reach.
+    pub host: String,
+    /// Port the command tried to reach.
+    pub port: u16,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SecretsRedactedEvent {
+    /// Identifier for the tool call whose output was redacted.
+    pub call_id: String,
+    /// Number of secrets masked in this output.
+    pub count: u32,
+    /// Number of secrets masked so far in this session.
+    pub session_total: u64,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1325,6 +1580,15 @@ pub struct UndoCompletedEvent {
     pub message: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CheckpointRestoredEvent {
+    /// 0-based index of the user turn that was rewound; it and all later
+    /// turns are no longer part of the conversation.
+    pub turn: usize,
+    /// Ghost commit the working tree was restored from.
+    pub commit_id: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct StreamErrorEvent {
     pub message: String,
@@ -1496,6 +1760,8 @@ pub enum TurnAbortReason {
     Interrupted,
     Replaced,
     ReviewEnded,
+    /// A configured token or cost budget was exhausted.
+    BudgetExceeded,
 }
 
 #[cfg(test)]
@@ -1589,4 +1855,58 @@ mod tests {
         assert_eq!(deserialized, event);
         Ok(())
     }
+
+    #[test]
+    fn review_request_serializes_tagged_target() -> Result<()> {
+        let request = ReviewRequest::new(ReviewTarget::Commit {
+            sha: "0123456789abcdef".to_string(),
+            title: None,
+        });
+        assert_eq!(
+            serde_json::to_value(&request)?,
+            json!({ "target": { "type": "commit", "sha": "0123456789abcdef" } })
+        );
+        assert_eq!(request.hint(), "commit 0123456");
+
+        let deserialized: ReviewRequest = serde_json::from_value(json!({
+            "target": { "type": "base_branch", "branch": "main" },
+            "user_facing_hint": "release branch",
+        }))?;
+        assert_eq!(
+            deserialized.target,
+            ReviewTarget::BaseBranch {
+                branch: "main".to_string()
+            }
+        );
+        assert_eq!(deserialized.hint(), "release branch");
+        Ok(())
+    }
+
+    #[test]
+    fn review_request_accepts_the_legacy_prompt_shape() -> Result<()> {
+        let legacy: ReviewRequest = serde_json::from_value(json!({
+            "prompt": "Review the error handling in src/lib.rs.",
+            "user_facing_hint": "error handling",
+        }))?;
+        assert_eq!(
+            legacy,
+            ReviewRequest {
+                target: ReviewTarget::Custom {
+                    instructions: "Review the error handling in src/lib.rs.".to_string(),
+                },
+                user_facing_hint: Some("error handling".to_string()),
+            }
+        );
+
+        let event: EventMsg = serde_json::from_value(json!({
+            "type": "entered_review_mode",
+            "prompt": "Review the diff.",
+            "user_facing_hint": "",
+        }))?;
+        let EventMsg::EnteredReviewMode(request) = event else {
+            panic!("expected EnteredReviewMode, got {event:?}");
+        };
+        assert_eq!(request.hint(), "Review the diff.");
+        Ok(())
+    }
 }
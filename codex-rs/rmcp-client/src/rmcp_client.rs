@@ -50,6 +50,7 @@ use crate::oauth::StoredOAuthTokens;
 use crate::utils::apply_default_headers;
 use crate::utils::build_default_headers;
 use crate::utils::convert_call_tool_result;
+use crate::utils::convert_list_tools_result;
 use crate::utils::convert_to_mcp;
 use crate::utils::convert_to_rmcp;
 use crate::utils::create_env_for_mcp_server;
@@ -266,7 +267,7 @@ impl RmcpClient {
 
         let fut = service.list_tools(rmcp_params);
         let result = run_with_timeout(fut, timeout, "tools/list").await?;
-        let converted = convert_to_mcp(result)?;
+        let converted = convert_list_tools_result(result)?;
         self.persist_oauth_tokens().await;
         Ok(converted)
     }
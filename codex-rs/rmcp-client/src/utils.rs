@@ -72,6 +72,70 @@ where
     serde_json::from_value(json).map_err(|err| anyhow!(err))
 }
 
+/// Converts a `tools/list` result, first inlining local `$ref`s in each tool's
+/// input schema. `mcp_types::ToolInputSchema` only keeps `properties` and
+/// `required`, so definitions under `$defs`/`definitions` would otherwise be
+/// lost along with the types they describe.
+pub(crate) fn convert_list_tools_result<T, U>(value: T) -> Result<U>
+where
+    T: serde::Serialize,
+    U: serde::de::DeserializeOwned,
+{
+    let mut json = serde_json::to_value(value)?;
+    if let Some(tools) = json.get_mut("tools").and_then(Value::as_array_mut) {
+        for tool in tools {
+            if let Some(schema) = tool.get_mut("inputSchema") {
+                inline_local_schema_refs(schema);
+            }
+        }
+    }
+    serde_json::from_value(json).map_err(|err| anyhow!(err))
+}
+
+/// Replaces every `$ref` that points into `schema` itself (e.g.
+/// `#/$defs/Point`) with the referenced schema. Keywords next to the `$ref`,
+/// such as `description`, take precedence. Recursive references are left in
+/// place.
+fn inline_local_schema_refs(schema: &mut Value) {
+    let root = schema.clone();
+    inline_refs(schema, &root, &mut Vec::new());
+}
+
+fn inline_refs(value: &mut Value, root: &Value, stack: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            let reference = map.get("$ref").and_then(Value::as_str).map(str::to_string);
+            if let Some(reference) = reference
+                && !stack.contains(&reference)
+                && let Some(target) = reference
+                    .strip_prefix('#')
+                    .and_then(|pointer| root.pointer(pointer))
+            {
+                let mut resolved = target.clone();
+                stack.push(reference);
+                inline_refs(&mut resolved, root, stack);
+                stack.pop();
+                map.remove("$ref");
+                if let Value::Object(resolved) = resolved {
+                    for (key, value) in resolved {
+                        map.entry(key).or_insert(value);
+                    }
+                }
+                return;
+            }
+            for child in map.values_mut() {
+                inline_refs(child, root, stack);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                inline_refs(item, root, stack);
+            }
+        }
+        _ => {}
+    }
+}
+
 pub(crate) fn create_env_for_mcp_server(
     extra_env: Option<HashMap<String, String>>,
     env_vars: &[String],
@@ -211,6 +275,46 @@ mod tests {
     use serial_test::serial;
     use std::ffi::OsString;
 
+    #[test]
+    fn inline_local_schema_refs_resolves_defs() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "start": { "$ref": "#/$defs/Point", "description": "Start point" },
+                "end": { "$ref": "#/$defs/Point" },
+                "tree": { "$ref": "#/$defs/Node" },
+            },
+            "$defs": {
+                "Point": {
+                    "type": "object",
+                    "properties": { "x": { "type": "integer" } },
+                    "description": "A point",
+                },
+                "Node": {
+                    "type": "object",
+                    "properties": { "child": { "$ref": "#/$defs/Node" } },
+                },
+            },
+        });
+
+        inline_local_schema_refs(&mut schema);
+
+        assert_eq!(
+            schema["properties"]["start"],
+            json!({
+                "type": "object",
+                "properties": { "x": { "type": "integer" } },
+                "description": "Start point",
+            })
+        );
+        assert_eq!(schema["properties"]["end"]["description"], json!("A point"));
+        // The recursive reference is inlined once and then left alone.
+        assert_eq!(
+            schema["properties"]["tree"]["properties"]["child"],
+            json!({ "$ref": "#/$defs/Node" })
+        );
+    }
+
     struct EnvVarGuard {
         key: String,
         original: Option<OsString>,
@@ -3,6 +3,7 @@ use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::ApprovalRequest;
 use crate::chatwidget::ChatWidget;
+use crate::chatwidget::approval_rule_label;
 use crate::diff_render::DiffSummary;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::file_search::FileSearchManager;
@@ -27,6 +28,7 @@ use codex_core::model_family::find_family_for_model;
 use codex_core::protocol::FinalOutput;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TokenUsage;
+use codex_core::protocol_config_types::ApprovalRule;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::ConversationId;
 use color_eyre::eyre::Result;
@@ -463,6 +465,7 @@ impl App {
                 self.chat_widget.on_commit_tick();
             }
             AppEvent::CodexEvent(event) => {
+                self.on_codex_event_for_rewind(tui, &event);
                 self.chat_widget.handle_codex_event(event);
             }
             AppEvent::ConversationHistory(ev) => {
@@ -582,6 +585,28 @@ impl App {
             AppEvent::UpdateAskForApprovalPolicy(policy) => {
                 self.chat_widget.set_approval_policy(policy);
             }
+            AppEvent::AddApprovalRule(rule) => {
+                let mut rules = self.config.approval_rules.clone();
+                if !rules.contains(&rule) {
+                    rules.push(rule.clone());
+                }
+                if self.save_approval_rules(rules).await {
+                    self.chat_widget.add_info_message(
+                        "Saved approval rule".to_string(),
+                        Some(approval_rule_label(&rule)),
+                    );
+                }
+            }
+            AppEvent::RemoveApprovalRule(rule) => {
+                let mut rules = self.config.approval_rules.clone();
+                rules.retain(|existing| existing != &rule);
+                if self.save_approval_rules(rules).await {
+                    self.chat_widget.add_info_message(
+                        "Removed approval rule".to_string(),
+                        Some(approval_rule_label(&rule)),
+                    );
+                }
+            }
             AppEvent::UpdateSandboxPolicy(policy) => {
                 #[cfg(target_os = "windows")]
                 let policy_is_workspace_write_or_ro = matches!(
@@ -719,6 +744,25 @@ impl App {
         Ok(true)
     }
 
+    /// Persist `rules` as the `[[approval_rules]]` list and apply them to the
+    /// running session. Returns `false` (after reporting the error) when the
+    /// config could not be written.
+    async fn save_approval_rules(&mut self, rules: Vec<ApprovalRule>) -> bool {
+        if let Err(err) = ConfigEditsBuilder::new(&self.config.codex_home)
+            .replace_approval_rules(&rules)
+            .apply()
+            .await
+        {
+            tracing::error!(error = %err, "failed to persist approval rules");
+            self.chat_widget
+                .add_error_message(format!("Failed to save approval rules: {err}"));
+            return false;
+        }
+        self.config.approval_rules = rules.clone();
+        self.chat_widget.set_approval_rules(rules);
+        true
+    }
+
     pub(crate) fn token_usage(&self) -> codex_core::protocol::TokenUsage {
         self.chat_widget.token_usage()
     }
@@ -9,6 +9,9 @@ use crate::pager_overlay::Overlay;
 use crate::tui;
 use crate::tui::TuiEvent;
 use codex_core::protocol::ConversationPathResponseEvent;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
 use codex_protocol::ConversationId;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
@@ -28,11 +31,14 @@ pub(crate) struct BacktrackState {
     pub(crate) overlay_preview_active: bool,
     /// Pending fork request: (base_id, nth_user_message, prefill).
     pub(crate) pending: Option<(ConversationId, usize, String)>,
+    /// Pending in-place rewind of code + chat: (nth_user_message, prefill).
+    pub(crate) pending_rewind: Option<(usize, String)>,
 }
 
 impl App {
     /// Route overlay events when transcript overlay is active.
-    /// - If backtrack preview is active: Esc steps selection; Enter confirms.
+    /// - If backtrack preview is active: Esc steps selection; Enter confirms;
+    ///   `r` rewinds code + chat to the selected message.
     /// - Otherwise: Esc begins preview; all other events forward to overlay.
     ///   interactions (Esc to step target, Enter to confirm) and overlay lifecycle.
     pub(crate) async fn handle_backtrack_overlay_event(
@@ -58,6 +64,14 @@ impl App {
                     self.overlay_confirm_backtrack(tui);
                     Ok(true)
                 }
+                TuiEvent::Key(KeyEvent {
+                    code: KeyCode::Char('r'),
+                    kind: KeyEventKind::Press,
+                    ..
+                }) if self.backtrack.nth_user_message != usize::MAX => {
+                    self.overlay_confirm_rewind(tui);
+                    Ok(true)
+                }
                 // Catchall: forward any other events to the overlay widget.
                 _ => {
                     self.overlay_forward_event(tui, event)?;
@@ -232,17 +246,60 @@ impl App {
     fn overlay_confirm_backtrack(&mut self, tui: &mut tui::Tui) {
         let nth_user_message = self.backtrack.nth_user_message;
         if let Some(base_id) = self.backtrack.base_id {
-            let prefill = nth_user_position(&self.transcript_cells, nth_user_message)
-                .and_then(|idx| self.transcript_cells.get(idx))
-                .and_then(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())
-                .map(|c| c.message.clone())
-                .unwrap_or_default();
+            let prefill = self.backtrack_prefill(nth_user_message);
             self.close_transcript_overlay(tui);
             self.request_backtrack(prefill, base_id, nth_user_message);
         }
         self.reset_backtrack_state();
     }
 
+    /// Handle `r` in overlay backtrack preview: rewind the working tree and the
+    /// conversation to the selected message in place, instead of forking.
+    fn overlay_confirm_rewind(&mut self, tui: &mut tui::Tui) {
+        let nth_user_message = self.backtrack.nth_user_message;
+        if self.backtrack.base_id.is_some() {
+            let prefill = self.backtrack_prefill(nth_user_message);
+            self.close_transcript_overlay(tui);
+            self.backtrack.pending_rewind = Some((nth_user_message, prefill));
+            self.chat_widget.submit_op(Op::RestoreCheckpoint {
+                turn: nth_user_message,
+            });
+        }
+        self.reset_backtrack_state();
+    }
+
+    /// Trim the transcript once the agent confirms a pending rewind. A failed
+    /// rewind leaves the transcript untouched; the agent reports why.
+    pub(crate) fn on_codex_event_for_rewind(&mut self, tui: &mut tui::Tui, event: &Event) {
+        match &event.msg {
+            EventMsg::CheckpointRestored(ev) => {
+                if let Some((nth_user_message, prefill)) = self.backtrack.pending_rewind.take()
+                    && nth_user_message == ev.turn
+                {
+                    self.trim_transcript_for_backtrack(nth_user_message);
+                    self.render_transcript_once(tui);
+                    if !prefill.is_empty() {
+                        self.chat_widget.set_composer_text(prefill);
+                    }
+                    tui.frame_requester().schedule_frame();
+                }
+            }
+            EventMsg::UndoCompleted(_) => {
+                self.backtrack.pending_rewind = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Text of the selected user message, used to prefill the composer.
+    fn backtrack_prefill(&self, nth_user_message: usize) -> String {
+        nth_user_position(&self.transcript_cells, nth_user_message)
+            .and_then(|idx| self.transcript_cells.get(idx))
+            .and_then(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())
+            .map(|c| c.message.clone())
+            .unwrap_or_default()
+    }
+
     /// Handle Esc in overlay backtrack preview: step selection if armed, else forward.
     fn overlay_step_backtrack(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         if self.backtrack.base_id.is_some() {
@@ -257,12 +314,7 @@ impl App {
     /// Computes the prefill from the selected user message and requests history.
     pub(crate) fn confirm_backtrack_from_main(&mut self) {
         if let Some(base_id) = self.backtrack.base_id {
-            let prefill =
-                nth_user_position(&self.transcript_cells, self.backtrack.nth_user_message)
-                    .and_then(|idx| self.transcript_cells.get(idx))
-                    .and_then(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())
-                    .map(|c| c.message.clone())
-                    .unwrap_or_default();
+            let prefill = self.backtrack_prefill(self.backtrack.nth_user_message);
             self.request_backtrack(prefill, base_id, self.backtrack.nth_user_message);
         }
         self.reset_backtrack_state();
@@ -11,6 +11,7 @@ use crate::history_cell::HistoryCell;
 
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol_config_types::ApprovalRule;
 use codex_core::protocol_config_types::ReasoningEffort;
 
 #[allow(clippy::large_enum_variant)]
@@ -94,6 +95,12 @@ pub(crate) enum AppEvent {
     /// Update the current approval policy in the running app and widget.
     UpdateAskForApprovalPolicy(AskForApproval),
 
+    /// Save a standing approval rule and apply it to the running session.
+    AddApprovalRule(ApprovalRule),
+
+    /// Delete a saved approval rule and stop applying it to the running session.
+    RemoveApprovalRule(ApprovalRule),
+
     /// Update the current sandbox policy in the running app and widget.
     UpdateSandboxPolicy(SandboxPolicy),
 
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 use crate::app_event::AppEvent;
@@ -16,11 +17,13 @@ use crate::key_hint::KeyBinding;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
+use codex_core::approval_rules::suggested_prefix;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SandboxCommandAssessment;
 use codex_core::protocol::SandboxRiskLevel;
+use codex_core::protocol_config_types::ApprovalRule;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
@@ -41,6 +44,8 @@ pub(crate) enum ApprovalRequest {
         command: Vec<String>,
         reason: Option<String>,
         risk: Option<SandboxCommandAssessment>,
+        env_profile: Option<String>,
+        cwd: PathBuf,
     },
     ApplyPatch {
         id: String,
@@ -97,8 +102,13 @@ impl ApprovalOverlay {
         header: Box<dyn Renderable>,
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
         let (options, title) = match &variant {
-            ApprovalVariant::Exec { .. } => (
-                exec_options(),
+            ApprovalVariant::Exec {
+                command,
+                cwd,
+                high_risk,
+                ..
+            } => (
+                exec_options(command, cwd, *high_risk),
                 "Would you like to run the following command?".to_string(),
             ),
             ApprovalVariant::ApplyPatch { .. } => (
@@ -150,7 +160,10 @@ impl ApprovalOverlay {
         };
         if let Some(variant) = self.current_variant.as_ref() {
             match (&variant, option.decision) {
-                (ApprovalVariant::Exec { id, command }, decision) => {
+                (ApprovalVariant::Exec { id, command, .. }, decision) => {
+                    if let Some(rule) = option.rule.clone() {
+                        self.app_event_tx.send(AppEvent::AddApprovalRule(rule));
+                    }
                     self.handle_exec_decision(id, command, decision);
                 }
                 (ApprovalVariant::ApplyPatch { id, .. }, decision) => {
@@ -238,7 +251,7 @@ impl BottomPaneView for ApprovalOverlay {
             && let Some(variant) = self.current_variant.as_ref()
         {
             match &variant {
-                ApprovalVariant::Exec { id, command } => {
+                ApprovalVariant::Exec { id, command, .. } => {
                     self.handle_exec_decision(id, command, ReviewDecision::Abort);
                 }
                 ApprovalVariant::ApplyPatch { id, .. } => {
@@ -291,16 +304,24 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 command,
                 reason,
                 risk,
+                env_profile,
+                cwd,
             } => {
                 let reason = reason.filter(|item| !item.is_empty());
-                let has_reason = reason.is_some();
+                let has_context = reason.is_some() || env_profile.is_some();
                 let mut header: Vec<Line<'static>> = Vec::new();
                 if let Some(reason) = reason {
                     header.push(Line::from(vec!["Reason: ".into(), reason.italic()]));
                 }
+                if let Some(env_profile) = env_profile {
+                    header.push(Line::from(vec![
+                        "Environment profile: ".into(),
+                        env_profile.bold(),
+                    ]));
+                }
                 if let Some(risk) = risk.as_ref() {
                     header.extend(render_risk_lines(risk));
-                } else if has_reason {
+                } else if has_context {
                     header.push(Line::from(""));
                 }
                 let full_cmd = strip_bash_lc_and_escape(&command);
@@ -309,8 +330,16 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     first.spans.insert(0, Span::from("$ "));
                 }
                 header.extend(full_cmd_lines);
+                let high_risk = risk
+                    .as_ref()
+                    .is_some_and(|risk| risk.risk_level == SandboxRiskLevel::High);
                 Self {
-                    variant: ApprovalVariant::Exec { id, command },
+                    variant: ApprovalVariant::Exec {
+                        id,
+                        command,
+                        cwd,
+                        high_risk,
+                    },
                     header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
                 }
             }
@@ -364,8 +393,17 @@ fn render_risk_lines(risk: &SandboxCommandAssessment) -> Vec<Line<'static>> {
 
 #[derive(Clone)]
 enum ApprovalVariant {
-    Exec { id: String, command: Vec<String> },
-    ApplyPatch { id: String },
+    Exec {
+        id: String,
+        command: Vec<String>,
+        cwd: PathBuf,
+        /// The risk assessment rated the command high risk, so no standing
+        /// approval is offered for it.
+        high_risk: bool,
+    },
+    ApplyPatch {
+        id: String,
+    },
 }
 
 #[derive(Clone)]
@@ -374,6 +412,8 @@ struct ApprovalOption {
     decision: ReviewDecision,
     display_shortcut: Option<KeyBinding>,
     additional_shortcuts: Vec<KeyBinding>,
+    /// Standing approval saved to `[[approval_rules]]` when this option is chosen.
+    rule: Option<ApprovalRule>,
 }
 
 impl ApprovalOption {
@@ -384,27 +424,60 @@ impl ApprovalOption {
     }
 }
 
-fn exec_options() -> Vec<ApprovalOption> {
-    vec![
+/// Options for an exec approval. "Always allow" options are offered only for
+/// commands with a suggested prefix, which excludes dangerous commands and
+/// launchers, and never for commands assessed as high risk.
+fn exec_options(command: &[String], cwd: &Path, high_risk: bool) -> Vec<ApprovalOption> {
+    let mut options = vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
             decision: ReviewDecision::Approved,
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+            rule: None,
         },
         ApprovalOption {
             label: "Yes, and don't ask again for this command".to_string(),
             decision: ReviewDecision::ApprovedForSession,
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+            rule: None,
         },
-        ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
-            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
-        },
-    ]
+    ];
+    if !high_risk && let Some(prefix) = suggested_prefix(command) {
+        let shown =
+            shlex::try_join(prefix.iter().map(String::as_str)).unwrap_or_else(|_| prefix.join(" "));
+        options.push(ApprovalOption {
+            label: format!("Yes, and always allow commands starting with `{shown}`"),
+            decision: ReviewDecision::Approved,
+            display_shortcut: None,
+            additional_shortcuts: Vec::new(),
+            rule: Some(ApprovalRule {
+                prefix: Some(prefix.clone()),
+                project: None,
+            }),
+        });
+        options.push(ApprovalOption {
+            label: format!(
+                "Yes, and always allow commands starting with `{shown}` in this project"
+            ),
+            decision: ReviewDecision::Approved,
+            display_shortcut: None,
+            additional_shortcuts: Vec::new(),
+            rule: Some(ApprovalRule {
+                prefix: Some(prefix),
+                project: Some(cwd.to_path_buf()),
+            }),
+        });
+    }
+    options.push(ApprovalOption {
+        label: "No, and tell Codex what to do differently".to_string(),
+        decision: ReviewDecision::Abort,
+        display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+        rule: None,
+    });
+    options
 }
 
 fn patch_options() -> Vec<ApprovalOption> {
@@ -414,12 +487,14 @@ fn patch_options() -> Vec<ApprovalOption> {
             decision: ReviewDecision::Approved,
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+            rule: None,
         },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
             decision: ReviewDecision::Abort,
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+            rule: None,
         },
     ]
 }
@@ -437,6 +512,8 @@ mod tests {
             command: vec!["echo".to_string(), "hi".to_string()],
             reason: Some("reason".to_string()),
             risk: None,
+            env_profile: None,
+            cwd: PathBuf::from("/repo"),
         }
     }
 
@@ -479,6 +556,8 @@ mod tests {
             command,
             reason: None,
             risk: None,
+            env_profile: None,
+            cwd: PathBuf::from("/repo"),
         };
 
         let view = ApprovalOverlay::new(exec_request, tx);
@@ -500,6 +579,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn header_names_the_environment_profile() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec_request = ApprovalRequest::Exec {
+            id: "test".into(),
+            command: vec!["gh".into(), "release".into(), "list".into()],
+            reason: None,
+            risk: None,
+            env_profile: Some("github".to_string()),
+            cwd: PathBuf::from("/repo"),
+        };
+
+        let view = ApprovalOverlay::new(exec_request, tx);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
+        view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
+
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        assert!(
+            rendered
+                .iter()
+                .any(|line| line.contains("Environment profile: github")),
+            "expected header to name the environment profile, got {rendered:?}"
+        );
+    }
+
     #[test]
     fn exec_history_cell_wraps_with_two_space_indent() {
         let command = vec![
@@ -527,6 +638,91 @@ mod tests {
         assert_eq!(rendered, expected);
     }
 
+    #[test]
+    fn always_allow_prefix_adds_rule_and_approves() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut rule = None;
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                AppEvent::AddApprovalRule(added) => rule = Some(added),
+                AppEvent::CodexOp(Op::ExecApproval { decision: d, .. }) => decision = Some(d),
+                _ => {}
+            }
+        }
+        assert_eq!(
+            rule,
+            Some(ApprovalRule {
+                prefix: Some(vec!["echo".to_string()]),
+                project: None,
+            })
+        );
+        assert_eq!(decision, Some(ReviewDecision::Approved));
+    }
+
+    #[test]
+    fn always_allow_in_project_is_scoped_to_the_prefix() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        for _ in 0..3 {
+            view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        }
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut rule = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::AddApprovalRule(added) = ev {
+                rule = Some(added);
+            }
+        }
+        assert_eq!(
+            rule,
+            Some(ApprovalRule {
+                prefix: Some(vec!["echo".to_string()]),
+                project: Some(PathBuf::from("/repo")),
+            })
+        );
+    }
+
+    #[test]
+    fn no_standing_approval_for_dangerous_or_high_risk_commands() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let dangerous = ApprovalRequest::Exec {
+            id: "test".to_string(),
+            command: vec!["rm".to_string(), "-rf".to_string(), "build".to_string()],
+            reason: None,
+            risk: None,
+            env_profile: None,
+            cwd: PathBuf::from("/repo"),
+        };
+        let high_risk = ApprovalRequest::Exec {
+            id: "test".to_string(),
+            command: vec!["echo".to_string(), "hi".to_string()],
+            reason: None,
+            risk: Some(SandboxCommandAssessment {
+                description: "prints".to_string(),
+                risk_level: SandboxRiskLevel::High,
+            }),
+            env_profile: None,
+            cwd: PathBuf::from("/repo"),
+        };
+        for request in [dangerous, high_risk] {
+            let view = ApprovalOverlay::new(request, tx.clone());
+            assert!(
+                view.options.iter().all(|option| option.rule.is_none()),
+                "unexpected always-allow option"
+            );
+        }
+    }
+
     #[test]
     fn enter_sets_last_selected_index_without_dismissing() {
         let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
@@ -112,6 +112,7 @@ pub(crate) struct ChatComposer {
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<i64>,
+    secrets_redacted: u64,
 }
 
 /// Popup state – at most one can be visible at any time.
@@ -155,6 +156,7 @@ impl ChatComposer {
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             context_window_percent: None,
+            secrets_redacted: 0,
         };
         // Apply configuration via the setter to keep side-effects centralized.
         this.set_disable_paste_burst(disable_paste_burst);
@@ -1392,6 +1394,7 @@ impl ChatComposer {
             use_shift_enter_hint: self.use_shift_enter_hint,
             is_task_running: self.is_task_running,
             context_window_percent: self.context_window_percent,
+            secrets_redacted: self.secrets_redacted,
         }
     }
 
@@ -1528,6 +1531,10 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_secrets_redacted(&mut self, total: u64) {
+        self.secrets_redacted = total;
+    }
+
     pub(crate) fn set_esc_backtrack_hint(&mut self, show: bool) {
         self.esc_backtrack_hint = show;
         if show {
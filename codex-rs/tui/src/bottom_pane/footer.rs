@@ -18,6 +18,7 @@ pub(crate) struct FooterProps {
     pub(crate) use_shift_enter_hint: bool,
     pub(crate) is_task_running: bool,
     pub(crate) context_window_percent: Option<i64>,
+    pub(crate) secrets_redacted: u64,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -81,7 +82,8 @@ fn footer_lines(props: FooterProps) -> Vec<Line<'static>> {
             is_task_running: props.is_task_running,
         })],
         FooterMode::ShortcutSummary => {
-            let mut line = context_window_line(props.context_window_percent);
+            let mut line =
+                context_window_line(props.context_window_percent, props.secrets_redacted);
             line.push_span(" · ".dim());
             line.extend(vec![
                 key_hint::plain(KeyCode::Char('?')).into(),
@@ -94,7 +96,10 @@ fn footer_lines(props: FooterProps) -> Vec<Line<'static>> {
             esc_backtrack_hint: props.esc_backtrack_hint,
         }),
         FooterMode::EscHint => vec![esc_hint_line(props.esc_backtrack_hint)],
-        FooterMode::ContextOnly => vec![context_window_line(props.context_window_percent)],
+        FooterMode::ContextOnly => vec![context_window_line(
+            props.context_window_percent,
+            props.secrets_redacted,
+        )],
     }
 }
 
@@ -221,9 +226,19 @@ fn build_columns(entries: Vec<Line<'static>>) -> Vec<Line<'static>> {
         .collect()
 }
 
-fn context_window_line(percent: Option<i64>) -> Line<'static> {
+fn context_window_line(percent: Option<i64>, secrets_redacted: u64) -> Line<'static> {
     let percent = percent.unwrap_or(100).clamp(0, 100);
-    Line::from(vec![Span::from(format!("{percent}% context left")).dim()])
+    let mut line = Line::from(vec![Span::from(format!("{percent}% context left")).dim()]);
+    if secrets_redacted > 0 {
+        let noun = if secrets_redacted == 1 {
+            "secret"
+        } else {
+            "secrets"
+        };
+        line.push_span(" · ".dim());
+        line.push_span(format!("{secrets_redacted} {noun} masked").dim());
+    }
+    line
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -400,6 +415,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                secrets_redacted: 0,
             },
         );
 
@@ -411,6 +427,7 @@ mod tests {
                 use_shift_enter_hint: true,
                 is_task_running: false,
                 context_window_percent: None,
+                secrets_redacted: 0,
             },
         );
 
@@ -422,6 +439,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                secrets_redacted: 0,
             },
         );
 
@@ -433,6 +451,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: true,
                 context_window_percent: None,
+                secrets_redacted: 0,
             },
         );
 
@@ -444,6 +463,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                secrets_redacted: 0,
             },
         );
 
@@ -455,6 +475,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                secrets_redacted: 0,
             },
         );
 
@@ -466,6 +487,19 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: true,
                 context_window_percent: Some(72),
+                secrets_redacted: 0,
+            },
+        );
+
+        snapshot_footer(
+            "footer_shortcuts_secrets_redacted",
+            FooterProps {
+                mode: FooterMode::ShortcutSummary,
+                esc_backtrack_hint: false,
+                use_shift_enter_hint: false,
+                is_task_running: true,
+                context_window_percent: Some(72),
+                secrets_redacted: 5,
             },
         );
     }
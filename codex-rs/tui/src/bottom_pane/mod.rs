@@ -333,6 +333,12 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    /// Update the session's count of masked secrets shown in the footer.
+    pub(crate) fn set_secrets_redacted(&mut self, total: u64) {
+        self.composer.set_secrets_redacted(total);
+        self.request_redraw();
+    }
+
     /// Show a generic list selection view with the provided items.
     pub(crate) fn show_selection_view(&mut self, params: list_selection_view::SelectionViewParams) {
         let view = list_selection_view::ListSelectionView::new(params, self.app_event_tx.clone());
@@ -532,6 +538,8 @@ mod tests {
             command: vec!["echo".into(), "ok".into()],
             reason: None,
             risk: None,
+            env_profile: None,
+            cwd: PathBuf::from("/repo"),
         }
     }
 
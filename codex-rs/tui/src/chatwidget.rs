@@ -5,8 +5,10 @@ use std::sync::Arc;
 
 use codex_core::config::Config;
 use codex_core::config::types::Notifications;
+use codex_core::features::Feature;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
+use codex_core::memory::MemoryStore;
 use codex_core::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
@@ -33,6 +35,8 @@ use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::ReviewTarget;
+use codex_core::protocol::SecretsRedactedEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
@@ -46,6 +50,7 @@ use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::sandboxing::read_deny::ReadDenyList;
 use codex_protocol::ConversationId;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::user_input::UserInput;
@@ -119,6 +124,7 @@ use codex_core::AuthManager;
 use codex_core::ConversationManager;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol_config_types::ApprovalRule;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_file_search::FileMatch;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -579,10 +585,18 @@ impl ChatWidget {
         // Finalize, log a gentle prompt, and clear running state.
         self.finalize_turn();
 
-        if reason != TurnAbortReason::ReviewEnded {
-            self.add_to_history(history_cell::new_error_event(
-                "Conversation interrupted - tell the model what to do differently. Something went wrong? Hit `/feedback` to report the issue.".to_owned(),
-            ));
+        match reason {
+            TurnAbortReason::ReviewEnded => {}
+            TurnAbortReason::BudgetExceeded => {
+                self.add_to_history(history_cell::new_error_event(
+                    "Turn stopped: budget exceeded. Raise the limits under [budget] in config.toml to continue.".to_owned(),
+                ));
+            }
+            TurnAbortReason::Interrupted | TurnAbortReason::Replaced => {
+                self.add_to_history(history_cell::new_error_event(
+                    "Conversation interrupted - tell the model what to do differently. Something went wrong? Hit `/feedback` to report the issue.".to_owned(),
+                ));
+            }
         }
 
         // If any messages were queued during the task, restore them into the composer.
@@ -652,6 +666,10 @@ impl ChatWidget {
         ));
     }
 
+    fn on_secrets_redacted(&mut self, event: SecretsRedactedEvent) {
+        self.bottom_pane.set_secrets_redacted(event.session_total);
+    }
+
     fn on_view_image_tool_call(&mut self, event: ViewImageToolCallEvent) {
         self.flush_answer_stream_with_separator();
         self.add_to_history(history_cell::new_view_image_tool_call(
@@ -911,6 +929,8 @@ impl ChatWidget {
             command: ev.command,
             reason: ev.reason,
             risk: ev.risk,
+            env_profile: ev.env_profile,
+            cwd: ev.cwd,
         };
         self.bottom_pane.push_approval_request(request);
         self.request_redraw();
@@ -1281,6 +1301,9 @@ impl ChatWidget {
             SlashCommand::Approvals => {
                 self.open_approvals_popup();
             }
+            SlashCommand::Permissions => {
+                self.open_permissions_popup();
+            }
             SlashCommand::Quit | SlashCommand::Exit => {
                 self.request_exit();
             }
@@ -1322,6 +1345,9 @@ impl ChatWidget {
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
+            SlashCommand::Memory => {
+                self.add_memory_output();
+            }
             SlashCommand::Rollout => {
                 if let Some(path) = self.rollout_path() {
                     self.add_info_message(
@@ -1550,6 +1576,9 @@ impl ChatWidget {
                 TurnAbortReason::ReviewEnded => {
                     self.on_interrupted_turn(ev.reason);
                 }
+                TurnAbortReason::BudgetExceeded => {
+                    self.on_interrupted_turn(ev.reason);
+                }
             },
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
             EventMsg::ExecApprovalRequest(ev) => {
@@ -1564,6 +1593,11 @@ impl ChatWidget {
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
             EventMsg::PatchApplyEnd(ev) => self.on_patch_apply_end(ev),
             EventMsg::ExecCommandEnd(ev) => self.on_exec_command_end(ev),
+            EventMsg::NetworkRequestDenied(ev) => self.on_warning(format!(
+                "Blocked network request to {}:{} because it is not in the sandbox network allowlist.",
+                ev.host, ev.port
+            )),
+            EventMsg::SecretsRedacted(ev) => self.on_secrets_redacted(ev),
             EventMsg::ViewImageToolCall(ev) => self.on_view_image_tool_call(ev),
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
@@ -1591,6 +1625,7 @@ impl ChatWidget {
             }
             EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(review),
             EventMsg::RawResponseItem(_)
+            | EventMsg::CheckpointRestored(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::ItemCompleted(_)
             | EventMsg::AgentMessageContentDelta(_)
@@ -1602,7 +1637,7 @@ impl ChatWidget {
     fn on_entered_review_mode(&mut self, review: ReviewRequest) {
         // Enter review mode and emit a concise banner
         self.is_review_mode = true;
-        let banner = format!(">> Code review started: {} <<", review.user_facing_hint);
+        let banner = format!(">> Code review started: {} <<", review.hint());
         self.add_to_history(history_cell::new_review_status_line(banner));
         self.request_redraw();
     }
@@ -1886,6 +1921,46 @@ impl ChatWidget {
         });
     }
 
+    /// Open a popup listing the saved approval rules; selecting one removes it.
+    pub(crate) fn open_permissions_popup(&mut self) {
+        if self.config.approval_rules.is_empty() {
+            self.add_info_message(
+                "No approval rules saved".to_string(),
+                Some(
+                    "Choose an \"always allow\" option when approving a command to add one."
+                        .to_string(),
+                ),
+            );
+            return;
+        }
+
+        let items: Vec<SelectionItem> = self
+            .config
+            .approval_rules
+            .iter()
+            .map(|rule| {
+                let rule_for_action = rule.clone();
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::RemoveApprovalRule(rule_for_action.clone()));
+                })];
+                SelectionItem {
+                    name: approval_rule_label(rule),
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Approval Rules".to_string()),
+            subtitle: Some("Commands matching these rules run without asking.".to_string()),
+            footer_hint: Some("Press enter to remove the selected rule, or esc to dismiss.".into()),
+            items,
+            ..Default::default()
+        });
+    }
+
     /// Open a popup to choose the reasoning effort (stage 2) for the given model.
     pub(crate) fn open_reasoning_popup(&mut self, preset: ModelPreset) {
         let default_effort: ReasoningEffortConfig = preset.default_reasoning_effort;
@@ -2438,6 +2513,13 @@ impl ChatWidget {
         self.config.approval_policy = policy;
     }
 
+    /// Replace the approval rules in the widget's config copy and in the
+    /// running session.
+    pub(crate) fn set_approval_rules(&mut self, rules: Vec<ApprovalRule>) {
+        self.config.approval_rules = rules.clone();
+        self.submit_op(Op::SetApprovalRules { rules });
+    }
+
     /// Set the sandbox policy in the widget's config copy.
     pub(crate) fn set_sandbox_policy(&mut self, policy: SandboxPolicy) {
         self.config.sandbox_policy = policy;
@@ -2500,8 +2582,21 @@ impl ChatWidget {
         }
     }
 
-    /// Forward file-search results to the bottom pane.
-    pub(crate) fn apply_file_search_result(&mut self, query: String, matches: Vec<FileMatch>) {
+    pub(crate) fn add_memory_output(&mut self) {
+        let store = MemoryStore::new(&self.config.codex_home, &self.config.cwd);
+        let enabled = self.config.features.enabled(Feature::Memory);
+        match history_cell::new_memory_output(&store, enabled) {
+            Ok(cell) => self.add_to_history(cell),
+            Err(e) => self.add_error_message(format!("Failed to read memories: {e}")),
+        }
+    }
+
+    /// Forward file-search results to the bottom pane, leaving out files the
+    /// sandbox policy does not allow reading so they cannot be `@`-mentioned.
+    pub(crate) fn apply_file_search_result(&mut self, query: String, mut matches: Vec<FileMatch>) {
+        let read_deny =
+            ReadDenyList::new(&self.config.sandbox_policy, &self.config.sandbox_read_deny);
+        matches.retain(|file_match| !read_deny.is_denied(&self.config.cwd.join(&file_match.path)));
         self.bottom_pane.on_file_search_result(query, matches);
     }
 
@@ -2591,16 +2686,11 @@ impl ChatWidget {
 
         items.push(SelectionItem {
             name: "Review uncommitted changes".to_string(),
-            actions: vec![Box::new(
-                move |tx: &AppEventSender| {
-                    tx.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
-                            user_facing_hint: "current changes".to_string(),
-                        },
-                    }));
-                },
-            )],
+            actions: vec![Box::new(move |tx: &AppEventSender| {
+                tx.send(AppEvent::CodexOp(Op::Review {
+                    review_request: ReviewRequest::new(ReviewTarget::UncommittedChanges),
+                }));
+            })],
             dismiss_on_select: true,
             ..Default::default()
         });
@@ -2648,12 +2738,9 @@ impl ChatWidget {
                 name: format!("{current_branch} -> {branch}"),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: format!(
-                                "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
-                            ),
-                            user_facing_hint: format!("changes against '{branch}'"),
-                        },
+                        review_request: ReviewRequest::new(ReviewTarget::BaseBranch {
+                            branch: branch.clone(),
+                        }),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2679,21 +2766,16 @@ impl ChatWidget {
         for entry in commits {
             let subject = entry.subject.clone();
             let sha = entry.sha.clone();
-            let short = sha.chars().take(7).collect::<String>();
             let search_val = format!("{subject} {sha}");
 
             items.push(SelectionItem {
                 name: subject.clone(),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
-                    let hint = format!("commit {short}");
-                    let prompt = format!(
-                        "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                    );
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt,
-                            user_facing_hint: hint,
-                        },
+                        review_request: ReviewRequest::new(ReviewTarget::Commit {
+                            sha: sha.clone(),
+                            title: Some(subject.clone()),
+                        }),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2724,10 +2806,9 @@ impl ChatWidget {
                     return;
                 }
                 tx.send(AppEvent::CodexOp(Op::Review {
-                    review_request: ReviewRequest {
-                        prompt: trimmed.clone(),
-                        user_facing_hint: trimmed,
-                    },
+                    review_request: ReviewRequest::new(ReviewTarget::Custom {
+                        instructions: trimmed,
+                    }),
                 }));
             }),
         );
@@ -2862,6 +2943,21 @@ const EXAMPLE_PROMPTS: [&str; 6] = [
     "Improve documentation in @filename",
 ];
 
+/// Human-readable summary of an `[[approval_rules]]` entry.
+pub(crate) fn approval_rule_label(rule: &ApprovalRule) -> String {
+    let prefix = rule.prefix.as_ref().map(|prefix| {
+        shlex::try_join(prefix.iter().map(String::as_str)).unwrap_or_else(|_| prefix.join(" "))
+    });
+    match (prefix, &rule.project) {
+        (Some(prefix), Some(project)) => {
+            format!("Commands starting with `{prefix}` in {}", project.display())
+        }
+        (Some(prefix), None) => format!("Commands starting with `{prefix}`"),
+        (None, Some(project)) => format!("Commands in {}", project.display()),
+        (None, None) => "Empty rule (matches nothing)".to_string(),
+    }
+}
+
 // Extract the first bold (Markdown) element in the form **...** from `s`.
 // Returns the inner text if found; otherwise `None`.
 fn extract_first_bold(s: &str) -> Option<String> {
@@ -2901,21 +2997,16 @@ pub(crate) fn show_review_commit_picker_with_entries(
     for entry in entries {
         let subject = entry.subject.clone();
         let sha = entry.sha.clone();
-        let short = sha.chars().take(7).collect::<String>();
         let search_val = format!("{subject} {sha}");
 
         items.push(SelectionItem {
             name: subject.clone(),
             actions: vec![Box::new(move |tx3: &AppEventSender| {
-                let hint = format!("commit {short}");
-                let prompt = format!(
-                    "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                );
                 tx3.send(AppEvent::CodexOp(Op::Review {
-                    review_request: ReviewRequest {
-                        prompt,
-                        user_facing_hint: hint,
-                    },
+                    review_request: ReviewRequest::new(ReviewTarget::Commit {
+                        sha: sha.clone(),
+                        title: Some(subject.clone()),
+                    }),
                 }));
             })],
             dismiss_on_select: true,
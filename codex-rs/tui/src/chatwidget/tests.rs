@@ -36,6 +36,8 @@ use codex_core::protocol::ReviewFinding;
 use codex_core::protocol::ReviewLineRange;
 use codex_core::protocol::ReviewOutputEvent;
 use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::ReviewTarget;
+use codex_core::protocol::SecretsRedactedEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TaskStartedEvent;
@@ -208,8 +210,10 @@ fn entered_review_mode_uses_request_hint() {
     chat.handle_codex_event(Event {
         id: "review-start".into(),
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
-            prompt: "Review the latest changes".to_string(),
-            user_facing_hint: "feature branch".to_string(),
+            target: ReviewTarget::BaseBranch {
+                branch: "main".to_string(),
+            },
+            user_facing_hint: Some("feature branch".to_string()),
         }),
     });
 
@@ -219,17 +223,14 @@ fn entered_review_mode_uses_request_hint() {
     assert!(chat.is_review_mode);
 }
 
-/// Entering review mode renders the current changes banner when requested.
+/// Without a hint, the banner describes the review target.
 #[test]
 fn entered_review_mode_defaults_to_current_changes_banner() {
     let (mut chat, mut rx, _ops) = make_chatwidget_manual();
 
     chat.handle_codex_event(Event {
         id: "review-start".into(),
-        msg: EventMsg::EnteredReviewMode(ReviewRequest {
-            prompt: "Review the current changes".to_string(),
-            user_facing_hint: "current changes".to_string(),
-        }),
+        msg: EventMsg::EnteredReviewMode(ReviewRequest::new(ReviewTarget::UncommittedChanges)),
     });
 
     let cells = drain_insert_history(&mut rx);
@@ -548,6 +549,7 @@ fn exec_approval_emits_proposed_command_and_decision_history() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         risk: None,
+        env_profile: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -591,6 +593,7 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         risk: None,
+        env_profile: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -640,6 +643,7 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
         risk: None,
+        env_profile: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -705,6 +709,7 @@ fn end_exec(chat: &mut ChatWidget, call_id: &str, stdout: &str, stderr: &str, ex
             exit_code,
             duration: std::time::Duration::from_millis(5),
             formatted_output: aggregated,
+            resource_limit_exceeded: None,
         }),
     });
 }
@@ -1254,8 +1259,8 @@ fn review_commit_picker_shows_subjects_without_timestamps() {
     );
 }
 
-/// Submitting the custom prompt view sends Op::Review with the typed prompt
-/// and uses the same text for the user-facing hint.
+/// Submitting the custom prompt view sends Op::Review with the typed
+/// instructions as a custom target, which is also its user-facing hint.
 #[test]
 fn custom_prompt_submit_sends_review_op() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -1270,13 +1275,12 @@ fn custom_prompt_submit_sends_review_op() {
     match evt {
         AppEvent::CodexOp(Op::Review { review_request }) => {
             assert_eq!(
-                review_request.prompt,
-                "please audit dependencies".to_string()
-            );
-            assert_eq!(
-                review_request.user_facing_hint,
-                "please audit dependencies".to_string()
+                review_request.target,
+                ReviewTarget::Custom {
+                    instructions: "please audit dependencies".to_string(),
+                }
             );
+            assert_eq!(review_request.hint(), "please audit dependencies");
         }
         other => panic!("unexpected app event: {other:?}"),
     }
@@ -1907,6 +1911,7 @@ fn approval_modal_exec_snapshot() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         risk: None,
+        env_profile: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -1952,6 +1957,7 @@ fn approval_modal_exec_without_reason_snapshot() {
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
         risk: None,
+        env_profile: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -2163,6 +2169,7 @@ fn status_widget_and_approval_modal_snapshot() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         risk: None,
+        env_profile: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -2705,6 +2712,32 @@ fn warning_event_adds_warning_history_cell() {
     );
 }
 
+#[test]
+fn secrets_redacted_event_updates_footer_counter() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::SecretsRedacted(SecretsRedactedEvent {
+            call_id: "call-1".to_string(),
+            count: 2,
+            session_total: 5,
+        }),
+    });
+
+    assert!(
+        drain_insert_history(&mut rx).is_empty(),
+        "redactions should not add history cells"
+    );
+    let area = Rect::new(0, 0, 80, chat.desired_height(80));
+    let mut buf = ratatui::buffer::Buffer::empty(area);
+    chat.render(area, &mut buf);
+    let rendered: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(
+        rendered.contains("5 secrets masked"),
+        "footer missing redaction counter: {rendered}"
+    );
+}
+
 #[test]
 fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -2890,6 +2923,7 @@ fn chatwidget_exec_and_status_layout_vt100_snapshot() {
             exit_code: 0,
             duration: std::time::Duration::from_millis(16000),
             formatted_output: String::new(),
+            resource_limit_exceeded: None,
         }),
     });
     chat.handle_codex_event(Event {
@@ -26,6 +26,8 @@ use codex_common::format_env_display::format_env_display;
 use codex_core::config::Config;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::config::types::ReasoningSummaryFormat;
+use codex_core::memory::MemoryScope;
+use codex_core::memory::MemoryStore;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
@@ -1238,6 +1240,58 @@ pub(crate) fn new_mcp_tools_output(
 
     PlainHistoryCell { lines }
 }
+/// Render the saved memories for both scopes together with the files that
+/// hold them so users know where to edit them.
+pub(crate) fn new_memory_output(
+    store: &MemoryStore,
+    enabled: bool,
+) -> std::io::Result<PlainHistoryCell> {
+    let mut lines: Vec<Line<'static>> = vec![
+        "/memory".magenta().into(),
+        "".into(),
+        vec!["🧠  ".into(), "Memories".bold()].into(),
+        "".into(),
+    ];
+
+    if !enabled {
+        lines.push(
+            "  • Memory is disabled; set `memory = true` under [features] to use it."
+                .italic()
+                .into(),
+        );
+        lines.push("".into());
+    }
+
+    for (label, scope) in [
+        ("Project", MemoryScope::Project),
+        ("Global", MemoryScope::Global),
+    ] {
+        let path = relativize_to_home(store.path(scope))
+            .map(|path| format!("~/{}", path.display()))
+            .unwrap_or_else(|| store.path(scope).display().to_string());
+        lines.push(
+            vec![
+                "  • ".into(),
+                label.bold(),
+                " ".into(),
+                format!("({path})").dim(),
+            ]
+            .into(),
+        );
+
+        let memories = store.list(scope)?;
+        if memories.is_empty() {
+            lines.push("    (none)".dim().into());
+        }
+        for memory in memories {
+            lines.push(vec!["    - ".into(), memory.text.into()].into());
+        }
+        lines.push("".into());
+    }
+
+    Ok(PlainHistoryCell { lines })
+}
+
 pub(crate) fn new_info_event(message: String, hint: Option<String>) -> PlainHistoryCell {
     let mut line = vec!["• ".dim(), message.into()];
     if let Some(hint) = hint {
@@ -61,6 +61,7 @@ pub mod public_widgets;
 mod render;
 mod resume_picker;
 mod selection_list;
+mod session_export;
 mod session_log;
 mod shimmer;
 mod slash_command;
@@ -91,6 +92,8 @@ pub use cli::Cli;
 pub use markdown_render::render_markdown_text;
 pub use public_widgets::composer_input::ComposerAction;
 pub use public_widgets::composer_input::ComposerInput;
+pub use session_export::ExportFormat;
+pub use session_export::export_rollout;
 use std::io::Write as _;
 
 // (tests access modules directly within the crate)
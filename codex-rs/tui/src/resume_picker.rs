@@ -2,6 +2,9 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 use chrono::DateTime;
 use chrono::Utc;
@@ -10,6 +13,7 @@ use codex_core::ConversationsPage;
 use codex_core::Cursor;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::RolloutRecorder;
+use codex_core::SearchHit;
 use codex_protocol::items::TurnItem;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
@@ -35,6 +39,9 @@ use codex_protocol::models::ResponseItem;
 
 const PAGE_SIZE: usize = 25;
 const LOAD_NEAR_THRESHOLD: usize = 5;
+const TEXT_SEARCH_LIMIT: usize = 200;
+/// How long the query must stay unchanged before a full-text search runs.
+const TEXT_SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
 
 #[derive(Debug, Clone)]
 pub enum ResumeSelection {
@@ -54,16 +61,28 @@ struct PageLoadRequest {
 
 type PageLoader = Arc<dyn Fn(PageLoadRequest) + Send + Sync>;
 
+#[derive(Clone)]
+struct TextSearchRequest {
+    codex_home: PathBuf,
+    query: String,
+}
+
+type TextSearchLoader = Arc<dyn Fn(TextSearchRequest) + Send + Sync>;
+
 enum BackgroundEvent {
     PageLoaded {
         request_token: usize,
         search_token: Option<usize>,
         page: std::io::Result<ConversationsPage>,
     },
+    TextSearchLoaded {
+        query: String,
+        hits: std::io::Result<Vec<SearchHit>>,
+    },
 }
 
-/// Interactive session picker that lists recorded rollout files with simple
-/// search and pagination. Shows the first user input as the preview, relative
+/// Interactive session picker that lists recorded rollout files with
+/// full-text search and pagination. Shows the first user input as the preview, relative
 /// time (e.g., "5 seconds ago"), and the absolute path.
 pub async fn run_resume_picker(
     tui: &mut Tui,
@@ -97,12 +116,39 @@ pub async fn run_resume_picker(
         });
     });
 
+    let search_tx = bg_tx.clone();
+    let latest_search = Arc::new(AtomicUsize::new(0));
+    let text_search_loader: TextSearchLoader = Arc::new(move |request: TextSearchRequest| {
+        let tx = search_tx.clone();
+        let latest_search = latest_search.clone();
+        let generation = latest_search.fetch_add(1, Ordering::SeqCst) + 1;
+        tokio::spawn(async move {
+            // Only search once the user stops typing.
+            tokio::time::sleep(TEXT_SEARCH_DEBOUNCE).await;
+            if latest_search.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let hits = RolloutRecorder::search_conversations(
+                &request.codex_home,
+                &request.query,
+                INTERACTIVE_SESSION_SOURCES,
+                TEXT_SEARCH_LIMIT,
+            )
+            .await;
+            let _ = tx.send(BackgroundEvent::TextSearchLoaded {
+                query: request.query,
+                hits,
+            });
+        });
+    });
+
     let mut state = PickerState::new(
         codex_home.to_path_buf(),
         alt.tui.frame_requester(),
         page_loader,
         default_provider.clone(),
     );
+    state.text_search_loader = Some(text_search_loader);
     state.load_initial_page().await?;
     state.request_frame();
 
@@ -177,6 +223,19 @@ struct PickerState {
     page_loader: PageLoader,
     view_rows: Option<usize>,
     default_provider: String,
+    /// Searches the full text of every session, not just the loaded previews.
+    text_search_loader: Option<TextSearchLoader>,
+    text_search: TextSearchState,
+}
+
+/// Sessions whose recorded text matches `query`, found by the full-text index.
+#[derive(Default)]
+struct TextSearchState {
+    query: String,
+    pending: bool,
+    rows: Vec<Row>,
+    /// Why the last search failed; shown in place of its results.
+    error: Option<String>,
 }
 
 struct PaginationState {
@@ -264,6 +323,8 @@ impl PickerState {
             page_loader,
             view_rows: None,
             default_provider,
+            text_search_loader: None,
+            text_search: TextSearchState::default(),
         }
     }
 
@@ -382,10 +443,40 @@ impl PickerState {
                 let completed_token = pending.search_token.or(search_token);
                 self.continue_search_if_token_matches(completed_token);
             }
+            BackgroundEvent::TextSearchLoaded { query, hits } => {
+                if query != self.text_search.query {
+                    return Ok(());
+                }
+                self.text_search.pending = false;
+                let hits = match hits {
+                    Ok(hits) => hits,
+                    Err(err) => {
+                        self.text_search.error = Some(err.to_string());
+                        self.request_frame();
+                        return Ok(());
+                    }
+                };
+                self.text_search.rows = hits
+                    .iter()
+                    .filter(|hit| self.is_listed_session(hit))
+                    .map(|hit| hit_to_row(hit, &query))
+                    .collect();
+                self.apply_filter();
+                if !self.filtered_rows.is_empty() {
+                    self.search_state = SearchState::Idle;
+                }
+            }
         }
         Ok(())
     }
 
+    /// Mirrors the provider filter applied to the paged listing.
+    fn is_listed_session(&self, hit: &SearchHit) -> bool {
+        hit.model_provider
+            .as_deref()
+            .is_none_or(|provider| provider == self.default_provider)
+    }
+
     fn reset_pagination(&mut self) {
         self.pagination.next_cursor = None;
         self.pagination.num_scanned_files = 0;
@@ -428,6 +519,12 @@ impl PickerState {
                 .filter(|r| r.preview.to_lowercase().contains(&q))
                 .cloned()
                 .collect();
+            // Full-text matches follow the sessions whose preview matched.
+            for row in &self.text_search.rows {
+                if !self.filtered_rows.iter().any(|r| r.path == row.path) {
+                    self.filtered_rows.push(row.clone());
+                }
+            }
         }
         if self.selected >= self.filtered_rows.len() {
             self.selected = self.filtered_rows.len().saturating_sub(1);
@@ -445,6 +542,7 @@ impl PickerState {
         }
         self.query = new_query;
         self.selected = 0;
+        self.start_text_search();
         self.apply_filter();
         if self.query.is_empty() {
             self.search_state = SearchState::Idle;
@@ -463,6 +561,24 @@ impl PickerState {
         self.load_more_if_needed(LoadTrigger::Search { token });
     }
 
+    fn start_text_search(&mut self) {
+        self.text_search = TextSearchState {
+            query: self.query.clone(),
+            ..Default::default()
+        };
+        if self.query.trim().is_empty() {
+            return;
+        }
+        let Some(loader) = self.text_search_loader.as_ref() else {
+            return;
+        };
+        self.text_search.pending = true;
+        loader(TextSearchRequest {
+            codex_home: self.codex_home.clone(),
+            query: self.query.clone(),
+        });
+    }
+
     fn continue_search_if_needed(&mut self) {
         let Some(token) = self.search_state.active_token() else {
             return;
@@ -619,6 +735,24 @@ fn head_to_row(item: &ConversationItem) -> Row {
     }
 }
 
+fn hit_to_row(hit: &SearchHit, query: &str) -> Row {
+    // Show the first message when it explains the match, otherwise the
+    // excerpt that matched.
+    let preview = hit
+        .preview
+        .as_deref()
+        .map(str::trim)
+        .filter(|preview| preview.to_lowercase().contains(&query.to_lowercase()))
+        .map_or_else(|| hit.snippet.clone(), str::to_string);
+    let created_at = parse_timestamp_str(&hit.created_at);
+    Row {
+        path: hit.path.clone(),
+        preview,
+        created_at,
+        updated_at: created_at,
+    }
+}
+
 fn parse_timestamp_str(ts: &str) -> Option<DateTime<Utc>> {
     chrono::DateTime::parse_from_rfc3339(ts)
         .map(|dt| dt.with_timezone(&Utc))
@@ -784,10 +918,15 @@ fn render_list(
 fn render_empty_state_line(state: &PickerState) -> Line<'static> {
     if !state.query.is_empty() {
         if state.search_state.is_active()
+            || state.text_search.pending
             || (state.pagination.loading.is_pending() && state.pagination.next_cursor.is_some())
         {
             return vec!["Searching…".italic().dim()].into();
         }
+        if let Some(error) = &state.text_search.error {
+            let msg = format!("Full-text search failed: {error}");
+            return vec![Span::from(msg).red()].into();
+        }
         if state.pagination.reached_scan_cap {
             let msg = format!(
                 "Search scanned first {} sessions; more may exist",
@@ -1432,4 +1571,116 @@ mod tests {
         assert!(!state.search_state.is_active());
         assert!(state.pagination.reached_scan_cap);
     }
+
+    #[test]
+    fn set_query_merges_full_text_matches() {
+        let page_loader: PageLoader = Arc::new(|_| {});
+        let searched: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let search_sink = searched.clone();
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            page_loader,
+            String::from("openai"),
+        );
+        state.text_search_loader = Some(Arc::new(move |req: TextSearchRequest| {
+            search_sink.lock().unwrap().push(req.query);
+        }));
+        state.reset_pagination();
+        state.ingest_page(page(
+            vec![
+                make_item("/tmp/a.jsonl", "2025-01-01T00:00:00Z", "fix the migration"),
+                make_item("/tmp/b.jsonl", "2025-01-02T00:00:00Z", "tidy up"),
+            ],
+            None,
+            2,
+            false,
+        ));
+
+        state.set_query("migration".to_string());
+        assert_eq!(*searched.lock().unwrap(), vec!["migration".to_string()]);
+        assert!(state.text_search.pending);
+
+        let hit = |path: &str, provider: &str| SearchHit {
+            conversation_id: codex_protocol::ConversationId::new(),
+            path: PathBuf::from(path),
+            created_at: "2025-01-02T00:00:00Z".to_string(),
+            cwd: PathBuf::from("/repo"),
+            source: codex_protocol::protocol::SessionSource::Cli,
+            model_provider: Some(provider.to_string()),
+            preview: Some("tidy up".to_string()),
+            snippet: "ran the migration tests".to_string(),
+            score: 1,
+        };
+        // Results for a query the user has since changed are dropped.
+        state
+            .handle_background_event(BackgroundEvent::TextSearchLoaded {
+                query: "migr".to_string(),
+                hits: Ok(vec![hit("/tmp/stale.jsonl", "openai")]),
+            })
+            .unwrap();
+        assert!(state.text_search.pending);
+
+        state
+            .handle_background_event(BackgroundEvent::TextSearchLoaded {
+                query: "migration".to_string(),
+                hits: Ok(vec![
+                    hit("/tmp/a.jsonl", "openai"),
+                    hit("/tmp/b.jsonl", "openai"),
+                    hit("/tmp/other-provider.jsonl", "azure"),
+                ]),
+            })
+            .unwrap();
+
+        assert!(!state.text_search.pending);
+        let rows: Vec<(PathBuf, String)> = state
+            .filtered_rows
+            .iter()
+            .map(|row| (row.path.clone(), row.preview.clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    PathBuf::from("/tmp/a.jsonl"),
+                    "fix the migration".to_string()
+                ),
+                (
+                    PathBuf::from("/tmp/b.jsonl"),
+                    "ran the migration tests".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn full_text_search_errors_are_shown_inline() {
+        let page_loader: PageLoader = Arc::new(|_| {});
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            page_loader,
+            String::from("openai"),
+        );
+        state.text_search_loader = Some(Arc::new(|_| {}));
+        state.reset_pagination();
+        state.ingest_page(page(Vec::new(), None, 0, false));
+
+        state.set_query("migration".to_string());
+        state
+            .handle_background_event(BackgroundEvent::TextSearchLoaded {
+                query: "migration".to_string(),
+                hits: Err(std::io::Error::other("index unreadable")),
+            })
+            .expect("a failed search keeps the picker open");
+
+        assert!(!state.text_search.pending);
+        let line = render_empty_state_line(&state);
+        let text: String = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, "Full-text search failed: index unreadable");
+    }
 }
@@ -0,0 +1,1143 @@
+//! Renders a recorded session as a Markdown, HTML or JSON transcript.
+//!
+//! The transcript follows what the chat widget showed while the session was
+//! live: user prompts, agent messages rendered as Markdown, `Ran` commands
+//! with their output collapsed, `Added`/`Edited`/`Deleted` patches with
+//! `(+N -M)` counts, plan updates, and the final token usage line.
+//!
+//! Patches are rendered with the chat widget's diff renderer from the
+//! recorded `PatchApplyBegin` events. Rollouts recorded before those events
+//! were persisted fall back to the patch text of the `apply_patch` call.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::RolloutRecorder;
+use codex_core::command_from_tool_args;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::FileChange;
+use codex_core::protocol::FinalOutput;
+use codex_core::protocol::TokenUsage;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::plan_tool::StepStatus;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_protocol::protocol::InitialHistory;
+use codex_protocol::protocol::RolloutItem;
+use pulldown_cmark::CodeBlockKind;
+use pulldown_cmark::Event;
+use pulldown_cmark::Options;
+use pulldown_cmark::Parser;
+use pulldown_cmark::Tag;
+use pulldown_cmark::TagEnd;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::text::Line;
+use serde::Serialize;
+
+use crate::diff_render::create_diff_summary;
+use crate::exec_command::strip_bash_lc_and_escape;
+
+/// Width the chat widget's diff renderer wraps patch lines at.
+const DIFF_WRAP_COLS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    #[value(name = "md", alias = "markdown")]
+    Markdown,
+    Html,
+    Json,
+}
+
+/// Reads the rollout at `path` and renders it in `format`.
+pub async fn export_rollout(path: &Path, format: ExportFormat) -> std::io::Result<String> {
+    let items = match RolloutRecorder::get_rollout_history(path).await? {
+        InitialHistory::New => Vec::new(),
+        InitialHistory::Resumed(resumed) => resumed.history,
+        InitialHistory::Forked(items) => items,
+    };
+    let transcript = Transcript::from_items(&items);
+    Ok(match format {
+        ExportFormat::Markdown => render_markdown(&transcript),
+        ExportFormat::Html => render_html(&transcript),
+        ExportFormat::Json => serde_json::to_string_pretty(&transcript)? + "\n",
+    })
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Transcript {
+    id: Option<String>,
+    started_at: Option<String>,
+    cwd: Option<PathBuf>,
+    model: Option<String>,
+    model_provider: Option<String>,
+    entries: Vec<Entry>,
+    token_usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Entry {
+    UserMessage {
+        message: String,
+    },
+    AgentMessage {
+        message: String,
+    },
+    Command {
+        command: String,
+        exit_code: Option<i32>,
+        output: Option<String>,
+    },
+    /// A patch as applied, from its `PatchApplyBegin` event.
+    Patch {
+        changes: BTreeMap<PathBuf, FileChange>,
+    },
+    /// A patch rebuilt from the `apply_patch` call of an older rollout.
+    PatchCall {
+        files: Vec<PatchFile>,
+    },
+    PatchFailed {
+        output: String,
+    },
+    Plan(UpdatePlanArgs),
+}
+
+impl Entry {
+    fn is_user(&self) -> bool {
+        matches!(self, Entry::UserMessage { .. })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PatchFile {
+    kind: PatchKind,
+    path: String,
+    move_path: Option<String>,
+    diff: String,
+    added: usize,
+    removed: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PatchKind {
+    Add,
+    Update,
+    Delete,
+}
+
+impl Transcript {
+    fn from_items(items: &[RolloutItem]) -> Self {
+        let mut transcript = Transcript::default();
+        // Shell calls are paired with their output by call id.
+        let mut pending_commands: HashMap<String, usize> = HashMap::new();
+        // Entry index of each patch by call id, so that a patch is shown once
+        // whether its call or its `PatchApplyBegin` event comes first.
+        let mut patches: HashMap<String, usize> = HashMap::new();
+
+        for item in items {
+            match item {
+                RolloutItem::SessionMeta(meta_line) => {
+                    if transcript.id.is_none() {
+                        let meta = &meta_line.meta;
+                        transcript.id = Some(meta.id.to_string());
+                        transcript.started_at = Some(meta.timestamp.clone());
+                        transcript.cwd = Some(meta.cwd.clone());
+                        transcript.model_provider = meta.model_provider.clone();
+                    }
+                }
+                RolloutItem::TurnContext(context) => {
+                    transcript
+                        .model
+                        .get_or_insert_with(|| context.model.clone());
+                }
+                RolloutItem::EventMsg(event) => transcript.push_event(event, &mut patches),
+                RolloutItem::ResponseItem(item) => {
+                    transcript.push_response_item(item, &mut pending_commands, &mut patches);
+                }
+                RolloutItem::Compacted(_) => {}
+            }
+        }
+        transcript
+    }
+
+    fn push_event(&mut self, event: &EventMsg, patches: &mut HashMap<String, usize>) {
+        match event {
+            EventMsg::UserMessage(ev) => self.entries.push(Entry::UserMessage {
+                message: ev.message.clone(),
+            }),
+            EventMsg::AgentMessage(ev) => self.entries.push(Entry::AgentMessage {
+                message: ev.message.clone(),
+            }),
+            EventMsg::PatchApplyBegin(ev) => {
+                let entry = Entry::Patch {
+                    changes: ev.changes.clone().into_iter().collect(),
+                };
+                match patches.get(&ev.call_id) {
+                    Some(&index) => self.entries[index] = entry,
+                    None => {
+                        patches.insert(ev.call_id.clone(), self.entries.len());
+                        self.entries.push(entry);
+                    }
+                }
+            }
+            EventMsg::PatchApplyEnd(ev) if !ev.success => {
+                let output = if ev.stderr.trim().is_empty() {
+                    ev.stdout.clone()
+                } else {
+                    ev.stderr.clone()
+                };
+                self.entries.push(Entry::PatchFailed { output });
+            }
+            EventMsg::TokenCount(ev) => {
+                if let Some(info) = &ev.info {
+                    self.token_usage = Some(info.total_token_usage.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_response_item(
+        &mut self,
+        item: &ResponseItem,
+        pending_commands: &mut HashMap<String, usize>,
+        patches: &mut HashMap<String, usize>,
+    ) {
+        match item {
+            ResponseItem::LocalShellCall {
+                call_id,
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => {
+                self.push_command(
+                    strip_bash_lc_and_escape(&exec.command),
+                    call_id.as_deref(),
+                    pending_commands,
+                );
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let Ok(args) = serde_json::from_str::<serde_json::Value>(arguments) else {
+                    return;
+                };
+                match name.as_str() {
+                    "apply_patch" => {
+                        if let Some(patch) = args.get("input").and_then(serde_json::Value::as_str) {
+                            self.push_patch_call(patch, call_id, patches);
+                        }
+                    }
+                    "update_plan" => {
+                        if let Ok(plan) = serde_json::from_value::<UpdatePlanArgs>(args) {
+                            self.entries.push(Entry::Plan(plan));
+                        }
+                    }
+                    "shell" | "container.exec" | "shell_command" | "exec_command" => {
+                        if let Some(command) = command_from_tool_args(&args) {
+                            self.push_command(command, Some(call_id), pending_commands);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            ResponseItem::CustomToolCall {
+                name,
+                input,
+                call_id,
+                ..
+            } if name == "apply_patch" => {
+                self.push_patch_call(input, call_id, patches);
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let Some(index) = pending_commands.remove(call_id) else {
+                    return;
+                };
+                let (exit_code, text) = parse_command_output(&output.content);
+                if let Some(Entry::Command {
+                    exit_code: entry_exit_code,
+                    output: entry_output,
+                    ..
+                }) = self.entries.get_mut(index)
+                {
+                    *entry_exit_code = exit_code;
+                    *entry_output = Some(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_command(
+        &mut self,
+        command: String,
+        call_id: Option<&str>,
+        pending_commands: &mut HashMap<String, usize>,
+    ) {
+        if let Some(call_id) = call_id {
+            pending_commands.insert(call_id.to_string(), self.entries.len());
+        }
+        self.entries.push(Entry::Command {
+            command,
+            exit_code: None,
+            output: None,
+        });
+    }
+
+    fn push_patch_call(
+        &mut self,
+        patch: &str,
+        call_id: &str,
+        patches: &mut HashMap<String, usize>,
+    ) {
+        if patches.contains_key(call_id) {
+            return;
+        }
+        let files = parse_patch_files(patch);
+        if !files.is_empty() {
+            patches.insert(call_id.to_string(), self.entries.len());
+            self.entries.push(Entry::PatchCall { files });
+        }
+    }
+}
+
+/// Splits a shell tool output into its exit code and the command's output.
+/// Handles both the structured `{"output", "metadata"}` payload and the
+/// freeform `Exit code: …\nOutput:\n…` text.
+fn parse_command_output(content: &str) -> (Option<i32>, String) {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(content)
+        && let Some(output) = value.get("output").and_then(serde_json::Value::as_str)
+    {
+        let exit_code = value
+            .get("metadata")
+            .and_then(|metadata| metadata.get("exit_code"))
+            .and_then(serde_json::Value::as_i64)
+            .and_then(|code| i32::try_from(code).ok());
+        return (exit_code, output.to_string());
+    }
+
+    let exit_code = content
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("Exit code: "))
+        .and_then(|code| code.trim().parse().ok());
+    match (exit_code, content.split_once("\nOutput:\n")) {
+        (Some(_), Some((_, output))) => (exit_code, output.to_string()),
+        _ => (exit_code, content.to_string()),
+    }
+}
+
+fn parse_patch_files(patch: &str) -> Vec<PatchFile> {
+    let mut files: Vec<PatchFile> = Vec::new();
+    for line in patch.lines() {
+        let header = [
+            ("*** Add File: ", PatchKind::Add),
+            ("*** Update File: ", PatchKind::Update),
+            ("*** Delete File: ", PatchKind::Delete),
+        ]
+        .into_iter()
+        .find_map(|(prefix, kind)| line.strip_prefix(prefix).map(|path| (kind, path)));
+        if let Some((kind, path)) = header {
+            files.push(PatchFile {
+                kind,
+                path: path.trim().to_string(),
+                move_path: None,
+                diff: String::new(),
+                added: 0,
+                removed: 0,
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if let Some(move_path) = line.strip_prefix("*** Move to: ") {
+            file.move_path = Some(move_path.trim().to_string());
+        } else if line.starts_with("*** ") {
+            // `*** End Patch` / `*** End of File` markers.
+        } else {
+            if line.starts_with('+') {
+                file.added += 1;
+            } else if line.starts_with('-') {
+                file.removed += 1;
+            }
+            file.diff.push_str(line);
+            file.diff.push('\n');
+        }
+    }
+    files
+}
+
+/// The lines the chat widget shows for a patch.
+fn diff_summary_lines(
+    changes: &BTreeMap<PathBuf, FileChange>,
+    cwd: Option<&Path>,
+) -> Vec<Line<'static>> {
+    let changes: HashMap<PathBuf, FileChange> = changes
+        .iter()
+        .map(|(path, change)| (path.clone(), change.clone()))
+        .collect();
+    create_diff_summary(&changes, cwd.unwrap_or(Path::new("")), DIFF_WRAP_COLS)
+}
+
+fn line_text(line: &Line<'_>) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+fn patch_verb(files: &[PatchFile]) -> &'static str {
+    match files {
+        [file] => match file.kind {
+            PatchKind::Add => "Added",
+            PatchKind::Delete => "Deleted",
+            PatchKind::Update => "Edited",
+        },
+        _ => "Edited",
+    }
+}
+
+fn output_summary(exit_code: Option<i32>, output: &str) -> String {
+    let lines = output.lines().count();
+    let noun = if lines == 1 { "line" } else { "lines" };
+    match exit_code {
+        Some(code) => format!("exit code {code} · {lines} {noun} of output"),
+        None => format!("{lines} {noun} of output"),
+    }
+}
+
+fn plan_marker(status: &StepStatus) -> &'static str {
+    match status {
+        StepStatus::Completed => "[x]",
+        StepStatus::InProgress | StepStatus::Pending => "[ ]",
+    }
+}
+
+fn speaker(entry: &Entry) -> &'static str {
+    if entry.is_user() { "User" } else { "Codex" }
+}
+
+fn title(transcript: &Transcript) -> String {
+    match &transcript.id {
+        Some(id) => format!("Codex session {id}"),
+        None => "Codex session".to_string(),
+    }
+}
+
+fn metadata(transcript: &Transcript) -> Vec<(&'static str, String)> {
+    let mut rows = Vec::new();
+    if let Some(started_at) = &transcript.started_at {
+        rows.push(("Started", started_at.clone()));
+    }
+    if let Some(cwd) = &transcript.cwd {
+        rows.push(("Directory", cwd.display().to_string()));
+    }
+    match (&transcript.model, &transcript.model_provider) {
+        (Some(model), Some(provider)) => rows.push(("Model", format!("{model} ({provider})"))),
+        (Some(model), None) => rows.push(("Model", model.clone())),
+        (None, Some(provider)) => rows.push(("Model provider", provider.clone())),
+        (None, None) => {}
+    }
+    rows
+}
+
+fn render_markdown(transcript: &Transcript) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", title(transcript));
+    let metadata = metadata(transcript);
+    for (label, value) in &metadata {
+        let _ = writeln!(out, "- **{label}:** {}", inline_code(value));
+    }
+    if !metadata.is_empty() {
+        out.push('\n');
+    }
+
+    let mut last_speaker = None;
+    for entry in &transcript.entries {
+        let who = speaker(entry);
+        if last_speaker != Some(who) {
+            let _ = writeln!(out, "## {who}\n");
+            last_speaker = Some(who);
+        }
+        match entry {
+            Entry::UserMessage { message } => {
+                for line in message.trim_end().lines() {
+                    let _ = writeln!(out, "> {line}");
+                }
+                out.push('\n');
+            }
+            Entry::AgentMessage { message } => {
+                let _ = writeln!(out, "{}\n", message.trim_end());
+            }
+            Entry::Command {
+                command,
+                exit_code,
+                output,
+            } => {
+                if command.contains('\n') {
+                    let _ = writeln!(out, "**Ran**\n\n{}", code_block("sh", command));
+                } else {
+                    let _ = writeln!(out, "**Ran** {}\n", inline_code(command));
+                }
+                if let Some(output) = output.as_deref().filter(|o| !o.trim().is_empty()) {
+                    let _ = writeln!(
+                        out,
+                        "<details>\n<summary>{}</summary>\n\n{}</details>\n",
+                        output_summary(*exit_code, output),
+                        code_block("", output)
+                    );
+                } else if let Some(code) = exit_code {
+                    let _ = writeln!(out, "_exit code {code}_\n");
+                }
+            }
+            Entry::Patch { changes } => {
+                let lines = diff_summary_lines(changes, transcript.cwd.as_deref());
+                let text: Vec<String> = lines.iter().map(line_text).collect();
+                let _ = writeln!(out, "{}", code_block("", &text.join("\n")));
+            }
+            Entry::PatchCall { files } => {
+                let verb = patch_verb(files);
+                if let [file] = files.as_slice() {
+                    let _ = writeln!(out, "**{verb}** {}\n", markdown_patch_label(file));
+                } else {
+                    let added: usize = files.iter().map(|f| f.added).sum();
+                    let removed: usize = files.iter().map(|f| f.removed).sum();
+                    let _ = writeln!(
+                        out,
+                        "**{verb}** {} files (+{added} -{removed})\n",
+                        files.len()
+                    );
+                }
+                for file in files {
+                    if files.len() > 1 {
+                        let _ = writeln!(out, "{}\n", markdown_patch_label(file));
+                    }
+                    if !file.diff.trim().is_empty() {
+                        out.push_str(&code_block("diff", &file.diff));
+                        out.push('\n');
+                    }
+                }
+            }
+            Entry::PatchFailed { output } => {
+                let _ = writeln!(out, "**Patch failed**\n\n{}", code_block("", output));
+            }
+            Entry::Plan(plan) => {
+                out.push_str("**Updated Plan**\n\n");
+                if let Some(explanation) = plan.explanation.as_deref().map(str::trim)
+                    && !explanation.is_empty()
+                {
+                    let _ = writeln!(out, "_{explanation}_\n");
+                }
+                for item in &plan.plan {
+                    let marker = plan_marker(&item.status);
+                    match item.status {
+                        StepStatus::InProgress => {
+                            let _ = writeln!(out, "- {marker} **{}**", item.step);
+                        }
+                        _ => {
+                            let _ = writeln!(out, "- {marker} {}", item.step);
+                        }
+                    }
+                }
+                out.push('\n');
+            }
+        }
+    }
+
+    if let Some(usage) = &transcript.token_usage {
+        let _ = writeln!(out, "---\n\n{}", FinalOutput::from(usage.clone()));
+    }
+    out
+}
+
+fn markdown_patch_label(file: &PatchFile) -> String {
+    let mut label = inline_code(&file.path);
+    if let Some(move_path) = &file.move_path {
+        let _ = write!(label, " → {}", inline_code(move_path));
+    }
+    let _ = write!(label, " (+{} -{})", file.added, file.removed);
+    label
+}
+
+/// Wraps `text` in a code span long enough not to be closed by backticks
+/// inside it.
+fn inline_code(text: &str) -> String {
+    let fence = "`".repeat(longest_backtick_run(text) + 1);
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{pad}{text}{pad}{fence}")
+}
+
+fn code_block(lang: &str, text: &str) -> String {
+    let fence = "`".repeat((longest_backtick_run(text) + 1).max(3));
+    let body = text.trim_end_matches('\n');
+    format!("{fence}{lang}\n{body}\n{fence}\n")
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', sans-serif; max-width: 56rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; }
+pre { background: #f6f8fa; padding: 0.75rem; overflow-x: auto; }
+blockquote { margin: 0 0 1rem; padding: 0.25rem 1rem; border-left: 4px solid #0969da; white-space: pre-wrap; }
+.meta { color: #57606a; }
+.diff .add { color: #1a7f37; }
+.diff .del { color: #cf222e; }
+.diff .hunk { color: #8250df; }
+.diff .dim { color: #57606a; }
+.plan { list-style: none; padding-left: 1rem; }
+.plan .in_progress { font-weight: bold; }
+.plan .completed { color: #57606a; text-decoration: line-through; }
+footer { color: #57606a; border-top: 1px solid #d0d7de; margin-top: 2rem; padding-top: 1rem; }
+";
+
+fn render_html(transcript: &Transcript) -> String {
+    let title = escape_html(&title(transcript));
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>"
+    );
+    let metadata = metadata(transcript);
+    if !metadata.is_empty() {
+        out.push_str("<ul class=\"meta\">\n");
+        for (label, value) in &metadata {
+            let _ = writeln!(
+                out,
+                "<li><strong>{label}:</strong> <code>{}</code></li>",
+                escape_html(value)
+            );
+        }
+        out.push_str("</ul>\n");
+    }
+
+    let mut last_speaker = None;
+    for entry in &transcript.entries {
+        let who = speaker(entry);
+        if last_speaker != Some(who) {
+            let _ = writeln!(out, "<h2>{who}</h2>");
+            last_speaker = Some(who);
+        }
+        match entry {
+            Entry::UserMessage { message } => {
+                let _ = writeln!(
+                    out,
+                    "<blockquote>{}</blockquote>",
+                    escape_html(message.trim_end())
+                );
+            }
+            Entry::AgentMessage { message } => {
+                out.push_str(&markdown_to_html(message));
+            }
+            Entry::Command {
+                command,
+                exit_code,
+                output,
+            } => {
+                if command.contains('\n') {
+                    let _ = writeln!(
+                        out,
+                        "<p><strong>Ran</strong></p>\n<pre><code>{}</code></pre>",
+                        escape_html(command)
+                    );
+                } else {
+                    let _ = writeln!(
+                        out,
+                        "<p><strong>Ran</strong> <code>{}</code></p>",
+                        escape_html(command)
+                    );
+                }
+                if let Some(output) = output.as_deref().filter(|o| !o.trim().is_empty()) {
+                    let _ = writeln!(
+                        out,
+                        "<details>\n<summary>{}</summary>\n<pre><code>{}</code></pre>\n</details>",
+                        output_summary(*exit_code, output),
+                        escape_html(output.trim_end_matches('\n'))
+                    );
+                } else if let Some(code) = exit_code {
+                    let _ = writeln!(out, "<p><em>exit code {code}</em></p>");
+                }
+            }
+            Entry::Patch { changes } => {
+                let lines = diff_summary_lines(changes, transcript.cwd.as_deref());
+                let _ = writeln!(
+                    out,
+                    "<pre class=\"diff\"><code>{}</code></pre>",
+                    styled_lines_html(&lines)
+                );
+            }
+            Entry::PatchCall { files } => {
+                let verb = patch_verb(files);
+                if let [file] = files.as_slice() {
+                    let _ = writeln!(
+                        out,
+                        "<p><strong>{verb}</strong> {}</p>",
+                        html_patch_label(file)
+                    );
+                } else {
+                    let added: usize = files.iter().map(|f| f.added).sum();
+                    let removed: usize = files.iter().map(|f| f.removed).sum();
+                    let _ = writeln!(
+                        out,
+                        "<p><strong>{verb}</strong> {} files {}</p>",
+                        files.len(),
+                        html_line_counts(added, removed)
+                    );
+                }
+                for file in files {
+                    if files.len() > 1 {
+                        let _ = writeln!(out, "<p>{}</p>", html_patch_label(file));
+                    }
+                    if !file.diff.trim().is_empty() {
+                        let _ = writeln!(
+                            out,
+                            "<pre class=\"diff\"><code>{}</code></pre>",
+                            diff_lines_html(&file.diff)
+                        );
+                    }
+                }
+            }
+            Entry::PatchFailed { output } => {
+                let _ = writeln!(
+                    out,
+                    "<p><strong>Patch failed</strong></p>\n<pre><code>{}</code></pre>",
+                    escape_html(output)
+                );
+            }
+            Entry::Plan(plan) => {
+                out.push_str("<p><strong>Updated Plan</strong></p>\n");
+                if let Some(explanation) = plan.explanation.as_deref().map(str::trim)
+                    && !explanation.is_empty()
+                {
+                    let _ = writeln!(out, "<p><em>{}</em></p>", escape_html(explanation));
+                }
+                out.push_str("<ul class=\"plan\">\n");
+                for item in &plan.plan {
+                    let (class, marker) = match item.status {
+                        StepStatus::Completed => ("completed", "✔"),
+                        StepStatus::InProgress => ("in_progress", "□"),
+                        StepStatus::Pending => ("pending", "□"),
+                    };
+                    let _ = writeln!(
+                        out,
+                        "<li class=\"{class}\">{marker} {}</li>",
+                        escape_html(&item.step)
+                    );
+                }
+                out.push_str("</ul>\n");
+            }
+        }
+    }
+
+    if let Some(usage) = &transcript.token_usage {
+        let _ = writeln!(
+            out,
+            "<footer>{}</footer>",
+            escape_html(&FinalOutput::from(usage.clone()).to_string())
+        );
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn html_patch_label(file: &PatchFile) -> String {
+    let mut label = format!("<code>{}</code>", escape_html(&file.path));
+    if let Some(move_path) = &file.move_path {
+        let _ = write!(label, " → <code>{}</code>", escape_html(move_path));
+    }
+    label.push(' ');
+    label.push_str(&html_line_counts(file.added, file.removed));
+    label
+}
+
+fn html_line_counts(added: usize, removed: usize) -> String {
+    format!("(<span class=\"add\">+{added}</span> <span class=\"del\">-{removed}</span>)")
+}
+
+/// Renders agent Markdown the way the chat widget does: raw HTML is shown as
+/// text rather than interpreted, and `diff` code blocks are colored. Links and
+/// images with a scheme other than http(s) or mailto, such as `javascript:`,
+/// are neutralized.
+fn markdown_to_html(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut in_diff = false;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref lang))) => {
+            in_diff = lang.as_ref() == "diff";
+            event
+        }
+        Event::End(TagEnd::CodeBlock) => {
+            in_diff = false;
+            event
+        }
+        Event::Text(text) if in_diff => Event::Html(diff_lines_html(&text).into()),
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !is_safe_url(&dest_url) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: "#".into(),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !is_safe_url(&dest_url) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: "#".into(),
+            title,
+            id,
+        }),
+        other => other,
+    });
+
+    let mut out = String::new();
+    pulldown_cmark::html::push_html(&mut out, events);
+    out
+}
+
+/// Renders styled terminal lines as HTML, keeping the colors the chat widget
+/// uses for diffs.
+fn styled_lines_html(lines: &[Line<'_>]) -> String {
+    let mut out = String::new();
+    for line in lines {
+        for span in &line.spans {
+            let style = line.style.patch(span.style);
+            let class = match style.fg {
+                Some(Color::Green) => Some("add"),
+                Some(Color::Red) => Some("del"),
+                _ if style.add_modifier.contains(Modifier::DIM) => Some("dim"),
+                _ => None,
+            };
+            let mut text = escape_html(&span.content);
+            if style.add_modifier.contains(Modifier::BOLD) {
+                text = format!("<strong>{text}</strong>");
+            }
+            match class {
+                Some(class) => {
+                    let _ = write!(out, "<span class=\"{class}\">{text}</span>");
+                }
+                None => out.push_str(&text),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn diff_lines_html(diff: &str) -> String {
+    let mut out = String::new();
+    for line in diff.lines() {
+        let class = if line.starts_with("+++") || line.starts_with("---") {
+            None
+        } else if line.starts_with('+') {
+            Some("add")
+        } else if line.starts_with('-') {
+            Some("del")
+        } else if line.starts_with("@@") {
+            Some("hunk")
+        } else {
+            None
+        };
+        match class {
+            Some(class) => {
+                let _ = writeln!(out, "<span class=\"{class}\">{}</span>", escape_html(line));
+            }
+            None => {
+                let _ = writeln!(out, "{}", escape_html(line));
+            }
+        }
+    }
+    out
+}
+
+/// Whether `url` is relative or uses a scheme that is safe to link to.
+/// Browsers ignore whitespace and control characters in a scheme, so they
+/// are dropped before looking at it.
+fn is_safe_url(url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let Some((scheme, _)) = url.split_once(':') else {
+        return true;
+    };
+    if scheme.contains(['/', '?', '#']) {
+        // The colon is part of a relative path, query or fragment.
+        return true;
+    }
+    matches!(scheme, "http" | "https" | "mailto")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::AgentMessageEvent;
+    use codex_core::protocol::PatchApplyBeginEvent;
+    use codex_core::protocol::PatchApplyEndEvent;
+    use codex_core::protocol::TokenCountEvent;
+    use codex_core::protocol::TokenUsageInfo;
+    use codex_core::protocol::UserMessageEvent;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn function_call(name: &str, call_id: &str, arguments: serde_json::Value) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            call_id: call_id.to_string(),
+        })
+    }
+
+    fn sample_items() -> Vec<RolloutItem> {
+        vec![
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: "fix the <flaky> test".to_string(),
+                images: None,
+            })),
+            function_call(
+                "update_plan",
+                "call-plan",
+                serde_json::json!({
+                    "plan": [
+                        {"step": "Reproduce", "status": "completed"},
+                        {"step": "Fix", "status": "in_progress"},
+                    ]
+                }),
+            ),
+            function_call(
+                "shell",
+                "call-1",
+                serde_json::json!({"command": ["bash", "-lc", "cargo test"]}),
+            ),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: serde_json::json!({
+                        "output": "running 1 test\ntest flaky ... FAILED\n",
+                        "metadata": {"exit_code": 101, "duration_seconds": 1.5},
+                    })
+                    .to_string(),
+                    ..Default::default()
+                },
+            }),
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call-2".to_string(),
+                name: "apply_patch".to_string(),
+                input: "*** Begin Patch\n*** Update File: src/lib.rs\n@@\n-    sleep(1);\n+    ready.await;\n*** End Patch".to_string(),
+            }),
+            RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Fixed `flaky` by awaiting readiness.".to_string(),
+            })),
+            RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                info: Some(TokenUsageInfo {
+                    total_token_usage: TokenUsage {
+                        input_tokens: 120,
+                        cached_input_tokens: 20,
+                        output_tokens: 30,
+                        reasoning_output_tokens: 0,
+                        total_tokens: 150,
+                    },
+                    last_token_usage: TokenUsage::default(),
+                    model_context_window: None,
+                }),
+                rate_limits: None,
+            })),
+        ]
+    }
+
+    #[test]
+    fn renders_markdown_transcript() {
+        let transcript = Transcript::from_items(&sample_items());
+        assert_eq!(
+            render_markdown(&transcript),
+            "# Codex session
+
+## User
+
+> fix the <flaky> test
+
+## Codex
+
+**Updated Plan**
+
+- [x] Reproduce
+- [ ] **Fix**
+
+**Ran** `cargo test`
+
+<details>
+<summary>exit code 101 · 2 lines of output</summary>
+
+```
+running 1 test
+test flaky ... FAILED
+```
+</details>
+
+**Edited** `src/lib.rs` (+1 -1)
+
+```diff
+@@
+-    sleep(1);
++    ready.await;
+```
+
+Fixed `flaky` by awaiting readiness.
+
+---
+
+Token usage: total=130 input=100 (+ 20 cached) output=30
+"
+        );
+    }
+
+    #[test]
+    fn html_escapes_messages_and_colors_diffs() {
+        let transcript = Transcript::from_items(&sample_items());
+        let html = render_html(&transcript);
+        assert!(html.contains("<blockquote>fix the &lt;flaky&gt; test</blockquote>"));
+        assert!(html.contains("<span class=\"del\">-    sleep(1);</span>"));
+        assert!(html.contains("<span class=\"add\">+    ready.await;</span>"));
+        assert!(html.contains("<p>Fixed <code>flaky</code> by awaiting readiness.</p>"));
+        assert_eq!(
+            markdown_to_html("a <b>bold</b> claim"),
+            "<p>a &lt;b&gt;bold&lt;/b&gt; claim</p>\n"
+        );
+    }
+
+    #[test]
+    fn json_transcript_pairs_commands_with_output() {
+        let transcript = Transcript::from_items(&sample_items());
+        let json = serde_json::to_value(&transcript).unwrap();
+        assert_eq!(
+            json["entries"][2],
+            serde_json::json!({
+                "type": "command",
+                "command": "cargo test",
+                "exit_code": 101,
+                "output": "running 1 test\ntest flaky ... FAILED\n",
+            })
+        );
+        assert_eq!(json["entries"][3]["files"][0]["path"], "src/lib.rs");
+    }
+
+    #[test]
+    fn parses_freeform_command_output() {
+        assert_eq!(
+            parse_command_output("Exit code: 2\nWall time: 0.1 seconds\nOutput:\nboom\n"),
+            (Some(2), "boom\n".to_string())
+        );
+        assert_eq!(
+            parse_command_output("plain text"),
+            (None, "plain text".to_string())
+        );
+        assert_eq!(inline_code("a `b` c"), "``a `b` c``");
+    }
+
+    #[test]
+    fn recorded_patch_events_use_the_chat_diff_renderer() {
+        let patch = "*** Begin Patch\n*** Update File: src/lib.rs\n@@\n-    sleep(1);\n+    ready.await;\n*** End Patch";
+        let items = vec![
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call-1".to_string(),
+                name: "apply_patch".to_string(),
+                input: patch.to_string(),
+            }),
+            RolloutItem::EventMsg(EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
+                call_id: "call-1".to_string(),
+                auto_approved: true,
+                changes: HashMap::from([(
+                    PathBuf::from("/repo/src/lib.rs"),
+                    FileChange::Update {
+                        unified_diff: "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -3 +3 @@\n-    sleep(1);\n+    ready.await;\n".to_string(),
+                        move_path: None,
+                    },
+                )]),
+            })),
+            RolloutItem::EventMsg(EventMsg::PatchApplyEnd(PatchApplyEndEvent {
+                call_id: "call-1".to_string(),
+                stdout: String::new(),
+                stderr: "src/lib.rs: permission denied".to_string(),
+                success: false,
+            })),
+        ];
+        let transcript = Transcript::from_items(&items);
+        assert_eq!(transcript.entries.len(), 2, "{:?}", transcript.entries);
+
+        let markdown = render_markdown(&transcript);
+        assert!(
+            markdown.contains(
+                "• Edited /repo/src/lib.rs (+1 -1)\n    3 -    sleep(1);\n    3 +    ready.await;\n"
+            ),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("**Patch failed**\n\n```\nsrc/lib.rs: permission denied\n```"),
+            "{markdown}"
+        );
+
+        let html = render_html(&transcript);
+        assert!(
+            html.contains("<span class=\"add\">+    ready.await;</span>"),
+            "{html}"
+        );
+    }
+
+    #[test]
+    fn html_neutralizes_unsafe_link_schemes() {
+        assert_eq!(
+            markdown_to_html(
+                "[docs](https://example.com) [x](JavaScript:alert(1)) ![y](<java\tscript:alert(1)>)"
+            ),
+            "<p><a href=\"https://example.com\">docs</a> <a href=\"#\">x</a> <img src=\"#\" alt=\"y\" /></p>\n"
+        );
+        assert!(is_safe_url("docs/setup.md#a:b"));
+        assert!(is_safe_url("mailto:dev@example.com"));
+        assert!(!is_safe_url("data:text/html,hi"));
+    }
+}
@@ -14,6 +14,7 @@ pub enum SlashCommand {
     // more frequently used commands should be listed first.
     Model,
     Approvals,
+    Permissions,
     Review,
     New,
     Init,
@@ -23,6 +24,7 @@ pub enum SlashCommand {
     Mention,
     Status,
     Mcp,
+    Memory,
     Logout,
     Quit,
     Exit,
@@ -47,7 +49,9 @@ impl SlashCommand {
             SlashCommand::Status => "show current session configuration and token usage",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
+            SlashCommand::Permissions => "list or remove saved approval rules",
             SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Memory => "show saved memories",
             SlashCommand::Logout => "log out of Codex",
             SlashCommand::Rollout => "print the rollout file path",
             SlashCommand::TestApproval => "test approval request",
@@ -75,6 +79,8 @@ impl SlashCommand {
             | SlashCommand::Mention
             | SlashCommand::Status
             | SlashCommand::Mcp
+            | SlashCommand::Memory
+            | SlashCommand::Permissions
             | SlashCommand::Feedback
             | SlashCommand::Quit
             | SlashCommand::Exit => true,
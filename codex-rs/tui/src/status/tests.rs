@@ -88,8 +88,11 @@ fn status_snapshot_includes_reasoning_details() {
     config.sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: Vec::new(),
         network_access: false,
+        network_allowlist: vec![],
+        read_only_subpaths: vec![],
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        container: false,
     };
 
     config.cwd = PathBuf::from("/workspace/tests");
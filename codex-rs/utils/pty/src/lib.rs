@@ -1,19 +1,19 @@
 use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::Path;
-use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
+use std::sync::atomic::AtomicBool;
 use std::time::Duration;
 
 use anyhow::Result;
-use portable_pty::native_pty_system;
 use portable_pty::CommandBuilder;
 use portable_pty::PtySize;
+use portable_pty::native_pty_system;
+use tokio::sync::Mutex as TokioMutex;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
-use tokio::sync::Mutex as TokioMutex;
 use tokio::task::JoinHandle;
 
 #[derive(Debug)]
@@ -26,6 +26,7 @@ pub struct ExecCommandSession {
     wait_handle: StdMutex<Option<JoinHandle<()>>>,
     exit_status: Arc<AtomicBool>,
     exit_code: Arc<StdMutex<Option<i32>>>,
+    exit_signal: Arc<StdMutex<Option<i32>>>,
 }
 
 impl ExecCommandSession {
@@ -39,6 +40,7 @@ impl ExecCommandSession {
         wait_handle: JoinHandle<()>,
         exit_status: Arc<AtomicBool>,
         exit_code: Arc<StdMutex<Option<i32>>>,
+        exit_signal: Arc<StdMutex<Option<i32>>>,
     ) -> (Self, broadcast::Receiver<Vec<u8>>) {
         let initial_output_rx = output_tx.subscribe();
         (
@@ -51,6 +53,7 @@ impl ExecCommandSession {
                 wait_handle: StdMutex::new(Some(wait_handle)),
                 exit_status,
                 exit_code,
+                exit_signal,
             },
             initial_output_rx,
         )
@@ -71,6 +74,11 @@ impl ExecCommandSession {
     pub fn exit_code(&self) -> Option<i32> {
         self.exit_code.lock().ok().and_then(|guard| *guard)
     }
+
+    /// Signal that killed the child, if it exited because of one.
+    pub fn exit_signal(&self) -> Option<i32> {
+        self.exit_signal.lock().ok().and_then(|guard| *guard)
+    }
 }
 
 impl Drop for ExecCommandSession {
@@ -180,11 +188,19 @@ pub async fn spawn_pty_process(
     let wait_exit_status = Arc::clone(&exit_status);
     let exit_code = Arc::new(StdMutex::new(None));
     let wait_exit_code = Arc::clone(&exit_code);
+    let exit_signal = Arc::new(StdMutex::new(None));
+    let wait_exit_signal = Arc::clone(&exit_signal);
     let wait_handle: JoinHandle<()> = tokio::task::spawn_blocking(move || {
-        let code = match child.wait() {
-            Ok(status) => status.exit_code() as i32,
-            Err(_) => -1,
+        let (code, signal) = match child.wait() {
+            Ok(status) => (
+                status.exit_code() as i32,
+                status.signal().and_then(signal_number),
+            ),
+            Err(_) => (-1, None),
         };
+        if let Ok(mut guard) = wait_exit_signal.lock() {
+            *guard = signal;
+        }
         wait_exit_status.store(true, std::sync::atomic::Ordering::SeqCst);
         if let Ok(mut guard) = wait_exit_code.lock() {
             *guard = Some(code);
@@ -201,6 +217,7 @@ pub async fn spawn_pty_process(
         wait_handle,
         exit_status,
         exit_code,
+        exit_signal,
     );
 
     Ok(SpawnedPty {
@@ -209,3 +226,20 @@ pub async fn spawn_pty_process(
         exit_rx,
     })
 }
+
+/// portable-pty only reports the signal that killed a child by its
+/// `strsignal(3)` description, so look up the signal with that description.
+#[cfg(unix)]
+fn signal_number(description: &str) -> Option<i32> {
+    // Standard signals are numbered below 32 on every Unix.
+    (1..32).find(|&signal| {
+        let name = unsafe { libc::strsignal(signal) };
+        !name.is_null()
+            && unsafe { std::ffi::CStr::from_ptr(name) }.to_string_lossy() == description
+    })
+}
+
+#[cfg(not(unix))]
+fn signal_number(_description: &str) -> Option<i32> {
+    None
+}
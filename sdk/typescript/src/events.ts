@@ -1,6 +1,6 @@
 // based on event types from codex-rs/exec/src/exec_events.rs
 
-import type { ThreadItem } from "./items";
+import type { FileUpdateChange, ThreadItem } from "./items";
 
 /** Emitted when a new thread is started as the first event. */
 export type ThreadStartedEvent = {
@@ -57,6 +57,20 @@ export type ItemCompletedEvent = {
   item: ThreadItem;
 };
 
+/** What the turn asks to be approved. */
+export type ApprovalRequestDetails =
+  | { kind: "command_execution"; command: string; cwd: string }
+  | { kind: "file_change"; changes: FileUpdateChange[] };
+
+/** Emitted when the running turn waits for the user to approve a command or a file change. */
+export type ApprovalRequestedEvent = {
+  type: "approval.requested";
+  /** The id of the tool call waiting for approval. */
+  call_id: string;
+  /** Why the approval is needed, e.g. a retry without the sandbox. */
+  reason: string | null;
+} & ApprovalRequestDetails;
+
 /** Fatal error emitted by the stream. */
 export type ThreadError = {
   message: string;
@@ -77,4 +91,5 @@ export type ThreadEvent =
   | ItemStartedEvent
   | ItemUpdatedEvent
   | ItemCompletedEvent
+  | ApprovalRequestedEvent
   | ThreadErrorEvent;
@@ -7,6 +7,8 @@ export type {
   ItemStartedEvent,
   ItemUpdatedEvent,
   ItemCompletedEvent,
+  ApprovalRequestedEvent,
+  ApprovalRequestDetails,
   ThreadError,
   ThreadErrorEvent,
   Usage,